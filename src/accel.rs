@@ -0,0 +1,203 @@
+use std::time::{Duration, Instant};
+
+use crossterm::event::{Event, KeyCode, MouseButton, MouseEventKind};
+use ratatui::{
+    backend::Backend,
+    layout::Alignment,
+    text::Line,
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame, Terminal,
+};
+
+use crate::{error, input};
+
+/// fast swipes travelling this much farther than slow ones mean acceleration is on
+const ACCEL_GAIN: f64 = 1.1;
+/// fast swipes need to be this much quicker than slow ones to compare them
+const MIN_SPEEDUP: f64 = 2.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pace {
+    Slow,
+    Fast,
+}
+
+impl Pace {
+    fn name(self) -> &'static str {
+        match self {
+            Pace::Slow => "slowly",
+            Pace::Fast => "fast",
+        }
+    }
+}
+
+/// one swipe between the marks on the desk
+#[derive(Debug, Clone, Copy)]
+pub struct Swipe {
+    /// columns the pointer moved
+    cells: f64,
+    time: Duration,
+}
+
+impl Swipe {
+    fn speed(&self) -> f64 {
+        self.cells / self.time.as_secs_f64().max(f64::EPSILON)
+    }
+}
+
+pub struct AccelReport {
+    slow: Vec<Swipe>,
+    fast: Vec<Swipe>,
+}
+
+struct AccelState {
+    swipes: usize,
+    pace: Pace,
+    /// column and time the button went down
+    pressed: Option<(u16, Instant)>,
+    slow: Vec<Swipe>,
+    fast: Vec<Swipe>,
+    /// the last swipe ran into the edge of the window
+    clipped: bool,
+}
+
+impl AccelState {
+    fn done(&self) -> usize {
+        match self.pace {
+            Pace::Slow => self.slow.len(),
+            Pace::Fast => self.fast.len(),
+        }
+    }
+
+    fn on_release(&mut self, column: u16, width: u16) {
+        let Some((start, at)) = self.pressed.take() else {
+            return;
+        };
+        // the pointer stops at the edge, the rest of the motion is lost
+        self.clipped = column == 0 || column + 1 >= width;
+        if self.clipped || column == start {
+            return;
+        }
+        let swipe = Swipe {
+            cells: f64::from(column.abs_diff(start)),
+            time: at.elapsed(),
+        };
+        match self.pace {
+            Pace::Slow => self.slow.push(swipe),
+            Pace::Fast => self.fast.push(swipe),
+        }
+        if self.pace == Pace::Slow && self.slow.len() >= self.swipes {
+            self.pace = Pace::Fast;
+        }
+    }
+}
+
+/// slow and then fast swipes over the same distance, `None` when aborted
+pub fn run<B: Backend>(
+    terminal: &mut Terminal<B>,
+    swipes: usize,
+) -> error::Result<Option<AccelReport>> {
+    let mut state = AccelState {
+        swipes,
+        pace: Pace::Slow,
+        pressed: None,
+        slow: Vec::new(),
+        fast: Vec::new(),
+        clipped: false,
+    };
+
+    while state.fast.len() < swipes {
+        let width = terminal.size()?.width;
+        terminal.draw(|f| ui(f, &state))?;
+
+        if !input::poll(Duration::from_millis(50))? {
+            continue;
+        }
+
+        match input::read()? {
+            Event::Key(key) if key.code == KeyCode::Char('q') => return Ok(None),
+            Event::Mouse(event) => match event.kind {
+                MouseEventKind::Down(MouseButton::Left) => {
+                    state.pressed = Some((event.column, Instant::now()));
+                }
+                MouseEventKind::Up(MouseButton::Left) => state.on_release(event.column, width),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    Ok(Some(AccelReport {
+        slow: state.slow,
+        fast: state.fast,
+    }))
+}
+
+fn ui<B: Backend>(f: &mut Frame<B>, state: &AccelState) {
+    let mut text = vec![
+        Line::from("mark two spots on the desk a hand width apart"),
+        Line::from("put the pointer near the left edge over the first, hold the left button"),
+        Line::from("and move the mouse to the second spot, then release"),
+        Line::from(""),
+        Line::from(format!(
+            "swipe {}: {}/{}",
+            state.pace.name(),
+            state.done(),
+            state.swipes
+        )),
+    ];
+    if state.clipped {
+        text.push(Line::from(""));
+        text.push(Line::from(
+            "the pointer hit the edge, mark the spots closer and repeat the swipe",
+        ));
+    }
+    text.push(Line::from(""));
+    text.push(Line::from("q: abort"));
+
+    let p = Paragraph::new(text)
+        .block(
+            Block::default()
+                .title("pointer acceleration")
+                .borders(Borders::ALL),
+        )
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+    f.render_widget(p, f.size());
+}
+
+fn mean(swipes: &[Swipe], value: fn(&Swipe) -> f64) -> f64 {
+    swipes.iter().map(value).sum::<f64>() / swipes.len().max(1) as f64
+}
+
+pub fn print_report(report: &AccelReport) {
+    let (slow, fast) = (
+        mean(&report.slow, |s| s.cells),
+        mean(&report.fast, |s| s.cells),
+    );
+    let speedup =
+        mean(&report.fast, Swipe::speed) / mean(&report.slow, Swipe::speed).max(f64::EPSILON);
+    println!(
+        "slow swipes: {:.1} columns, fast swipes: {:.1} columns ({:.1}x the speed)",
+        slow, fast, speedup
+    );
+    if speedup < MIN_SPEEDUP {
+        println!("the fast swipes were not much faster, repeat with quicker flicks");
+        return;
+    }
+
+    let gain = fast / slow.max(f64::EPSILON);
+    if gain >= ACCEL_GAIN {
+        println!(
+            "pointer acceleration is on, fast moves go {:.2}x as far",
+            gain
+        );
+    } else if gain <= 1.0 / ACCEL_GAIN {
+        println!(
+            "fast moves cover only {:.2}x the distance, the sensor may lose tracking at speed",
+            gain
+        );
+    } else {
+        println!("no pointer acceleration, gain {:.2}", gain);
+    }
+}
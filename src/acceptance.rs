@@ -0,0 +1,326 @@
+use std::time::Duration;
+
+use crate::{
+    error::{self, Error},
+    i18n::{self, tr, Msg},
+    json::{self, Value},
+    session::Session,
+    stats,
+};
+
+/// limits a mouse has to meet to pass, declared in the config
+#[derive(Debug, Clone, Default)]
+pub struct Criteria {
+    pub max_chatter_per_1000: Option<f64>,
+    /// most clicks inside one second, the switch has to keep up with fast clicking
+    pub min_peak_cps: Option<f64>,
+    /// 99th percentile of the chatter intervals, how long the contact keeps bouncing
+    pub max_bounce_p99: Option<Duration>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Criterion {
+    ChatterPer1000,
+    PeakCps,
+    BounceP99,
+}
+
+impl Criterion {
+    pub fn key(self) -> &'static str {
+        match self {
+            Criterion::ChatterPer1000 => "max_chatter_per_1000",
+            Criterion::PeakCps => "min_peak_cps",
+            Criterion::BounceP99 => "max_bounce_p99_ms",
+        }
+    }
+
+    fn name(self) -> &'static str {
+        tr(match self {
+            Criterion::ChatterPer1000 => Msg::CriterionChatter,
+            Criterion::PeakCps => Msg::CriterionCps,
+            Criterion::BounceP99 => Msg::CriterionBounce,
+        })
+    }
+}
+
+/// the outcome of one criterion
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub criterion: Criterion,
+    pub limit: f64,
+    /// `None` when the session has nothing to measure, which passes
+    pub value: Option<f64>,
+    pub pass: bool,
+}
+
+impl Check {
+    pub fn to_json(&self) -> Value {
+        json::object([
+            ("criterion", Value::from(self.criterion.key())),
+            ("limit", Value::from(self.limit)),
+            ("value", Value::from(self.value)),
+            ("pass", Value::from(self.pass)),
+            ("explanation", Value::from(self.explain())),
+        ])
+    }
+
+    /// what the failed criterion measured and why it matters, none when it passed
+    pub fn explain(&self) -> Option<String> {
+        let value = self.value.filter(|_| !self.pass)?;
+        let limit = i18n::decimal(self.limit, 1);
+        Some(match self.criterion {
+            Criterion::ChatterPer1000 => i18n::fmt(
+                Msg::ExplainCheckChatter,
+                &[
+                    &i18n::decimal(value, 1),
+                    &limit,
+                    &i18n::number((1000.0 / value).round() as u64),
+                ],
+            ),
+            Criterion::PeakCps => {
+                i18n::fmt(Msg::ExplainCheckCps, &[&i18n::decimal(value, 0), &limit])
+            }
+            Criterion::BounceP99 => {
+                i18n::fmt(Msg::ExplainCheckBounce, &[&i18n::decimal(value, 1), &limit])
+            }
+        })
+    }
+
+    pub fn describe(&self) -> String {
+        let value = self
+            .value
+            .map(|v| i18n::decimal(v, 1))
+            .unwrap_or_else(|| "-".to_string());
+        i18n::fmt(
+            if self.pass {
+                Msg::CheckPass
+            } else {
+                Msg::CheckFail
+            },
+            &[
+                &self.criterion.name(),
+                &value,
+                &i18n::decimal(self.limit, 1),
+            ],
+        )
+    }
+}
+
+impl Criteria {
+    pub fn to_json(&self) -> Value {
+        json::object([
+            (
+                Criterion::ChatterPer1000.key(),
+                Value::from(self.max_chatter_per_1000),
+            ),
+            (Criterion::PeakCps.key(), Value::from(self.min_peak_cps)),
+            (
+                Criterion::BounceP99.key(),
+                Value::from(self.max_bounce_p99.map(|d| d.as_secs_f64() * 1000.0)),
+            ),
+        ])
+    }
+
+    /// a bounce limit that is no duration fails, negative ones are left out
+    pub fn from_json(value: &Value) -> Result<Self, String> {
+        let number = |criterion: Criterion| value.get(criterion.key()).and_then(Value::as_f64);
+        let max_bounce_p99 = number(Criterion::BounceP99)
+            .filter(|ms| *ms >= 0.0)
+            .map(|ms| {
+                Duration::try_from_secs_f64(ms / 1000.0).map_err(|_| {
+                    format!(
+                        "acceptance `{}` of {} ms is no duration",
+                        Criterion::BounceP99.key(),
+                        ms
+                    )
+                })
+            })
+            .transpose()?;
+        Ok(Self {
+            max_chatter_per_1000: number(Criterion::ChatterPer1000),
+            min_peak_cps: number(Criterion::PeakCps),
+            max_bounce_p99,
+        })
+    }
+
+    /// every declared criterion against the session, in declaration order
+    pub fn check(&self, session: &Session) -> Vec<Check> {
+        let mut checks = Vec::new();
+        if let Some(limit) = self.max_chatter_per_1000 {
+            let clicks = session.click_count();
+            let value =
+                (clicks > 0).then(|| session.chatter_count() as f64 * 1000.0 / clicks as f64);
+            checks.push(Check {
+                criterion: Criterion::ChatterPer1000,
+                limit,
+                value,
+                pass: value.is_none_or(|v| v <= limit),
+            });
+        }
+        if let Some(limit) = self.min_peak_cps {
+            let value = peak_cps(session) as f64;
+            checks.push(Check {
+                criterion: Criterion::PeakCps,
+                limit,
+                value: Some(value),
+                pass: value >= limit,
+            });
+        }
+        if let Some(limit) = self.max_bounce_p99 {
+            let intervals = session
+                .clicks
+                .iter()
+                .filter_map(|c| c.interval)
+                .map(|i| i.as_secs_f64() * 1000.0)
+                .collect::<Vec<_>>();
+            let limit = limit.as_secs_f64() * 1000.0;
+            let value = stats::quantile(&intervals, 0.99);
+            checks.push(Check {
+                criterion: Criterion::BounceP99,
+                limit,
+                value,
+                pass: value.is_none_or(|v| v <= limit),
+            });
+        }
+        checks
+    }
+}
+
+/// the mouse is rejected, exit code 2, once any check failed
+pub fn verdict(checks: &[Check]) -> error::Result<()> {
+    let failed = checks.iter().filter(|c| !c.pass).count();
+    if failed == 0 {
+        return Ok(());
+    }
+    Err(Error::Rejected {
+        failed,
+        total: checks.len(),
+    })
+}
+
+/// most clicks in memory inside one second
+fn peak_cps(session: &Session) -> usize {
+    let mut start = 0;
+    let mut peak = 0;
+    for (end, click) in session.clicks.iter().enumerate() {
+        while click.at.saturating_sub(session.clicks[start].at) > Duration::from_secs(1) {
+            start += 1;
+        }
+        peak = peak.max(end - start + 1);
+    }
+    peak
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::{KeyModifiers, MouseButton};
+
+    use super::*;
+    use crate::session::Click;
+
+    const WINDOW: Duration = Duration::from_millis(100);
+
+    /// left clicks at the milliseconds, classified with a 100 ms window
+    fn session(at: &[u64]) -> Session {
+        let mut session = Session::new(None, WINDOW);
+        session.clicks = at
+            .iter()
+            .map(|ms| Click {
+                at: Duration::from_millis(*ms),
+                interval: None,
+                button: MouseButton::Left,
+                hold: None,
+                bookmarked: false,
+                keyboard: false,
+                position: None,
+                modifiers: KeyModifiers::NONE,
+            })
+            .collect();
+        session.reclassify(WINDOW);
+        session
+    }
+
+    /// one chatter 20 ms after the fourth of four clicks inside a second
+    fn chattering() -> Session {
+        session(&[0, 300, 600, 900, 920])
+    }
+
+    fn pass(criteria: Criteria) -> Vec<bool> {
+        criteria
+            .check(&chattering())
+            .iter()
+            .map(|c| c.pass)
+            .collect()
+    }
+
+    #[test]
+    fn chatter_per_1000() {
+        let limit = |max| Criteria {
+            max_chatter_per_1000: Some(max),
+            ..Criteria::default()
+        };
+        // 1 of 5 clicks is 200 per 1000
+        assert_eq!(pass(limit(200.0)), [true]);
+        assert_eq!(pass(limit(199.0)), [false]);
+    }
+
+    #[test]
+    fn peak_cps() {
+        let limit = |min| Criteria {
+            min_peak_cps: Some(min),
+            ..Criteria::default()
+        };
+        assert_eq!(pass(limit(5.0)), [true]);
+        assert_eq!(pass(limit(6.0)), [false]);
+    }
+
+    #[test]
+    fn bounce_p99() {
+        let limit = |ms| Criteria {
+            max_bounce_p99: Some(Duration::from_millis(ms)),
+            ..Criteria::default()
+        };
+        assert_eq!(pass(limit(20)), [true]);
+        assert_eq!(pass(limit(19)), [false]);
+    }
+
+    #[test]
+    fn nothing_measured_passes() {
+        let criteria = Criteria {
+            max_chatter_per_1000: Some(0.0),
+            max_bounce_p99: Some(Duration::ZERO),
+            ..Criteria::default()
+        };
+        assert!(criteria.check(&session(&[])).iter().all(|c| c.pass));
+    }
+
+    #[test]
+    fn failed_check_exits_with_2() {
+        let criteria = Criteria {
+            max_chatter_per_1000: Some(0.0),
+            min_peak_cps: Some(1.0),
+            ..Criteria::default()
+        };
+        let checks = criteria.check(&chattering());
+        let error = verdict(&checks).unwrap_err();
+        assert_eq!(error.exit_code(), 2);
+        assert!(matches!(
+            error,
+            Error::Rejected {
+                failed: 1,
+                total: 2
+            }
+        ));
+        assert!(verdict(&checks[1..]).is_ok());
+    }
+
+    #[test]
+    fn bounce_limit_out_of_range_fails_to_load() {
+        let value = json::parse(r#"{"max_bounce_p99_ms": 1e999}"#).unwrap();
+        assert!(Criteria::from_json(&value).is_err());
+        let value = json::parse(r#"{"max_bounce_p99_ms": 2.5, "min_peak_cps": 8}"#).unwrap();
+        let criteria = Criteria::from_json(&value).unwrap();
+        assert_eq!(criteria.max_bounce_p99, Some(Duration::from_micros(2500)));
+        assert_eq!(criteria.min_peak_cps, Some(8.0));
+    }
+}
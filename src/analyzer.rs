@@ -0,0 +1,1232 @@
+use std::{collections::VecDeque, time::Duration};
+
+use crossterm::event::MouseButton;
+use ratatui::{
+    style::{Modifier, Style},
+    text::Line,
+};
+
+use crate::{
+    i18n::{self, tr, Msg},
+    session::{self, Actuation, Click, Session},
+    stats, theme,
+    timeline::{self, Verdict},
+};
+
+/// clicks closer than this belong to one burst
+const BURST_GAP: Duration = Duration::from_millis(150);
+/// clicks a burst needs to be counted
+const MIN_BURST: usize = 3;
+/// equal gaps in a row no hand keeps up, from here on a train looks like a macro
+const MIN_TRAIN: usize = 8;
+/// gaps longer than this are not rapid fire
+const MAX_TRAIN_GAP: Duration = Duration::from_millis(500);
+/// gaps of a train may differ from its mean by this share, or by `MIN_JITTER`
+const TRAIN_SPREAD: f64 = 0.03;
+const MIN_JITTER: Duration = Duration::from_millis(1);
+/// clicks of a clean run needed before a detect window is suggested
+const SUGGEST_CLICKS: usize = 20;
+/// the suggestion keeps this share of the fastest gap free, at least `SUGGEST_MIN_MARGIN`
+const SUGGEST_MARGIN: f64 = 0.2;
+const SUGGEST_MIN_MARGIN: Duration = Duration::from_millis(10);
+/// suggestions are rounded down to these steps
+const SUGGEST_STEP: u64 = 5;
+/// holds shorter than this are the contact opening again, no finger lifts that fast
+pub const SHORT_HOLD: Duration = Duration::from_millis(5);
+/// released clicks needed for the hold profile, and chatter among them for its reading
+const MIN_HOLDS: usize = 10;
+const MIN_CHATTER_HOLDS: usize = 3;
+/// chatter holding at least this share of the usual hold looks like a real press
+const PRESS_HOLD_SHARE: f64 = 0.5;
+/// gaps needed before the halves of a session are compared
+const CONSISTENCY_GAPS: usize = 40;
+/// chance of a false alarm when both halves come from the same conditions
+const CONSISTENCY_ALPHA: f64 = 0.01;
+/// clicks of a button needed before its first and last quarter are compared
+const FATIGUE_CLICKS: usize = 100;
+/// a quiet stretch this long is a rest break, as is a mark noted `rest` or `break`
+const REST_GAP: Duration = Duration::from_secs(60);
+/// clicks needed on both sides of a rest break to see what it changed
+const REST_CLICKS: usize = 10;
+/// share the median press gap may grow by before the button counts as slowing down
+const FATIGUE_SLOWDOWN: f64 = 0.1;
+/// rise of the chatter share that counts as the switch getting worse
+const FATIGUE_CHATTER_RISE: f64 = 0.02;
+/// a second button going down this soon after the first is faster than two fingers press,
+/// the board or the wiring fired it
+const CHORD_CROSSTALK: Duration = Duration::from_millis(5);
+
+/// what an analyzer gets to build its stats lines
+pub struct Context<'a> {
+    pub session: &'a Session,
+    /// gap outlier flag of every click in memory
+    pub outliers: &'a [bool],
+    /// time since the session started
+    pub now: Duration,
+}
+
+/// a detection strategy fed with every click, contributing lines to the stats pane
+pub trait Analyzer {
+    fn on_click(&mut self, _click: &Click) {}
+
+    fn on_release(&mut self, _button: MouseButton) {}
+
+    fn lines(&self, cx: &Context) -> Vec<Line<'static>>;
+
+    /// a finding important enough for the banner on top of the screen
+    fn verdict(&self, _cx: &Context) -> Option<Line<'static>> {
+        None
+    }
+}
+
+/// the clicks and releases of a session in order, a click with none for a release
+fn events(session: &Session) -> Vec<(Duration, Option<Click>, MouseButton)> {
+    let mut events = Vec::new();
+    for c in &session.clicks {
+        events.push((c.at, Some(*c), c.button));
+        if let Some(hold) = c.hold {
+            events.push((c.at + hold, None, c.button));
+        }
+    }
+    events.sort_by_key(|(at, ..)| *at);
+    events
+}
+
+/// the analyzers of a session in the order their lines are shown
+pub struct Registry {
+    analyzers: Vec<Box<dyn Analyzer>>,
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        let mut registry = Self {
+            analyzers: Vec::new(),
+        };
+        registry.register(Box::new(Chatter));
+        registry.register(Box::<Bounces>::default());
+        registry.register(Box::<Chords>::default());
+        registry.register(Box::new(Holds));
+        registry.register(Box::new(StuckButtons));
+        registry.register(Box::new(Trigger));
+        registry.register(Box::new(Outliers));
+        registry.register(Box::<Cps>::default());
+        registry.register(Box::<Bursts>::default());
+        registry.register(Box::<Macros>::default());
+        registry.register(Box::new(Quantization));
+        registry.register(Box::new(Coalescing));
+        registry.register(Box::new(Consistency));
+        registry
+    }
+}
+
+impl Registry {
+    /// fresh analyzers fed with the clicks and releases of a session in order
+    pub fn replay(session: &Session) -> Self {
+        let mut registry = Self::default();
+        for (_, click, button) in events(session) {
+            match click {
+                Some(click) => registry.on_click(&click),
+                None => registry.on_release(button),
+            }
+        }
+        registry
+    }
+
+    pub fn register(&mut self, analyzer: Box<dyn Analyzer>) {
+        self.analyzers.push(analyzer);
+    }
+
+    pub fn on_click(&mut self, click: &Click) {
+        self.analyzers.iter_mut().for_each(|a| a.on_click(click));
+    }
+
+    pub fn on_release(&mut self, button: MouseButton) {
+        self.analyzers.iter_mut().for_each(|a| a.on_release(button));
+    }
+
+    pub fn lines(&self, cx: &Context) -> Vec<Line<'static>> {
+        self.analyzers.iter().flat_map(|a| a.lines(cx)).collect()
+    }
+
+    /// the verdicts of all analyzers, an all clear when none has one
+    pub fn banner(&self, cx: &Context) -> Vec<Line<'static>> {
+        let verdicts = self
+            .analyzers
+            .iter()
+            .filter_map(|a| a.verdict(cx))
+            .collect::<Vec<_>>();
+        if !verdicts.is_empty() {
+            return verdicts;
+        }
+        let clicks = cx.session.click_count();
+        vec![Line::from(if clicks == 0 {
+            tr(Msg::VerdictWaiting).to_string()
+        } else {
+            i18n::fmt(Msg::VerdictClean, &[&i18n::number(clicks)])
+        })]
+    }
+}
+
+/// whether the chatter is faster than a hand, `None` without chatter
+pub fn human_verdict(session: &Session) -> Option<String> {
+    let chatter = session.chatter_count();
+    let fast = session.fast_chatter_count();
+    let floor = i18n::number(session.human_floor.as_millis());
+    match (chatter, fast) {
+        (0, _) => None,
+        (_, 0) => Some(i18n::fmt(
+            Msg::VerdictHuman,
+            &[&i18n::number(chatter), &floor],
+        )),
+        _ => Some(i18n::fmt(
+            Msg::VerdictHardware,
+            &[&i18n::number(fast), &floor, &i18n::number(chatter - fast)],
+        )),
+    }
+}
+
+/// the longest detect window that would have left the clicks of a clean run alone
+pub fn suggested_window(session: &Session) -> Option<Duration> {
+    if session.chatter_count() > 0 || session.click_count() < SUGGEST_CLICKS {
+        return None;
+    }
+    let fastest = session.shortest_gap()?;
+    let margin = fastest.mul_f64(SUGGEST_MARGIN).max(SUGGEST_MIN_MARGIN);
+    let ms = fastest.saturating_sub(margin).as_millis() as u64 / SUGGEST_STEP * SUGGEST_STEP;
+    (ms >= SUGGEST_MIN_MARGIN.as_millis() as u64).then(|| Duration::from_millis(ms))
+}
+
+/// how long the buttons were held, a proxy for how the switch actuates
+pub struct HoldProfile {
+    pub holds: usize,
+    pub median: Duration,
+    /// a tenth of the holds are shorter
+    pub p10: Duration,
+    /// holds shorter than `SHORT_HOLD`
+    pub short: usize,
+    /// median hold of the chatter clicks
+    pub chatter_median: Option<Duration>,
+}
+
+impl HoldProfile {
+    pub fn new(session: &Session) -> Option<Self> {
+        let held = |only_chatter: bool| {
+            session
+                .clicks
+                .iter()
+                .filter(|c| !only_chatter || c.interval.is_some())
+                .filter_map(|c| c.hold)
+                .map(|h| h.as_secs_f64())
+                .collect::<Vec<_>>()
+        };
+        let holds = held(false);
+        if holds.len() < MIN_HOLDS {
+            return None;
+        }
+        let chatter = held(true);
+        let secs = Duration::from_secs_f64;
+        Some(Self {
+            holds: holds.len(),
+            median: secs(stats::median(&holds)?),
+            p10: secs(stats::quantile(&holds, 0.1)?),
+            short: holds
+                .iter()
+                .filter(|h| **h < SHORT_HOLD.as_secs_f64())
+                .count(),
+            chatter_median: (chatter.len() >= MIN_CHATTER_HOLDS)
+                .then(|| stats::median(&chatter))
+                .flatten()
+                .map(secs),
+        })
+    }
+
+    /// from the running estimates of the tally instead of sorting every hold, for the stats
+    /// pane; the medians are estimates once there are more than five holds
+    pub fn live(session: &Session) -> Option<Self> {
+        let tally = session.tally();
+        if tally.holds < MIN_HOLDS {
+            return None;
+        }
+        let secs = Duration::from_secs_f64;
+        Some(Self {
+            holds: tally.holds,
+            median: secs(tally.hold_median.value()?),
+            p10: secs(tally.hold_p10.value()?),
+            short: tally.short_holds,
+            chatter_median: (tally.chatter_hold_median.count() >= MIN_CHATTER_HOLDS)
+                .then(|| tally.chatter_hold_median.value())
+                .flatten()
+                .map(secs),
+        })
+    }
+
+    pub fn describe(&self) -> String {
+        i18n::fmt(
+            Msg::Holds,
+            &[
+                &ms(self.median),
+                &ms(self.p10),
+                &i18n::number(self.short),
+                &i18n::number(SHORT_HOLD.as_millis()),
+            ],
+        )
+    }
+
+    /// whether the holds of the chatter look like bounce or like real presses
+    pub fn reading(&self) -> Option<String> {
+        let chatter = self.chatter_median?;
+        if chatter < SHORT_HOLD {
+            Some(i18n::fmt(Msg::HoldsBounce, &[&ms(chatter)]))
+        } else if chatter.as_secs_f64() >= self.median.as_secs_f64() * PRESS_HOLD_SHARE {
+            Some(i18n::fmt(
+                Msg::HoldsTechnique,
+                &[&ms(chatter), &ms(self.median)],
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+fn ms(d: Duration) -> String {
+    i18n::decimal(d.as_secs_f64() * 1000.0, 1)
+}
+
+/// the shortest firmware debounce that would have swallowed every bounce, chatter slower
+/// than the human floor is left to the hand
+pub fn debounce_recommendation(session: &Session) -> Option<Duration> {
+    session
+        .clicks
+        .windows(2)
+        .filter(|w| w[1].interval.is_some_and(|i| i < session.human_floor))
+        .map(|w| {
+            // debounce restarts with the release when the button went up in between
+            let since = w[0].hold.map_or(w[0].at, |hold| w[0].at + hold);
+            w[1].at.saturating_sub(since)
+        })
+        .max()
+        .map(|gap| {
+            Duration::from_millis(gap.as_micros().div_ceil(1000) as u64)
+                .max(Duration::from_millis(1))
+        })
+}
+
+pub fn debounce_advice(debounce: Duration) -> String {
+    i18n::fmt(Msg::Debounce, &[&i18n::number(debounce.as_millis())])
+}
+
+/// the detector's calls on the windows the operator marked as pressed exactly once, every
+/// click after the first of such a window is a bounce
+pub struct Agreement {
+    pub marked: usize,
+    /// marked windows with more than one click
+    pub doubled: usize,
+    /// doubled windows the detector called chatter rather than a double click
+    pub agreed: usize,
+}
+
+impl Agreement {
+    /// none before the first mark
+    pub fn new(session: &Session) -> Option<Self> {
+        let verdicts = timeline::groups(session)
+            .into_iter()
+            .filter(|g| session.one_press.contains(&session.clicks[g.first].at))
+            .map(|g| g.verdict(session.human_floor))
+            .collect::<Vec<_>>();
+        if verdicts.is_empty() {
+            return None;
+        }
+        let count = |wanted: &[Verdict]| verdicts.iter().filter(|v| wanted.contains(v)).count();
+        Some(Self {
+            marked: verdicts.len(),
+            doubled: count(&[Verdict::DoubleClick, Verdict::Chatter]),
+            agreed: count(&[Verdict::Chatter]),
+        })
+    }
+
+    pub fn describe(&self) -> String {
+        i18n::fmt(
+            Msg::Agreement,
+            &[
+                &i18n::number(self.marked),
+                &i18n::number(self.doubled),
+                &i18n::number(self.agreed),
+            ],
+        )
+    }
+
+    /// the bounces the human floor let pass as double clicks
+    pub fn missed(&self, human_floor: Duration) -> Option<String> {
+        (self.agreed < self.doubled).then(|| {
+            i18n::fmt(
+                Msg::AgreementMissed,
+                &[
+                    &i18n::number(self.doubled - self.agreed),
+                    &i18n::number(human_floor.as_millis()),
+                ],
+            )
+        })
+    }
+}
+
+/// how a stretch of one button's clicks went
+#[derive(Debug, Clone, Copy)]
+pub struct Stretch {
+    /// median time between presses of the button, rest breaks left out
+    pub gap: Duration,
+    pub hold: Option<Duration>,
+    /// share of the clicks that were chatter
+    pub chatter: f64,
+}
+
+impl Stretch {
+    fn new(clicks: &[&Click]) -> Option<Self> {
+        let secs = |values: Vec<f64>| stats::median(&values).map(Duration::from_secs_f64);
+        let gaps = clicks
+            .windows(2)
+            .map(|w| w[1].at.saturating_sub(w[0].at))
+            .filter(|g| *g < REST_GAP)
+            .map(|g| g.as_secs_f64())
+            .collect();
+        let holds = clicks
+            .iter()
+            .filter_map(|c| c.hold)
+            .map(|h| h.as_secs_f64())
+            .collect();
+        let chatter = clicks.iter().filter(|c| c.interval.is_some()).count();
+        Some(Self {
+            gap: secs(gaps)?,
+            hold: secs(holds),
+            chatter: chatter as f64 / clicks.len() as f64,
+        })
+    }
+
+    fn slower(&self, than: &Stretch) -> bool {
+        self.gap.as_secs_f64() > than.gap.as_secs_f64() * (1.0 + FATIGUE_SLOWDOWN)
+    }
+
+    fn chattier(&self, than: &Stretch) -> bool {
+        self.chatter > than.chatter + FATIGUE_CHATTER_RISE
+    }
+}
+
+/// what wore out over a long session
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Wear {
+    /// the button drifted and went back to how it started after a rest
+    Finger,
+    /// the chatter kept up after a rest
+    Switch,
+    /// it drifted with no rest break to tell the two apart
+    Unclear,
+}
+
+impl Wear {
+    pub fn name(self) -> &'static str {
+        match self {
+            Wear::Finger => "finger",
+            Wear::Switch => "switch",
+            Wear::Unclear => "unclear",
+        }
+    }
+}
+
+/// the first quarter of a button's clicks against the last, to see it tire or wear
+pub struct Fatigue {
+    pub button: MouseButton,
+    pub clicks: usize,
+    pub first: Stretch,
+    pub last: Stretch,
+    /// the clicks right before and after the last rest break
+    pub around_break: Option<(Stretch, Stretch)>,
+}
+
+impl Fatigue {
+    /// one for each button clicked often enough, clicks in memory only
+    pub fn new(session: &Session) -> Vec<Self> {
+        let breaks = rest_breaks(session);
+        [MouseButton::Left, MouseButton::Right, MouseButton::Middle]
+            .into_iter()
+            .filter_map(|button| {
+                let clicks = session
+                    .clicks
+                    .iter()
+                    .filter(|c| c.button == button)
+                    .collect::<Vec<_>>();
+                if clicks.len() < FATIGUE_CLICKS {
+                    return None;
+                }
+                let quarter = clicks.len() / 4;
+                let around_break = breaks.iter().rev().find_map(|at| {
+                    let split = clicks.partition_point(|c| c.at < *at);
+                    let (before, after) = clicks.split_at(split);
+                    let take = quarter.min(before.len()).min(after.len());
+                    (take >= REST_CLICKS)
+                        .then(|| {
+                            Some((
+                                Stretch::new(&before[before.len() - take..])?,
+                                Stretch::new(&after[..take])?,
+                            ))
+                        })
+                        .flatten()
+                });
+                Some(Self {
+                    button,
+                    clicks: clicks.len(),
+                    first: Stretch::new(&clicks[..quarter])?,
+                    last: Stretch::new(&clicks[clicks.len() - quarter..])?,
+                    around_break,
+                })
+            })
+            .collect()
+    }
+
+    /// none while the button held steady
+    pub fn wear(&self) -> Option<Wear> {
+        let drifted = |s: &Stretch| s.slower(&self.first) || s.chattier(&self.first);
+        let Some((before, after)) = &self.around_break else {
+            return drifted(&self.last).then_some(Wear::Unclear);
+        };
+        if !drifted(before) && !drifted(&self.last) {
+            return None;
+        }
+        Some(if !drifted(after) {
+            Wear::Finger
+        } else if after.chattier(&self.first) {
+            Wear::Switch
+        } else {
+            Wear::Unclear
+        })
+    }
+
+    pub fn describe(&self) -> String {
+        let hold = |s: &Stretch| s.hold.map_or_else(|| "-".to_string(), i18n::duration);
+        let percent = |share: f64| i18n::decimal(share * 100.0, 1);
+        i18n::fmt(
+            Msg::Fatigue,
+            &[
+                &session::button_name(self.button),
+                &i18n::duration(self.first.gap),
+                &i18n::duration(self.last.gap),
+                &hold(&self.first),
+                &hold(&self.last),
+                &percent(self.first.chatter),
+                &percent(self.last.chatter),
+            ],
+        )
+    }
+
+    pub fn reading(&self) -> Option<String> {
+        let button = session::button_name(self.button);
+        Some(match (self.wear()?, &self.around_break) {
+            (Wear::Finger, Some((before, after))) => i18n::fmt(
+                Msg::FatigueFinger,
+                &[
+                    &button,
+                    &i18n::duration(before.gap),
+                    &i18n::duration(after.gap),
+                ],
+            ),
+            (Wear::Switch, _) => i18n::fmt(Msg::FatigueSwitch, &[&button]),
+            _ => i18n::fmt(Msg::FatigueUnclear, &[&button]),
+        })
+    }
+}
+
+/// start of every rest break, oldest first
+fn rest_breaks(session: &Session) -> Vec<Duration> {
+    let mut breaks = session
+        .clicks
+        .windows(2)
+        .filter(|w| w[1].at.saturating_sub(w[0].at) >= REST_GAP)
+        .map(|w| w[0].at)
+        .chain(
+            session
+                .marks
+                .iter()
+                .filter(|m| {
+                    let note = m.note.to_lowercase();
+                    note.starts_with("rest") || note.starts_with(session::BREAK_NOTE)
+                })
+                .map(|m| m.at),
+        )
+        .collect::<Vec<_>>();
+    breaks.sort();
+    breaks
+}
+
+/// repeated clicks inside the detect window
+pub struct Chatter;
+
+impl Analyzer for Chatter {
+    fn lines(&self, cx: &Context) -> Vec<Line<'static>> {
+        let (current, longest) = cx.session.clean_streaks();
+        let mut lines = vec![
+            Line::styled(
+                i18n::fmt(Msg::Chatter, &[&i18n::number(cx.session.chatter_count())]),
+                theme::palette().chatter,
+            ),
+            Line::from(i18n::fmt(
+                Msg::CleanStreak,
+                &[&i18n::number(current), &i18n::number(longest)],
+            )),
+        ];
+        if let Some(at) = cx.session.last_chatter() {
+            let since = cx.now.saturating_sub(at);
+            lines.push(Line::from(i18n::fmt(
+                Msg::LastChatter,
+                &[&i18n::number(since.as_secs())],
+            )));
+        }
+        lines.extend(human_verdict(cx.session).map(Line::from));
+        lines.extend(debounce_recommendation(cx.session).map(|d| Line::from(debounce_advice(d))));
+        lines
+    }
+
+    fn verdict(&self, cx: &Context) -> Option<Line<'static>> {
+        let worst = cx.session.shortest_chatter()?;
+        Some(Line::styled(
+            i18n::fmt(
+                Msg::VerdictChatter,
+                &[
+                    &i18n::number(cx.session.chatter_count()),
+                    &i18n::number(worst.as_millis()),
+                ],
+            ),
+            theme::palette().chatter.add_modifier(Modifier::BOLD),
+        ))
+    }
+}
+
+/// chatter split by whether the button was released before it
+#[derive(Default)]
+pub struct Bounces {
+    /// buttons down without an up event yet
+    held: Vec<MouseButton>,
+    /// no breakdown before the terminal reported any release
+    tracked: bool,
+    /// down, down
+    press: usize,
+    /// up, down
+    release: usize,
+}
+
+impl Analyzer for Bounces {
+    fn on_click(&mut self, click: &Click) {
+        let held = self.held.contains(&click.button);
+        if click.interval.is_some() {
+            if held {
+                self.press += 1;
+            } else {
+                self.release += 1;
+            }
+        }
+        if !held {
+            self.held.push(click.button);
+        }
+    }
+
+    fn on_release(&mut self, button: MouseButton) {
+        self.tracked = true;
+        self.held.retain(|b| *b != button);
+    }
+
+    fn lines(&self, _cx: &Context) -> Vec<Line<'static>> {
+        if !self.tracked {
+            return Vec::new();
+        }
+        vec![Line::from(i18n::fmt(
+            Msg::Bounces,
+            &[&i18n::number(self.press), &i18n::number(self.release)],
+        ))]
+    }
+}
+
+/// a press of one button while another is held down
+#[derive(Debug, Clone, Copy)]
+pub struct Chord {
+    pub held: MouseButton,
+    pub pressed: MouseButton,
+    /// time from the press of the held button to this one
+    pub lag: Duration,
+}
+
+impl Chord {
+    /// both went down together, crosstalk or a flexing board rather than two fingers
+    pub fn crosstalk(&self) -> bool {
+        self.lag < CHORD_CROSSTALK
+    }
+}
+
+/// buttons pressed while another is held, which a flexing board or crosstalk between the
+/// switches fires without the user asking
+#[derive(Default)]
+pub struct Chords {
+    /// buttons down without an up event yet, with the time of the press
+    held: Vec<(MouseButton, Duration)>,
+    /// no chords before the terminal reported any release, every button looks held then
+    tracked: bool,
+    pub chords: Vec<Chord>,
+}
+
+impl Chords {
+    /// the chords of a session, replayed from its clicks and holds
+    pub fn new(session: &Session) -> Self {
+        let mut chords = Self::default();
+        for (_, click, button) in events(session) {
+            match click {
+                Some(click) => chords.on_click(&click),
+                None => chords.on_release(button),
+            }
+        }
+        chords
+    }
+
+    pub fn crosstalk(&self) -> usize {
+        self.chords.iter().filter(|c| c.crosstalk()).count()
+    }
+
+    /// how many chords and how many of them crosstalk, none without any
+    pub fn describe(&self) -> Option<String> {
+        let fastest = self.chords.iter().map(|c| c.lag).min()?;
+        Some(i18n::fmt(
+            Msg::Chords,
+            &[
+                &i18n::number(self.chords.len()),
+                &i18n::number(self.crosstalk()),
+                &i18n::duration(fastest),
+            ],
+        ))
+    }
+}
+
+impl Analyzer for Chords {
+    fn on_click(&mut self, click: &Click) {
+        // a key press has no release to end it
+        if click.keyboard {
+            return;
+        }
+        if self.tracked {
+            let chords = self
+                .held
+                .iter()
+                .filter(|(b, _)| *b != click.button)
+                .map(|(held, at)| Chord {
+                    held: *held,
+                    pressed: click.button,
+                    lag: click.at.saturating_sub(*at),
+                });
+            self.chords.extend(chords);
+        }
+        if !self.held.iter().any(|(b, _)| *b == click.button) {
+            self.held.push((click.button, click.at));
+        }
+    }
+
+    fn on_release(&mut self, button: MouseButton) {
+        self.tracked = true;
+        self.held.retain(|(b, _)| *b != button);
+    }
+
+    fn lines(&self, _cx: &Context) -> Vec<Line<'static>> {
+        let style = if self.crosstalk() > 0 {
+            theme::palette().outlier
+        } else {
+            Style::default()
+        };
+        self.describe()
+            .map(|text| Line::styled(text, style))
+            .into_iter()
+            .collect()
+    }
+
+    fn verdict(&self, _cx: &Context) -> Option<Line<'static>> {
+        let chord = self
+            .chords
+            .iter()
+            .filter(|c| c.crosstalk())
+            .min_by_key(|c| c.lag)?;
+        Some(Line::styled(
+            i18n::fmt(
+                Msg::VerdictChord,
+                &[
+                    &session::button_name(chord.pressed),
+                    &i18n::duration(chord.lag),
+                    &session::button_name(chord.held),
+                ],
+            ),
+            theme::palette().outlier.add_modifier(Modifier::BOLD),
+        ))
+    }
+}
+
+/// the hold profile, telling switch bounce from the way the user clicks
+pub struct Holds;
+
+impl Analyzer for Holds {
+    fn lines(&self, cx: &Context) -> Vec<Line<'static>> {
+        let Some(profile) = HoldProfile::live(cx.session) else {
+            return Vec::new();
+        };
+        let mut lines = vec![Line::from(profile.describe())];
+        lines.extend(profile.reading().map(Line::from));
+        lines
+    }
+}
+
+/// presses the watchdog found held down with no release
+pub struct StuckButtons;
+
+/// how many presses got stuck and the longest one, none without any
+pub fn describe_stuck(session: &Session) -> Option<String> {
+    let longest = session.stuck.iter().map(|s| s.held).max()?;
+    Some(i18n::fmt(
+        Msg::StuckButtons,
+        &[&i18n::number(session.stuck.len()), &i18n::duration(longest)],
+    ))
+}
+
+impl Analyzer for StuckButtons {
+    fn lines(&self, cx: &Context) -> Vec<Line<'static>> {
+        describe_stuck(cx.session)
+            .map(|text| Line::styled(text, theme::palette().outlier))
+            .into_iter()
+            .collect()
+    }
+
+    fn verdict(&self, cx: &Context) -> Option<Line<'static>> {
+        let stuck = cx.session.stuck.iter().max_by_key(|s| s.held)?;
+        Some(Line::styled(
+            i18n::fmt(
+                Msg::VerdictStuck,
+                &[
+                    &session::button_name(stuck.button),
+                    &i18n::duration(stuck.held),
+                ],
+            ),
+            theme::palette().outlier.add_modifier(Modifier::BOLD),
+        ))
+    }
+}
+
+/// what came of a press an actuator rig made
+#[derive(Debug, Clone, Copy)]
+pub struct Outcome {
+    pub actuation: Actuation,
+    /// clicks of its button inside its window, more than one is chatter
+    pub clicks: usize,
+    /// time from the actuation to the first of them, less the loopback baseline
+    pub latency: Option<Duration>,
+}
+
+/// the presses an actuator rig reported matched with the clicks the terminal did
+pub struct Actuations {
+    pub outcomes: Vec<Outcome>,
+    /// clicks after the first actuation that belong to none
+    pub stray: usize,
+}
+
+impl Actuations {
+    /// none before the rig reported a press; the presses before the clicks in memory are
+    /// left out, their clicks were spilled
+    pub fn new(session: &Session) -> Option<Self> {
+        let since = session
+            .clicks
+            .first()
+            .filter(|_| session.spilled.clicks > 0)
+            .map_or(Duration::ZERO, |c| c.at);
+        let actuations = session
+            .actuations
+            .iter()
+            .filter(|a| a.at >= since)
+            .collect::<Vec<_>>();
+        let first = actuations.first()?.at;
+        let clicks = &session.clicks;
+        let mut claimed = vec![false; clicks.len()];
+        let mut outcomes = Vec::with_capacity(actuations.len());
+        for (i, actuation) in actuations.iter().enumerate() {
+            // the next press of the same button closes the window early
+            let end = actuations[i + 1..]
+                .iter()
+                .find(|a| a.button == actuation.button)
+                .map_or(actuation.at + session.actuation_window, |a| {
+                    a.at.min(actuation.at + session.actuation_window)
+                });
+            let start = clicks.partition_point(|c| c.at < actuation.at);
+            let mut outcome = Outcome {
+                actuation: **actuation,
+                clicks: 0,
+                latency: None,
+            };
+            for (c, claim) in clicks[start..].iter().zip(&mut claimed[start..]) {
+                if c.at >= end {
+                    break;
+                }
+                if c.button == actuation.button && !c.keyboard && !*claim {
+                    *claim = true;
+                    outcome.clicks += 1;
+                    let latency = c.at - actuation.at;
+                    let baseline = session.loopback.unwrap_or_default();
+                    outcome
+                        .latency
+                        .get_or_insert(latency.saturating_sub(baseline));
+                }
+            }
+            outcomes.push(outcome);
+        }
+        let stray = clicks
+            .iter()
+            .zip(&claimed)
+            .filter(|(c, claim)| c.at >= first && !c.keyboard && !**claim)
+            .count();
+        Some(Self { outcomes, stray })
+    }
+
+    pub fn missed(&self) -> usize {
+        self.outcomes.iter().filter(|o| o.clicks == 0).count()
+    }
+
+    /// clicks past the first of an actuation and the stray ones
+    pub fn extra(&self) -> usize {
+        self.outcomes
+            .iter()
+            .map(|o| o.clicks.saturating_sub(1))
+            .sum::<usize>()
+            + self.stray
+    }
+
+    /// median and worst time from an actuation to its click, none when every one was missed
+    pub fn latency(&self) -> Option<(Duration, Duration)> {
+        let latencies = self
+            .outcomes
+            .iter()
+            .filter_map(|o| o.latency)
+            .collect::<Vec<_>>();
+        let worst = latencies.iter().max().copied()?;
+        let seconds = latencies
+            .iter()
+            .map(Duration::as_secs_f64)
+            .collect::<Vec<_>>();
+        let median = stats::median(&seconds).map(Duration::from_secs_f64)?;
+        Some((median, worst))
+    }
+
+    pub fn describe(&self) -> Vec<String> {
+        let mut lines = vec![i18n::fmt(
+            Msg::Actuations,
+            &[
+                &i18n::number(self.outcomes.len()),
+                &i18n::number(self.missed()),
+                &i18n::number(self.extra()),
+            ],
+        )];
+        lines.extend(self.latency().map(|(median, worst)| {
+            i18n::fmt(
+                Msg::ActuationLatency,
+                &[&i18n::duration(median), &i18n::duration(worst)],
+            )
+        }));
+        lines
+    }
+}
+
+/// the presses of an actuator rig against the clicks they gave
+pub struct Trigger;
+
+impl Analyzer for Trigger {
+    fn lines(&self, cx: &Context) -> Vec<Line<'static>> {
+        let Some(actuations) = Actuations::new(cx.session) else {
+            return Vec::new();
+        };
+        let style = if actuations.missed() + actuations.extra() > 0 {
+            theme::palette().outlier
+        } else {
+            Style::default()
+        };
+        actuations
+            .describe()
+            .into_iter()
+            .map(|text| Line::styled(text, style))
+            .collect()
+    }
+
+    fn verdict(&self, cx: &Context) -> Option<Line<'static>> {
+        let actuations = Actuations::new(cx.session)?;
+        let missed = actuations.missed();
+        (missed > 0).then(|| {
+            Line::styled(
+                i18n::fmt(
+                    Msg::VerdictMissed,
+                    &[
+                        &i18n::number(missed),
+                        &i18n::number(actuations.outcomes.len()),
+                    ],
+                ),
+                theme::palette().outlier.add_modifier(Modifier::BOLD),
+            )
+        })
+    }
+}
+
+/// gaps far off the usual spacing of the clicks
+pub struct Outliers;
+
+impl Analyzer for Outliers {
+    fn lines(&self, cx: &Context) -> Vec<Line<'static>> {
+        vec![Line::styled(
+            i18n::fmt(
+                Msg::Outliers,
+                &[&i18n::number(cx.outliers.iter().filter(|o| **o).count())],
+            ),
+            theme::palette().outlier,
+        )]
+    }
+}
+
+/// clicks per second over the last second
+#[derive(Default)]
+pub struct Cps {
+    recent: VecDeque<Duration>,
+    peak: usize,
+}
+
+impl Analyzer for Cps {
+    fn on_click(&mut self, click: &Click) {
+        self.recent.push_back(click.at);
+        while self
+            .recent
+            .front()
+            .is_some_and(|t| click.at.saturating_sub(*t) > Duration::from_secs(1))
+        {
+            self.recent.pop_front();
+        }
+        self.peak = self.peak.max(self.recent.len());
+    }
+
+    fn lines(&self, cx: &Context) -> Vec<Line<'static>> {
+        let current = self
+            .recent
+            .iter()
+            .filter(|t| cx.now.saturating_sub(**t) <= Duration::from_secs(1))
+            .count();
+        vec![Line::from(i18n::fmt(
+            Msg::Cps,
+            &[&i18n::number(current), &i18n::number(self.peak)],
+        ))]
+    }
+}
+
+/// runs of quick clicks like drag clicking
+#[derive(Default)]
+pub struct Bursts {
+    last: Option<Duration>,
+    current: usize,
+    count: usize,
+    longest: usize,
+}
+
+impl Analyzer for Bursts {
+    fn on_click(&mut self, click: &Click) {
+        if self
+            .last
+            .is_some_and(|last| click.at.saturating_sub(last) <= BURST_GAP)
+        {
+            self.current += 1;
+        } else {
+            self.current = 1;
+        }
+        if self.current == MIN_BURST {
+            self.count += 1;
+        }
+        self.longest = self.longest.max(self.current);
+        self.last = Some(click.at);
+    }
+
+    fn lines(&self, _cx: &Context) -> Vec<Line<'static>> {
+        let longest = if self.longest >= MIN_BURST {
+            self.longest
+        } else {
+            0
+        };
+        vec![Line::from(i18n::fmt(
+            Msg::Bursts,
+            &[&i18n::number(self.count), &i18n::number(longest)],
+        ))]
+    }
+}
+
+/// click trains too evenly spaced for a hand, like macros or rapid fire firmware
+#[derive(Default)]
+pub struct Macros {
+    last: Option<Duration>,
+    /// gaps of the running train
+    gaps: Vec<Duration>,
+    /// chatter clicks of the running train
+    chatter: usize,
+    trains: usize,
+    longest: usize,
+    /// chatter inside counted trains, not the switch bouncing
+    train_chatter: usize,
+    /// session time the last counted train started
+    last_train: Option<Duration>,
+}
+
+impl Macros {
+    fn fits(&self, gap: Duration) -> bool {
+        if self.gaps.is_empty() {
+            return true;
+        }
+        let mean = self.gaps.iter().sum::<Duration>() / self.gaps.len() as u32;
+        let spread = mean.mul_f64(TRAIN_SPREAD).max(MIN_JITTER);
+        gap.abs_diff(mean) <= spread
+    }
+}
+
+impl Analyzer for Macros {
+    fn on_click(&mut self, click: &Click) {
+        let Some(last) = self.last.replace(click.at) else {
+            return;
+        };
+        let gap = click.at.saturating_sub(last);
+        if gap > MAX_TRAIN_GAP || !self.fits(gap) {
+            self.gaps.clear();
+            self.chatter = 0;
+        }
+        if gap > MAX_TRAIN_GAP {
+            return;
+        }
+        self.gaps.push(gap);
+        let chatter = usize::from(click.interval.is_some());
+        self.chatter += chatter;
+
+        match self.gaps.len() {
+            n if n == MIN_TRAIN => {
+                self.trains += 1;
+                self.train_chatter += self.chatter;
+                let span = self.gaps.iter().sum::<Duration>();
+                self.last_train = Some(click.at.saturating_sub(span));
+            }
+            n if n > MIN_TRAIN => self.train_chatter += chatter,
+            _ => {}
+        }
+        if self.gaps.len() >= MIN_TRAIN {
+            // a train of n gaps has n + 1 clicks
+            self.longest = self.longest.max(self.gaps.len() + 1);
+        }
+    }
+
+    fn lines(&self, _cx: &Context) -> Vec<Line<'static>> {
+        let Some(at) = self.last_train else {
+            return Vec::new();
+        };
+        vec![Line::styled(
+            i18n::fmt(
+                Msg::Macros,
+                &[
+                    &i18n::number(self.trains),
+                    &i18n::number(self.longest),
+                    &i18n::decimal(at.as_secs_f64(), 1),
+                    &i18n::number(self.train_chatter),
+                ],
+            ),
+            theme::palette().outlier,
+        )]
+    }
+
+    fn verdict(&self, _cx: &Context) -> Option<Line<'static>> {
+        (self.trains > 0).then(|| {
+            Line::styled(
+                i18n::fmt(Msg::VerdictMacro, &[&i18n::number(self.trains)]),
+                theme::palette().outlier.add_modifier(Modifier::BOLD),
+            )
+        })
+    }
+}
+
+/// timestamps that only move in coarse steps of the clock
+pub struct Quantization;
+
+impl Analyzer for Quantization {
+    fn lines(&self, cx: &Context) -> Vec<Line<'static>> {
+        cx.session
+            .quantum()
+            .map(|step| {
+                Line::styled(
+                    i18n::fmt(
+                        Msg::Quantized,
+                        &[&i18n::number(step.as_micros()), &cx.session.clock.name()],
+                    ),
+                    theme::palette().threshold,
+                )
+            })
+            .into_iter()
+            .collect()
+    }
+}
+
+/// gaps snapping to the frames of a terminal that merges events
+pub struct Coalescing;
+
+/// the coalescing warning with the measurement floor it implies
+pub fn coalescing_warning(frame: Duration) -> String {
+    i18n::fmt(
+        Msg::Coalesced,
+        &[&i18n::decimal(frame.as_secs_f64() * 1000.0, 1)],
+    )
+}
+
+impl Analyzer for Coalescing {
+    fn lines(&self, cx: &Context) -> Vec<Line<'static>> {
+        cx.session
+            .coalescing()
+            .map(|frame| Line::styled(coalescing_warning(frame), theme::palette().threshold))
+            .into_iter()
+            .collect()
+    }
+}
+
+/// the gap distributions of both halves of the session, to spot conditions changing midway
+pub struct Consistency;
+
+impl Consistency {
+    /// ks distance of the halves and the limit it may reach by chance
+    pub fn compare(session: &Session) -> Option<(f64, f64)> {
+        let gaps = session
+            .clicks
+            .windows(2)
+            .map(|w| w[1].at.saturating_sub(w[0].at).as_secs_f64())
+            .collect::<Vec<_>>();
+        if gaps.len() < CONSISTENCY_GAPS {
+            return None;
+        }
+        let (first, second) = gaps.split_at(gaps.len() / 2);
+        Some((
+            stats::ks_distance(first, second),
+            stats::ks_critical(first.len(), second.len(), CONSISTENCY_ALPHA),
+        ))
+    }
+}
+
+impl Analyzer for Consistency {
+    fn lines(&self, cx: &Context) -> Vec<Line<'static>> {
+        Self::compare(cx.session)
+            .map(|(distance, limit)| {
+                Line::from(i18n::fmt(
+                    Msg::Consistency,
+                    &[&i18n::decimal(distance, 2), &i18n::decimal(limit, 2)],
+                ))
+            })
+            .into_iter()
+            .collect()
+    }
+
+    fn verdict(&self, cx: &Context) -> Option<Line<'static>> {
+        let (distance, limit) = Self::compare(cx.session)?;
+        (distance > limit).then(|| {
+            Line::styled(
+                i18n::fmt(Msg::VerdictInconsistent, &[&i18n::decimal(distance, 2)]),
+                theme::palette().outlier.add_modifier(Modifier::BOLD),
+            )
+        })
+    }
+}
@@ -0,0 +1,2881 @@
+use std::{
+    borrow::Cow,
+    collections::VecDeque,
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use crossterm::event::{Event, KeyCode, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{
+    backend::Backend,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    symbols,
+    text::{Line, Span},
+    widgets::{
+        block::{Position, Title},
+        Axis, Block, Borders, Cell, Chart, Clear, Dataset, Gauge, GraphType, Paragraph, Row, Table,
+        TableState, Tabs, Wrap,
+    },
+    Frame, Terminal,
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    analyzer::Actuations,
+    battery,
+    clock::Clock,
+    config,
+    control::{self, Control, Listener},
+    dialog::{centered, Confirm},
+    error, explain,
+    focus::{self, Nav},
+    handedness, health, html,
+    i18n::{self, tr, Msg},
+    input,
+    json::{self, Value},
+    pane::{Pane, Panes},
+    pipeline::Pipeline,
+    query::Query,
+    quirks,
+    records::Records,
+    region::{self, WHEEL_ROWS},
+    remote,
+    session::{self, Actuation, Click, Mark, Screen, Session},
+    snapshot,
+    stats::{self, Estimate},
+    summary::{Format, Summary},
+    svg, terminal, theme, timeline, usb,
+};
+
+/// shortest time between two frames
+const MIN_FRAME: Duration = Duration::from_millis(16);
+/// longest a frame is put off while input keeps waiting
+const MAX_DEFER: Duration = Duration::from_millis(250);
+/// redraw interval while the detect window gauge is running
+const GAUGE_TICK: Duration = Duration::from_millis(50);
+/// redraw interval for relative times like "last chatter 3 s ago", also the
+/// slowest chart and gauge update with reduced motion
+const IDLE_TICK: Duration = Duration::from_secs(1);
+/// rows of the `--mini` widget
+pub const MINI_ROWS: u16 = 3;
+/// clicks kept in memory, older ones are spilled to disk
+pub const KEEP_CLICKS: usize = 10_000;
+/// clicks spilled at once so the log isn't written on every click
+pub const SPILL_CHUNK: usize = 5_000;
+/// time without any mouse event after which the terminal is assumed not to report the mouse
+const MOUSE_PROBE: Duration = Duration::from_secs(10);
+/// time between two checkpoints of the click test
+const AUTOSAVE_EVERY: Duration = Duration::from_secs(30);
+/// the wall clock running ahead of the session clock by this much at once is a suspend, ntp
+/// slews it by far less
+const SUSPEND_MIN: Duration = Duration::from_secs(2);
+/// how long a message stays in the corner
+const TOAST_FOR: Duration = Duration::from_secs(4);
+/// messages in the corner at once, a new one pushes the oldest out
+const TOASTS_SHOWN: usize = 3;
+/// clicks the interval chart shows at the closest zoom
+const ZOOM_MIN: usize = 8;
+/// width of the popup of a click
+const POPUP_WIDTH: u16 = 60;
+/// clicks listed before and after the one opened in the popup
+const POPUP_CONTEXT: usize = 5;
+/// left and right move the detect window by this much
+const WINDOW_STEP_MS: i64 = 5;
+/// the human floor moves by this much in the settings
+const FLOOR_STEP_MS: i64 = 10;
+/// confidence levels in percent the settings step through
+const CONFIDENCE_LEVELS: [f64; 4] = [80.0, 90.0, 95.0, 99.0];
+/// target chatter rates in percent the settings step through
+const TARGET_LEVELS: [f64; 7] = [0.1, 0.2, 0.5, 1.0, 2.0, 5.0, 10.0];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SortColumn {
+    Index,
+    Button,
+    Interval,
+    Hold,
+}
+
+impl SortColumn {
+    const ALL: [SortColumn; 4] = [
+        SortColumn::Index,
+        SortColumn::Button,
+        SortColumn::Interval,
+        SortColumn::Hold,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            SortColumn::Index => "index",
+            SortColumn::Button => "button",
+            SortColumn::Interval => "interval",
+            SortColumn::Hold => "hold",
+        }
+    }
+}
+
+/// the layout, sort and filter of the last run, restored on the next one
+fn view_path() -> PathBuf {
+    session::data_dir().join("view.json")
+}
+
+/// rows of the event list that are shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Filter {
+    All,
+    Button(MouseButton),
+    /// chatter or gap outliers
+    Flagged,
+}
+
+impl Filter {
+    /// `None` for text that names no filter
+    fn parse(text: &str) -> Option<Self> {
+        match text.trim() {
+            "" | "all" => Some(Filter::All),
+            "flagged" => Some(Filter::Flagged),
+            name => session::parse_button(name).map(Filter::Button),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Filter::All => "all",
+            Filter::Button(button) => session::button_name(button),
+            Filter::Flagged => "flagged",
+        }
+    }
+}
+
+/// what the text typed into the bar below the event list is for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Prompt {
+    Filter,
+    Search,
+}
+
+/// keys that lose the clicks recorded so far, they ask first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Quit,
+    StartOver,
+}
+
+/// a row of the settings opened with o
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Setting {
+    Window,
+    HumanFloor,
+    Confidence,
+    TargetRate,
+    NewestFirst,
+    Follow,
+    Theme,
+    Precision,
+}
+
+impl Setting {
+    const ALL: [Setting; 8] = [
+        Setting::Window,
+        Setting::HumanFloor,
+        Setting::Confidence,
+        Setting::TargetRate,
+        Setting::NewestFirst,
+        Setting::Follow,
+        Setting::Theme,
+        Setting::Precision,
+    ];
+
+    /// the unit a typed number without one counts in, none for the settings that take no
+    /// duration; the same as the option of the setting
+    fn bare_unit(self) -> Option<Duration> {
+        match self {
+            Setting::Window => Some(Duration::from_secs(1)),
+            Setting::HumanFloor => Some(Duration::from_millis(1)),
+            _ => None,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        tr(match self {
+            Setting::Window => Msg::SettingWindow,
+            Setting::HumanFloor => Msg::SettingFloor,
+            Setting::Confidence => Msg::SettingConfidence,
+            Setting::TargetRate => Msg::SettingTarget,
+            Setting::NewestFirst => Msg::SettingNewestFirst,
+            Setting::Follow => Msg::SettingFollow,
+            Setting::Theme => Msg::SettingTheme,
+            Setting::Precision => Msg::SettingPrecision,
+        })
+    }
+}
+
+/// the next of the levels past the value, the value itself at either end
+fn step(levels: &[f64], value: f64, forward: bool) -> f64 {
+    let next = if forward {
+        levels.iter().find(|l| **l > value + 1e-9)
+    } else {
+        levels.iter().rev().find(|l| **l < value - 1e-9)
+    };
+    next.copied().unwrap_or(value)
+}
+
+fn on_off(on: bool) -> &'static str {
+    tr(if on { Msg::On } else { Msg::Off })
+}
+
+/// the clicks the interval chart is zoomed into, by click number, the newest end
+/// stays put while clicks come in
+#[derive(Debug, Clone, Copy)]
+struct Zoom {
+    span: usize,
+    end: usize,
+}
+
+/// a message of the corner, chatter is only put into words when drawn so a click does not
+/// format anything
+enum Toast {
+    Text(String),
+    Chatter(Duration),
+}
+
+impl Toast {
+    fn text(&self) -> Cow<'_, str> {
+        match self {
+            Toast::Text(text) => Cow::Borrowed(text),
+            Toast::Chatter(interval) => {
+                Cow::Owned(i18n::fmt(Msg::ChatterToast, &[&i18n::duration(*interval)]))
+            }
+        }
+    }
+}
+
+impl From<String> for Toast {
+    fn from(text: String) -> Self {
+        Toast::Text(text)
+    }
+}
+
+/// short messages in the corner of the screen, each one goes after a few seconds
+#[derive(Default)]
+struct Toasts {
+    queue: VecDeque<(Toast, Instant)>,
+}
+
+impl Toasts {
+    fn push(&mut self, toast: impl Into<Toast>) {
+        if self.queue.len() == TOASTS_SHOWN {
+            self.queue.pop_front();
+        }
+        self.queue.push_back((toast.into(), Instant::now()));
+    }
+
+    /// drop the messages that timed out, whether any did
+    fn expire(&mut self) -> bool {
+        let before = self.queue.len();
+        self.queue.retain(|(_, at)| at.elapsed() < TOAST_FOR);
+        self.queue.len() != before
+    }
+
+    /// time until the oldest message goes
+    fn next_expiry(&self) -> Option<Duration> {
+        self.queue
+            .front()
+            .map(|(_, at)| TOAST_FOR.saturating_sub(at.elapsed()))
+    }
+}
+
+struct EventTable {
+    sort: SortColumn,
+    descending: bool,
+    /// selected click index into the session
+    selected: Option<usize>,
+    popup: bool,
+    /// the popup explains the flags of the click
+    explain: bool,
+    /// first row shown, only the visible rows are built each frame
+    offset: usize,
+    /// keep the newest click in view instead of the rows scrolled to
+    follow: bool,
+    filter: Filter,
+    /// clicks jumped between with n and N
+    search: Option<Query>,
+    /// text typed into the bar while it is open
+    prompt: Option<(Prompt, String)>,
+}
+
+pub struct AppState {
+    pub duration: Duration,
+    /// whether the detect window is open, from its first click until a click past it
+    pub window_open: bool,
+    /// clock time of the first click of the detect window
+    pub instant: Duration,
+    pub clock: Clock,
+    pub session: Session,
+    /// motion events of the last second
+    pub moves: VecDeque<Instant>,
+    pub polling_rate: Option<u32>,
+    pub confidence: f64,
+    pub target_rate: f64,
+    /// the analyzers, working on a copy of the session
+    pipeline: Pipeline,
+    /// dump the next frame into a file
+    snapshot: bool,
+    /// feedback of the last actions, shown until it times out
+    toasts: Toasts,
+    /// firmware debounce the d key simulates next to the raw clicks
+    debounce: Duration,
+    simulating: bool,
+    /// operator mode, the keys changing the settings do nothing
+    locked: bool,
+    /// end of a saved session shown read only, stands in for the clock
+    viewing: Option<Duration>,
+    /// commands of a harness or supervisor, from stdin or the control socket
+    control: Option<Listener>,
+    /// clicks are not counted until resumed
+    paused: bool,
+    /// the bench console the clicks are forwarded to, `--agent`
+    agent: Option<remote::Agent>,
+    /// the terminal reports the mouse, c hands it back for selecting and copying
+    captured: bool,
+    #[cfg(feature = "cast")]
+    pub cast: Option<crate::cast::Recorder>,
+    table: EventTable,
+    /// clicks shown in the chart, only refreshed every tick with reduced motion
+    chart_clicks: usize,
+    chart_at: Instant,
+    /// when the session was last checkpointed and with how many clicks
+    checkpointed: (Instant, usize),
+    /// how far the wall clock was ahead of the session clock at the last look
+    slept: Duration,
+    /// the config file, reloaded when it is edited
+    watch: Option<config::Watch>,
+    /// start of the wait for the first mouse event, none once one arrived or the user
+    /// went on without
+    probe: Option<Instant>,
+    /// row chosen in the settings while they are open
+    settings: Option<usize>,
+    /// the duration typed into the chosen setting and why the last one was refused
+    setting_input: Option<String>,
+    setting_error: Option<String>,
+    /// the question asked before a key loses data
+    confirm: Option<Confirm<Action>>,
+    /// the session is saved into the history once the test ends
+    saving: bool,
+    quitting: bool,
+    /// where the event list was drawn, the wheel scrolls it there
+    list_area: Rect,
+    panes: Panes,
+    /// none shows the whole session
+    zoom: Option<Zoom>,
+    /// clicks of the blind protocol, nothing but the progress shows until they are in
+    blind: Option<usize>,
+    /// the chart pane shows the detect windows one per row instead of the intervals, g
+    /// switches
+    timeline: bool,
+    /// the interval chart draws the mean of this many clicks around each, a switches
+    smoothing: usize,
+    smooth: bool,
+    /// the interval chart draws the lowest and highest gap of each column once there are
+    /// more than it has room for, the clicks themselves are kept as they are
+    downsample: bool,
+    /// best results of the earlier sessions on the device
+    records: Records,
+    /// capture pauses for a rest every this long, `--break-every`
+    break_every: Option<Duration>,
+    /// when the running segment started
+    segment_start: Duration,
+    /// start of the break being taken, enter ends it
+    resting: Option<Duration>,
+    /// only the lines of `--mini` are drawn
+    mini: bool,
+    /// quitting shows the results first, off with `--no-results`
+    results_screen: bool,
+    /// page of the results screen while it is shown
+    results: Option<usize>,
+    /// where the results screen saved the session
+    saved: Option<PathBuf>,
+    /// the file `--resume` took the session from, the session is saved back into it
+    resumed_from: Option<PathBuf>,
+    /// the session this one is held against, from `--baseline` or a restart from the results;
+    /// its gaps show behind the live ones and its chatter next to this one's
+    baseline: Option<Session>,
+}
+
+impl AppState {
+    pub fn new(duration: Duration, device: Option<String>) -> Self {
+        let mut session = Session {
+            battery: battery::level(),
+            identity: usb::identity(),
+            ..Session::new(device, duration)
+        };
+        make_room(&mut session);
+        let pipeline = Pipeline::new(&session);
+
+        Self {
+            window_open: false,
+            duration,
+            instant: Duration::ZERO,
+            clock: Clock::default(),
+            session,
+            moves: VecDeque::new(),
+            polling_rate: usb::polling_rate(),
+            confidence: 0.95,
+            target_rate: 0.01,
+            pipeline,
+            snapshot: false,
+            toasts: Toasts::default(),
+            debounce: Duration::from_millis(10),
+            simulating: false,
+            locked: false,
+            viewing: None,
+            control: None,
+            paused: false,
+            agent: None,
+            captured: true,
+            #[cfg(feature = "cast")]
+            cast: None,
+            table: EventTable {
+                sort: SortColumn::Index,
+                descending: true,
+                selected: None,
+                popup: false,
+                explain: false,
+                offset: 0,
+                follow: true,
+                filter: Filter::All,
+                search: None,
+                prompt: None,
+            },
+            chart_clicks: 0,
+            chart_at: Instant::now(),
+            checkpointed: (Instant::now(), 0),
+            slept: Duration::ZERO,
+            probe: Some(Instant::now()),
+            watch: None,
+            settings: None,
+            setting_input: None,
+            setting_error: None,
+            confirm: None,
+            saving: false,
+            results_screen: true,
+            results: None,
+            baseline: None,
+            saved: None,
+            resumed_from: None,
+            quitting: false,
+            list_area: Rect::default(),
+            panes: Panes::default(),
+            zoom: None,
+            timeline: false,
+            smoothing: config::Config::default().chart_smoothing,
+            smooth: false,
+            downsample: config::Config::default().chart_downsample,
+            blind: None,
+            records: Records::default(),
+            break_every: None,
+            segment_start: Duration::ZERO,
+            resting: None,
+            mini: false,
+        }
+    }
+
+    /// the panes, sort and filter the last run ended with, the defaults when there is
+    /// no saved view or it is unreadable
+    pub fn restore_view(&mut self) {
+        let Some(view) = fs::read_to_string(view_path())
+            .ok()
+            .and_then(|text| json::parse(&text).ok())
+        else {
+            return;
+        };
+        if let Some(panes) = view.get("panes") {
+            self.panes = Panes::from_json(panes);
+        }
+        let sort = view
+            .get("sort")
+            .and_then(Value::as_str)
+            .and_then(|name| SortColumn::ALL.into_iter().find(|c| c.name() == name));
+        if let Some(sort) = sort {
+            self.table.sort = sort;
+            // the direction of the click order is newest_first of the config
+            if sort != SortColumn::Index {
+                if let Some(descending) = view.get("descending").and_then(Value::as_bool) {
+                    self.table.descending = descending;
+                }
+            }
+        }
+        if let Some(filter) = view
+            .get("filter")
+            .and_then(Value::as_str)
+            .and_then(Filter::parse)
+        {
+            self.table.filter = filter;
+        }
+    }
+
+    pub fn save_view(&self) -> error::Result<()> {
+        let view = json::object([
+            ("panes", self.panes.to_json()),
+            ("sort", Value::from(self.table.sort.name())),
+            ("descending", Value::from(self.table.descending)),
+            ("filter", Value::from(self.table.filter.name())),
+        ]);
+        session::write_atomic(&view_path(), &view.to_string_pretty())
+    }
+
+    /// a saved session to look at, no input is captured into it
+    pub fn view(session: Session, path: &Path) -> Self {
+        let mut app = Self::new(session.detect_window, session.device.clone());
+        // the mouse of the session is not the one plugged in here
+        app.polling_rate = None;
+        app.viewing = Some(
+            session
+                .clicks
+                .iter()
+                .map(|c| c.at + c.hold.unwrap_or_default())
+                .max()
+                .unwrap_or_default(),
+        );
+        app.pipeline.replay(&session);
+        app.set_session(session);
+        app.probe = None;
+        app.toasts.push(i18n::fmt(Msg::Viewing, &[&path.display()]));
+        app
+    }
+
+    /// the session once the test ends and the file saving it goes over, none for one that
+    /// was never saved
+    pub fn finish(mut self) -> (Session, Option<PathBuf>) {
+        self.session.quirks = quirks::active();
+        (self.session, self.saved.or(self.resumed_from))
+    }
+
+    /// the clock, or the end of the viewed session
+    fn now(&self) -> Duration {
+        self.viewing.unwrap_or_else(|| self.clock.now())
+    }
+
+    pub fn on_move(&mut self) {
+        let now = Instant::now();
+        self.moves.push_back(now);
+        while self
+            .moves
+            .front()
+            .is_some_and(|t| now.duration_since(*t) > Duration::from_secs(1))
+        {
+            self.moves.pop_front();
+        }
+    }
+
+    pub fn estimate(&self) -> Estimate {
+        Estimate::new(
+            self.session.chatter_count(),
+            self.session.click_count(),
+            self.confidence,
+            self.target_rate,
+        )
+    }
+
+    /// pick up edits of the config file, the settings pinned by flags stay
+    pub fn watch_config(&mut self, watch: config::Watch) {
+        self.watch = Some(watch);
+    }
+
+    /// apply the config when its file changed since the last look, with what happened
+    pub fn reload_config(&mut self) -> Option<String> {
+        let watch = self.watch.as_mut()?;
+        let pinned = watch.pinned;
+        let config = match watch.changed()? {
+            Err(e) => return Some(e.to_string()),
+            Ok(config) => config,
+        };
+
+        let mut notice = tr(Msg::ConfigReloaded).to_string();
+        if !pinned.detect_window && config.detect_window != self.duration {
+            notice = format!("{}, {}", notice, self.set_window(config.detect_window));
+        }
+        if !pinned.human_floor {
+            self.session.human_floor = config.human_floor;
+            self.pipeline.replace(&self.session);
+        }
+        if !pinned.confidence {
+            self.confidence = config.confidence / 100.0;
+        }
+        if !pinned.target_rate {
+            self.target_rate = config.target_rate / 100.0;
+        }
+        if !pinned.theme {
+            theme::set(config.theme);
+        }
+        health::set_weights(config.health);
+        self.set_list_order(config.newest_first, config.follow);
+        self.set_chart(config.chart_smoothing, config.chart_downsample);
+        Some(notice)
+    }
+
+    /// how the interval chart smooths and thins the gaps it draws
+    pub fn set_chart(&mut self, smoothing: usize, downsample: bool) {
+        self.smoothing = smoothing;
+        self.downsample = downsample;
+    }
+
+    /// where new clicks appear in the click order and whether the view follows them
+    pub fn set_list_order(&mut self, newest_first: bool, follow: bool) {
+        self.table.descending = newest_first;
+        self.table.follow = follow;
+    }
+
+    pub fn set_human_floor(&mut self, floor: Duration) {
+        self.session.human_floor = floor;
+        self.pipeline.replace(&self.session);
+    }
+
+    pub fn set_records(&mut self, records: Records) {
+        self.records = records;
+    }
+
+    pub fn set_saving(&mut self, saving: bool) {
+        self.saving = saving;
+    }
+
+    pub fn set_results_screen(&mut self, on: bool) {
+        self.results_screen = on;
+    }
+
+    pub fn set_locked(&mut self, locked: bool) {
+        self.locked = locked;
+    }
+
+    pub fn set_control(&mut self, control: Option<Listener>) {
+        self.control = control;
+    }
+
+    /// the os swaps the buttons, the clicks are recorded as the buttons pressed
+    pub fn set_swapped(&mut self, swapped: bool) {
+        self.session.swapped = swapped;
+    }
+
+    pub fn set_blind(&mut self, clicks: Option<usize>) {
+        self.blind = clicks.filter(|n| *n > 0);
+    }
+
+    /// clicks collected and the clicks of the blind protocol, none once it is done
+    pub fn blind(&self) -> Option<(usize, usize)> {
+        self.blind.map(|total| (self.session.click_count(), total))
+    }
+
+    pub fn set_mini(&mut self, mini: bool) {
+        self.mini = mini;
+    }
+
+    pub fn set_break_every(&mut self, every: Option<Duration>) {
+        self.break_every = every.filter(|e| !e.is_zero());
+    }
+
+    /// whether the running segment is long enough for the next break
+    fn break_due(&self) -> bool {
+        self.viewing.is_none()
+            && self.resting.is_none()
+            && self
+                .break_every
+                .is_some_and(|every| self.clock.now().saturating_sub(self.segment_start) >= every)
+    }
+
+    /// number of the running segment, counting from 1
+    fn segment(&self) -> usize {
+        let breaks = self.session.marks.iter();
+        breaks
+            .filter(|m| m.note.starts_with(session::BREAK_NOTE))
+            .count()
+            + 1
+    }
+
+    /// pause capture and end the segment with a break mark
+    fn take_break(&mut self) {
+        let note = format!("{} {}", session::BREAK_NOTE, self.segment());
+        self.resting = Some(self.mark(&note));
+        self.paused = true;
+    }
+
+    /// capture again in a new segment, what happened for a toast
+    fn end_break(&mut self) -> String {
+        self.resting = None;
+        self.paused = false;
+        self.segment_start = self.clock.now();
+        i18n::fmt(Msg::SegmentStarted, &[&i18n::number(self.segment())])
+    }
+
+    pub fn set_agent(&mut self, agent: Option<remote::Agent>) {
+        self.agent = agent;
+    }
+
+    /// whether commands may arrive, the event loop has to look for them regularly
+    pub fn controlled(&self) -> bool {
+        self.control.is_some()
+    }
+
+    pub fn paused(&self) -> bool {
+        self.paused
+    }
+
+    /// free the mouse for selecting text or capture it again, with what happened
+    pub fn toggle_capture(&mut self) -> String {
+        match terminal::set_capture(!self.captured) {
+            Ok(()) => {
+                self.captured = !self.captured;
+                // no mouse event arrives while it is free
+                self.probe = None;
+                tr(if self.captured {
+                    Msg::Captured
+                } else {
+                    Msg::Released
+                })
+                .to_string()
+            }
+            Err(e) => e.to_string(),
+        }
+    }
+
+    /// carry out the commands that arrived, with the answer to each and whether one was stop
+    pub fn run_controls(&mut self) -> (Vec<String>, bool) {
+        let mut answers = Vec::new();
+        let mut stop = false;
+        while let Some(request) = self.control.as_ref().and_then(Listener::next) {
+            let answer = match Control::parse(&request.line) {
+                Ok(Control::Pause) => {
+                    self.paused = true;
+                    tr(Msg::ControlPaused).to_string()
+                }
+                Ok(Control::Resume) => {
+                    if self.resting.is_some() {
+                        self.end_break();
+                    }
+                    self.paused = false;
+                    tr(Msg::ControlResumed).to_string()
+                }
+                Ok(Control::Mark(note)) => {
+                    let at = self.mark(&note);
+                    i18n::fmt(
+                        Msg::ControlMarked,
+                        &[&i18n::decimal(at.as_secs_f64(), 3), &note],
+                    )
+                }
+                Ok(Control::Actuate(button)) => self.actuate(button),
+                Ok(Control::Stats) => {
+                    let mut stats = Summary::new(&self.session, self.estimate()).to_json();
+                    if let Value::Object(map) = &mut stats {
+                        map.insert("paused".to_string(), Value::from(self.paused));
+                    }
+                    stats.to_string_compact()
+                }
+                Ok(Control::Stop) => {
+                    stop = true;
+                    tr(Msg::ControlStopped).to_string()
+                }
+                Err(command) => i18n::fmt(Msg::ControlUnknown, &[&command]),
+            };
+            request.answer(&answer);
+            answers.push(answer);
+            if stop {
+                break;
+            }
+        }
+        (answers, stop)
+    }
+
+    /// checkpoint the session when it is due and has new clicks, the error when it failed
+    pub fn autosave(&mut self) -> Option<String> {
+        let (at, clicks) = self.checkpointed;
+        if self.viewing.is_some()
+            || at.elapsed() < AUTOSAVE_EVERY
+            || self.session.click_count() == clicks
+        {
+            return None;
+        }
+        self.checkpointed = (Instant::now(), self.session.click_count());
+        self.session.checkpoint().err().map(|e| e.to_string())
+    }
+
+    /// a mark on the timeline when the machine was suspended since the last look, the gap
+    /// until the next click is not an interval and opens a new detect window
+    pub fn watch_suspend(&mut self) -> Option<String> {
+        if self.viewing.is_some() {
+            return None;
+        }
+        let slept = self.clock.slept();
+        let gap = slept.saturating_sub(std::mem::replace(&mut self.slept, slept));
+        if gap < SUSPEND_MIN {
+            return None;
+        }
+        let note = i18n::fmt(Msg::Suspended, &[&i18n::number(gap.as_secs())]);
+        self.mark(&note);
+        self.window_open = false;
+        Some(note)
+    }
+
+    /// a message for every press found stuck since the last look
+    pub fn watch_stuck(&mut self) -> Vec<String> {
+        if self.viewing.is_some() || self.paused {
+            return Vec::new();
+        }
+        let found = self.session.watch_stuck(self.clock.now());
+        // the analyzers show how long a button has been down so far
+        if !found.is_empty() || self.session.stuck.iter().any(|s| !s.released) {
+            self.pipeline.stuck(&self.session.stuck);
+        }
+        found
+            .into_iter()
+            .map(|s| {
+                i18n::fmt(
+                    Msg::Stuck,
+                    &[&session::button_name(s.button), &i18n::duration(s.held)],
+                )
+            })
+            .collect()
+    }
+
+    pub fn set_stuck_after(&mut self, after: Duration) {
+        self.session.stuck_after = after;
+    }
+
+    pub fn set_debounce(&mut self, debounce: Duration) {
+        self.debounce = debounce;
+    }
+
+    fn toggle_simulation(&mut self) {
+        self.simulating = !self.simulating;
+        self.toasts.push(if self.simulating {
+            i18n::fmt(Msg::Simulating, &[&i18n::number(self.debounce.as_millis())])
+        } else {
+            tr(Msg::SimulationOff).to_string()
+        });
+    }
+
+    /// keep adding clicks to an earlier session, classified with the current settings
+    pub fn resume(&mut self, mut session: Session, path: &Path) {
+        self.resumed_from = Some(path.to_path_buf());
+        // the new clicks start a window past the old ones, the sittings never run together
+        let end = session.clicks.last().map(|c| c.at).unwrap_or_default();
+        self.clock = self.clock.continuing(end + self.duration);
+        session.human_floor = self.session.human_floor;
+        session.clock = self.clock.source();
+        session.reclassify(self.duration);
+        self.pipeline.replay(&session);
+        self.toasts.push(i18n::fmt(
+            Msg::Resumed,
+            &[
+                &session::format_timestamp(session.started_at),
+                &i18n::number(session.click_count()),
+            ],
+        ));
+        self.set_session(session);
+    }
+
+    /// every session the clicks go into comes in here, with room made for them
+    fn set_session(&mut self, mut session: Session) {
+        make_room(&mut session);
+        self.session = session;
+    }
+
+    pub fn set_clock(&mut self, clock: Clock) {
+        self.clock = clock;
+        self.slept = clock.slept();
+        self.session.clock = clock.source();
+        self.pipeline.replace(&self.session);
+    }
+
+    /// move the detect window and classify the recorded clicks again
+    fn adjust_window(&mut self, delta: i64) {
+        let ms = (self.duration.as_millis() as i64 + delta * WINDOW_STEP_MS).max(WINDOW_STEP_MS);
+        let adjusted = self.set_window(Duration::from_millis(ms as u64));
+        self.toasts.push(adjusted);
+    }
+
+    /// classify the recorded clicks again with another detect window, with how the
+    /// chatter count changed
+    fn set_window(&mut self, window: Duration) -> String {
+        let before = self.session.chatter_count();
+        self.duration = window;
+        let start = self.session.reclassify(self.duration);
+
+        // the open window keeps counting down from its first click
+        self.instant = start.unwrap_or_default();
+        self.window_open = start.is_some();
+
+        // the analyzers saw the old intervals
+        self.pipeline.replay(&self.session);
+
+        i18n::fmt(
+            Msg::WindowAdjusted,
+            &[
+                &i18n::number(window.as_millis()),
+                &i18n::number(self.session.chatter_count()),
+                &i18n::number(before),
+            ],
+        )
+    }
+
+    fn reset(&mut self) {
+        self.instant = self.clock.now();
+        self.window_open = false;
+    }
+
+    pub fn on_click(&mut self, event: MouseEvent) {
+        if let MouseEventKind::Down(button) = event.kind {
+            let button = handedness::physical(button, self.session.swapped);
+            self.push_click(button, Some((event.column, event.row)), event.modifiers);
+        }
+    }
+
+    /// a left click made with the space key where the terminal reports no mouse
+    pub fn on_key_click(&mut self) {
+        self.push_click(MouseButton::Left, None, KeyModifiers::NONE);
+    }
+
+    /// any mouse event, the terminal does report the mouse
+    pub fn mouse_arrived(&mut self) {
+        self.probe = None;
+    }
+
+    /// whether the probe window passed without a single mouse event
+    pub fn mouse_missing(&self) -> bool {
+        self.probe.is_some_and(|t| t.elapsed() >= MOUSE_PROBE)
+    }
+
+    /// stop waiting for the mouse and carry on with the space key
+    pub fn dismiss_probe(&mut self) {
+        self.probe = None;
+    }
+
+    /// mark the newest detect window as pressed exactly once, what happened for a toast
+    pub fn mark_one_press(&mut self) -> String {
+        let Some(group) = timeline::groups(&self.session).pop() else {
+            return tr(Msg::NoWindow).to_string();
+        };
+        let at = self.session.clicks[group.first].at;
+        if !self.session.one_press.contains(&at) {
+            self.session.one_press.push(at);
+        }
+        i18n::fmt(
+            Msg::MarkedOnePress,
+            &[&i18n::number(self.session.spilled.clicks + group.first + 1)],
+        )
+    }
+
+    /// a note on the timeline at the current time, returned
+    pub fn mark(&mut self, note: &str) -> Duration {
+        let at = self.clock.now();
+        self.session.marks.push(Mark {
+            at,
+            note: note.to_string(),
+        });
+        self.pipeline.replace(&self.session);
+        at
+    }
+
+    /// note a press of an actuator rig, the answer has how the one before went since its
+    /// window is closed by now
+    pub fn actuate(&mut self, button: MouseButton) -> String {
+        let at = self.clock.now();
+        self.session.actuations.push(Actuation { at, button });
+        self.pipeline.replace(&self.session);
+        let count = self.session.actuations.len();
+        let answer = i18n::fmt(
+            Msg::ControlActuated,
+            &[&i18n::number(count), &i18n::decimal(at.as_secs_f64(), 3)],
+        );
+        let outcomes = Actuations::new(&self.session).map(|a| a.outcomes);
+        let Some(previous) = outcomes.as_deref().and_then(|o| o.iter().rev().nth(1)) else {
+            return answer;
+        };
+        let number = i18n::number(count - 1);
+        let outcome = match previous.latency {
+            Some(latency) => i18n::fmt(
+                Msg::ActuationOutcome,
+                &[
+                    &number,
+                    &i18n::number(previous.clicks),
+                    &i18n::duration(latency),
+                ],
+            ),
+            None => i18n::fmt(Msg::ActuationMissed, &[&number]),
+        };
+        format!("{}, {}", answer, outcome)
+    }
+
+    pub fn set_actuation_window(&mut self, window: Duration) {
+        self.session.actuation_window = window;
+    }
+
+    /// the terminal got a new size, the cells of later clicks are shares of it
+    pub fn on_resize(&mut self, columns: u16, rows: u16) {
+        if self.viewing.is_none() {
+            self.session.resize(self.clock.now(), columns, rows);
+        }
+    }
+
+    pub fn set_baseline(&mut self, baseline: Option<Session>) {
+        self.baseline = baseline;
+    }
+
+    pub fn set_loopback(&mut self, baseline: Option<Duration>) {
+        self.session.loopback = baseline;
+    }
+
+    /// a click of the mouse where it landed, a key click has no position
+    fn push_click(
+        &mut self,
+        button: MouseButton,
+        position: Option<(u16, u16)>,
+        modifiers: KeyModifiers,
+    ) {
+        if self.paused {
+            return;
+        }
+        self.probe = None;
+        let now = self.clock.now();
+        let interval = if !self.window_open {
+            self.instant = now;
+            None
+        } else {
+            Some(now.saturating_sub(self.instant))
+        };
+        // a click after the window closed is the first of the next one
+        let interval = match interval {
+            Some(dur) if dur > self.duration => {
+                self.reset();
+                None
+            }
+            interval => interval,
+        };
+
+        self.window_open = true;
+        if let Some(dur) = interval.filter(|_| self.blind.is_none()) {
+            self.toasts.push(Toast::Chatter(dur));
+        }
+        let click = Click {
+            at: now,
+            interval,
+            button,
+            hold: None,
+            bookmarked: false,
+            keyboard: position.is_none(),
+            position,
+            modifiers,
+        };
+        self.session.clicks.push(click);
+        self.pipeline.click(click);
+        if self
+            .blind
+            .is_some_and(|total| self.session.click_count() >= total)
+        {
+            self.blind = None;
+            self.toasts.push(tr(Msg::BlindDone).to_string());
+        }
+        if let Some(agent) = self.agent.as_mut() {
+            agent.send(remote::Message::Click {
+                at: now,
+                button,
+                interval,
+            });
+        }
+        if self.table.follow {
+            if self.table.selected.is_some() {
+                self.table.selected = Some(self.session.clicks.len() - 1);
+            }
+        } else if self.table.sort == SortColumn::Index && self.table.descending {
+            // the new row pushes the rows scrolled to down by one
+            self.table.offset += 1;
+        }
+
+        // on failure the clicks simply stay in memory
+        if self.session.clicks.len() >= KEEP_CLICKS + SPILL_CHUNK
+            && self.session.spill(SPILL_CHUNK).is_ok()
+        {
+            self.pipeline.replace(&self.session);
+            self.table.selected = self.table.selected.and_then(|s| s.checked_sub(SPILL_CHUNK));
+            if !self.table.descending {
+                self.table.offset = self.table.offset.saturating_sub(SPILL_CHUNK);
+            }
+        }
+    }
+
+    pub fn on_release(&mut self, button: MouseButton) {
+        let button = handedness::physical(button, self.session.swapped);
+        let now = self.clock.now();
+        let stuck = self.session.release(button, now);
+        self.pipeline.release(button, now);
+        if let Some(stuck) = stuck {
+            self.toasts.push(i18n::fmt(
+                Msg::StuckReleased,
+                &[
+                    &session::button_name(stuck.button),
+                    &i18n::duration(stuck.held),
+                ],
+            ));
+        }
+        if let Some(agent) = self.agent.as_mut() {
+            agent.send(remote::Message::Release { at: now, button });
+        }
+    }
+
+    /// click numbers the chart shows, first and last
+    fn chart_range(&self) -> (usize, usize) {
+        let first = self.session.spilled.clicks + 1;
+        let last = self.chart_clicks.max(first);
+        match self.zoom {
+            Some(zoom) => {
+                // spilled clicks are not in memory to be shown any more
+                let end = zoom.end.clamp(first, last);
+                ((end + 1).saturating_sub(zoom.span).max(first), end)
+            }
+            None => (first, last),
+        }
+    }
+
+    /// halve or double the clicks in the chart around the middle of what it shows
+    fn zoom_chart(&mut self, closer: bool) {
+        let (from, to) = self.chart_range();
+        let all = self
+            .chart_clicks
+            .saturating_sub(self.session.spilled.clicks);
+        let span = to + 1 - from;
+        let span = if closer {
+            (span / 2).max(ZOOM_MIN)
+        } else {
+            span * 2
+        };
+        if span >= all {
+            self.zoom = None;
+            return;
+        }
+        let middle = (from + to) / 2;
+        let end = (middle + span / 2).clamp(self.session.spilled.clicks + span, self.chart_clicks);
+        self.zoom = Some(Zoom { span, end });
+    }
+
+    /// move the zoomed chart along the timeline by a quarter of what it shows
+    fn pan_chart(&mut self, forward: bool) {
+        let Some(zoom) = self.zoom.as_mut() else {
+            return;
+        };
+        let step = (zoom.span / 4).max(1);
+        let end = if forward {
+            zoom.end + step
+        } else {
+            zoom.end.saturating_sub(step)
+        };
+        zoom.end = end.clamp(self.session.spilled.clicks + zoom.span, self.chart_clicks);
+    }
+
+    /// a wheel notch over the event list moves the selection, elsewhere it does nothing
+    fn on_scroll(&mut self, column: u16, row: u16, notches: isize) {
+        let covered = self.confirm.is_some() || self.settings.is_some() || self.table.popup;
+        if !covered && region::hit(self.list_area, column, row) {
+            self.select_offset(notches * WHEEL_ROWS);
+        }
+    }
+
+    /// whether keys go to the filter bar
+    pub fn typing(&self) -> bool {
+        self.table.prompt.is_some()
+    }
+
+    /// click indices in table order, without the ones hidden by the filter
+    fn sorted(&self) -> Vec<usize> {
+        let clicks = &self.session.clicks;
+        let outliers = match self.table.filter {
+            Filter::Flagged => self.session.gap_outliers(),
+            _ => Vec::new(),
+        };
+        let mut order = (0..clicks.len())
+            .filter(|i| match self.table.filter {
+                Filter::All => true,
+                Filter::Button(button) => clicks[*i].button == button,
+                Filter::Flagged => clicks[*i].interval.is_some() || outliers[*i],
+            })
+            .collect::<Vec<_>>();
+        if self.table.sort == SortColumn::Index {
+            if self.table.descending {
+                order.reverse();
+            }
+            return order;
+        }
+        order.sort_by(|a, b| {
+            let (a, b) = (&clicks[*a], &clicks[*b]);
+            match self.table.sort {
+                SortColumn::Index => a.at.cmp(&b.at),
+                SortColumn::Button => {
+                    session::button_name(a.button).cmp(session::button_name(b.button))
+                }
+                SortColumn::Interval => a.shown_interval().cmp(&b.shown_interval()),
+                SortColumn::Hold => a.hold.cmp(&b.hold),
+            }
+        });
+        if self.table.descending {
+            order.reverse();
+        }
+        order
+    }
+
+    fn sort_by(&mut self, column: SortColumn) {
+        if self.table.sort == column {
+            self.table.descending = !self.table.descending;
+        } else {
+            self.table.sort = column;
+            self.table.descending = false;
+        }
+    }
+
+    fn select_offset(&mut self, offset: isize) {
+        let order = self.sorted();
+        if order.is_empty() {
+            return;
+        }
+        let position = self
+            .table
+            .selected
+            .and_then(|s| order.iter().position(|i| *i == s))
+            .map(|p| (p as isize + offset).clamp(0, order.len() as isize - 1) as usize)
+            .unwrap_or(0);
+        self.table.selected = Some(order[position]);
+    }
+
+    /// select the next click in table order matching the search, wrapping around
+    fn jump(&mut self, forward: bool) {
+        if let Some(query) = self.table.search.clone() {
+            self.jump_to(forward, |click| query.matches(click));
+        }
+    }
+
+    fn jump_to(&mut self, forward: bool, matches: impl Fn(&Click) -> bool) {
+        let order = self.sorted();
+        let len = order.len();
+        let current = self
+            .table
+            .selected
+            .and_then(|s| order.iter().position(|i| *i == s));
+        let found = (1..=len)
+            .map(|step| match (current, forward) {
+                (Some(p), true) => (p + step) % len,
+                (Some(p), false) => (p + len - step % len) % len,
+                (None, true) => step - 1,
+                (None, false) => len - step,
+            })
+            .find(|p| matches(&self.session.clicks[order[*p]]));
+        if let Some(position) = found {
+            self.table.selected = Some(order[position]);
+        }
+    }
+
+    fn on_prompt_key(&mut self, code: KeyCode) {
+        let Some((kind, prompt)) = self.table.prompt.as_mut() else {
+            return;
+        };
+        match code {
+            KeyCode::Esc => self.table.prompt = None,
+            // unknown filters and malformed queries keep the bar open
+            KeyCode::Enter => match kind {
+                Prompt::Filter => {
+                    if let Some(filter) = Filter::parse(prompt) {
+                        self.table.filter = filter;
+                        self.table.prompt = None;
+                    }
+                }
+                Prompt::Search => {
+                    if let Some(query) = Query::parse(prompt) {
+                        self.table.search = Some(query);
+                        self.table.prompt = None;
+                        self.jump(true);
+                    }
+                }
+            },
+            KeyCode::Backspace => {
+                prompt.pop();
+            }
+            KeyCode::Char(c) => prompt.push(c),
+            _ => {}
+        }
+    }
+
+    fn setting_value(&self, setting: Setting) -> String {
+        match setting {
+            Setting::Window => format!("{} ms", i18n::number(self.duration.as_millis())),
+            Setting::HumanFloor => {
+                format!("{} ms", i18n::number(self.session.human_floor.as_millis()))
+            }
+            Setting::Confidence => format!("{:.0} %", self.confidence * 100.0),
+            Setting::TargetRate => format!("{:.1} %", self.target_rate * 100.0),
+            Setting::NewestFirst => on_off(self.table.descending).to_string(),
+            Setting::Follow => on_off(self.table.follow).to_string(),
+            Setting::Theme => theme::current().name().to_string(),
+            Setting::Precision => i18n::duration(Duration::from_micros(1500)),
+        }
+    }
+
+    fn change_setting(&mut self, setting: Setting, forward: bool) {
+        let sign = if forward { 1 } else { -1 };
+        match setting {
+            Setting::Window => self.adjust_window(sign),
+            Setting::HumanFloor => {
+                let ms = (self.session.human_floor.as_millis() as i64 + sign * FLOOR_STEP_MS)
+                    .max(FLOOR_STEP_MS);
+                self.session.human_floor = Duration::from_millis(ms as u64);
+                self.pipeline.replace(&self.session);
+            }
+            Setting::Confidence => {
+                self.confidence = step(&CONFIDENCE_LEVELS, self.confidence * 100.0, forward) / 100.0
+            }
+            Setting::TargetRate => {
+                self.target_rate = step(&TARGET_LEVELS, self.target_rate * 100.0, forward) / 100.0
+            }
+            Setting::NewestFirst => self.table.descending = !self.table.descending,
+            Setting::Follow => self.table.follow = !self.table.follow,
+            Setting::Theme => {
+                let all = theme::Theme::ALL;
+                let at = all.iter().position(|t| *t == theme::current()).unwrap_or(0);
+                let len = all.len() as isize;
+                theme::set(all[(at as isize + sign as isize).rem_euclid(len) as usize]);
+            }
+            Setting::Precision => i18n::set_precision(
+                i18n::precision()
+                    .saturating_add_signed(sign as isize)
+                    .min(i18n::MAX_PRECISION),
+            ),
+        }
+    }
+
+    /// write the settings into the config file, keeping what the menu does not show
+    fn save_settings(&mut self) {
+        let saved = config::Config::load().and_then(|config| {
+            config::Config {
+                detect_window: self.duration,
+                human_floor: self.session.human_floor,
+                confidence: self.confidence * 100.0,
+                target_rate: self.target_rate * 100.0,
+                newest_first: self.table.descending,
+                follow: self.table.follow,
+                theme: theme::current(),
+                precision: i18n::precision(),
+                ..config.unwrap_or_default()
+            }
+            .save()
+        });
+        if let Some(watch) = self.watch.as_mut() {
+            watch.saved();
+        }
+        self.toasts.push(match saved {
+            Ok(path) => i18n::fmt(Msg::SettingsSaved, &[&path.display()]),
+            Err(e) => e.to_string(),
+        });
+    }
+
+    /// take the typed duration for the setting, keeping the input open with the reason
+    /// when it is refused
+    fn enter_setting(&mut self, setting: Setting, bare: Duration) {
+        let text = self.setting_input.as_deref().unwrap_or_default();
+        let parsed = session::parse_duration(text, bare).and_then(|d| {
+            (!d.is_zero())
+                .then_some(d)
+                .ok_or(tr(Msg::DurationZero).to_string())
+        });
+        match parsed {
+            Ok(duration) => {
+                self.setting_input = None;
+                self.setting_error = None;
+                match setting {
+                    Setting::Window => {
+                        let adjusted = self.set_window(duration);
+                        self.toasts.push(adjusted);
+                    }
+                    _ => self.set_human_floor(duration),
+                }
+            }
+            Err(e) => self.setting_error = Some(e),
+        }
+    }
+
+    fn on_settings_key(&mut self, code: KeyCode) {
+        let Some(row) = self.settings else {
+            return;
+        };
+        let setting = Setting::ALL[row];
+        if let (Some(input), Some(bare)) = (self.setting_input.as_mut(), setting.bare_unit()) {
+            match code {
+                KeyCode::Esc => {
+                    self.setting_input = None;
+                    self.setting_error = None;
+                }
+                KeyCode::Enter => self.enter_setting(setting, bare),
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) => input.push(c),
+                _ => {}
+            }
+            return;
+        }
+        match code {
+            KeyCode::Char(c)
+                if setting.bare_unit().is_some() && (c.is_ascii_digit() || c == '.') =>
+            {
+                self.setting_input = Some(c.to_string());
+                self.setting_error = None;
+            }
+            KeyCode::Esc | KeyCode::Char('o') => self.settings = None,
+            KeyCode::Enter => {
+                self.save_settings();
+                self.settings = None;
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.settings = Some(row.saturating_sub(1)),
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.settings = Some((row + 1).min(Setting::ALL.len() - 1))
+            }
+            KeyCode::Tab | KeyCode::BackTab => {
+                let forward = code == KeyCode::Tab;
+                self.settings = Some(focus::step(row, Setting::ALL.len(), forward))
+            }
+            KeyCode::Home => self.settings = Some(0),
+            KeyCode::End => self.settings = Some(Setting::ALL.len() - 1),
+            KeyCode::Left | KeyCode::Char('h') => self.change_setting(setting, false),
+            KeyCode::Right | KeyCode::Char('l') => self.change_setting(setting, true),
+            _ => {}
+        }
+    }
+
+    /// ask first when quitting drops clicks that are not saved
+    fn quit(&mut self) {
+        let clicks = self.session.click_count();
+        let results = self.results_screen && !self.mini && self.blind().is_none();
+        if clicks == 0 || self.viewing.is_some() || (self.saving && !results) {
+            self.quitting = true;
+            return;
+        }
+        if results && self.results.is_none() {
+            self.results = Some(0);
+            self.table.popup = false;
+            self.settings = None;
+            return;
+        }
+        let question = match &self.saved {
+            Some(path) => i18n::fmt(Msg::ConfirmLeave, &[&path.display()]),
+            None if self.saving => tr(Msg::ConfirmLeaveSaving).to_string(),
+            None if results => i18n::fmt(Msg::ConfirmLeaveUnsaved, &[&i18n::number(clicks)]),
+            None => i18n::fmt(Msg::ConfirmQuit, &[&i18n::number(clicks)]),
+        };
+        self.confirm = Some(Confirm::new(Action::Quit, question));
+    }
+
+    /// the chatter of the baseline against this one's, marked once this one chatters more
+    fn before(&self) -> Option<Line<'static>> {
+        let baseline = self.baseline.as_ref()?;
+        let (before, now) = (baseline.chatter_rate(), self.session.chatter_rate());
+        let mut text = i18n::fmt(
+            Msg::Before,
+            &[
+                &i18n::number(baseline.click_count()),
+                &i18n::decimal(before * 100.0, 1),
+                &i18n::decimal(now * 100.0, 1),
+            ],
+        );
+        // a baseline of an older build was judged by its detectors
+        if let (Some(then), Some(build)) = (&baseline.build, &self.session.build) {
+            if !then.same_code(build) {
+                text.push_str("  ");
+                text.push_str(&i18n::fmt(
+                    Msg::BuildDiffers,
+                    &[&then.describe(), &build.describe()],
+                ));
+            }
+        }
+        let style = if now > before {
+            theme::palette().chatter
+        } else {
+            Style::default()
+        };
+        Some(Line::from(Span::styled(text, style)))
+    }
+
+    /// the keys of the results screen, esc goes back to the test
+    fn on_results_key(&mut self, page: usize, code: KeyCode) {
+        let pages = RESULT_PAGES.len();
+        match code {
+            KeyCode::Right | KeyCode::Tab | KeyCode::Char('l') => {
+                self.results = Some(focus::step(page, pages, true))
+            }
+            KeyCode::Left | KeyCode::BackTab | KeyCode::Char('h') => {
+                self.results = Some(focus::step(page, pages, false))
+            }
+            KeyCode::Home => self.results = Some(0),
+            KeyCode::End => self.results = Some(pages - 1),
+            KeyCode::Char(c @ '1'..='9') => {
+                let chosen = c as usize - '1' as usize;
+                if chosen < pages {
+                    self.results = Some(chosen);
+                }
+            }
+            KeyCode::Char('s') => {
+                let text = match self.save_results() {
+                    Ok(path) => i18n::fmt(Msg::ResultsSaved, &[&path.display()]),
+                    Err(e) => e.to_string(),
+                };
+                self.toasts.push(text);
+            }
+            KeyCode::Char('e') => {
+                let text = match self.export_results() {
+                    Ok(path) => i18n::fmt(Msg::ResultsExported, &[&path.display()]),
+                    Err(e) => e.to_string(),
+                };
+                self.toasts.push(text);
+            }
+            KeyCode::Char('y') => {
+                let summary = Summary::new(&self.session, self.estimate()).render(Format::Text);
+                let text = match terminal::copy(&summary) {
+                    Ok(()) => tr(Msg::ResultsCopied).to_string(),
+                    Err(e) => e.to_string(),
+                };
+                self.toasts.push(text);
+            }
+            KeyCode::Char('n') => self.restart(false),
+            KeyCode::Char('b') => self.restart(true),
+            KeyCode::Esc => self.results = None,
+            KeyCode::Enter => self.quit(),
+            _ => {}
+        }
+    }
+
+    /// a fresh session with the settings of this one, `compare` keeps this one to show its
+    /// numbers next to the new ones; a session meant to be saved is saved first
+    fn restart(&mut self, compare: bool) {
+        if self.saving && self.saved.is_none() {
+            let text = match self.save_results() {
+                Ok(path) => i18n::fmt(Msg::ResultsSaved, &[&path.display()]),
+                Err(e) => e.to_string(),
+            };
+            self.toasts.push(text);
+        }
+        let baseline = compare.then(|| self.session.clone());
+        self.start_over();
+        if compare {
+            self.baseline = baseline;
+        }
+        self.results = None;
+        self.saved = None;
+        self.toasts.push(tr(Msg::Restarted).to_string());
+    }
+
+    /// the session saved again goes over the file it was saved to or resumed from
+    fn save_results(&mut self) -> error::Result<PathBuf> {
+        let path = match self.saved.clone().or_else(|| self.resumed_from.clone()) {
+            Some(path) => {
+                self.session.save_to(&path)?;
+                path
+            }
+            None => self.session.save()?,
+        };
+        self.saved = Some(path.clone());
+        Ok(path)
+    }
+
+    /// the html report and the svg charts next to the saved session, saving it first
+    fn export_results(&mut self) -> error::Result<PathBuf> {
+        let path = match self.saved.clone() {
+            Some(path) => path,
+            None => self.save_results()?,
+        };
+        let session = Session::load(&path)?;
+        let summary = Summary::new(&session, self.estimate());
+        svg::write(&session, &path)?;
+        html::write(&session, &summary, &path)
+    }
+
+    /// drop the clicks so far and begin a new session with the same settings
+    fn start_over(&mut self) {
+        self.session.discard_spill();
+        self.session.discard_checkpoint();
+        if let Ok(clock) = Clock::new(self.clock.source()) {
+            self.clock = clock;
+        }
+        // the terminal keeps its size, the new clock starts at zero
+        let screens = self
+            .session
+            .screens
+            .last()
+            .map(|s| {
+                vec![Screen {
+                    at: Duration::ZERO,
+                    ..*s
+                }]
+            })
+            .unwrap_or_default();
+        self.set_session(Session {
+            battery: battery::level(),
+            identity: usb::identity(),
+            operator: self.session.operator.clone(),
+            human_floor: self.session.human_floor,
+            clock: self.session.clock,
+            swapped: self.session.swapped,
+            stuck_after: self.session.stuck_after,
+            actuation_window: self.session.actuation_window,
+            loopback: self.session.loopback,
+            screens,
+            ..Session::new(self.session.device.clone(), self.duration)
+        });
+        self.resumed_from = None;
+        self.window_open = false;
+        self.instant = Duration::ZERO;
+        self.segment_start = Duration::ZERO;
+        self.pipeline.replay(&self.session);
+        self.checkpointed = (Instant::now(), 0);
+        self.table.selected = None;
+        self.table.popup = false;
+        self.table.offset = 0;
+        self.zoom = None;
+    }
+
+    fn on_confirm_key(&mut self, code: KeyCode) {
+        let Some(answer) = self.confirm.as_mut().and_then(|c| c.answer(code)) else {
+            return;
+        };
+        self.confirm = None;
+        match answer {
+            Some(Action::Quit) => self.quitting = true,
+            Some(Action::StartOver) => self.start_over(),
+            None => {}
+        }
+    }
+
+    fn on_key(&mut self, code: KeyCode) {
+        if self.confirm.is_some() {
+            self.on_confirm_key(code);
+            return;
+        }
+        if let Some(page) = self.results {
+            self.on_results_key(page, code);
+            return;
+        }
+        if self.resting.is_some() {
+            if code == KeyCode::Enter {
+                let text = self.end_break();
+                self.toasts.push(text);
+            }
+            return;
+        }
+        if self.mouse_missing() {
+            match code {
+                KeyCode::Enter => self.probe = Some(Instant::now()),
+                KeyCode::Esc => self.dismiss_probe(),
+                _ => {}
+            }
+            return;
+        }
+        if self.typing() {
+            self.on_prompt_key(code);
+            return;
+        }
+        if self.table.popup {
+            match code {
+                KeyCode::Esc | KeyCode::Enter => {
+                    self.table.popup = false;
+                    self.table.explain = false;
+                }
+                KeyCode::Char('x') => self.table.explain = !self.table.explain,
+                _ => {}
+            }
+            return;
+        }
+        if self.settings.is_some() {
+            self.on_settings_key(code);
+            return;
+        }
+
+        // the keys of the wheel over the list, a row, a page or to either end
+        let page = focus::page(self.list_area.height);
+        if let Some(rows) = Nav::of(code).and_then(|nav| nav.rows(page)) {
+            self.select_offset(rows);
+            return;
+        }
+        match code {
+            KeyCode::Char('k') => self.select_offset(-1),
+            KeyCode::Char('j') => self.select_offset(1),
+            KeyCode::Enter => self.table.popup = self.table.selected.is_some(),
+            KeyCode::Esc => self.table.selected = None,
+            KeyCode::Char('m') => theme::toggle_reduced_motion(),
+            KeyCode::Char('o') if !self.locked => self.settings = Some(0),
+            KeyCode::Char('f') => self.table.follow = !self.table.follow,
+            KeyCode::Char('/') => self.table.prompt = Some((Prompt::Filter, String::new())),
+            KeyCode::Char('s') => self.table.prompt = Some((Prompt::Search, String::new())),
+            KeyCode::Char('n') => self.jump(true),
+            KeyCode::Char('N') => self.jump(false),
+            KeyCode::Char('p') => self.snapshot = true,
+            KeyCode::Char('c') if self.viewing.is_none() => {
+                let done = self.toggle_capture();
+                self.toasts.push(done);
+            }
+            KeyCode::Tab => self.panes.cycle(true),
+            KeyCode::BackTab => self.panes.cycle(false),
+            KeyCode::Char('>') => self.panes.resize(true),
+            KeyCode::Char('<') => self.panes.resize(false),
+            KeyCode::Char('z') => self.panes.toggle_maximized(),
+            KeyCode::Char('g') => self.timeline = !self.timeline,
+            KeyCode::Char('a') => self.smooth = !self.smooth,
+            KeyCode::Char('+' | '=') => self.zoom_chart(true),
+            KeyCode::Char('-') => self.zoom_chart(false),
+            KeyCode::Char('h') => self.pan_chart(false),
+            KeyCode::Char('l') => self.pan_chart(true),
+            KeyCode::Char('r') if self.viewing.is_none() && self.session.click_count() > 0 => {
+                self.confirm = Some(Confirm::new(
+                    Action::StartOver,
+                    i18n::fmt(
+                        Msg::ConfirmStartOver,
+                        &[&i18n::number(self.session.click_count())],
+                    ),
+                ));
+            }
+            KeyCode::Char('d') if !self.locked => self.toggle_simulation(),
+            KeyCode::Left if !self.locked => self.adjust_window(-1),
+            KeyCode::Right if !self.locked => self.adjust_window(1),
+            KeyCode::Char('b') => {
+                if let Some(selected) = self.table.selected {
+                    let click = &mut self.session.clicks[selected];
+                    click.bookmarked = !click.bookmarked;
+                    self.pipeline.replace(&self.session);
+                }
+            }
+            KeyCode::Char('i') if self.viewing.is_none() => {
+                let text = self.mark_one_press();
+                self.toasts.push(text);
+            }
+            KeyCode::Char(']') => self.jump_to(true, |c| c.bookmarked),
+            KeyCode::Char('[') => self.jump_to(false, |c| c.bookmarked),
+            KeyCode::Char(c @ '1'..='4') => {
+                self.sort_by(SortColumn::ALL[c as usize - '1' as usize]);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// the clicks are spilled before they outgrow this, a click never reallocates
+fn make_room(session: &mut Session) {
+    let kept = KEEP_CLICKS + SPILL_CHUNK;
+    session
+        .clicks
+        .reserve(kept.saturating_sub(session.clicks.len()));
+}
+
+pub fn run_app<B: Backend>(
+    terminal: &mut Terminal<B>,
+    mut app: AppState,
+) -> error::Result<(Session, Option<PathBuf>)> {
+    let mut dirty = true;
+    let mut last_draw: Option<Instant> = None;
+    // a due frame was put off for the input waiting
+    let mut deferred = false;
+    app.restore_view();
+    let size = terminal.size()?;
+    app.on_resize(size.width, size.height);
+
+    loop {
+        // keep the window gauge moving while a group is open
+        let tick = if !app.window_open || theme::reduced_motion() {
+            IDLE_TICK
+        } else {
+            GAUGE_TICK
+        };
+        let since_draw = last_draw.map(|t| t.elapsed());
+        let frame_due = since_draw.is_none_or(|d| d >= MIN_FRAME);
+        let tick_due = since_draw.is_none_or(|d| d >= tick);
+
+        // waiting input goes first, the frame is put off for it up to `MAX_DEFER`
+        let behind = input::poll(Duration::ZERO)?;
+        if frame_due && (dirty || tick_due) && behind && since_draw.is_some_and(|d| d < MAX_DEFER) {
+            deferred = true;
+        } else if frame_due && (dirty || tick_due) {
+            if std::mem::take(&mut deferred) {
+                let slots = since_draw.unwrap_or_default().as_micros() / MIN_FRAME.as_micros();
+                app.session.backlog.skipped_frames += (slots as usize).saturating_sub(1);
+            }
+            let frame = terminal.draw(|f| ui(f, &mut app))?;
+            dirty = false;
+            last_draw = Some(Instant::now());
+            #[cfg(feature = "cast")]
+            if let Some(cast) = app.cast.as_mut() {
+                cast.frame(frame.buffer)?;
+            }
+            if std::mem::take(&mut app.snapshot) {
+                app.toasts.push(match snapshot::save(frame.buffer) {
+                    Ok(path) => i18n::fmt(Msg::SnapshotSaved, &[&path.display()]),
+                    Err(e) => e.to_string(),
+                });
+                dirty = true;
+            }
+        }
+
+        if let Some(e) = app.autosave() {
+            app.toasts.push(e);
+            dirty = true;
+        }
+        if let Some(suspended) = app.watch_suspend() {
+            app.toasts.push(suspended);
+            dirty = true;
+        }
+        for stuck in app.watch_stuck() {
+            app.toasts.push(stuck);
+            dirty = true;
+        }
+        if let Some(reloaded) = app.reload_config() {
+            app.toasts.push(reloaded);
+            dirty = true;
+        }
+        let (answers, stop) = app.run_controls();
+        if let Some(answer) = answers.last() {
+            app.toasts.push(answer.clone());
+            dirty = true;
+        }
+        if app.toasts.expire() {
+            dirty = true;
+        }
+        if stop {
+            break;
+        }
+        if app.results.is_none() && app.break_due() {
+            app.take_break();
+            dirty = true;
+        }
+
+        let since_draw = last_draw.map(|t| t.elapsed()).unwrap_or_default();
+        let mut timeout = if dirty {
+            MIN_FRAME.saturating_sub(since_draw)
+        } else {
+            tick.saturating_sub(since_draw)
+        };
+        if app.controlled() {
+            timeout = timeout.min(control::POLL);
+        }
+        if let Some(expiry) = app.toasts.next_expiry() {
+            timeout = timeout.min(expiry);
+        }
+        if !input::poll(timeout)? {
+            continue;
+        }
+
+        dirty = true;
+        let event = input::read()?;
+        if app.viewing.is_none() && input::poll(Duration::ZERO)? {
+            app.session.backlog.events += 1;
+        }
+        match event {
+            Event::Mouse(event) => {
+                if app.viewing.is_none() {
+                    app.mouse_arrived();
+                }
+                match event.kind {
+                    MouseEventKind::ScrollUp => app.on_scroll(event.column, event.row, -1),
+                    MouseEventKind::ScrollDown => app.on_scroll(event.column, event.row, 1),
+                    // a saved session only scrolls, the results take no more clicks
+                    _ if app.viewing.is_some() || app.results.is_some() => {}
+                    MouseEventKind::Down(_) => app.on_click(event),
+                    MouseEventKind::Up(button) => app.on_release(button),
+                    MouseEventKind::Moved | MouseEventKind::Drag(_) => app.on_move(),
+                    _ => {}
+                }
+            }
+            Event::Key(event)
+                if event.code == KeyCode::Char('q') && !app.typing() && app.confirm.is_none() =>
+            {
+                app.quit()
+            }
+            Event::Key(event)
+                if event.code == KeyCode::Char(' ') && !app.typing() && app.viewing.is_none() =>
+            {
+                app.on_key_click()
+            }
+            Event::Key(event) => app.on_key(event.code),
+            Event::Resize(columns, rows) => app.on_resize(columns, rows),
+            _ => {}
+        }
+        if app.quitting {
+            break;
+        }
+    }
+
+    // the test result matters more than the layout, a view that is not saved is only
+    // the defaults next time
+    let _ = app.save_view();
+    Ok(app.finish())
+}
+
+pub fn ui<B: Backend>(f: &mut Frame<B>, app: &mut AppState) {
+    let size = f.size();
+    if app.mini {
+        mini_view(f, app, size);
+        return;
+    }
+    if let Some((done, total)) = app.blind() {
+        blind_view(f, app, done, total, size);
+        return;
+    }
+    if let Some(page) = app.results {
+        results_view(f, app, page, size);
+        return;
+    }
+    if !app.captured && app.viewing.is_none() {
+        selection_view(f, app, size);
+        return;
+    }
+
+    let screen = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)].as_ref())
+        .split(size);
+    let areas = app.panes.split(screen[1]);
+
+    window_gauge(f, app, areas.gauge);
+
+    let outliers = app.session.gap_outliers();
+    app.list_area = Rect::default();
+    if let Some(area) = areas.list {
+        event_table(f, app, &outliers, area);
+    }
+    if !theme::reduced_motion() || app.chart_at.elapsed() >= IDLE_TICK {
+        app.chart_clicks = app.session.click_count();
+        app.chart_at = Instant::now();
+    }
+    if let Some(area) = areas.chart {
+        if app.timeline {
+            timeline::draw(f, &app.session, app.panes.focus == Pane::Chart, area);
+        } else {
+            interval_chart(f, app, area);
+        }
+    }
+
+    let mut stats = vec![Line::from(i18n::fmt(
+        Msg::Clicks,
+        &[&i18n::number(app.session.click_count())],
+    ))];
+    let snapshot = app.pipeline.frame(app.now());
+    if snapshot.stale {
+        app.session.backlog.stale_frames += 1;
+    }
+    stats.extend(snapshot.lines);
+    if !app.records.is_empty() {
+        stats.push(Line::from(app.records.describe()));
+    }
+    let backlog = app.session.backlog;
+    if backlog.events > 0 {
+        stats.push(Line::styled(
+            i18n::fmt(
+                Msg::Backlog,
+                &[
+                    &i18n::number(backlog.events),
+                    &i18n::number(backlog.skipped_frames),
+                ],
+            ),
+            theme::palette().outlier,
+        ));
+    }
+    if app.simulating {
+        stats.push(Line::from(""));
+        stats.extend(simulation(app).into_iter().map(Line::from));
+    }
+
+    let mut banner = Vec::new();
+    if app.viewing.is_none() {
+        banner.push(if app.captured {
+            Span::raw(tr(Msg::CaptureOn))
+        } else {
+            Span::styled(tr(Msg::CaptureOff), theme::palette().outlier)
+        });
+    }
+    for verdict in snapshot.banner {
+        if !banner.is_empty() {
+            banner.push(Span::raw("  |  "));
+        }
+        banner.extend(verdict.spans);
+    }
+    f.render_widget(Paragraph::new(Line::from(banner)), screen[0]);
+    stats.push(Line::from(""));
+    stats.push(Line::from(app.estimate().describe()));
+    stats.extend(app.before());
+
+    let p = Paragraph::new(stats)
+        .block(app.panes.block(Pane::Stats, tr(Msg::Stats)))
+        .wrap(Wrap { trim: true });
+
+    if let Some(area) = areas.stats {
+        f.render_widget(p, area);
+    }
+
+    // messages stay below the popups asking for keys
+    toasts(f, &app.toasts, size);
+    if let (true, Some(selected)) = (app.table.popup, app.table.selected) {
+        event_popup(f, app, selected, &outliers, size);
+    }
+    if app.settings.is_some() {
+        settings(f, app, size);
+    }
+    if app.mouse_missing() {
+        no_mouse(f, size);
+    }
+    if let Some(since) = app.resting {
+        rest(f, app, since, size);
+    }
+    if let Some(confirm) = &app.confirm {
+        confirm.draw(f, size);
+    }
+}
+
+/// the pages of the results screen shown on quitting
+const RESULT_PAGES: [Msg; 3] = [Msg::Stats, Msg::ResultsVerdict, Msg::ResultsChart];
+
+/// the summary, the verdicts or the interval chart, a page at a time until the test is left
+fn results_view<B: Backend>(f: &mut Frame<B>, app: &mut AppState, page: usize, area: Rect) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Length(3),
+                Constraint::Min(0),
+                Constraint::Length(1),
+            ]
+            .as_ref(),
+        )
+        .split(area);
+    let palette = theme::palette();
+    let titles = RESULT_PAGES
+        .iter()
+        .enumerate()
+        .map(|(i, msg)| Line::from(focus::title(&format!("{} {}", i + 1, tr(*msg)), i == page)))
+        .collect::<Vec<_>>();
+    f.render_widget(
+        Tabs::new(titles)
+            .select(page)
+            .highlight_style(palette.focus)
+            .block(
+                Block::default()
+                    .title(tr(Msg::ResultsTitle))
+                    .borders(Borders::ALL),
+            ),
+        rows[0],
+    );
+
+    let lines = match page {
+        0 => Summary::new(&app.session, app.estimate())
+            .render(Format::Text)
+            .lines()
+            .map(|l| Line::from(l.to_string()))
+            .collect(),
+        1 => {
+            let mut lines = app.pipeline.frame(app.now()).banner;
+            lines.push(Line::from(""));
+            lines.push(Line::from(app.estimate().describe()));
+            lines.extend(app.before());
+            lines
+        }
+        _ => {
+            interval_chart(f, app, rows[1]);
+            Vec::new()
+        }
+    };
+    if !lines.is_empty() {
+        f.render_widget(
+            Paragraph::new(lines)
+                .block(Block::default().borders(Borders::ALL))
+                .wrap(Wrap { trim: false }),
+            rows[1],
+        );
+    }
+    f.render_widget(Paragraph::new(tr(Msg::ResultsKeys)), rows[2]);
+
+    toasts(f, &app.toasts, area);
+    if let Some(confirm) = &app.confirm {
+        confirm.draw(f, area);
+    }
+}
+
+/// the break reminder, capture is paused until enter
+fn rest<B: Backend>(f: &mut Frame<B>, app: &AppState, since: Duration, area: Rect) {
+    let segment = app.segment();
+    let minutes = since.saturating_sub(app.segment_start).as_secs() / 60;
+    let resting = Duration::from_secs(app.clock.now().saturating_sub(since).as_secs());
+    let lines = [
+        i18n::fmt(
+            Msg::BreakText,
+            &[
+                &i18n::number(segment - 1),
+                &i18n::number(minutes),
+                &i18n::duration(resting),
+            ],
+        ),
+        String::new(),
+        i18n::fmt(Msg::BreakKeys, &[&i18n::number(segment)]),
+    ];
+    let width = 60.min(area.width);
+    let inner = width.saturating_sub(2).max(1) as usize;
+    let rows = lines
+        .iter()
+        .map(|l| l.width().div_ceil(inner).max(1))
+        .sum::<usize>();
+    let popup = centered(area, width, rows as u16 + 2);
+    f.render_widget(Clear, popup);
+    f.render_widget(
+        Paragraph::new(lines.into_iter().map(Line::from).collect::<Vec<_>>())
+            .block(
+                Block::default()
+                    .title(tr(Msg::BreakTitle))
+                    .borders(Borders::ALL)
+                    .border_style(theme::palette().focus),
+            )
+            .wrap(Wrap { trim: true }),
+        popup,
+    );
+}
+
+/// the last interval, the counts and the verdict, a question in their place while one is
+/// asked and only the progress during a blind test; there is no room for the messages
+fn mini_view<B: Backend>(f: &mut Frame<B>, app: &mut AppState, area: Rect) {
+    let palette = theme::palette();
+    let lines = if let Some(confirm) = &app.confirm {
+        vec![
+            Line::styled(confirm.text().to_string(), palette.focus),
+            confirm.buttons(),
+        ]
+    } else if let Some((done, total)) = app.blind() {
+        vec![
+            Line::from(i18n::fmt(
+                Msg::BlindProgress,
+                &[&i18n::number(done), &i18n::number(total)],
+            )),
+            Line::from(tr(Msg::BlindHint)),
+        ]
+    } else {
+        let last = match app.session.clicks.last() {
+            None => Line::from(tr(Msg::MiniWaiting)),
+            Some(click) => match click.interval {
+                Some(interval) => Line::styled(
+                    i18n::fmt(
+                        Msg::MiniLast,
+                        &[
+                            &session::button_name(click.button),
+                            &i18n::duration(interval),
+                        ],
+                    ),
+                    palette.chatter,
+                ),
+                None => Line::from(i18n::fmt(
+                    Msg::MiniLast,
+                    &[&session::button_name(click.button), &tr(Msg::WindowClean)],
+                )),
+            },
+        };
+        let counts = Line::from(i18n::fmt(
+            Msg::MiniCounts,
+            &[
+                &i18n::number(app.session.click_count()),
+                &i18n::number(app.session.chatter_count()),
+                &i18n::decimal(app.session.chatter_rate() * 100.0, 1),
+            ],
+        ));
+        let snapshot = app.pipeline.frame(app.now());
+        let verdict = snapshot
+            .banner
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| Line::from(app.estimate().describe()));
+        vec![last, counts, verdict]
+    };
+    f.render_widget(Paragraph::new(lines), area);
+}
+
+/// how far the blind protocol got and nothing else
+fn blind_view<B: Backend>(f: &mut Frame<B>, app: &AppState, done: usize, total: usize, area: Rect) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Percentage(40),
+                Constraint::Length(3),
+                Constraint::Length(1),
+                Constraint::Min(0),
+            ]
+            .as_ref(),
+        )
+        .split(area);
+    let progress = Gauge::default()
+        .block(Block::default().borders(Borders::ALL))
+        .gauge_style(theme::palette().interval)
+        .ratio((done as f64 / total as f64).min(1.0))
+        .label(i18n::fmt(
+            Msg::BlindProgress,
+            &[&i18n::number(done), &i18n::number(total)],
+        ));
+    f.render_widget(progress, rows[1]);
+    f.render_widget(
+        Paragraph::new(tr(Msg::BlindHint)).alignment(Alignment::Center),
+        rows[2],
+    );
+    if let Some(confirm) = &app.confirm {
+        confirm.draw(f, area);
+    }
+}
+
+/// the summary as bare lines while the mouse is free, so what the terminal copies has no
+/// borders of the panes in it
+fn selection_view<B: Backend>(f: &mut Frame<B>, app: &AppState, area: Rect) {
+    let summary = Summary::new(&app.session, app.estimate()).render(Format::Text);
+    let mut lines = vec![
+        Line::styled(tr(Msg::CaptureOff), theme::palette().outlier),
+        Line::from(""),
+    ];
+    lines.extend(summary.lines().map(|l| Line::from(l.to_string())));
+    f.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), area);
+    if let Some(confirm) = &app.confirm {
+        confirm.draw(f, area);
+    }
+}
+
+/// the messages stacked in the bottom right corner, newest last
+fn toasts<B: Backend>(f: &mut Frame<B>, toasts: &Toasts, area: Rect) {
+    if toasts.queue.is_empty() {
+        return;
+    }
+    let texts = toasts
+        .queue
+        .iter()
+        .map(|(toast, _)| toast.text())
+        .collect::<Vec<_>>();
+    let width = texts
+        .iter()
+        .map(|text| text.width() as u16 + 2)
+        .max()
+        .unwrap_or_default()
+        .min(area.width / 2)
+        .max(3);
+    let mut bottom = area.y + area.height;
+    for text in texts.iter().rev() {
+        let inner = width.saturating_sub(2).max(1) as usize;
+        let height = text.width().div_ceil(inner).max(1) as u16 + 2;
+        if bottom < area.y + height {
+            break;
+        }
+        bottom -= height;
+        let toast = Rect {
+            x: area.x + area.width.saturating_sub(width),
+            y: bottom,
+            width: width.min(area.width),
+            height,
+        };
+        f.render_widget(Clear, toast);
+        f.render_widget(
+            Paragraph::new(text.as_ref())
+                .block(Block::default().borders(Borders::ALL))
+                .wrap(Wrap { trim: true }),
+            toast,
+        );
+    }
+}
+
+fn settings<B: Backend>(f: &mut Frame<B>, app: &AppState, area: Rect) {
+    let labels = Setting::ALL
+        .iter()
+        .map(|s| s.label().width())
+        .max()
+        .unwrap_or_default();
+    let mut lines = Setting::ALL
+        .iter()
+        .enumerate()
+        .map(|(i, setting)| {
+            let label = setting.label();
+            let value = match &app.setting_input {
+                Some(input) if app.settings == Some(i) => format!("{}_", input),
+                _ => app.setting_value(*setting),
+            };
+            let text = format!(
+                "{}{}{}  ‹ {} › ",
+                if app.settings == Some(i) {
+                    focus::MARKER
+                } else {
+                    "  "
+                },
+                label,
+                " ".repeat(labels - label.width()),
+                value
+            );
+            if app.settings == Some(i) {
+                Line::styled(text, theme::palette().highlight)
+            } else {
+                Line::from(text)
+            }
+        })
+        .collect::<Vec<_>>();
+    lines.push(Line::from(""));
+    lines.push(Line::from(tr(Msg::SettingsKeys)));
+    let bare = app
+        .settings
+        .and_then(|row| Setting::ALL[row].bare_unit())
+        .filter(|_| app.setting_input.is_some());
+    if let Some(bare) = bare {
+        let unit = if bare >= Duration::from_secs(1) {
+            "s"
+        } else {
+            "ms"
+        };
+        lines.push(match &app.setting_error {
+            Some(e) => Line::styled(format!(" {}", e), theme::palette().chatter),
+            None => Line::from(format!(" {}", i18n::fmt(Msg::SettingsDuration, &[&unit]))),
+        });
+    }
+
+    let width = lines.iter().map(Line::width).max().unwrap_or_default() as u16 + 2;
+    let popup = centered(area, width, lines.len() as u16 + 2);
+    f.render_widget(Clear, popup);
+    f.render_widget(
+        Paragraph::new(lines).block(
+            Block::default()
+                .title(tr(Msg::SettingsTitle))
+                .borders(Borders::ALL),
+        ),
+        popup,
+    );
+}
+
+/// why nothing is recorded and what to try, for both the tui and the plain output
+pub fn no_mouse_help() -> Vec<String> {
+    vec![
+        i18n::fmt(Msg::NoMouse, &[&i18n::number(MOUSE_PROBE.as_secs())]),
+        tr(Msg::NoMouseTerminal).to_string(),
+        tr(Msg::NoMouseTmux).to_string(),
+    ]
+}
+
+fn no_mouse<B: Backend>(f: &mut Frame<B>, area: Rect) {
+    let lines = no_mouse_help()
+        .into_iter()
+        .chain([tr(Msg::NoMouseKeys).to_string()])
+        .collect::<Vec<_>>();
+    let width = 72.min(area.width);
+    // rows of the wrapped lines with a blank row after each
+    let inner = width.saturating_sub(2).max(1) as usize;
+    let rows = lines
+        .iter()
+        .map(|l| l.width().div_ceil(inner) + 1)
+        .sum::<usize>();
+    let text = lines
+        .into_iter()
+        .flat_map(|line| [Line::from(line), Line::from("")])
+        .collect::<Vec<_>>();
+
+    let popup = centered(area, width, rows as u16 + 2);
+    f.render_widget(Clear, popup);
+    f.render_widget(
+        Paragraph::new(text)
+            .block(
+                Block::default()
+                    .title(tr(Msg::NoMouseTitle))
+                    .borders(Borders::ALL),
+            )
+            .wrap(Wrap { trim: true }),
+        popup,
+    );
+}
+
+/// raw against debounced counts of the simulated firmware debounce
+fn simulation(app: &AppState) -> Vec<String> {
+    let raw = &app.session;
+    let debounced = raw.debounced(app.debounce);
+    let percent = |s: &Session| format!("{}%", i18n::decimal(s.chatter_rate() * 100.0, 1));
+    vec![
+        i18n::fmt(Msg::Simulating, &[&i18n::number(app.debounce.as_millis())]),
+        i18n::fmt(
+            Msg::SimulatedClicks,
+            &[
+                &i18n::number(raw.click_count()),
+                &i18n::number(debounced.click_count()),
+            ],
+        ),
+        i18n::fmt(
+            Msg::SimulatedChatter,
+            &[
+                &i18n::number(raw.chatter_count()),
+                &i18n::number(debounced.chatter_count()),
+                &percent(raw),
+                &percent(&debounced),
+            ],
+        ),
+    ]
+}
+
+fn flags(click: &Click, outlier: bool) -> String {
+    let mut flags = Vec::new();
+    if click.interval.is_some() {
+        flags.push(tr(Msg::FlagChatter));
+    }
+    if outlier {
+        flags.push(tr(Msg::FlagOutlier));
+    }
+    if click.bookmarked {
+        flags.push(tr(Msg::FlagBookmark));
+    }
+    if click.keyboard {
+        flags.push(tr(Msg::FlagKeyboard));
+    }
+    flags.join(" ")
+}
+
+fn ms(d: Option<Duration>) -> String {
+    d.map(i18n::duration).unwrap_or_else(|| "-".to_string())
+}
+
+fn event_table<B: Backend>(f: &mut Frame<B>, app: &mut AppState, outliers: &[bool], area: Rect) {
+    app.list_area = area;
+    // the name given with `--device` and what the mouse says it is
+    let device = match (&app.session.device, &app.session.identity) {
+        (Some(device), Some(identity)) => Some(format!("{} - {}", device, identity.describe())),
+        (device, identity) => device.clone().or(identity.as_ref().map(|i| i.describe())),
+    };
+    let title = match (&device, app.session.battery) {
+        (Some(device), Some(battery)) => i18n::fmt(Msg::DeviceBattery, &[device, &battery]),
+        (None, Some(battery)) => i18n::fmt(Msg::Battery, &[&battery]),
+        (device, None) => device.clone().unwrap_or_default(),
+    };
+    let mut rate = i18n::fmt(Msg::EventRate, &[&app.moves.len()]);
+    if let Some(polling_rate) = app.polling_rate {
+        rate.push_str(&i18n::fmt(Msg::UsbRate, &[&polling_rate]));
+    }
+    if !app.table.follow {
+        rate.push_str(tr(Msg::NotFollowing));
+    }
+    let bottom = match &app.table.prompt {
+        Some((kind, prompt)) => Line::styled(
+            i18n::fmt(
+                match kind {
+                    Prompt::Filter => Msg::FilterPrompt,
+                    Prompt::Search => Msg::SearchPrompt,
+                },
+                &[prompt],
+            ),
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        None => Line::from(rate),
+    };
+
+    let order = app.sorted();
+    let mut title = title;
+    if app.table.filter != Filter::All {
+        let hidden = app.session.clicks.len() - order.len();
+        title.push_str(&i18n::fmt(
+            Msg::FilterHidden,
+            &[&app.table.filter.name(), &i18n::number(hidden)],
+        ));
+    }
+    if let Some(query) = &app.table.search {
+        let matches = order
+            .iter()
+            .filter(|i| query.matches(&app.session.clicks[**i]))
+            .count();
+        title.push_str(&i18n::fmt(
+            Msg::SearchMatches,
+            &[&query.text, &i18n::number(matches)],
+        ));
+    }
+
+    let block = app
+        .panes
+        .block(Pane::List, &title)
+        .title(Title::from(bottom).position(Position::Bottom));
+
+    if app.session.clicks.is_empty() {
+        let p = Paragraph::new(tr(Msg::ClickPrompt))
+            .block(block)
+            .alignment(Alignment::Center);
+        f.render_widget(p, area);
+        return;
+    }
+
+    let header_names = [
+        "#",
+        tr(Msg::ColTime),
+        tr(Msg::ColButton),
+        tr(Msg::ColInterval),
+        tr(Msg::ColHold),
+        tr(Msg::ColFlags),
+    ];
+    let sort_column = match app.table.sort {
+        SortColumn::Index => 0,
+        SortColumn::Button => 2,
+        SortColumn::Interval => 3,
+        SortColumn::Hold => 4,
+    };
+    let header = Row::new(header_names.iter().enumerate().map(|(i, name)| {
+        let marker = match (i == sort_column, app.table.descending) {
+            (true, true) => " v",
+            (true, false) => " ^",
+            _ => "",
+        };
+        let name = format!("{}{}", name, marker);
+        // over the right aligned times
+        if i == 3 || i == 4 {
+            Cell::from(format!("{:>9}", name))
+        } else {
+            Cell::from(name)
+        }
+    }))
+    .style(Style::default().add_modifier(Modifier::BOLD));
+
+    // borders and header
+    let visible = (area.height as usize).saturating_sub(3).max(1);
+    let position = app
+        .table
+        .selected
+        .and_then(|s| order.iter().position(|i| *i == s));
+    let table = &mut app.table;
+    if table.follow && position.is_none() && table.sort == SortColumn::Index {
+        table.offset = if table.descending { 0 } else { order.len() };
+    }
+    if let Some(position) = position {
+        if position < table.offset {
+            table.offset = position;
+        } else if position >= table.offset + visible {
+            table.offset = position + 1 - visible;
+        }
+    }
+    table.offset = table.offset.min(order.len().saturating_sub(visible));
+    let end = (table.offset + visible).min(order.len());
+
+    let palette = theme::palette();
+    let rows = order[table.offset..end].iter().map(|i| {
+        let click = &app.session.clicks[*i];
+        let (style, mark) = if outliers[*i] {
+            (palette.outlier, palette.outlier_mark)
+        } else if click.interval.is_some() {
+            (palette.chatter, palette.chatter_mark)
+        } else {
+            (Style::default(), "")
+        };
+        Row::new([
+            format!(
+                "{}{}",
+                mark,
+                i18n::number(app.session.spilled.clicks + i + 1)
+            ),
+            format!("{} s", i18n::decimal(click.at.as_secs_f64(), 3)),
+            session::button_name(click.button).to_string(),
+            // right aligned so the units line up
+            format!("{:>9}", ms(click.shown_interval())),
+            format!("{:>9}", ms(click.hold)),
+            flags(click, outliers[*i]),
+        ])
+        .style(style)
+    });
+
+    let mut state = TableState::default();
+    state.select(position.map(|p| p - table.offset));
+
+    let table = Table::new(rows)
+        .header(header)
+        .block(block)
+        .widths(&[
+            Constraint::Length(9),
+            Constraint::Length(12),
+            Constraint::Length(8),
+            Constraint::Length(10),
+            Constraint::Length(10),
+            Constraint::Min(8),
+        ])
+        .highlight_style(palette.highlight)
+        .highlight_symbol(focus::MARKER);
+
+    f.render_stateful_widget(table, area, &mut state);
+}
+
+fn event_popup<B: Backend>(
+    f: &mut Frame<B>,
+    app: &AppState,
+    selected: usize,
+    outliers: &[bool],
+    area: Rect,
+) {
+    let clicks = &app.session.clicks;
+    let click = &clicks[selected];
+    let gap = selected
+        .checked_sub(1)
+        .map(|prev| click.at - clicks[prev].at);
+    // the start is only known to the second, so is the wall clock
+    let wall = app.session.started_at + click.at.as_secs();
+    let relative = app.session.relative(click).map(|(x, y)| {
+        i18n::fmt(
+            Msg::PopupShare,
+            &[
+                &i18n::number((x * 100.0).round() as u64),
+                &i18n::number((y * 100.0).round() as u64),
+            ],
+        )
+    });
+    let position = click
+        .position
+        .map(|(column, row)| {
+            let cell = i18n::fmt(
+                Msg::PopupCell,
+                &[&i18n::number(column + 1), &i18n::number(row + 1)],
+            );
+            match &relative {
+                Some(share) => format!("{} ({})", cell, share),
+                None => cell,
+            }
+        })
+        .unwrap_or_else(|| "-".to_string());
+    let modifiers = Some(session::modifier_names(click.modifiers))
+        .filter(|m| !m.is_empty())
+        .unwrap_or_else(|| "-".to_string());
+    // the notes placed since the click before
+    let since = selected.checked_sub(1).map(|prev| clicks[prev].at);
+    let notes = app
+        .session
+        .marks
+        .iter()
+        .filter(|m| m.at <= click.at && since.is_none_or(|s| m.at > s))
+        .map(|m| m.note.as_str())
+        .collect::<Vec<_>>();
+    let notes = if notes.is_empty() {
+        "-".to_string()
+    } else {
+        notes.join(", ")
+    };
+
+    let mut text = vec![
+        Line::from(i18n::fmt(
+            Msg::PopupClick,
+            &[&i18n::number(app.session.spilled.clicks + selected + 1)],
+        )),
+        Line::from(i18n::fmt(
+            Msg::PopupTime,
+            &[&i18n::decimal(click.at.as_secs_f64(), 3)],
+        )),
+        Line::from(i18n::fmt(
+            Msg::PopupWallClock,
+            &[&format!(
+                "{}:{:02}",
+                session::format_timestamp(wall),
+                wall % 60
+            )],
+        )),
+        Line::from(i18n::fmt(
+            Msg::PopupButton,
+            &[&session::button_name(click.button)],
+        )),
+        Line::from(i18n::fmt(
+            Msg::PopupInterval,
+            &[&ms(click.shown_interval())],
+        )),
+        Line::from(i18n::fmt(Msg::PopupGap, &[&ms(gap)])),
+        Line::from(i18n::fmt(Msg::PopupHold, &[&ms(click.hold)])),
+        Line::from(i18n::fmt(Msg::PopupPosition, &[&position])),
+        Line::from(i18n::fmt(Msg::PopupModifiers, &[&modifiers])),
+        Line::from(i18n::fmt(
+            Msg::PopupFlags,
+            &[&flags(click, outliers[selected])],
+        )),
+        Line::from(i18n::fmt(Msg::PopupNotes, &[&notes])),
+        Line::from(""),
+    ];
+    if app.table.explain {
+        let mut why = explain::click(&app.session, selected, outliers[selected]);
+        if why.is_empty() {
+            why.push(tr(Msg::ExplainNothing).to_string());
+        }
+        text.push(Line::from(tr(Msg::ExplainTitle)));
+        text.extend(why.into_iter().map(Line::from));
+    } else {
+        text.push(Line::from(tr(Msg::ExplainHint)));
+    }
+    text.push(Line::from(""));
+    text.push(Line::from(tr(Msg::PopupContext)));
+    let from = selected.saturating_sub(POPUP_CONTEXT);
+    let to = (selected + POPUP_CONTEXT + 1).min(clicks.len());
+    for i in from..to {
+        let c = &clicks[i];
+        let row = format!(
+            "{:>7}  {:>10} s  {:<6}  {:>6}  {}",
+            i18n::number(app.session.spilled.clicks + i + 1),
+            i18n::decimal(c.at.as_secs_f64(), 3),
+            session::button_name(c.button),
+            ms(i.checked_sub(1).map(|prev| c.at - clicks[prev].at)),
+            flags(c, outliers[i])
+        );
+        text.push(if i == selected {
+            Line::styled(row, theme::palette().highlight)
+        } else {
+            Line::from(row)
+        });
+    }
+
+    // the explanations wrap, every line takes as many rows as it needs
+    let inner = usize::from(POPUP_WIDTH - 2);
+    let rows = text
+        .iter()
+        .map(|l| l.width().div_ceil(inner).max(1))
+        .sum::<usize>();
+    let popup = centered(area, POPUP_WIDTH, rows as u16 + 2);
+    f.render_widget(Clear, popup);
+    f.render_widget(
+        Paragraph::new(text)
+            .wrap(Wrap { trim: false })
+            .block(Block::default().title(tr(Msg::Event)).borders(Borders::ALL)),
+        popup,
+    );
+}
+
+fn window_gauge<B: Backend>(f: &mut Frame<B>, app: &AppState, area: Rect) {
+    let remaining = if !app.window_open {
+        Duration::ZERO
+    } else {
+        app.duration
+            .saturating_sub(app.now().saturating_sub(app.instant))
+    };
+
+    let label = if remaining.is_zero() {
+        tr(Msg::WindowClosed).to_string()
+    } else {
+        i18n::fmt(
+            Msg::WindowOpen,
+            &[&i18n::decimal(remaining.as_secs_f64(), 1)],
+        )
+    };
+
+    let ratio = remaining.as_secs_f64() / app.duration.as_secs_f64().max(f64::EPSILON);
+    let gauge = Gauge::default()
+        .block(
+            Block::default()
+                .title(tr(Msg::DetectWindow))
+                .borders(Borders::ALL),
+        )
+        .ratio(ratio.clamp(0.0, 1.0))
+        .label(label);
+
+    f.render_widget(gauge, area);
+}
+
+fn interval_chart<B: Backend>(f: &mut Frame<B>, app: &AppState, area: Rect) {
+    let threshold = app.duration.as_secs_f64() * 1000.0;
+    let first = app.session.spilled.clicks;
+    let (from, to) = app.chart_range();
+    // the gap before click number n is the gap of clicks[n - 2 - first]
+    let points = app
+        .session
+        .gaps()
+        .enumerate()
+        .map(|(i, gap)| (first + i + 2, gap))
+        .skip_while(|(n, _)| *n < from)
+        .take_while(|(n, _)| *n <= to)
+        .map(|(n, gap)| (n as f64, gap.as_secs_f64() * 1000.0))
+        .collect::<Vec<_>>();
+    // a braille cell is two dots wide, more points than that only cost drawing time
+    let columns = usize::from(area.width) * 2;
+    let shown = |points: Vec<(f64, f64)>| {
+        let points = if app.smooth {
+            stats::moving_mean(&points, app.smoothing)
+        } else {
+            points
+        };
+        if app.downsample {
+            stats::min_max_bins(&points, columns)
+        } else {
+            points
+        }
+    };
+    let points = shown(points);
+    // the gaps of the baseline by the same click numbers, drawn behind the live ones
+    let ghost = app.baseline.as_ref().map_or_else(Vec::new, |baseline| {
+        baseline
+            .gaps()
+            .enumerate()
+            .map(|(i, gap)| (baseline.spilled.clicks + i + 2, gap))
+            .skip_while(|(n, _)| *n < from)
+            .take_while(|(n, _)| *n <= to)
+            .map(|(n, gap)| (n as f64, gap.as_secs_f64() * 1000.0))
+            .collect()
+    });
+    let ghost = shown(ghost);
+
+    let min_x = from as f64;
+    let max_x = (to as f64).max(min_x + 1.0);
+    // long pauses between tests would flatten the interesting range
+    let max_y = points
+        .iter()
+        .chain(&ghost)
+        .map(|p| p.1)
+        .fold(threshold * 1.5, f64::max)
+        .min(threshold * 4.0);
+    let threshold_line = [(min_x, threshold), (max_x, threshold)];
+
+    let palette = theme::palette();
+    let mut datasets = Vec::new();
+    if app.baseline.is_some() {
+        datasets.push(
+            Dataset::default()
+                .name(tr(Msg::Baseline))
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(palette.interval.add_modifier(Modifier::DIM))
+                .data(&ghost),
+        );
+    }
+    datasets.extend([
+        Dataset::default()
+            .name(tr(Msg::DetectWindow))
+            .marker(palette.threshold_marker)
+            .graph_type(GraphType::Line)
+            .style(palette.threshold)
+            .data(&threshold_line),
+        Dataset::default()
+            .name(if app.smooth && app.smoothing > 1 {
+                i18n::fmt(Msg::Smoothed, &[&app.smoothing])
+            } else {
+                tr(Msg::Interval).to_string()
+            })
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(palette.interval)
+            .data(&points),
+    ]);
+
+    let mut block = app.panes.block(Pane::Chart, tr(Msg::IntervalMs));
+    if app.zoom.is_some() {
+        // the stats of the zoomed part apart from the ones of the whole session
+        let shown = &app.session.clicks[from - first - 1..to - first];
+        let chatter = shown.iter().filter_map(|c| c.interval);
+        let text = i18n::fmt(
+            Msg::ChartWindow,
+            &[
+                &i18n::number(from),
+                &i18n::number(to),
+                &i18n::number(chatter.clone().count()),
+                &ms(chatter.min()),
+            ],
+        );
+        block = block.title(Title::from(text).position(Position::Bottom));
+    }
+
+    let chart = Chart::new(datasets)
+        .block(block)
+        .x_axis(
+            Axis::default()
+                .title(tr(Msg::ChartClick))
+                .bounds([min_x, max_x])
+                .labels(vec![
+                    format!("{}", min_x).into(),
+                    format!("{}", max_x).into(),
+                ]),
+        )
+        .y_axis(
+            Axis::default()
+                .bounds([0.0, max_y])
+                .labels(vec!["0".into(), format!("{:.0}", max_y).into()]),
+        );
+
+    f.render_widget(chart, area);
+}
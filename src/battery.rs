@@ -0,0 +1,31 @@
+#[cfg(feature = "battery")]
+use std::{fs, path::Path};
+
+#[cfg(feature = "battery")]
+const POWER_SUPPLY: &str = "/sys/class/power_supply";
+
+/// battery level in percent of the first wireless peripheral found
+///
+/// the kernel exposes hid device batteries as power supplies with the
+/// `Device` scope, laptop batteries and adapters use the `System` scope
+#[cfg(feature = "battery")]
+pub fn level() -> Option<u8> {
+    fs::read_dir(POWER_SUPPLY)
+        .ok()?
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| read(p, "scope").as_deref() == Some("Device"))
+        .find_map(|p| read(&p, "capacity")?.parse().ok())
+}
+
+#[cfg(not(feature = "battery"))]
+pub fn level() -> Option<u8> {
+    None
+}
+
+#[cfg(feature = "battery")]
+fn read(dir: &Path, name: &str) -> Option<String> {
+    fs::read_to_string(dir.join(name))
+        .ok()
+        .map(|s| s.trim().to_string())
+}
@@ -0,0 +1,70 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+use ratatui::buffer::Buffer;
+
+use crate::{
+    error::{self, Error},
+    json::{self, Value},
+    session, snapshot,
+};
+
+/// writes every drawn frame into an asciicast v2 file
+pub struct Recorder {
+    path: PathBuf,
+    out: BufWriter<File>,
+    /// time of the first frame, the header is written with it
+    started: Option<Instant>,
+}
+
+impl Recorder {
+    pub fn create(path: &Path) -> error::Result<Self> {
+        let file = File::create(path).map_err(|source| Error::Export {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            out: BufWriter::new(file),
+            started: None,
+        })
+    }
+
+    pub fn frame(&mut self, buffer: &Buffer) -> error::Result<()> {
+        self.write(buffer).map_err(|source| Error::Export {
+            path: self.path.clone(),
+            source,
+        })
+    }
+
+    fn write(&mut self, buffer: &Buffer) -> std::io::Result<()> {
+        let started = match self.started {
+            Some(started) => started,
+            None => {
+                let header = json::object([
+                    ("version", Value::from(2)),
+                    ("width", Value::from(u64::from(buffer.area.width))),
+                    ("height", Value::from(u64::from(buffer.area.height))),
+                    ("timestamp", Value::from(session::unix_now())),
+                ]);
+                writeln!(self.out, "{}", header.to_string_compact())?;
+                *self.started.insert(Instant::now())
+            }
+        };
+
+        // the whole screen from the top left, players need carriage returns
+        let (_, ansi) = snapshot::render(buffer);
+        let data = format!("\x1b[H{}", ansi.trim_end().replace('\n', "\r\n"));
+        let event = Value::Array(vec![
+            Value::from(started.elapsed().as_secs_f64()),
+            Value::from("o"),
+            Value::from(data),
+        ]);
+        writeln!(self.out, "{}", event.to_string_compact())?;
+        self.out.flush()
+    }
+}
@@ -0,0 +1,284 @@
+use std::{
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::Mutex,
+};
+
+use crate::{
+    error::{self, Error},
+    json::{self, Value},
+};
+
+/// field of a saved session holding the hash of the rest of it
+pub const FIELD: &str = "content_sha256";
+/// start of the last line of an html or svg export, the hash of the lines above it
+const TRAILER: &str = "<!-- content-sha256 ";
+
+/// secret minisign key the saved sessions and reports are signed with, `--sign`
+static KEY: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+pub fn set_key(key: Option<PathBuf>) {
+    if let Ok(mut current) = KEY.lock() {
+        *current = key;
+    }
+}
+
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// sha-256 of the bytes as lowercase hex
+pub fn sha256(bytes: &[u8]) -> String {
+    let mut h: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ];
+    let mut message = bytes.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&(bytes.len() as u64 * 8).to_be_bytes());
+
+    for block in message.chunks_exact(64) {
+        let mut w = [0u32; 64];
+        for (i, word) in block.chunks_exact(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = h;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = hh
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(K[i])
+                .wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            hh = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(t1);
+            d = c;
+            c = b;
+            b = a;
+            a = t1.wrapping_add(t2);
+        }
+        for (state, value) in h.iter_mut().zip([a, b, c, d, e, f, g, hh]) {
+            *state = state.wrapping_add(value);
+        }
+    }
+    h.iter().fold(String::with_capacity(64), |mut hex, word| {
+        let _ = write!(hex, "{:08x}", word);
+        hex
+    })
+}
+
+/// hash of a json object without its own hash field, over the compact form so the
+/// indentation of the file does not matter
+fn content_hash(value: &Value) -> String {
+    let mut value = value.clone();
+    if let Value::Object(fields) = &mut value {
+        fields.remove(FIELD);
+    }
+    sha256(value.to_string_compact().as_bytes())
+}
+
+/// the object with the hash of its content added
+pub fn seal(mut value: Value) -> Value {
+    let hash = content_hash(&value);
+    if let Value::Object(fields) = &mut value {
+        fields.insert(FIELD.to_string(), Value::from(hash));
+    }
+    value
+}
+
+/// whether the hash a sealed object holds matches its content, none without a hash
+pub fn intact(value: &Value) -> Option<bool> {
+    let hash = value.get(FIELD)?.as_str()?;
+    Some(hash == content_hash(value))
+}
+
+/// the html or svg with a last line holding the hash of everything above it as a comment
+pub fn seal_markup(markup: String) -> String {
+    let hash = sha256(markup.as_bytes());
+    format!("{}{}{} -->\n", markup, TRAILER, hash)
+}
+
+fn markup_intact(markup: &str) -> Option<bool> {
+    let start = markup.rfind(TRAILER)?;
+    let hash = markup[start + TRAILER.len()..]
+        .trim_end()
+        .strip_suffix("-->")?;
+    Some(hash.trim() == sha256(&markup.as_bytes()[..start]))
+}
+
+/// sign the file with the `--sign` key, the signature goes next to it as `.minisig`
+pub fn sign(path: &Path) -> error::Result<()> {
+    let Some(key) = KEY.lock().ok().and_then(|k| k.clone()) else {
+        return Ok(());
+    };
+    let failed = |message: String| Error::Sign {
+        path: path.to_path_buf(),
+        message,
+    };
+    // without a terminal to ask on, a key with a password fails instead of hanging
+    let output = Command::new("minisign")
+        .arg("-S")
+        .arg("-s")
+        .arg(&key)
+        .arg("-m")
+        .arg(path)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| failed(format!("could not run minisign: {}", e)))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        Err(failed(if stderr.is_empty() {
+            format!("minisign exited with {}", output.status)
+        } else {
+            stderr
+        }))
+    }
+}
+
+/// whether the `.minisig` next to the file was made with the secret key of `pubkey`
+fn signed(path: &Path, pubkey: &Path) -> Result<(), String> {
+    let output = Command::new("minisign")
+        .arg("-V")
+        .arg("-q")
+        .arg("-p")
+        .arg(pubkey)
+        .arg("-m")
+        .arg(path)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("could not run minisign: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        Err(if stderr.is_empty() {
+            "the signature does not match".to_string()
+        } else {
+            stderr
+        })
+    }
+}
+
+/// what is wrong with one exported file, none when it is unchanged
+fn problem(path: &Path, pubkey: Option<&Path>) -> error::Result<Option<String>> {
+    let text = fs::read_to_string(path).map_err(|e| Error::Config {
+        path: path.to_path_buf(),
+        message: e.to_string(),
+    })?;
+    let intact = match json::parse(&text) {
+        Ok(value) => intact(&value),
+        Err(_) => markup_intact(&text),
+    };
+    Ok(match intact {
+        None => Some("holds no content hash".to_string()),
+        Some(false) => Some("changed after it was exported".to_string()),
+        Some(true) => pubkey.and_then(|key| signed(path, key).err()),
+    })
+}
+
+/// check the content hash of saved sessions and html and svg exports and with `pubkey` their
+/// signatures, fails when any of them was changed
+pub fn verify(paths: &[PathBuf], pubkey: Option<&Path>) -> error::Result<()> {
+    let mut changed = Vec::new();
+    for path in paths {
+        match problem(path, pubkey)? {
+            None => println!("{}: ok", path.display()),
+            Some(problem) => {
+                println!("{}: {}", path.display(), problem);
+                changed.push(path.clone());
+            }
+        }
+    }
+    if changed.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::Tampered(changed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sha256_known_answers() {
+        for (input, hash) in [
+            (
+                "",
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            ),
+            (
+                "abc",
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            ),
+            // two blocks, the padding does not fit after the message
+            (
+                "abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+                "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+            ),
+        ] {
+            assert_eq!(sha256(input.as_bytes()), hash, "{:?}", input);
+        }
+        assert_eq!(
+            sha256(&[b'a'; 1_000_000]),
+            "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0"
+        );
+    }
+
+    #[test]
+    fn a_sealed_object_is_intact_until_it_changes() {
+        let sealed = seal(json::object([("clicks", Value::from(3.0))]));
+        assert_eq!(intact(&sealed), Some(true));
+        // the hash is over the compact form, the indentation of the file does not matter
+        assert_eq!(
+            intact(&json::parse(&sealed.to_string_pretty()).unwrap()),
+            Some(true)
+        );
+
+        let mut changed = sealed.clone();
+        if let Value::Object(fields) = &mut changed {
+            fields.insert("clicks".to_string(), Value::from(4.0));
+        }
+        assert_eq!(intact(&changed), Some(false));
+        assert_eq!(intact(&json::object([("clicks", Value::from(3.0))])), None);
+    }
+
+    #[test]
+    fn sealed_markup_is_intact_until_it_changes() {
+        let sealed = seal_markup("<svg>\n</svg>\n".to_string());
+        assert_eq!(markup_intact(&sealed), Some(true));
+        assert_eq!(
+            markup_intact(&sealed.replace("svg>\n<", "svg> <")),
+            Some(false)
+        );
+        assert_eq!(markup_intact("<svg>\n</svg>\n"), None);
+    }
+}
@@ -0,0 +1,121 @@
+use std::{
+    io,
+    time::{Duration, Instant, SystemTime},
+};
+
+use clap::ValueEnum;
+
+use crate::error::{self, Error};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum Source {
+    /// the monotonic clock of the standard library
+    #[default]
+    Instant,
+    /// CLOCK_MONOTONIC_RAW, not slewed by ntp (linux)
+    MonotonicRaw,
+}
+
+impl Source {
+    pub fn name(self) -> &'static str {
+        match self {
+            Source::Instant => "instant",
+            Source::MonotonicRaw => "monotonic-raw",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        [Source::Instant, Source::MonotonicRaw]
+            .into_iter()
+            .find(|s| s.name() == name)
+    }
+}
+
+/// the time source of the click timestamps
+#[derive(Debug, Clone, Copy)]
+pub struct Clock {
+    source: Source,
+    origin: Instant,
+    raw_origin: Duration,
+    /// where a resumed session left off
+    offset: Duration,
+    /// the wall clock at the origin, it keeps running while the machine is suspended
+    wall_origin: SystemTime,
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self {
+            source: Source::Instant,
+            origin: Instant::now(),
+            raw_origin: Duration::ZERO,
+            offset: Duration::ZERO,
+            wall_origin: SystemTime::now(),
+        }
+    }
+}
+
+impl Clock {
+    pub fn new(source: Source) -> error::Result<Self> {
+        let raw_origin = match source {
+            Source::Instant => Duration::ZERO,
+            Source::MonotonicRaw => monotonic_raw().map_err(Error::Clock)?,
+        };
+        Ok(Self {
+            source,
+            origin: Instant::now(),
+            raw_origin,
+            offset: Duration::ZERO,
+            wall_origin: SystemTime::now(),
+        })
+    }
+
+    pub fn source(&self) -> Source {
+        self.source
+    }
+
+    /// the clock starting at `offset` instead of zero
+    pub fn continuing(self, offset: Duration) -> Self {
+        Self { offset, ..self }
+    }
+
+    /// time since the clock was created
+    pub fn now(&self) -> Duration {
+        let elapsed = match self.source {
+            Source::Instant => self.origin.elapsed(),
+            // the clock was readable when it was created
+            Source::MonotonicRaw => monotonic_raw()
+                .map(|now| now.saturating_sub(self.raw_origin))
+                .unwrap_or_else(|_| self.origin.elapsed()),
+        };
+        self.offset + elapsed
+    }
+
+    /// how far the wall clock ran ahead of this one, the monotonic clocks stand still while
+    /// the machine is suspended
+    pub fn slept(&self) -> Duration {
+        let wall = self.wall_origin.elapsed().unwrap_or_default();
+        wall.saturating_sub(self.now() - self.offset)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn monotonic_raw() -> io::Result<Duration> {
+    let mut ts = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    // SAFETY: clock_gettime only writes into the timespec it is given
+    if unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC_RAW, &mut ts) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn monotonic_raw() -> io::Result<Duration> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "CLOCK_MONOTONIC_RAW only exists on linux",
+    ))
+}
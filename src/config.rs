@@ -0,0 +1,298 @@
+use std::{
+    env, fs, io,
+    path::PathBuf,
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::{
+    acceptance::Criteria,
+    error::{self, Error},
+    health::Weights,
+    i18n,
+    json::{self, Value},
+    retention::Retention,
+    session,
+    theme::Theme,
+};
+
+/// settings written by the setup, flags override them
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub backend: String,
+    pub device: Option<String>,
+    pub detect_window: Duration,
+    /// confidence level in percent
+    pub confidence: f64,
+    /// chatter rate in percent
+    pub target_rate: f64,
+    /// chatter faster than this is the switch, slower may be a double click
+    pub human_floor: Duration,
+    /// new clicks on top of the event list
+    pub newest_first: bool,
+    /// the event list scrolls along with new clicks
+    pub follow: bool,
+    pub theme: Theme,
+    /// limits the click test has to meet, checked in the summary and the exit code
+    pub acceptance: Criteria,
+    /// test plan diagnose runs without `--plan`, the only one in operator mode
+    pub plan: Option<PathBuf>,
+    /// age and size limits of the saved sessions, checked after every save
+    pub retention: Retention,
+    /// paste endpoint `--upload` posts the json summary to, none is set up by default
+    pub upload_url: Option<String>,
+    /// decimals of the intervals shown, each in the unit that reads best for it
+    pub precision: usize,
+    /// how much each part counts towards the health score
+    pub health: Weights,
+    /// clicks averaged into each point of the interval chart once smoothing is on
+    pub chart_smoothing: usize,
+    /// long timelines are drawn with the lowest and highest gap of each column only
+    pub chart_downsample: bool,
+    /// look for a newer release at startup, off unless asked for since it goes online
+    pub update_check: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            backend: "terminal".to_string(),
+            device: None,
+            detect_window: Duration::from_secs(1),
+            confidence: 95.0,
+            target_rate: 1.0,
+            human_floor: session::HUMAN_FLOOR,
+            newest_first: true,
+            follow: true,
+            theme: Theme::Default,
+            acceptance: Criteria::default(),
+            plan: None,
+            retention: Retention::default(),
+            upload_url: None,
+            precision: i18n::DEFAULT_PRECISION,
+            health: Weights::default(),
+            chart_smoothing: 9,
+            chart_downsample: true,
+            update_check: false,
+        }
+    }
+}
+
+impl Config {
+    pub fn to_json(&self) -> Value {
+        json::object([
+            ("backend", Value::from(self.backend.as_str())),
+            ("device", Value::from(self.device.clone())),
+            (
+                "detect_window_ms",
+                Value::from(self.detect_window.as_millis() as u64),
+            ),
+            ("confidence", Value::from(self.confidence)),
+            ("target_rate", Value::from(self.target_rate)),
+            (
+                "human_floor_ms",
+                Value::from(self.human_floor.as_millis() as u64),
+            ),
+            ("newest_first", Value::from(self.newest_first)),
+            ("follow", Value::from(self.follow)),
+            ("theme", Value::from(self.theme.name())),
+            ("acceptance", self.acceptance.to_json()),
+            (
+                "plan",
+                Value::from(self.plan.as_ref().map(|p| p.display().to_string())),
+            ),
+            ("retention", self.retention.to_json()),
+            ("upload_url", Value::from(self.upload_url.clone())),
+            ("precision", Value::from(self.precision as u64)),
+            ("health_weights", self.health.to_json()),
+            ("chart_smoothing", Value::from(self.chart_smoothing as u64)),
+            ("chart_downsample", Value::from(self.chart_downsample)),
+            ("update_check", Value::from(self.update_check)),
+        ])
+    }
+
+    /// missing fields fall back to the defaults, fields out of any range fail
+    pub fn from_json(value: &Value) -> Result<Self, String> {
+        let default = Self::default();
+        Ok(Self {
+            backend: value
+                .get("backend")
+                .and_then(Value::as_str)
+                .map(String::from)
+                .unwrap_or(default.backend),
+            device: value
+                .get("device")
+                .and_then(Value::as_str)
+                .map(String::from),
+            detect_window: value
+                .get("detect_window_ms")
+                .and_then(Value::as_u64)
+                .map(Duration::from_millis)
+                .unwrap_or(default.detect_window),
+            confidence: value
+                .get("confidence")
+                .and_then(Value::as_f64)
+                .unwrap_or(default.confidence),
+            target_rate: value
+                .get("target_rate")
+                .and_then(Value::as_f64)
+                .unwrap_or(default.target_rate),
+            human_floor: value
+                .get("human_floor_ms")
+                .and_then(Value::as_u64)
+                .map(Duration::from_millis)
+                .unwrap_or(default.human_floor),
+            newest_first: value
+                .get("newest_first")
+                .and_then(Value::as_bool)
+                .unwrap_or(default.newest_first),
+            follow: value
+                .get("follow")
+                .and_then(Value::as_bool)
+                .unwrap_or(default.follow),
+            theme: value
+                .get("theme")
+                .and_then(Value::as_str)
+                .and_then(Theme::parse)
+                .unwrap_or(default.theme),
+            acceptance: value
+                .get("acceptance")
+                .map(Criteria::from_json)
+                .transpose()?
+                .unwrap_or_default(),
+            plan: value.get("plan").and_then(Value::as_str).map(PathBuf::from),
+            retention: value
+                .get("retention")
+                .map(Retention::from_json)
+                .unwrap_or_default(),
+            upload_url: value
+                .get("upload_url")
+                .and_then(Value::as_str)
+                .map(String::from),
+            precision: value
+                .get("precision")
+                .and_then(Value::as_u64)
+                .map(|p| p as usize)
+                .unwrap_or(default.precision),
+            health: value
+                .get("health_weights")
+                .map(Weights::from_json)
+                .unwrap_or_default(),
+            chart_smoothing: value
+                .get("chart_smoothing")
+                .and_then(Value::as_u64)
+                .map(|w| w as usize)
+                .unwrap_or(default.chart_smoothing),
+            chart_downsample: value
+                .get("chart_downsample")
+                .and_then(Value::as_bool)
+                .unwrap_or(default.chart_downsample),
+            update_check: value
+                .get("update_check")
+                .and_then(Value::as_bool)
+                .unwrap_or(default.update_check),
+        })
+    }
+
+    /// `None` before the first setup
+    pub fn load() -> error::Result<Option<Self>> {
+        let path = path();
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => {
+                return Err(Error::Config {
+                    path,
+                    message: e.to_string(),
+                })
+            }
+        };
+        json::parse(&text)
+            .and_then(|v| Self::from_json(&v))
+            .map(Some)
+            .map_err(|message| Error::Config { path, message })
+    }
+
+    pub fn save(&self) -> error::Result<PathBuf> {
+        let path = path();
+        path.parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&path, self.to_json().to_string_pretty()))
+            .map_err(|source| Error::Export {
+                path: path.clone(),
+                source,
+            })?;
+        Ok(path)
+    }
+}
+
+/// how often the watched config file is looked at
+const WATCH_EVERY: Duration = Duration::from_secs(1);
+
+/// settings the command line pins, a reloaded config leaves them alone
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Pinned {
+    pub detect_window: bool,
+    pub confidence: bool,
+    pub target_rate: bool,
+    pub human_floor: bool,
+    pub theme: bool,
+}
+
+/// the config file, watched for edits while a test runs
+pub struct Watch {
+    modified: Option<SystemTime>,
+    checked: Instant,
+    pub pinned: Pinned,
+}
+
+fn modified() -> Option<SystemTime> {
+    fs::metadata(path()).and_then(|m| m.modified()).ok()
+}
+
+impl Watch {
+    pub fn new(pinned: Pinned) -> Self {
+        Self {
+            modified: modified(),
+            checked: Instant::now(),
+            pinned,
+        }
+    }
+
+    /// the file was written by this run, it is not reloaded for that
+    pub fn saved(&mut self) {
+        self.modified = modified();
+    }
+
+    /// the config again once the file changed, looked at no more than once a second
+    pub fn changed(&mut self) -> Option<error::Result<Config>> {
+        if self.checked.elapsed() < WATCH_EVERY {
+            return None;
+        }
+        self.checked = Instant::now();
+        let modified = modified();
+        if modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+        // a removed file keeps what is running
+        Config::load().transpose()
+    }
+}
+
+pub fn config_dir() -> PathBuf {
+    if let Some(dir) = env::var_os("XDG_CONFIG_HOME").filter(|d| !d.is_empty()) {
+        return PathBuf::from(dir).join("mouse-checker");
+    }
+
+    if let Some(dir) = env::var_os("APPDATA") {
+        return PathBuf::from(dir).join("mouse-checker");
+    }
+
+    env::var_os("HOME")
+        .map(|home| PathBuf::from(home).join(".config/mouse-checker"))
+        .unwrap_or_else(|| PathBuf::from(".mouse-checker"))
+}
+
+pub fn path() -> PathBuf {
+    config_dir().join("config.json")
+}
@@ -0,0 +1,434 @@
+use std::{
+    io::{self, BufRead, BufReader, IsTerminal},
+    net::{TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, Receiver, Sender},
+        Arc,
+    },
+    thread,
+    time::Instant,
+};
+
+use crossterm::event::{Event, KeyCode, KeyModifiers};
+use ratatui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Paragraph, Row, Table, TableState},
+    Frame, Terminal,
+};
+
+use crate::{
+    app, control,
+    error::{self, Error},
+    focus::{self, Nav},
+    i18n, input,
+    remote::{self, Message},
+    session::{Click, Session},
+    theme,
+};
+
+/// lines of the log shown under the stations
+const LOG_LINES: usize = 6;
+
+/// what a connection thread tells the console
+enum Sighting {
+    Joined {
+        id: usize,
+        hello: Message,
+        stream: TcpStream,
+    },
+    Said {
+        id: usize,
+        message: Message,
+    },
+    Left {
+        id: usize,
+    },
+}
+
+/// one test bench an agent connected from
+pub struct Station {
+    id: usize,
+    pub name: String,
+    /// the clicks the agent forwarded, with the device and detect window of its hello
+    pub session: Session,
+    last_click: Option<Instant>,
+    /// none once the agent left
+    stream: Option<TcpStream>,
+}
+
+impl Station {
+    pub fn state(&self) -> String {
+        match (&self.stream, self.last_click) {
+            (None, _) => "left".to_string(),
+            (Some(_), None) => "waiting".to_string(),
+            (Some(_), Some(at)) => format!("{} s idle", at.elapsed().as_secs()),
+        }
+    }
+
+    /// share of the clicks that chattered, none before the first
+    pub fn rate(&self) -> Option<f64> {
+        let clicks = self.session.click_count();
+        (clicks > 0).then(|| self.session.chatter_count() as f64 / clicks as f64)
+    }
+}
+
+/// the stations of the bench and what they said, fed by the connection threads
+pub struct Console {
+    sightings: Receiver<Sighting>,
+    pub stations: Vec<Station>,
+    pub log: Vec<String>,
+}
+
+/// welcome an agent of the same protocol version and pass on what it sends
+fn serve(id: usize, stream: TcpStream, tx: Sender<Sighting>) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    let mut lines = BufReader::new(stream.try_clone()?).lines();
+    let Some(line) = lines.next().transpose()? else {
+        return Ok(());
+    };
+    let hello = match Message::parse(&line) {
+        Ok(hello @ Message::Hello { version, .. }) if version == remote::VERSION => hello,
+        Ok(Message::Hello { version, .. }) => {
+            let reason = format!(
+                "the console speaks protocol version {}, the agent {}",
+                remote::VERSION,
+                version
+            );
+            return remote::send(&mut writer, &Message::Refused { reason });
+        }
+        _ => {
+            let reason = "an agent starts with a hello".to_string();
+            return remote::send(&mut writer, &Message::Refused { reason });
+        }
+    };
+    remote::send(
+        &mut writer,
+        &Message::Welcome {
+            version: remote::VERSION,
+        },
+    )?;
+    let _ = stream.set_nodelay(true);
+    if tx.send(Sighting::Joined { id, hello, stream }).is_err() {
+        return Ok(());
+    }
+    for line in lines.map_while(Result::ok) {
+        match Message::parse(&line) {
+            Ok(Message::Bye) => break,
+            Ok(message) => {
+                if tx.send(Sighting::Said { id, message }).is_err() {
+                    return Ok(());
+                }
+            }
+            // a newer agent may send more than this console knows
+            Err(_) => continue,
+        }
+    }
+    let _ = tx.send(Sighting::Left { id });
+    Ok(())
+}
+
+impl Console {
+    /// take the agents connecting on any of the addresses
+    pub fn listen(addresses: &[String]) -> error::Result<Self> {
+        let (tx, rx) = mpsc::channel();
+        // the ids of the connections count up over all the addresses
+        let next = Arc::new(AtomicUsize::new(0));
+        for address in addresses {
+            let listener = TcpListener::bind(address).map_err(|e| Error::Remote {
+                address: address.clone(),
+                message: e.to_string(),
+            })?;
+            let tx = tx.clone();
+            let next = Arc::clone(&next);
+            thread::spawn(move || {
+                for stream in listener.incoming().map_while(Result::ok) {
+                    let id = next.fetch_add(1, Ordering::Relaxed);
+                    let tx = tx.clone();
+                    thread::spawn(move || serve(id, stream, tx));
+                }
+            });
+        }
+        Ok(Self {
+            sightings: rx,
+            stations: Vec::new(),
+            log: Vec::new(),
+        })
+    }
+
+    fn station(&mut self, id: usize) -> Option<&mut Station> {
+        self.stations.iter_mut().find(|s| s.id == id)
+    }
+
+    /// take in what the stations said, with a log line for each but the clicks
+    pub fn update(&mut self) -> Vec<String> {
+        let mut said = Vec::new();
+        while let Ok(sighting) = self.sightings.try_recv() {
+            match sighting {
+                Sighting::Joined {
+                    id,
+                    hello:
+                        Message::Hello {
+                            station,
+                            device,
+                            detect_window,
+                            ..
+                        },
+                    stream,
+                } => {
+                    said.push(format!(
+                        "{}: joined with {}",
+                        station,
+                        device.as_deref().unwrap_or("an unnamed device")
+                    ));
+                    let mut session = Session::new(device, detect_window);
+                    // who sits at the station is not known here
+                    session.operator = None;
+                    let joined = Station {
+                        id,
+                        name: station,
+                        session,
+                        last_click: None,
+                        stream: Some(stream),
+                    };
+                    // a station coming back starts over in its old row
+                    match self.stations.iter().position(|s| s.name == joined.name) {
+                        Some(i) => self.stations[i] = joined,
+                        None => self.stations.push(joined),
+                    }
+                }
+                Sighting::Joined { .. } => {}
+                Sighting::Said { id, message } => {
+                    let Some(station) = self.station(id) else {
+                        continue;
+                    };
+                    match message {
+                        Message::Click {
+                            at,
+                            button,
+                            interval,
+                        } => {
+                            station.session.clicks.push(Click {
+                                at,
+                                interval,
+                                button,
+                                hold: None,
+                                bookmarked: false,
+                                keyboard: false,
+                                position: None,
+                                modifiers: KeyModifiers::NONE,
+                            });
+                            // the bench never saves a station, the oldest clicks only count
+                            let clicks = station.session.clicks.len();
+                            if clicks >= app::KEEP_CLICKS + app::SPILL_CHUNK {
+                                station.session.forget(app::SPILL_CHUNK);
+                            }
+                            station.last_click = Some(Instant::now());
+                            if let Some(interval) = interval {
+                                said.push(format!(
+                                    "{}: chatter after {}",
+                                    station.name,
+                                    i18n::duration(interval)
+                                ));
+                            }
+                        }
+                        Message::Release { at, button } => {
+                            station.session.release(button, at);
+                        }
+                        Message::Answer { text } => {
+                            said.push(format!("{}: {}", station.name, text));
+                        }
+                        _ => {}
+                    }
+                }
+                Sighting::Left { id } => {
+                    if let Some(station) = self.station(id) {
+                        station.stream = None;
+                        said.push(format!("{}: left", station.name));
+                    }
+                }
+            }
+        }
+        self.log.extend(said.iter().cloned());
+        let excess = self.log.len().saturating_sub(LOG_LINES);
+        self.log.drain(..excess);
+        said
+    }
+
+    /// send a `--plain` command to the station, why it could not be otherwise
+    pub fn control(&mut self, index: usize, line: &str) -> Result<(), String> {
+        let station = self
+            .stations
+            .get_mut(index)
+            .ok_or_else(|| "no such station".to_string())?;
+        let stream = station
+            .stream
+            .as_mut()
+            .ok_or_else(|| format!("{} left", station.name))?;
+        let message = Message::Control {
+            line: line.to_string(),
+        };
+        remote::send(stream, &message).map_err(|e| format!("{}: {}", station.name, e))
+    }
+}
+
+/// the stations as they come and go until q, p, r and s pause, resume and stop the chosen one
+pub fn run<B: Backend>(terminal: &mut Terminal<B>, address: &str) -> error::Result<()> {
+    let mut console = Console::listen(&[address.to_string()])?;
+    let mut table = TableState::default();
+    loop {
+        console.update();
+        if table.selected().is_none() && !console.stations.is_empty() {
+            table.select(Some(0));
+        }
+        let mut area = Rect::default();
+        terminal.draw(|f| area = ui(f, &console, &mut table, address))?;
+
+        if !input::poll(control::POLL)? {
+            continue;
+        }
+        let Event::Key(key) = input::read()? else {
+            continue;
+        };
+        let selected = table.selected().unwrap_or(0);
+        let stations = console.stations.len();
+        let nav = Nav::of(key.code);
+        if let Some(rows) = nav.and_then(|nav| nav.rows(focus::page(area.height))) {
+            let last = stations.saturating_sub(1) as isize;
+            table.select(Some((selected as isize + rows).clamp(0, last) as usize));
+        }
+        if let Some(nav @ (Nav::Next | Nav::Previous)) = nav {
+            table.select(Some(focus::step(selected, stations, nav == Nav::Next)));
+        }
+        let command = match key.code {
+            KeyCode::Char('q') => break,
+            KeyCode::Char('p') => Some("pause"),
+            KeyCode::Char('r') => Some("resume"),
+            KeyCode::Char('s') => Some("stop"),
+            _ => None,
+        };
+        if let Some(Err(e)) = command.map(|c| console.control(selected, c)) {
+            console.log.push(e);
+        }
+    }
+    Ok(())
+}
+
+/// draws the bench, returns the area of the stations table for the page keys
+fn ui<B: Backend>(
+    f: &mut Frame<B>,
+    console: &Console,
+    table: &mut TableState,
+    address: &str,
+) -> Rect {
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Min(4),
+                Constraint::Length(LOG_LINES as u16 + 2),
+                Constraint::Length(1),
+            ]
+            .as_ref(),
+        )
+        .split(f.size());
+
+    let header = Row::new([
+        "station", "device", "window", "clicks", "chatter", "rate", "state",
+    ])
+    .style(Style::default().add_modifier(Modifier::BOLD));
+    let rows = console.stations.iter().map(|s| {
+        let row = Row::new([
+            s.name.clone(),
+            s.session.device.clone().unwrap_or_else(|| "-".to_string()),
+            format!("{} ms", s.session.detect_window.as_millis()),
+            s.session.click_count().to_string(),
+            s.session.chatter_count().to_string(),
+            s.rate()
+                .map(|r| format!("{:.2}%", r * 100.0))
+                .unwrap_or_else(|| "-".to_string()),
+            s.state(),
+        ]);
+        if s.session.chatter_count() > 0 {
+            row.style(theme::palette().chatter)
+        } else {
+            row
+        }
+    });
+    let title = if console.stations.is_empty() {
+        format!(
+            "bench on {} (no stations, start them with --agent)",
+            address
+        )
+    } else {
+        format!("bench on {}", address)
+    };
+    let stations = Table::new(rows)
+        .header(header)
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .widths(&[
+            Constraint::Min(12),
+            Constraint::Min(12),
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Length(12),
+        ])
+        .highlight_style(theme::palette().highlight)
+        .highlight_symbol(focus::MARKER);
+    f.render_stateful_widget(stations, layout[0], table);
+
+    let log = console
+        .log
+        .iter()
+        .map(|l| Line::from(l.as_str()))
+        .collect::<Vec<_>>();
+    f.render_widget(
+        Paragraph::new(log).block(Block::default().title("log").borders(Borders::ALL)),
+        layout[1],
+    );
+    f.render_widget(
+        Paragraph::new("arrows tab pgup/pgdn: select  p: pause  r: resume  s: stop  q: quit"),
+        layout[2],
+    );
+    layout[0]
+}
+
+/// the log of the stations on stdout, `<station> <command>` lines on a piped stdin are sent
+/// to the station
+pub fn run_plain(address: &str) -> error::Result<()> {
+    let mut console = Console::listen(&[address.to_string()])?;
+    println!("listening for stations on {}", address);
+    let (tx, commands) = mpsc::channel::<String>();
+    if !io::stdin().is_terminal() {
+        thread::spawn(move || {
+            for line in io::stdin().lock().lines().map_while(Result::ok) {
+                if tx.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+    loop {
+        for line in console.update() {
+            println!("{}", line);
+        }
+        while let Ok(line) = commands.try_recv() {
+            let Some((name, command)) = line.trim().split_once(char::is_whitespace) else {
+                continue;
+            };
+            let sent = match console.stations.iter().position(|s| s.name == name) {
+                Some(index) => console.control(index, command.trim()),
+                None => Err(format!("no station {}", name)),
+            };
+            if let Err(e) = sent {
+                println!("{}", e);
+            }
+        }
+        thread::sleep(control::POLL);
+    }
+}
@@ -0,0 +1,180 @@
+use std::{
+    io::{self, BufRead, IsTerminal},
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+    time::Duration,
+};
+
+use crossterm::event::MouseButton;
+
+use crate::{
+    error::{self, Error},
+    session,
+};
+
+/// how often the event loops look for commands while no input arrives
+pub const POLL: Duration = Duration::from_millis(50);
+
+/// a command of a harness or supervisor driving the capture
+pub enum Control {
+    Pause,
+    Resume,
+    Mark(String),
+    /// an actuator rig pressed the button, the clicks that follow are matched with it
+    Actuate(MouseButton),
+    /// the summary so far as one line of json
+    Stats,
+    Stop,
+}
+
+impl Control {
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let line = line.trim();
+        let (command, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        match command {
+            "pause" => Ok(Self::Pause),
+            "resume" => Ok(Self::Resume),
+            "mark" => Ok(Self::Mark(rest.trim().to_string())),
+            "actuate" => match rest.trim() {
+                "" => Ok(Self::Actuate(MouseButton::Left)),
+                name => session::parse_button(name)
+                    .map(Self::Actuate)
+                    .ok_or_else(|| line.to_string()),
+            },
+            "stats" => Ok(Self::Stats),
+            "stop" => Ok(Self::Stop),
+            _ => Err(line.to_string()),
+        }
+    }
+}
+
+/// one command line and the client waiting for the answer, none for stdin
+pub struct Request {
+    pub line: String,
+    reply: Option<Sender<String>>,
+}
+
+impl Request {
+    pub fn new(line: String, reply: Option<Sender<String>>) -> Self {
+        Self { line, reply }
+    }
+
+    pub fn answer(self, text: &str) {
+        if let Some(reply) = self.reply {
+            // a client that hung up does not need it
+            let _ = reply.send(text.to_string());
+        }
+    }
+}
+
+/// the commands of a piped stdin, of the control socket and of a bench console, in the
+/// order they arrive
+pub struct Listener {
+    requests: Receiver<Request>,
+    /// handed to the sources that connect later
+    sender: Sender<Request>,
+    socket: Option<PathBuf>,
+}
+
+impl Listener {
+    /// none when nothing can send commands, `remote` when a console will
+    pub fn new(socket: Option<&Path>, stdin: bool, remote: bool) -> error::Result<Option<Self>> {
+        let stdin = stdin && !io::stdin().is_terminal();
+        if socket.is_none() && !stdin && !remote {
+            return Ok(None);
+        }
+        let (tx, rx) = mpsc::channel();
+        if let Some(path) = socket {
+            bind(path, tx.clone()).map_err(|source| Error::Export {
+                path: path.to_path_buf(),
+                source,
+            })?;
+        }
+        if stdin {
+            let tx = tx.clone();
+            thread::spawn(move || {
+                for line in io::stdin().lock().lines().map_while(Result::ok) {
+                    if !line.trim().is_empty() && tx.send(Request { line, reply: None }).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        Ok(Some(Self {
+            requests: rx,
+            sender: tx,
+            socket: socket.map(Path::to_path_buf),
+        }))
+    }
+
+    pub fn sender(&self) -> Sender<Request> {
+        self.sender.clone()
+    }
+
+    /// the next command that arrived, none while there is none
+    pub fn next(&self) -> Option<Request> {
+        self.requests.try_recv().ok()
+    }
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        if let Some(path) = &self.socket {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+#[cfg(unix)]
+fn bind(path: &Path, tx: Sender<Request>) -> io::Result<()> {
+    use std::{
+        io::{BufReader, Write},
+        os::unix::{
+            fs::FileTypeExt,
+            net::{UnixListener, UnixStream},
+        },
+    };
+
+    // a socket nobody answers on is left over from a run that crashed
+    let stale = std::fs::metadata(path).is_ok_and(|m| m.file_type().is_socket())
+        && UnixStream::connect(path).is_err();
+    if stale {
+        std::fs::remove_file(path)?;
+    }
+    let listener = UnixListener::bind(path)?;
+    thread::spawn(move || {
+        for stream in listener.incoming().map_while(Result::ok) {
+            let tx = tx.clone();
+            thread::spawn(move || -> io::Result<()> {
+                let mut writer = stream.try_clone()?;
+                for line in BufReader::new(stream).lines() {
+                    let line = line?;
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    let (reply, answer) = mpsc::channel();
+                    let request = Request {
+                        line,
+                        reply: Some(reply),
+                    };
+                    if tx.send(request).is_err() {
+                        break;
+                    }
+                    let Ok(text) = answer.recv() else { break };
+                    writeln!(writer, "{}", text)?;
+                }
+                Ok(())
+            });
+        }
+    });
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn bind(_: &Path, _: Sender<Request>) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "control sockets need a unix system",
+    ))
+}
@@ -0,0 +1,128 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    error::{self, Error},
+    json::{self, Value},
+    session::{self, Session},
+    stats::Estimate,
+    summary::Summary,
+    version::Build,
+};
+
+/// ending of the stored summary next to each session of a corpus
+const GOLDEN: &str = ".golden.json";
+
+fn golden_path(session: &Path) -> PathBuf {
+    let name = session.file_stem().unwrap_or_default().to_string_lossy();
+    session.with_file_name(format!("{}{}", name, GOLDEN))
+}
+
+/// the saved sessions of the directory, sorted by name
+fn sessions(dir: &Path) -> error::Result<Vec<PathBuf>> {
+    let entries = fs::read_dir(dir).map_err(|e| Error::Config {
+        path: dir.to_path_buf(),
+        message: e.to_string(),
+    })?;
+    let mut paths = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|e| e == "json"))
+        .filter(|p| !p.to_string_lossy().ends_with(GOLDEN))
+        .collect::<Vec<_>>();
+    paths.sort();
+    Ok(paths)
+}
+
+/// the summary the analyzers give today, with the settings the session was recorded with
+fn analyze(path: &Path, confidence: f64, target: f64) -> error::Result<Value> {
+    let session = Session::load(path)?;
+    let estimate = Estimate::new(
+        session.chatter_count(),
+        session.click_count(),
+        confidence,
+        target,
+    );
+    Ok(Summary::new(&session, estimate).to_json())
+}
+
+/// the build of the summaries, it changes with every commit and is no regression
+const BUILD: &str = "build";
+
+/// `field: before -> now` for every field that changed, an empty list when none did
+fn changes(golden: &Value, fresh: &Value) -> Vec<String> {
+    let (Value::Object(golden), Value::Object(fresh)) = (golden, fresh) else {
+        return vec![format!(
+            "{} -> {}",
+            golden.to_string_compact(),
+            fresh.to_string_compact()
+        )];
+    };
+    let shown =
+        |v: Option<&Value>| v.map_or_else(|| "missing".to_string(), Value::to_string_compact);
+    let mut keys = golden.keys().chain(fresh.keys()).collect::<Vec<_>>();
+    keys.sort();
+    keys.dedup();
+    keys.into_iter()
+        .filter(|key| *key != BUILD)
+        .filter_map(|key| {
+            let (before, now) = (shown(golden.get(key)), shown(fresh.get(key)));
+            (before != now).then(|| format!("{}: {} -> {}", key, before, now))
+        })
+        .collect()
+}
+
+/// run the analyzers over every session of the directory and compare each summary with the
+/// golden one stored next to it, `bless` stores the fresh ones instead; fails when any
+/// changed or has none
+pub fn run(dir: &Path, bless: bool, confidence: f64, target: f64) -> error::Result<()> {
+    let mut changed = Vec::new();
+    for path in sessions(dir)? {
+        let golden = golden_path(&path);
+        let fresh = analyze(&path, confidence, target)?;
+        if bless {
+            session::write_atomic(&golden, &fresh.to_string_pretty())?;
+            println!("{}: blessed", path.display());
+            continue;
+        }
+        let Ok(text) = fs::read_to_string(&golden) else {
+            println!(
+                "{}: no golden summary, bless it with --bless",
+                path.display()
+            );
+            changed.push(path);
+            continue;
+        };
+        let stored = json::parse(&text).map_err(|message| Error::Config {
+            path: golden.clone(),
+            message,
+        })?;
+        let built = |summary: &Value| summary.get(BUILD).and_then(Build::from_json);
+        if let (Some(then), Some(now)) = (built(&stored), built(&fresh)) {
+            if !then.same_code(&now) {
+                println!(
+                    "{}: blessed by {}, analyzed by {}",
+                    path.display(),
+                    then.describe(),
+                    now.describe()
+                );
+            }
+        }
+        let diff = changes(&stored, &fresh);
+        if diff.is_empty() {
+            println!("{}: ok", path.display());
+        } else {
+            println!("{}: changed", path.display());
+            for line in diff {
+                println!("  {}", line);
+            }
+            changed.push(path);
+        }
+    }
+    if changed.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::Regressed(changed))
+    }
+}
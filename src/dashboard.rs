@@ -0,0 +1,287 @@
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame, Terminal,
+};
+
+use crate::{
+    console::{Console, Station},
+    control, error,
+    focus::{self, Nav},
+    i18n, input,
+    session::{self, Session},
+    stats::Estimate,
+    summary::{Format, Summary},
+    theme,
+};
+
+/// size of a station's card in the grid
+const CARD_WIDTH: u16 = 28;
+const CARD_HEIGHT: u16 = 8;
+/// clicks the drill-down lists, newest first
+const RECENT: usize = 200;
+
+struct DashboardState {
+    console: Console,
+    addresses: Vec<String>,
+    selected: usize,
+    /// the station shown alone, enter opens it and esc goes back to the grid
+    drilled: bool,
+    /// cards in a row of the last frame, up and down move by this many
+    columns: usize,
+    /// rows of cards on the last frame, page up and down move by a screen of them
+    rows: usize,
+    confidence: f64,
+    target: f64,
+}
+
+impl DashboardState {
+    fn estimate(&self, session: &Session) -> Estimate {
+        Estimate::new(
+            session.chatter_count(),
+            session.click_count(),
+            self.confidence,
+            self.target,
+        )
+    }
+
+    fn select(&mut self, by: isize) {
+        let last = self.console.stations.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(by).min(last);
+    }
+}
+
+/// a card per station of the agents connecting on any of the addresses, until q
+pub fn run<B: Backend>(
+    terminal: &mut Terminal<B>,
+    addresses: &[String],
+    confidence: f64,
+    target: f64,
+) -> error::Result<()> {
+    let mut state = DashboardState {
+        console: Console::listen(addresses)?,
+        addresses: addresses.to_vec(),
+        selected: 0,
+        drilled: false,
+        columns: 1,
+        rows: 1,
+        confidence,
+        target,
+    };
+    loop {
+        state.console.update();
+        terminal.draw(|f| ui(f, &mut state))?;
+
+        if !input::poll(control::POLL)? {
+            continue;
+        }
+        let Event::Key(key) = input::read()? else {
+            continue;
+        };
+        let columns = state.columns as isize;
+        let screen = columns * state.rows as isize;
+        let stations = state.console.stations.len();
+        match Nav::of(key.code) {
+            Some(Nav::Back) => state.drilled = false,
+            Some(Nav::Activate) => state.drilled = stations > 0,
+            Some(nav) if !state.drilled => match nav {
+                Nav::Left => state.select(-1),
+                Nav::Right => state.select(1),
+                Nav::Up => state.select(-columns),
+                Nav::Down => state.select(columns),
+                Nav::PageUp => state.select(-screen),
+                Nav::PageDown => state.select(screen),
+                Nav::First => state.selected = 0,
+                Nav::Last => state.selected = stations.saturating_sub(1),
+                Nav::Next | Nav::Previous => {
+                    state.selected = focus::step(state.selected, stations, nav == Nav::Next)
+                }
+                Nav::Activate | Nav::Back => {}
+            },
+            _ => {}
+        }
+        let command = match key.code {
+            KeyCode::Char('q') => break,
+            KeyCode::Backspace => {
+                state.drilled = false;
+                None
+            }
+            KeyCode::Char('p') => Some("pause"),
+            KeyCode::Char('r') => Some("resume"),
+            KeyCode::Char('s') => Some("stop"),
+            _ => None,
+        };
+        if let Some(Err(e)) = command.map(|c| state.console.control(state.selected, c)) {
+            state.console.log.push(e);
+        }
+    }
+    Ok(())
+}
+
+fn ui<B: Backend>(f: &mut Frame<B>, state: &mut DashboardState) {
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(CARD_HEIGHT), Constraint::Length(1)].as_ref())
+        .split(f.size());
+
+    let help = match state.console.stations.get(state.selected) {
+        Some(station) if state.drilled => {
+            drill_ui(f, state, station, layout[0]);
+            "esc: back  p: pause  r: resume  s: stop  q: quit"
+        }
+        _ => {
+            grid_ui(f, state, layout[0]);
+            "arrows tab pgup/pgdn: select  enter: open  p: pause  r: resume  s: stop  q: quit"
+        }
+    };
+    f.render_widget(Paragraph::new(help), layout[1]);
+}
+
+fn card(state: &DashboardState, station: &Station) -> Vec<Line<'static>> {
+    let session = &station.session;
+    let estimate = state.estimate(session);
+    let mut lines = vec![
+        Line::from(session.device.clone().unwrap_or_else(|| "-".to_string())),
+        Line::from(format!(
+            "{} clicks, {} chatter",
+            session.click_count(),
+            session.chatter_count()
+        )),
+        Line::from(match station.rate() {
+            Some(rate) => format!(
+                "{:.2}% ({:.1}-{:.1}%)",
+                rate * 100.0,
+                estimate.low * 100.0,
+                estimate.high * 100.0
+            ),
+            None => "no clicks yet".to_string(),
+        }),
+    ];
+    if let Some(shortest) = session.shortest_chatter() {
+        lines.push(Line::from(format!(
+            "fastest chatter {}",
+            i18n::duration(shortest)
+        )));
+    }
+    lines.push(Line::from(station.state()));
+    lines
+}
+
+fn grid_ui<B: Backend>(f: &mut Frame<B>, state: &mut DashboardState, area: Rect) {
+    let outer = Block::default()
+        .title(format!("dashboard on {}", state.addresses.join(", ")))
+        .borders(Borders::ALL);
+    let inner = outer.inner(area);
+    f.render_widget(outer, area);
+    if state.console.stations.is_empty() {
+        f.render_widget(
+            Paragraph::new("no stations yet, start the click tests with --agent"),
+            inner,
+        );
+        return;
+    }
+
+    let columns = (inner.width / CARD_WIDTH).max(1);
+    let rows = (inner.height / CARD_HEIGHT).max(1);
+    state.columns = usize::from(columns);
+    state.rows = usize::from(rows);
+    // the rows scroll to keep the chosen card on screen
+    let first_row = (state.selected / state.columns).saturating_sub(usize::from(rows) - 1);
+    let first = first_row * state.columns;
+    let shown = state
+        .console
+        .stations
+        .iter()
+        .enumerate()
+        .skip(first)
+        .take(usize::from(columns * rows));
+    for (i, station) in shown {
+        let slot = (i - first) as u16;
+        let card_area = Rect {
+            x: inner.x + slot % columns * CARD_WIDTH,
+            y: inner.y + slot / columns * CARD_HEIGHT,
+            width: CARD_WIDTH.min(inner.width),
+            height: CARD_HEIGHT.min(inner.height),
+        };
+        let border = if i == state.selected {
+            theme::palette().focus
+        } else if station.session.chatter_count() > 0 {
+            theme::palette().chatter
+        } else {
+            Style::default()
+        };
+        let block = Block::default()
+            .title(focus::title(&station.name, i == state.selected))
+            .borders(Borders::ALL)
+            .border_style(border);
+        f.render_widget(Paragraph::new(card(state, station)).block(block), card_area);
+    }
+}
+
+fn drill_ui<B: Backend>(f: &mut Frame<B>, state: &DashboardState, station: &Station, area: Rect) {
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)].as_ref())
+        .split(area);
+
+    let session = &station.session;
+    let summary = Summary::new(session, state.estimate(session)).render(Format::Text);
+    let mut text = summary.lines().map(String::from).collect::<Vec<_>>();
+    text.push(String::new());
+    let prefix = format!("{}: ", station.name);
+    text.extend(
+        state
+            .console
+            .log
+            .iter()
+            .filter(|l| l.starts_with(&prefix))
+            .cloned(),
+    );
+    f.render_widget(
+        Paragraph::new(text.into_iter().map(Line::from).collect::<Vec<_>>())
+            .wrap(Wrap { trim: false })
+            .block(
+                Block::default()
+                    .title(format!("{} ({})", station.name, station.state()))
+                    .borders(Borders::ALL),
+            ),
+        columns[0],
+    );
+
+    let recent = session
+        .clicks
+        .iter()
+        .enumerate()
+        .rev()
+        .take(RECENT)
+        .map(|(i, click)| {
+            let text = format!(
+                "{:>5}  {:<6}  {:>10}",
+                i + 1,
+                session::button_name(click.button),
+                click
+                    .shown_interval()
+                    .map(i18n::duration)
+                    .unwrap_or_else(|| "-".to_string())
+            );
+            let style = if click.interval.is_some() {
+                theme::palette().chatter
+            } else {
+                Style::default()
+            };
+            Line::from(Span::styled(text, style))
+        })
+        .collect::<Vec<_>>();
+    f.render_widget(
+        Paragraph::new(recent).block(
+            Block::default()
+                .title("clicks, interval")
+                .borders(Borders::ALL),
+        ),
+        columns[1],
+    );
+}
@@ -0,0 +1,602 @@
+use std::{
+    fs,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use crossterm::event::{Event, KeyCode, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{
+    backend::Backend,
+    layout::{Alignment, Constraint, Direction, Layout},
+    text::Line,
+    widgets::{Block, Borders, Gauge, Paragraph, Wrap},
+    Frame, Terminal,
+};
+
+use crate::{
+    app::AppState,
+    error::{self, Error},
+    health::Health,
+    input,
+    json::{self, Value},
+    session::Session,
+    version::Build,
+};
+
+/// a release and press closer than this is a dropout of the switch, not the user
+const BREAK_GAP: Duration = Duration::from_millis(100);
+/// scroll events closer than this come faster than a hand turns a notched wheel
+const MOMENTUM_GAP: Duration = Duration::from_millis(25);
+/// fast events in a row needed before a run counts as inertial scrolling
+const MOMENTUM_RUN: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Test {
+    Left,
+    Right,
+    Wheel,
+    /// notches counted by the user against the events reported
+    Detents,
+    Drag,
+    Hold,
+}
+
+impl Test {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "left" => Some(Test::Left),
+            "right" => Some(Test::Right),
+            "wheel" => Some(Test::Wheel),
+            "detents" => Some(Test::Detents),
+            "drag" => Some(Test::Drag),
+            "hold" => Some(Test::Hold),
+            _ => None,
+        }
+    }
+
+    /// the name a plan gives the test
+    pub fn key(self) -> &'static str {
+        match self {
+            Test::Left => "left",
+            Test::Right => "right",
+            Test::Wheel => "wheel",
+            Test::Detents => "detents",
+            Test::Drag => "drag",
+            Test::Hold => "hold",
+        }
+    }
+
+    pub fn component(self) -> &'static str {
+        match self {
+            Test::Left => "left switch",
+            Test::Right => "right switch",
+            Test::Wheel => "wheel encoder",
+            Test::Detents => "wheel detents",
+            Test::Drag => "left switch while dragging",
+            Test::Hold => "left switch while holding",
+        }
+    }
+
+    fn instruction(self, amount: u32) -> String {
+        match self {
+            Test::Left => format!("click the left button {} times, one at a time", amount),
+            Test::Right => format!("click the right button {} times, one at a time", amount),
+            Test::Wheel => format!("scroll down {} notches, slowly and only down", amount),
+            Test::Detents => format!(
+                "scroll down exactly {} notches, counting every click of the wheel, then press enter",
+                amount
+            ),
+            Test::Drag => format!(
+                "hold the left button and keep moving the mouse for {} seconds, then release",
+                amount
+            ),
+            Test::Hold => format!(
+                "hold the left button still for {} seconds, then release",
+                amount
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct StepSpec {
+    pub test: Test,
+    /// clicks or notches, seconds for drag and hold
+    pub amount: u32,
+    /// share of bad events in percent above which the component fails
+    pub max_rate: f64,
+}
+
+pub struct Plan {
+    pub steps: Vec<StepSpec>,
+}
+
+impl Default for Plan {
+    fn default() -> Self {
+        let step = |test, amount| StepSpec {
+            test,
+            amount,
+            max_rate: 1.0,
+        };
+        Self {
+            steps: vec![
+                step(Test::Left, 30),
+                step(Test::Right, 30),
+                step(Test::Wheel, 30),
+                step(Test::Detents, 20),
+                step(Test::Drag, 5),
+                step(Test::Hold, 5),
+            ],
+        }
+    }
+}
+
+impl Plan {
+    /// read a plan of `[[step]]` tables with `test`, `amount` and `max_rate` keys
+    pub fn load(path: &Path) -> error::Result<Self> {
+        fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| Self::parse(&text))
+            .map_err(|message| Error::Config {
+                path: path.to_path_buf(),
+                message,
+            })
+    }
+
+    /// the small toml subset needed for plans
+    fn parse(text: &str) -> Result<Self, String> {
+        let mut steps = Vec::new();
+        let mut draft: Option<Draft> = None;
+
+        for (i, line) in text.lines().enumerate() {
+            let n = i + 1;
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            if line == "[[step]]" {
+                if let Some(done) = draft.take() {
+                    steps.push(done.finish()?);
+                }
+                draft = Some(Draft {
+                    line: n,
+                    test: None,
+                    amount: None,
+                    max_rate: 1.0,
+                });
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(format!("line {}: expected `key = value`", n));
+            };
+            let (key, value) = (key.trim(), value.trim());
+            let Some(step) = draft.as_mut() else {
+                return Err(format!("line {}: `{}` outside of a [[step]]", n, key));
+            };
+            match key {
+                "test" => {
+                    let name = value.trim_matches('"');
+                    let test = Test::parse(name);
+                    step.test = Some(test.ok_or(format!("line {}: unknown test `{}`", n, name))?);
+                }
+                "amount" => {
+                    let amount = value.parse().ok();
+                    step.amount =
+                        Some(amount.ok_or(format!("line {}: invalid amount `{}`", n, value))?);
+                }
+                "max_rate" => {
+                    let rate = value.parse().ok();
+                    step.max_rate =
+                        rate.ok_or(format!("line {}: invalid max_rate `{}`", n, value))?;
+                }
+                key => return Err(format!("line {}: unknown key `{}`", n, key)),
+            }
+        }
+        if let Some(done) = draft {
+            steps.push(done.finish()?);
+        }
+
+        if steps.is_empty() {
+            return Err("the plan has no steps".to_string());
+        }
+        Ok(Self { steps })
+    }
+}
+
+/// a `[[step]]` table while it is parsed
+struct Draft {
+    line: usize,
+    test: Option<Test>,
+    amount: Option<u32>,
+    max_rate: f64,
+}
+
+impl Draft {
+    fn finish(self) -> Result<StepSpec, String> {
+        Ok(StepSpec {
+            test: self
+                .test
+                .ok_or(format!("line {}: step without `test`", self.line))?,
+            amount: self
+                .amount
+                .ok_or(format!("line {}: step without `amount`", self.line))?,
+            max_rate: self.max_rate,
+        })
+    }
+}
+
+/// ordered from best to worst
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verdict {
+    Healthy,
+    Marginal,
+    Failing,
+}
+
+impl Verdict {
+    pub fn name(self) -> &'static str {
+        match self {
+            Verdict::Healthy => "healthy",
+            Verdict::Marginal => "marginal",
+            Verdict::Failing => "failing",
+        }
+    }
+}
+
+pub struct Outcome {
+    pub spec: StepSpec,
+    /// chatter, reversed notches or dropouts
+    pub bad: usize,
+    pub total: usize,
+    /// trailing scroll events of a spinning wheel or os inertia
+    pub momentum: usize,
+    /// whether `momentum` is left out of `bad` and `total`
+    pub momentum_dropped: bool,
+    /// the clicks of the left and right steps
+    pub session: Option<Session>,
+}
+
+impl Outcome {
+    pub fn rate(&self) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.bad as f64 / self.total as f64
+        }
+    }
+
+    pub fn verdict(&self) -> Verdict {
+        if self.bad == 0 {
+            Verdict::Healthy
+        } else if self.rate() * 100.0 <= self.spec.max_rate {
+            Verdict::Marginal
+        } else {
+            Verdict::Failing
+        }
+    }
+
+    fn describe(&self) -> String {
+        let text = match self.spec.test {
+            Test::Left | Test::Right => format!("{} chatter in {} clicks", self.bad, self.total),
+            Test::Wheel => format!("{} reversed in {} notches", self.bad, self.total),
+            Test::Detents => format!("{} skipped or doubled in {} notches", self.bad, self.total),
+            Test::Drag | Test::Hold => format!("{} dropouts in {} presses", self.bad, self.total),
+        };
+        match (self.momentum, self.momentum_dropped) {
+            (0, _) => text,
+            (n, true) => format!("{}, {} momentum events left out", text, n),
+            (n, false) => format!("{}, {} momentum events counted", text, n),
+        }
+    }
+}
+
+/// progress of the running step
+struct Step {
+    spec: StepSpec,
+    started: bool,
+    clicks: AppState,
+    /// time and direction of every scroll event, true for down
+    scrolls: Vec<(Instant, bool)>,
+    drop_momentum: bool,
+    /// start of the current press of drag and hold
+    pressed: Option<Instant>,
+    released: Option<Instant>,
+    presses: usize,
+    dropouts: usize,
+    held: bool,
+    /// the user is done counting notches
+    confirmed: bool,
+}
+
+impl Step {
+    fn new(spec: StepSpec, detect_window: Duration, drop_momentum: bool) -> Self {
+        Self {
+            spec,
+            started: false,
+            clicks: AppState::new(detect_window, None),
+            scrolls: Vec::new(),
+            drop_momentum,
+            pressed: None,
+            released: None,
+            presses: 0,
+            dropouts: 0,
+            held: false,
+            confirmed: false,
+        }
+    }
+
+    fn button(&self) -> MouseButton {
+        match self.spec.test {
+            Test::Right => MouseButton::Right,
+            _ => MouseButton::Left,
+        }
+    }
+
+    fn hold_time(&self) -> Duration {
+        Duration::from_secs(u64::from(self.spec.amount))
+    }
+
+    fn on_mouse(&mut self, event: MouseEvent) {
+        match (self.spec.test, event.kind) {
+            (Test::Left | Test::Right, MouseEventKind::Down(button)) if button == self.button() => {
+                self.clicks.on_click(event);
+            }
+            (Test::Wheel | Test::Detents, MouseEventKind::ScrollDown) => {
+                self.scrolls.push((Instant::now(), true));
+            }
+            (Test::Wheel | Test::Detents, MouseEventKind::ScrollUp) => {
+                self.scrolls.push((Instant::now(), false));
+            }
+            (Test::Drag | Test::Hold, MouseEventKind::Down(MouseButton::Left)) => {
+                let now = Instant::now();
+                self.presses += 1;
+                match self.released.take() {
+                    // the switch let go for a moment, keep timing the press
+                    Some(at) if now - at < BREAK_GAP => self.dropouts += 1,
+                    _ => self.pressed = Some(now),
+                }
+            }
+            (Test::Drag | Test::Hold, MouseEventKind::Up(MouseButton::Left)) => {
+                if self
+                    .pressed
+                    .is_some_and(|p| p.elapsed() >= self.hold_time())
+                {
+                    self.held = true;
+                } else {
+                    self.released = Some(Instant::now());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// which scroll events trail a flick as inertia, the first event of a fast run is
+    /// the hand and the rest is momentum
+    fn momentum(&self) -> Vec<bool> {
+        let mut flags = vec![false; self.scrolls.len()];
+        let mut start = 0;
+        for i in 1..=self.scrolls.len() {
+            let fast =
+                i < self.scrolls.len() && self.scrolls[i].0 - self.scrolls[i - 1].0 < MOMENTUM_GAP;
+            if fast {
+                continue;
+            }
+            if i - start > MOMENTUM_RUN {
+                flags[start + 1..i].iter_mut().for_each(|f| *f = true);
+            }
+            start = i;
+        }
+        flags
+    }
+
+    /// down and up events, without momentum when it is dropped
+    fn scroll_counts(&self) -> (usize, usize, usize) {
+        let momentum = self.momentum();
+        let (mut down, mut up) = (0, 0);
+        for ((_, is_down), inertial) in self.scrolls.iter().zip(&momentum) {
+            if *inertial && self.drop_momentum {
+                continue;
+            }
+            if *is_down {
+                down += 1;
+            } else {
+                up += 1;
+            }
+        }
+        (down, up, momentum.iter().filter(|m| **m).count())
+    }
+
+    /// done and ready for the next step
+    fn finished(&self) -> bool {
+        match self.spec.test {
+            Test::Left | Test::Right => {
+                self.clicks.session.click_count() >= self.spec.amount as usize
+            }
+            Test::Wheel => self.scroll_counts().0 >= self.spec.amount as usize,
+            Test::Detents => self.confirmed,
+            Test::Drag | Test::Hold => self.held,
+        }
+    }
+
+    fn progress(&self) -> f64 {
+        let done = match self.spec.test {
+            Test::Left | Test::Right => self.clicks.session.click_count() as f64,
+            Test::Wheel | Test::Detents => self.scroll_counts().0 as f64,
+            Test::Drag | Test::Hold => self
+                .pressed
+                .filter(|_| self.released.is_none())
+                .map(|p| p.elapsed().as_secs_f64())
+                .unwrap_or_default(),
+        };
+        (done / f64::from(self.spec.amount.max(1))).clamp(0.0, 1.0)
+    }
+
+    fn outcome(&self) -> Outcome {
+        let (notches, reversed, momentum) = self.scroll_counts();
+        let (bad, total) = match self.spec.test {
+            Test::Left | Test::Right => (
+                self.clicks.session.chatter_count(),
+                self.clicks.session.click_count(),
+            ),
+            Test::Wheel => (reversed, notches + reversed),
+            // fewer events than notches are skipped detents, more are doubled ones
+            Test::Detents => (
+                notches.abs_diff(self.spec.amount as usize) + reversed,
+                self.spec.amount as usize,
+            ),
+            Test::Drag | Test::Hold => (self.dropouts, self.presses),
+        };
+        Outcome {
+            spec: self.spec,
+            bad,
+            total,
+            momentum,
+            momentum_dropped: self.drop_momentum,
+            session: matches!(self.spec.test, Test::Left | Test::Right)
+                .then(|| self.clicks.session.clone()),
+        }
+    }
+}
+
+/// run every step of the plan, `None` when aborted
+pub fn run<B: Backend>(
+    terminal: &mut Terminal<B>,
+    detect_window: Duration,
+    plan: &Plan,
+    drop_momentum: bool,
+) -> error::Result<Option<Vec<Outcome>>> {
+    let mut outcomes = Vec::with_capacity(plan.steps.len());
+
+    for (index, spec) in plan.steps.iter().enumerate() {
+        let mut step = Step::new(*spec, detect_window, drop_momentum);
+
+        while !step.finished() {
+            terminal.draw(|f| ui(f, &step, index, plan.steps.len()))?;
+
+            // keep the hold gauge moving
+            if !input::poll(Duration::from_millis(50))? {
+                continue;
+            }
+
+            match input::read()? {
+                Event::Key(key) if key.code == KeyCode::Char('q') => return Ok(None),
+                Event::Key(key) if key.code == KeyCode::Enter => {
+                    step.confirmed = step.started && step.spec.test == Test::Detents;
+                    step.started = true;
+                }
+                Event::Mouse(event) if step.started => step.on_mouse(event),
+                _ => {}
+            }
+        }
+
+        outcomes.push(step.outcome());
+    }
+
+    Ok(Some(outcomes))
+}
+
+fn ui<B: Backend>(f: &mut Frame<B>, step: &Step, index: usize, steps: usize) {
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(5), Constraint::Length(3)].as_ref())
+        .split(f.size());
+
+    let mut text = vec![
+        Line::from(format!(
+            "step {}/{}: {}",
+            index + 1,
+            steps,
+            step.spec.test.component()
+        )),
+        Line::from(""),
+        Line::from(step.spec.test.instruction(step.spec.amount)),
+    ];
+    if !step.started {
+        text.push(Line::from(""));
+        text.push(Line::from("press enter when ready, q to abort"));
+    }
+
+    let p = Paragraph::new(text)
+        .block(Block::default().title("diagnose").borders(Borders::ALL))
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+    f.render_widget(p, layout[0]);
+
+    let gauge = Gauge::default()
+        .block(Block::default().title("progress").borders(Borders::ALL))
+        .ratio(step.progress());
+    f.render_widget(gauge, layout[1]);
+}
+
+/// the worst verdict of all components
+pub fn overall(outcomes: &[Outcome]) -> Verdict {
+    outcomes
+        .iter()
+        .map(Outcome::verdict)
+        .max()
+        .unwrap_or(Verdict::Healthy)
+}
+
+/// a line per component, which of them fail and the health score
+pub fn report(outcomes: &[Outcome]) -> Vec<String> {
+    let mut lines = outcomes
+        .iter()
+        .map(|outcome| {
+            format!(
+                "{:<28}{:<10}{}",
+                outcome.spec.test.component(),
+                outcome.verdict().name(),
+                outcome.describe()
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let failing = outcomes
+        .iter()
+        .filter(|o| o.verdict() == Verdict::Failing)
+        .map(|o| o.spec.test.component())
+        .collect::<Vec<_>>();
+    if failing.is_empty() {
+        lines.push("all components look healthy".to_string());
+    } else {
+        let verb = if failing.len() == 1 { "is" } else { "are" };
+        lines.push(format!("your {} {} failing", failing.join(" and "), verb));
+    }
+    if let Some(health) = Health::of_diagnosis(outcomes) {
+        lines.extend(health.describe());
+    }
+    lines
+}
+
+pub fn print_report(outcomes: &[Outcome]) {
+    for line in report(outcomes) {
+        println!("{}", line);
+    }
+}
+
+/// the outcome of every step and the health score
+pub fn to_json(outcomes: &[Outcome]) -> Value {
+    let steps = outcomes
+        .iter()
+        .map(|o| {
+            json::object([
+                ("test", Value::from(o.spec.test.component())),
+                ("verdict", Value::from(o.verdict().name())),
+                ("bad", Value::from(o.bad as u64)),
+                ("total", Value::from(o.total as u64)),
+                ("max_rate", Value::from(o.spec.max_rate)),
+                ("momentum", Value::from(o.momentum as u64)),
+            ])
+        })
+        .collect();
+    json::object([
+        ("verdict", Value::from(overall(outcomes).name())),
+        ("steps", Value::Array(steps)),
+        (
+            "health",
+            Value::from(Health::of_diagnosis(outcomes).map(|h| h.to_json())),
+        ),
+        ("build", Build::current().to_json()),
+    ])
+}
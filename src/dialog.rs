@@ -0,0 +1,100 @@
+use crossterm::event::KeyCode;
+use ratatui::{
+    backend::Backend,
+    layout::Rect,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    focus::{self, Nav},
+    i18n::{tr, Msg},
+};
+
+/// a yes or no question over the screen, asked before `action` loses data
+pub struct Confirm<A> {
+    pub action: A,
+    text: String,
+    /// the focus is on no, enter answers with the button it is on
+    no: bool,
+}
+
+impl<A: Copy> Confirm<A> {
+    pub fn new(action: A, text: impl Into<String>) -> Self {
+        Self {
+            action,
+            text: text.into(),
+            no: false,
+        }
+    }
+
+    /// the action once y, or enter on yes, is pressed, none for n, esc or enter on no; the
+    /// dialog stays open for any other key, tab and the arrows move between the buttons
+    pub fn answer(&mut self, code: KeyCode) -> Option<Option<A>> {
+        match (code, Nav::of(code)) {
+            (KeyCode::Char('y' | 'Y'), _) => Some(Some(self.action)),
+            (KeyCode::Char('n' | 'N'), _) | (_, Some(Nav::Back)) => Some(None),
+            (_, Some(Nav::Activate)) => Some((!self.no).then_some(self.action)),
+            (_, Some(Nav::Left | Nav::Right | Nav::Next | Nav::Previous)) => {
+                self.no = !self.no;
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// yes and no, the one enter takes marked
+    pub fn buttons(&self) -> Line<'static> {
+        Line::from(vec![
+            focus::button(tr(Msg::Yes), !self.no),
+            Span::raw("  "),
+            focus::button(tr(Msg::No), self.no),
+        ])
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn draw<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
+        let keys = tr(Msg::ConfirmKeys);
+        let buttons = self.buttons();
+        let width = (self.text.width().max(keys.width()).max(buttons.width()) as u16 + 4).min(60);
+        // the question may wrap, a blank row, the buttons and the keys follow it
+        let inner = width.saturating_sub(2).max(1) as usize;
+        let rows =
+            self.text.width().div_ceil(inner) as u16 + keys.width().div_ceil(inner) as u16 + 2;
+
+        let popup = centered(area, width, rows + 2);
+        f.render_widget(Clear, popup);
+        f.render_widget(
+            Paragraph::new(vec![
+                Line::from(self.text.as_str()),
+                Line::from(""),
+                buttons,
+                Line::from(keys),
+            ])
+            .block(
+                Block::default()
+                    .title(tr(Msg::ConfirmTitle))
+                    .borders(Borders::ALL),
+            )
+            .wrap(Wrap { trim: true }),
+            popup,
+        );
+    }
+}
+
+/// a box of the size in the middle of the area, cut to fit
+pub fn centered(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
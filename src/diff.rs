@@ -0,0 +1,332 @@
+use std::{path::Path, time::Duration};
+
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::Modifier,
+    symbols,
+    text::{Line, Span},
+    widgets::{Axis, Block, Borders, Chart, Dataset, GraphType, Paragraph},
+    Frame, Terminal,
+};
+
+use crate::{
+    analyzer::HoldProfile,
+    error, input,
+    session::{self, Session},
+    stats, theme,
+    version::Build,
+};
+
+/// bars of the overlaid histograms
+const BINS: usize = 30;
+/// the histograms stop above this quantile of the gaps, long pauses would flatten them
+const RANGE_QUANTILE: f64 = 0.95;
+/// relative change below which a metric counts as unchanged
+const SAME: f64 = 0.05;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Histogram {
+    Gaps,
+    Holds,
+}
+
+/// one row of the stats panes
+struct Metric {
+    name: &'static str,
+    unit: &'static str,
+    value: fn(&Session) -> Option<f64>,
+    /// whether a rise is a change for the worse
+    lower_is_better: bool,
+}
+
+fn ms(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+const METRICS: [Metric; 8] = [
+    Metric {
+        name: "clicks",
+        unit: "",
+        value: |s| Some(s.click_count() as f64),
+        lower_is_better: false,
+    },
+    Metric {
+        name: "chatter",
+        unit: "",
+        value: |s| Some(s.chatter_count() as f64),
+        lower_is_better: true,
+    },
+    Metric {
+        name: "chatter rate",
+        unit: "%",
+        value: |s| Some(s.chatter_rate() * 100.0),
+        lower_is_better: true,
+    },
+    Metric {
+        name: "switch fault chatter",
+        unit: "",
+        value: |s| Some(s.fast_chatter_count() as f64),
+        lower_is_better: true,
+    },
+    Metric {
+        name: "shortest chatter",
+        unit: " ms",
+        value: |s| s.shortest_chatter().map(ms),
+        lower_is_better: false,
+    },
+    Metric {
+        name: "longest clean streak",
+        unit: "",
+        value: |s| Some(s.clean_streaks().1 as f64),
+        lower_is_better: false,
+    },
+    Metric {
+        name: "mean interval",
+        unit: " ms",
+        value: |s| s.mean_interval().map(ms),
+        lower_is_better: false,
+    },
+    Metric {
+        name: "hold median",
+        unit: " ms",
+        value: |s| HoldProfile::new(s).map(|h| ms(h.median)),
+        lower_is_better: false,
+    },
+];
+
+struct DiffState {
+    /// the baseline first, the deltas are of the second
+    sessions: [(String, Session); 2],
+    histogram: Histogram,
+}
+
+/// two saved sessions side by side until q
+pub fn run<B: Backend>(terminal: &mut Terminal<B>, a: &Path, b: &Path) -> error::Result<()> {
+    let load = |path: &Path| -> error::Result<(String, Session)> {
+        let name = match path.file_name() {
+            _ if path == Path::new("-") => "stdin".into(),
+            name => name.unwrap_or(path.as_os_str()).to_string_lossy(),
+        };
+        Ok((name.into_owned(), Session::load(path)?))
+    };
+    let mut state = DiffState {
+        sessions: [load(a)?, load(b)?],
+        histogram: Histogram::Gaps,
+    };
+
+    loop {
+        terminal.draw(|f| ui(f, &state))?;
+
+        let Event::Key(key) = input::read()? else {
+            continue;
+        };
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => break,
+            KeyCode::Char('s') => state.sessions.swap(0, 1),
+            KeyCode::Char('h') => {
+                state.histogram = match state.histogram {
+                    Histogram::Gaps => Histogram::Holds,
+                    Histogram::Holds => Histogram::Gaps,
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn ui<B: Backend>(f: &mut Frame<B>, state: &DiffState) {
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Length(METRICS.len() as u16 + 4),
+                Constraint::Min(5),
+                Constraint::Length(1),
+            ]
+            .as_ref(),
+        )
+        .split(f.size());
+    let panes = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+        .split(layout[0]);
+
+    let [(_, base), _] = &state.sessions;
+    for (i, (name, session)) in state.sessions.iter().enumerate() {
+        pane(f, name, session, (i == 1).then_some(base), panes[i]);
+    }
+    histogram(f, state, layout[1]);
+    f.render_widget(
+        Paragraph::new("s: swap baseline  h: gaps or holds  q: quit"),
+        layout[2],
+    );
+}
+
+/// the stats of one session, with the change from `base` next to each
+fn pane<B: Backend>(
+    f: &mut Frame<B>,
+    name: &str,
+    session: &Session,
+    base: Option<&Session>,
+    area: Rect,
+) {
+    let palette = theme::palette();
+    let mut header = format!(
+        "{}  {}",
+        session::format_timestamp(session.started_at),
+        session.device.as_deref().unwrap_or("-")
+    );
+    if session.swapped {
+        header.push_str("  buttons swapped");
+    }
+    // left and right mean other switches when only one machine swapped them
+    let header = if base.is_some_and(|b| b.swapped != session.swapped) {
+        Line::styled(header, palette.outlier)
+    } else {
+        Line::from(header)
+    };
+    let build = session.build.as_ref().map_or_else(
+        || "recorded with an unknown build".to_string(),
+        Build::describe,
+    );
+    // detectors change between versions, a delta may be the build and not the mouse
+    let build = if base.is_some_and(|b| !same_build(b, session)) {
+        Line::styled(build, palette.outlier)
+    } else {
+        Line::from(build)
+    };
+    let mut lines = vec![header, build];
+    for metric in &METRICS {
+        let value = (metric.value)(session);
+        let mut spans = vec![Span::raw(format!(
+            "{:<22}{}",
+            metric.name,
+            value
+                .map(|v| format!("{}{}", round(v), metric.unit))
+                .unwrap_or_else(|| "-".to_string())
+        ))];
+        if let (Some(value), Some(before)) = (value, base.and_then(metric.value)) {
+            let delta = value - before;
+            let changed = delta.abs() > before.abs() * SAME && delta != 0.0;
+            if changed {
+                let worse = (delta > 0.0) == metric.lower_is_better;
+                let text = format!(
+                    "  {}{}{} {}",
+                    if delta > 0.0 { "+" } else { "" },
+                    round(delta),
+                    metric.unit,
+                    if worse { "worse" } else { "better" }
+                );
+                spans.push(if worse {
+                    Span::styled(text, palette.chatter.add_modifier(Modifier::BOLD))
+                } else {
+                    Span::raw(text)
+                });
+            }
+        }
+        lines.push(Line::from(spans));
+    }
+
+    let title = match base {
+        Some(_) => format!("{} (vs baseline)", name),
+        None => format!("{} (baseline)", name),
+    };
+    f.render_widget(
+        Paragraph::new(lines).block(Block::default().title(title).borders(Borders::ALL)),
+        area,
+    );
+}
+
+fn same_build(a: &Session, b: &Session) -> bool {
+    match (&a.build, &b.build) {
+        (Some(a), Some(b)) => a.same_code(b),
+        (a, b) => a.is_none() && b.is_none(),
+    }
+}
+
+fn round(value: f64) -> String {
+    if value.fract() == 0.0 {
+        format!("{:.0}", value)
+    } else {
+        format!("{:.1}", value)
+    }
+}
+
+/// milliseconds the histogram is drawn over
+fn samples(session: &Session, histogram: Histogram) -> Vec<f64> {
+    match histogram {
+        Histogram::Gaps => session.gaps().map(ms).collect(),
+        Histogram::Holds => session
+            .clicks
+            .iter()
+            .filter_map(|c| c.hold)
+            .map(ms)
+            .collect(),
+    }
+}
+
+/// share of the samples in each bin, in percent, at the middle of the bin
+fn bins(samples: &[f64], max: f64) -> Vec<(f64, f64)> {
+    let width = max / BINS as f64;
+    stats::histogram(samples, max, BINS)
+        .iter()
+        .enumerate()
+        .map(|(i, count)| {
+            (
+                (i as f64 + 0.5) * width,
+                *count as f64 * 100.0 / samples.len().max(1) as f64,
+            )
+        })
+        .collect()
+}
+
+fn histogram<B: Backend>(f: &mut Frame<B>, state: &DiffState, area: Rect) {
+    let samples = state
+        .sessions
+        .iter()
+        .map(|(_, s)| samples(s, state.histogram))
+        .collect::<Vec<_>>();
+    let max = samples
+        .iter()
+        .filter_map(|s| stats::quantile(s, RANGE_QUANTILE))
+        .fold(1.0, f64::max);
+    let data = samples.iter().map(|s| bins(s, max)).collect::<Vec<_>>();
+    let max_share = data.iter().flatten().map(|p| p.1).fold(1.0, f64::max);
+
+    let palette = theme::palette();
+    let datasets = data
+        .iter()
+        .zip(&state.sessions)
+        .zip([palette.interval, palette.threshold])
+        .map(|((points, (name, _)), style)| {
+            Dataset::default()
+                .name(name.as_str())
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(style)
+                .data(points)
+        })
+        .collect();
+
+    let title = match state.histogram {
+        Histogram::Gaps => "gaps between clicks (% of clicks)",
+        Histogram::Holds => "hold times (% of clicks)",
+    };
+    let chart = Chart::new(datasets)
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .x_axis(
+            Axis::default()
+                .title("ms")
+                .bounds([0.0, max])
+                .labels(vec!["0".into(), format!("{:.0}", max).into()]),
+        )
+        .y_axis(
+            Axis::default()
+                .bounds([0.0, max_share])
+                .labels(vec!["0".into(), format!("{:.0}", max_share).into()]),
+        );
+    f.render_widget(chart, area);
+}
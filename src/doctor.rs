@@ -0,0 +1,298 @@
+use std::{
+    env, fs,
+    io::{self, IsTerminal, Write},
+    time::{Duration, Instant},
+};
+
+use crossterm::event::Event;
+
+use crate::{
+    clock::{self, Clock},
+    config::{self, Config},
+    diagnose::Plan,
+    environment,
+    error::{self, Error},
+    handedness, input, quirks, session,
+    terminal::CaptureGuard,
+};
+
+/// time the mouse has to be moved in to show the terminal reports it
+const MOUSE_WAIT: Duration = Duration::from_secs(5);
+/// clock reads the resolution is taken from
+const CLOCK_READS: usize = 100_000;
+/// coarser clocks blur the short chatter intervals
+const CLOCK_COARSE: Duration = Duration::from_micros(100);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Ok,
+    Warn,
+    Fail,
+}
+
+struct Finding {
+    status: Status,
+    topic: &'static str,
+    text: String,
+}
+
+fn finding(status: Status, topic: &'static str, text: impl Into<String>) -> Finding {
+    Finding {
+        status,
+        topic,
+        text: text.into(),
+    }
+}
+
+fn terminal() -> Vec<Finding> {
+    let mut findings = Vec::new();
+    if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        findings.push(finding(
+            Status::Fail,
+            "terminal",
+            "stdin or stdout is not a terminal, run mouse-checker in an interactive terminal",
+        ));
+    }
+    match env::var("TERM") {
+        Err(_) => findings.push(finding(Status::Fail, "terminal", "TERM is not set")),
+        Ok(term) if term == "dumb" => findings.push(finding(
+            Status::Fail,
+            "terminal",
+            "TERM is dumb, the terminal supports neither the tui nor the mouse",
+        )),
+        Ok(term) => {
+            let program = env::var("TERM_PROGRAM")
+                .map(|p| format!(" in {}", p))
+                .unwrap_or_default();
+            findings.push(finding(
+                Status::Ok,
+                "terminal",
+                format!("TERM={}{}", term, program),
+            ));
+        }
+    }
+    let problems = environment::timing_problems();
+    if problems.is_empty() {
+        findings.push(finding(
+            Status::Ok,
+            "timing",
+            "no multiplexer, remote shell or console in the way",
+        ));
+    }
+    findings.extend(
+        problems
+            .into_iter()
+            .map(|p| finding(Status::Warn, "timing", p)),
+    );
+    findings.push(match handedness::detected() {
+        Some(true) => finding(
+            Status::Ok,
+            "buttons",
+            "swapped for a left hand, the clicks are recorded as the buttons pressed",
+        ),
+        Some(false) => finding(Status::Ok, "buttons", "not swapped"),
+        None => finding(
+            Status::Warn,
+            "buttons",
+            "the button swap setting could not be read, pass --swap-buttons if it is on",
+        ),
+    });
+    let quirks = quirks::active();
+    if !quirks.is_empty() {
+        findings.push(finding(
+            Status::Ok,
+            "quirks",
+            format!("working around {}", quirks.join(", ")),
+        ));
+    }
+    findings
+}
+
+/// whether a mouse event arrives while the user moves the mouse
+fn mouse() -> Finding {
+    if !io::stdin().is_terminal() {
+        return finding(Status::Warn, "mouse", "skipped, stdin is not a terminal");
+    }
+    print!(
+        "move the mouse over this terminal within {} s… ",
+        MOUSE_WAIT.as_secs()
+    );
+    let _ = io::stdout().flush();
+    let arrived = (|| -> error::Result<bool> {
+        let _guard = CaptureGuard::new()?;
+        let deadline = Instant::now() + MOUSE_WAIT;
+        while let Some(left) = deadline.checked_duration_since(Instant::now()) {
+            if input::poll(left)? && matches!(input::read()?, Event::Mouse(_)) {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    })();
+    println!();
+    match arrived {
+        Ok(true) => finding(Status::Ok, "mouse", "the terminal reports mouse events"),
+        Ok(false) => finding(
+            Status::Fail,
+            "mouse",
+            format!(
+                "no mouse event in {} s, try another terminal emulator or turn the mouse on \
+                 in tmux (`set -g mouse on`)",
+                MOUSE_WAIT.as_secs()
+            ),
+        ),
+        Err(e) => finding(Status::Fail, "mouse", e.to_string()),
+    }
+}
+
+/// smallest step between two reads of the clock
+fn resolution(clock: &Clock) -> Option<Duration> {
+    let mut last = clock.now();
+    let mut finest = None::<Duration>;
+    for _ in 0..CLOCK_READS {
+        let now = clock.now();
+        if now > last {
+            finest = Some(finest.map_or(now - last, |f| f.min(now - last)));
+        }
+        last = now;
+    }
+    finest
+}
+
+fn clocks() -> Vec<Finding> {
+    [clock::Source::Instant, clock::Source::MonotonicRaw]
+        .into_iter()
+        .map(|source| match Clock::new(source) {
+            Err(e) => finding(
+                Status::Warn,
+                "clock",
+                format!("{} is not available: {}", source.name(), e),
+            ),
+            Ok(clock) => match resolution(&clock) {
+                Some(step) if step <= CLOCK_COARSE => finding(
+                    Status::Ok,
+                    "clock",
+                    format!("{} ticks in {} ns", source.name(), step.as_nanos()),
+                ),
+                Some(step) => finding(
+                    Status::Warn,
+                    "clock",
+                    format!(
+                        "{} only ticks in {} µs, short chatter intervals blur",
+                        source.name(),
+                        step.as_micros()
+                    ),
+                ),
+                None => finding(
+                    Status::Fail,
+                    "clock",
+                    format!("{} never moved", source.name()),
+                ),
+            },
+        })
+        .collect()
+}
+
+fn configuration() -> Vec<Finding> {
+    let config = match Config::load() {
+        Ok(Some(config)) => config,
+        Ok(None) => {
+            return vec![finding(
+                Status::Warn,
+                "config",
+                format!(
+                    "no {} yet, `mouse-checker setup` writes one",
+                    config::path().display()
+                ),
+            )]
+        }
+        Err(e) => return vec![finding(Status::Fail, "config", e.to_string())],
+    };
+    let mut findings = vec![finding(
+        Status::Ok,
+        "config",
+        format!("{} is valid", config::path().display()),
+    )];
+    if config.backend != "terminal" {
+        findings.push(finding(
+            Status::Warn,
+            "config",
+            format!(
+                "backend `{}` is unknown, this build reads terminal mouse events",
+                config.backend
+            ),
+        ));
+    }
+    if let Some(plan) = &config.plan {
+        findings.push(match Plan::load(plan) {
+            Ok(_) => finding(
+                Status::Ok,
+                "config",
+                format!("plan {} loads", plan.display()),
+            ),
+            Err(e) => finding(Status::Fail, "config", e.to_string()),
+        });
+    }
+    findings
+}
+
+/// the sessions dir can be written, saving fails at the very end otherwise
+fn storage() -> Finding {
+    let dir = session::sessions_dir();
+    let probe = dir.join(".doctor");
+    let writable = fs::create_dir_all(&dir).and_then(|_| fs::write(&probe, b""));
+    let _ = fs::remove_file(&probe);
+    match writable {
+        Ok(()) => finding(
+            Status::Ok,
+            "storage",
+            format!("sessions are saved to {}", dir.display()),
+        ),
+        Err(e) => finding(
+            Status::Fail,
+            "storage",
+            format!("cannot write {}: {}", dir.display(), e),
+        ),
+    }
+}
+
+impl Finding {
+    fn line(&self) -> String {
+        let mark = match self.status {
+            Status::Ok => "ok  ",
+            Status::Warn => "warn",
+            Status::Fail => "FAIL",
+        };
+        format!("[{}] {:<8} {}", mark, self.topic, self.text)
+    }
+}
+
+/// the checklist without the mouse probe, as `doctor` prints it
+pub fn checklist() -> Vec<String> {
+    let mut findings = terminal();
+    findings.extend(clocks());
+    findings.extend(configuration());
+    findings.push(storage());
+    findings.iter().map(Finding::line).collect()
+}
+
+/// check the environment and print a checklist, failing when anything would break a test
+pub fn run(probe_mouse: bool) -> error::Result<()> {
+    let mut findings = terminal();
+    if probe_mouse {
+        findings.push(mouse());
+    }
+    findings.extend(clocks());
+    findings.extend(configuration());
+    findings.push(storage());
+
+    for f in &findings {
+        println!("{}", f.line());
+    }
+    let count = |status| findings.iter().filter(|f| f.status == status).count();
+    let (warnings, failures) = (count(Status::Warn), count(Status::Fail));
+    println!("{} warnings, {} failures", warnings, failures);
+    if failures > 0 {
+        return Err(Error::Unhealthy(failures));
+    }
+    Ok(())
+}
@@ -0,0 +1,183 @@
+use std::time::{Duration, Instant};
+
+use crossterm::event::{Event, KeyCode, MouseEventKind};
+use ratatui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout},
+    text::Line,
+    widgets::{Block, Borders, Paragraph, Sparkline},
+    Frame, Terminal,
+};
+
+use crate::{error, input};
+
+/// recent bursts listed below the chart
+const KEEP_BURSTS: usize = 10;
+
+struct Burst {
+    /// time of every click since the first click of the burst
+    clicks: Vec<Duration>,
+}
+
+impl Burst {
+    fn span(&self) -> Duration {
+        self.clicks.last().copied().unwrap_or_default()
+    }
+
+    fn rate(&self) -> f64 {
+        let secs = self.span().as_secs_f64();
+        if secs > 0.0 {
+            (self.clicks.len() - 1) as f64 / secs
+        } else {
+            0.0
+        }
+    }
+
+    /// gaps between consecutive clicks in microseconds
+    fn intervals(&self) -> Vec<u64> {
+        self.clicks
+            .windows(2)
+            .map(|w| (w[1] - w[0]).as_micros() as u64)
+            .collect()
+    }
+}
+
+struct DragState {
+    gap: Duration,
+    current: Option<(Instant, Burst)>,
+    last_click: Option<Instant>,
+    bursts: Vec<Burst>,
+}
+
+impl DragState {
+    fn on_click(&mut self) {
+        let now = Instant::now();
+        self.tick();
+        let (start, burst) = self
+            .current
+            .get_or_insert_with(|| (now, Burst { clicks: Vec::new() }));
+        burst.clicks.push(now - *start);
+        self.last_click = Some(now);
+    }
+
+    /// close the running burst once no click arrived for the gap
+    fn tick(&mut self) {
+        if self.last_click.is_some_and(|t| t.elapsed() > self.gap) {
+            if let Some((_, burst)) = self.current.take() {
+                self.bursts.push(burst);
+            }
+            self.last_click = None;
+        }
+    }
+
+    fn shown(&self) -> Option<&Burst> {
+        self.current
+            .as_ref()
+            .map(|(_, b)| b)
+            .or_else(|| self.bursts.last())
+    }
+}
+
+pub fn run<B: Backend>(terminal: &mut Terminal<B>, gap: Duration) -> error::Result<()> {
+    let mut state = DragState {
+        gap,
+        current: None,
+        last_click: None,
+        bursts: Vec::new(),
+    };
+
+    loop {
+        state.tick();
+        terminal.draw(|f| ui(f, &state))?;
+
+        if !input::poll(Duration::from_millis(50))? {
+            continue;
+        }
+
+        match input::read()? {
+            Event::Mouse(event) => {
+                if let MouseEventKind::Down(_) = event.kind {
+                    state.on_click();
+                }
+            }
+            Event::Key(key) => match key.code {
+                KeyCode::Char('q') => break,
+                KeyCode::Char('r') => {
+                    state.current = None;
+                    state.last_click = None;
+                    state.bursts.clear();
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn ui<B: Backend>(f: &mut Frame<B>, state: &DragState) {
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Length(4),
+                Constraint::Min(5),
+                Constraint::Length(KEEP_BURSTS as u16 + 2),
+                Constraint::Length(1),
+            ]
+            .as_ref(),
+        )
+        .split(f.size());
+
+    let summary = match state.shown() {
+        Some(burst) => vec![
+            Line::from(format!(
+                "{} clicks in {} ms ({:.1} clicks/s)",
+                burst.clicks.len(),
+                burst.span().as_millis(),
+                burst.rate()
+            )),
+            Line::from(format!(
+                "a burst ends after {} ms without clicks",
+                state.gap.as_millis()
+            )),
+        ],
+        None => vec![Line::from("drag click across the button")],
+    };
+    f.render_widget(
+        Paragraph::new(summary).block(Block::default().title("drag click").borders(Borders::ALL)),
+        layout[0],
+    );
+
+    let intervals = state.shown().map(Burst::intervals).unwrap_or_default();
+    let sparkline = Sparkline::default()
+        .block(
+            Block::default()
+                .title("interval per click")
+                .borders(Borders::ALL),
+        )
+        .data(&intervals);
+    f.render_widget(sparkline, layout[1]);
+
+    let recent = state
+        .bursts
+        .iter()
+        .rev()
+        .take(KEEP_BURSTS)
+        .map(|b| {
+            Line::from(format!(
+                "{:>4} clicks  {:>6} ms  {:>6.1} clicks/s",
+                b.clicks.len(),
+                b.span().as_millis(),
+                b.rate()
+            ))
+        })
+        .collect::<Vec<_>>();
+    f.render_widget(
+        Paragraph::new(recent).block(Block::default().title("bursts").borders(Borders::ALL)),
+        layout[2],
+    );
+
+    f.render_widget(Paragraph::new("r: reset  q: quit"), layout[3]);
+}
@@ -0,0 +1,497 @@
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::Path,
+    sync::Mutex,
+    time::Instant,
+};
+
+use crossterm::event::{Event, KeyEventKind, MouseButton, MouseEventKind};
+
+use crate::{
+    error::{self, Error},
+    json::{self, Value},
+};
+
+/// first bytes of a compact dump, json lines start with `{`
+const MAGIC: &[u8] = b"MCDUMP1\n";
+
+struct Dump {
+    out: BufWriter<File>,
+    started: Instant,
+    /// time of the last event of a compact dump, none for json lines
+    compact: Option<u64>,
+}
+
+/// the `--raw-dump` file, every event read from the terminal goes in before any quirk
+/// is worked around
+static DUMP: Mutex<Option<Dump>> = Mutex::new(None);
+
+/// start the dump, `compact` writes the binary format instead of a json object per line
+pub fn open(path: &Path, compact: bool) -> error::Result<()> {
+    let failed = |source| Error::Export {
+        path: path.to_path_buf(),
+        source,
+    };
+    let mut out = BufWriter::new(File::create(path).map_err(failed)?);
+    if compact {
+        out.write_all(MAGIC).map_err(failed)?;
+    }
+    if let Ok(mut dump) = DUMP.lock() {
+        *dump = Some(Dump {
+            out,
+            started: Instant::now(),
+            compact: compact.then_some(0),
+        });
+    }
+    // the screen the events were read on, a replay starts with it
+    if let Ok((columns, rows)) = crossterm::terminal::size() {
+        record(&Event::Resize(columns, rows));
+    }
+    Ok(())
+}
+
+/// stop the dump, the events read after are left out
+pub fn close() {
+    if let Ok(mut dump) = DUMP.lock() {
+        *dump = None;
+    }
+}
+
+/// append an event as one line of json, a dump that cannot be written any more is dropped
+/// rather than ending the test
+pub fn record(event: &Event) {
+    let Ok(mut guard) = DUMP.lock() else {
+        return;
+    };
+    let Some(dump) = guard.as_mut() else {
+        return;
+    };
+    let line = with_time(to_json(event), dump.started.elapsed().as_micros() as u64);
+    let written = match &mut dump.compact {
+        // every event crossterm gives has the fields the compact form needs
+        Some(last) => {
+            let mut bytes = Vec::new();
+            if encode(&line, last, &mut bytes).is_err() {
+                // a dump with an event left out would replay wrong, so it ends here
+                *guard = None;
+                return;
+            }
+            dump.out.write_all(&bytes)
+        }
+        None => writeln!(dump.out, "{}", line.to_string_compact()),
+    };
+    if written.and_then(|_| dump.out.flush()).is_err() {
+        *guard = None;
+    }
+}
+
+fn with_time(mut line: Value, at: u64) -> Value {
+    if let Value::Object(map) = &mut line {
+        map.insert("at_us".to_string(), Value::from(at));
+    }
+    line
+}
+
+fn button_name(button: MouseButton) -> &'static str {
+    match button {
+        MouseButton::Left => "left",
+        MouseButton::Right => "right",
+        MouseButton::Middle => "middle",
+    }
+}
+
+fn mouse_kind(kind: MouseEventKind) -> (&'static str, Option<MouseButton>) {
+    match kind {
+        MouseEventKind::Down(b) => ("down", Some(b)),
+        MouseEventKind::Up(b) => ("up", Some(b)),
+        MouseEventKind::Drag(b) => ("drag", Some(b)),
+        MouseEventKind::Moved => ("moved", None),
+        MouseEventKind::ScrollDown => ("scroll_down", None),
+        MouseEventKind::ScrollUp => ("scroll_up", None),
+        MouseEventKind::ScrollLeft => ("scroll_left", None),
+        MouseEventKind::ScrollRight => ("scroll_right", None),
+    }
+}
+
+fn key_kind(kind: KeyEventKind) -> &'static str {
+    match kind {
+        KeyEventKind::Press => "press",
+        KeyEventKind::Repeat => "repeat",
+        KeyEventKind::Release => "release",
+    }
+}
+
+/// every field of the event, the key code as crossterm prints it
+fn to_json(event: &Event) -> Value {
+    match event {
+        Event::Mouse(mouse) => {
+            let (kind, button) = mouse_kind(mouse.kind);
+            json::object([
+                ("type", Value::from("mouse")),
+                ("kind", Value::from(kind)),
+                ("button", Value::from(button.map(button_name))),
+                ("column", Value::from(u64::from(mouse.column))),
+                ("row", Value::from(u64::from(mouse.row))),
+                ("modifiers", Value::from(u64::from(mouse.modifiers.bits()))),
+            ])
+        }
+        Event::Key(key) => json::object([
+            ("type", Value::from("key")),
+            ("code", Value::from(format!("{:?}", key.code))),
+            ("kind", Value::from(key_kind(key.kind))),
+            ("modifiers", Value::from(u64::from(key.modifiers.bits()))),
+            ("state", Value::from(u64::from(key.state.bits()))),
+        ]),
+        Event::Resize(columns, rows) => json::object([
+            ("type", Value::from("resize")),
+            ("columns", Value::from(u64::from(*columns))),
+            ("rows", Value::from(u64::from(*rows))),
+        ]),
+        Event::FocusGained => json::object([("type", Value::from("focus_gained"))]),
+        Event::FocusLost => json::object([("type", Value::from("focus_lost"))]),
+        Event::Paste(text) => json::object([
+            ("type", Value::from("paste")),
+            ("text", Value::from(text.as_str())),
+        ]),
+    }
+}
+
+fn put_varint(out: &mut Vec<u8>, mut n: u64) {
+    while n >= 0x80 {
+        out.push(n as u8 | 0x80);
+        n >>= 7;
+    }
+    out.push(n as u8);
+}
+
+fn put_text(out: &mut Vec<u8>, text: &str) {
+    put_varint(out, text.len() as u64);
+    out.extend_from_slice(text.as_bytes());
+}
+
+/// the compact form of a line of json: the time as the zigzag varint of its step from the
+/// event before, a tag byte for the type and the fields the type has, numbers as varints
+/// and names as their index into the tables below; nothing is written and `last` stays
+/// for a line that fails
+fn encode(line: &Value, last: &mut u64, bytes: &mut Vec<u8>) -> Result<(), String> {
+    let number = |name: &str| {
+        line.get(name)
+            .and_then(Value::as_u64)
+            .ok_or_else(|| format!("no `{}`", name))
+    };
+    let text = |name: &str| {
+        line.get(name)
+            .and_then(Value::as_str)
+            .ok_or_else(|| format!("no `{}`", name))
+    };
+    let index = |table: &[&str], name: &str| {
+        let value = text(name)?;
+        table
+            .iter()
+            .position(|t| *t == value)
+            .map(|i| i as u8)
+            .ok_or_else(|| format!("unknown `{}` {}", name, value))
+    };
+    let at = number("at_us")?;
+    let step = i64::try_from(i128::from(at) - i128::from(*last))
+        .map_err(|_| "`at_us` too far from the event before".to_string())?;
+    let mut event = Vec::new();
+    let out = &mut event;
+    put_varint(out, ((step << 1) ^ (step >> 63)) as u64);
+    match text("type")? {
+        "mouse" => {
+            out.push(TAG_MOUSE);
+            out.push(index(&MOUSE_KINDS, "kind")?);
+            out.push(match line.get("button") {
+                Some(Value::Null) | None => NO_BUTTON,
+                Some(_) => index(&BUTTONS, "button")?,
+            });
+            for name in ["column", "row", "modifiers"] {
+                put_varint(out, number(name)?);
+            }
+        }
+        "key" => {
+            out.push(TAG_KEY);
+            put_text(out, text("code")?);
+            out.push(index(&KEY_KINDS, "kind")?);
+            put_varint(out, number("modifiers")?);
+            put_varint(out, number("state")?);
+        }
+        "resize" => {
+            out.push(TAG_RESIZE);
+            put_varint(out, number("columns")?);
+            put_varint(out, number("rows")?);
+        }
+        "focus_gained" => out.push(TAG_FOCUS_GAINED),
+        "focus_lost" => out.push(TAG_FOCUS_LOST),
+        "paste" => {
+            out.push(TAG_PASTE);
+            put_text(out, text("text")?);
+        }
+        other => return Err(format!("unknown event type {}", other)),
+    }
+    bytes.append(&mut event);
+    *last = at;
+    Ok(())
+}
+
+const TAG_MOUSE: u8 = 0;
+const TAG_KEY: u8 = 1;
+const TAG_RESIZE: u8 = 2;
+const TAG_FOCUS_GAINED: u8 = 3;
+const TAG_FOCUS_LOST: u8 = 4;
+const TAG_PASTE: u8 = 5;
+const NO_BUTTON: u8 = u8::MAX;
+const MOUSE_KINDS: [&str; 8] = [
+    "down",
+    "up",
+    "drag",
+    "moved",
+    "scroll_down",
+    "scroll_up",
+    "scroll_left",
+    "scroll_right",
+];
+const BUTTONS: [&str; 3] = ["left", "right", "middle"];
+const KEY_KINDS: [&str; 3] = ["press", "repeat", "release"];
+
+/// reads a compact dump back, every read fails at the end of the bytes
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn byte(&mut self) -> Result<u8, String> {
+        let byte = *self.bytes.get(self.pos).ok_or("cut off in an event")?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn varint(&mut self) -> Result<u64, String> {
+        let mut n = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            n |= u64::from(byte & 0x7f) << shift;
+            if byte < 0x80 {
+                return Ok(n);
+            }
+        }
+        Err("varint too long".to_string())
+    }
+
+    fn text(&mut self) -> Result<String, String> {
+        let len = self.varint()? as usize;
+        let end = self.pos.checked_add(len).filter(|e| *e <= self.bytes.len());
+        let bytes = &self.bytes[self.pos..end.ok_or("cut off in a text")?];
+        self.pos += len;
+        String::from_utf8(bytes.to_vec()).map_err(|e| e.to_string())
+    }
+
+    fn name(&mut self, table: &[&'static str]) -> Result<&'static str, String> {
+        let i = usize::from(self.byte()?);
+        table
+            .get(i)
+            .copied()
+            .ok_or_else(|| format!("unknown name {}", i))
+    }
+
+    /// the event as the json line it was written from
+    fn event(&mut self, last: &mut u64) -> Result<Value, String> {
+        let zigzag = self.varint()?;
+        let step = (zigzag >> 1) as i64 ^ -((zigzag & 1) as i64);
+        *last = last.checked_add_signed(step).ok_or("time out of range")?;
+        let line = match self.byte()? {
+            TAG_MOUSE => {
+                let kind = self.name(&MOUSE_KINDS)?;
+                let button = match self.byte()? {
+                    NO_BUTTON => Value::Null,
+                    i => Value::from(*BUTTONS.get(usize::from(i)).ok_or("unknown button")?),
+                };
+                json::object([
+                    ("type", Value::from("mouse")),
+                    ("kind", Value::from(kind)),
+                    ("button", button),
+                    ("column", Value::from(self.varint()?)),
+                    ("row", Value::from(self.varint()?)),
+                    ("modifiers", Value::from(self.varint()?)),
+                ])
+            }
+            TAG_KEY => json::object([
+                ("type", Value::from("key")),
+                ("code", Value::from(self.text()?)),
+                ("kind", Value::from(self.name(&KEY_KINDS)?)),
+                ("modifiers", Value::from(self.varint()?)),
+                ("state", Value::from(self.varint()?)),
+            ]),
+            TAG_RESIZE => json::object([
+                ("type", Value::from("resize")),
+                ("columns", Value::from(self.varint()?)),
+                ("rows", Value::from(self.varint()?)),
+            ]),
+            TAG_FOCUS_GAINED => json::object([("type", Value::from("focus_gained"))]),
+            TAG_FOCUS_LOST => json::object([("type", Value::from("focus_lost"))]),
+            TAG_PASTE => json::object([
+                ("type", Value::from("paste")),
+                ("text", Value::from(self.text()?)),
+            ]),
+            tag => return Err(format!("unknown event tag {}", tag)),
+        };
+        Ok(with_time(line, *last))
+    }
+}
+
+/// whether the bytes of a dump are the compact form
+pub fn is_compact(bytes: &[u8]) -> bool {
+    bytes.starts_with(MAGIC)
+}
+
+/// the json lines of a compact dump, as `--raw-dump` without `--compact` writes them
+pub fn lines(bytes: &[u8]) -> Result<Vec<String>, String> {
+    let mut reader = Reader {
+        bytes,
+        pos: MAGIC.len(),
+    };
+    let mut last = 0;
+    let mut lines = Vec::new();
+    while reader.pos < bytes.len() {
+        let line = reader
+            .event(&mut last)
+            .map_err(|e| format!("event {}: {}", lines.len() + 1, e))?;
+        lines.push(line.to_string_compact());
+    }
+    Ok(lines)
+}
+
+/// turn a compact dump into json lines or json lines into a compact dump, whichever the
+/// input is not
+pub fn convert(input: &Path, output: &Path) -> error::Result<()> {
+    let failed = |message: String| Error::Config {
+        path: input.to_path_buf(),
+        message,
+    };
+    let bytes = fs::read(input).map_err(|e| failed(e.to_string()))?;
+    let out = if is_compact(&bytes) {
+        let mut text = lines(&bytes).map_err(failed)?.join("\n");
+        text.push('\n');
+        text.into_bytes()
+    } else {
+        let text = String::from_utf8(bytes).map_err(|e| failed(e.to_string()))?;
+        let mut out = MAGIC.to_vec();
+        let mut last = 0;
+        for (i, line) in text
+            .lines()
+            .enumerate()
+            .filter(|(_, l)| !l.trim().is_empty())
+        {
+            json::parse(line)
+                .and_then(|line| encode(&line, &mut last, &mut out))
+                .map_err(|e| failed(format!("line {}: {}", i + 1, e)))?;
+        }
+        out
+    };
+    fs::write(output, out).map_err(|source| Error::Export {
+        path: output.to_path_buf(),
+        source,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::{KeyCode, KeyEvent, KeyEventState, KeyModifiers, MouseEvent};
+
+    use super::*;
+
+    /// one event of every kind, a mouse event with and one without a button
+    fn events() -> Vec<Event> {
+        let mouse = |kind| {
+            Event::Mouse(MouseEvent {
+                kind,
+                column: 200,
+                row: 3,
+                modifiers: KeyModifiers::SHIFT,
+            })
+        };
+        vec![
+            Event::Resize(120, 40),
+            mouse(MouseEventKind::Down(MouseButton::Left)),
+            mouse(MouseEventKind::Up(MouseButton::Middle)),
+            mouse(MouseEventKind::Moved),
+            Event::Key(KeyEvent {
+                code: KeyCode::Char('é'),
+                modifiers: KeyModifiers::CONTROL,
+                kind: KeyEventKind::Repeat,
+                state: KeyEventState::NONE,
+            }),
+            Event::FocusLost,
+            Event::FocusGained,
+            Event::Paste("two\nlines".to_string()),
+        ]
+    }
+
+    /// the json lines of the events, the times going back once
+    fn json_lines() -> Vec<String> {
+        events()
+            .iter()
+            .zip([0, 150, 90_000, 89_000, 1 << 40, 5, 6, 7])
+            .map(|(event, at)| with_time(to_json(event), at).to_string_compact())
+            .collect()
+    }
+
+    fn compact(lines: &[String]) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        let mut last = 0;
+        for line in lines {
+            encode(&json::parse(line).unwrap(), &mut last, &mut bytes).unwrap();
+        }
+        bytes
+    }
+
+    #[test]
+    fn every_event_comes_back_from_the_compact_form() {
+        let lines = json_lines();
+        let bytes = compact(&lines);
+        assert!(is_compact(&bytes));
+        assert_eq!(super::lines(&bytes).unwrap(), lines);
+    }
+
+    #[test]
+    fn a_cut_off_dump_fails() {
+        let bytes = compact(&json_lines()[7..]);
+        for end in MAGIC.len() + 1..bytes.len() {
+            assert!(super::lines(&bytes[..end]).is_err(), "cut at {}", end);
+        }
+        // cut anywhere else in a longer dump, what is left reads or fails but never panics
+        let bytes = compact(&json_lines());
+        for end in MAGIC.len()..bytes.len() {
+            let _ = super::lines(&bytes[..end]);
+        }
+    }
+
+    #[test]
+    fn garbage_fails() {
+        for garbage in [
+            &[0xff; 16][..],
+            &[0, 9],
+            &[0, TAG_MOUSE, 200],
+            &[0, TAG_KEY, 0xff, 0xff, 0xff, 0x0f],
+            &[0, TAG_PASTE, 2, 0xc3, 0x28],
+            // a step back from the start
+            &[1, TAG_FOCUS_LOST],
+        ] {
+            let mut bytes = MAGIC.to_vec();
+            bytes.extend_from_slice(garbage);
+            assert!(super::lines(&bytes).is_err(), "{:?}", garbage);
+        }
+    }
+
+    #[test]
+    fn a_failed_line_writes_nothing() {
+        let mut bytes = Vec::new();
+        let mut last = 10;
+        let line = json::parse(r#"{"type":"mouse","kind":"down","at_us":20}"#).unwrap();
+        assert!(encode(&line, &mut last, &mut bytes).is_err());
+        assert_eq!((bytes.len(), last), (0, 10));
+    }
+}
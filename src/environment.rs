@@ -0,0 +1,72 @@
+use std::{env, fs, process::Command};
+
+/// processes up the tree checked for a terminal multiplexer or remote shell
+const MAX_ANCESTORS: usize = 16;
+
+/// reasons the click timestamps of this environment would not be trustworthy
+pub fn timing_problems() -> Vec<String> {
+    let mut problems = Vec::new();
+    let ancestors = ancestors();
+
+    if env::var_os("TMUX").is_some() && !tmux_passthrough() {
+        problems.push(
+            "tmux re-encodes mouse events and batches them with its redraws, \
+             run outside tmux or `set -g allow-passthrough on`"
+                .to_string(),
+        );
+    }
+    if env::var_os("STY").is_some() {
+        problems.push(
+            "gnu screen relays mouse events with its own buffering, run outside screen".to_string(),
+        );
+    }
+    if ancestors.iter().any(|name| name == "mosh-server") {
+        problems.push(
+            "mosh sends input in network frames, the gaps come out in steps of its frame rate"
+                .to_string(),
+        );
+    } else if env::var_os("SSH_CONNECTION").is_some() {
+        problems.push(
+            "the events cross the network over ssh, its jitter ends up in every gap, \
+             run on the machine the mouse is plugged into"
+                .to_string(),
+        );
+    }
+    if env::var("TERM").is_ok_and(|term| term == "linux") {
+        problems.push(
+            "the linux console reports the mouse through gpm at its own rate, \
+             use a terminal emulator"
+                .to_string(),
+        );
+    }
+    problems
+}
+
+/// whether tmux hands escape sequences through to the outer terminal
+fn tmux_passthrough() -> bool {
+    Command::new("tmux")
+        .args(["show-options", "-gv", "allow-passthrough"])
+        .output()
+        .is_ok_and(|out| matches!(String::from_utf8_lossy(&out.stdout).trim(), "on" | "all"))
+}
+
+/// command names of the parent processes, only known on linux
+fn ancestors() -> Vec<String> {
+    let mut names = Vec::new();
+    let mut pid = std::process::id().to_string();
+    for _ in 0..MAX_ANCESTORS {
+        let Ok(stat) = fs::read_to_string(format!("/proc/{}/stat", pid)) else {
+            break;
+        };
+        // the name is in parentheses and may contain spaces itself
+        let (Some(open), Some(close)) = (stat.find('('), stat.rfind(')')) else {
+            break;
+        };
+        names.push(stat[open + 1..close].to_string());
+        match stat[close + 1..].split_whitespace().nth(1) {
+            Some(parent) if parent != "0" => pid = parent.to_string(),
+            _ => break,
+        }
+    }
+    names
+}
@@ -0,0 +1,210 @@
+use std::{fmt, io, path::PathBuf};
+
+#[derive(Debug)]
+pub enum Error {
+    /// raw mode or the alternate screen could not be entered or left
+    Terminal(io::Error),
+    MouseCapture(io::Error),
+    /// the selected clock source cannot be read
+    Clock(io::Error),
+    /// reading events or drawing a frame failed
+    Backend(io::Error),
+    /// `--calibrate` or `self-test --inject` could not send synthetic clicks
+    Loopback(io::Error),
+    /// a session or score file could not be written
+    Export {
+        path: PathBuf,
+        source: io::Error,
+    },
+    /// a stored file could not be read or parsed
+    Config {
+        path: PathBuf,
+        message: String,
+    },
+    /// `--strict` found an environment that corrupts the timing
+    Environment(Vec<String>),
+    /// options `--operator` does not allow to change
+    Locked(Vec<String>),
+    /// the click test missed acceptance criteria of the config
+    Rejected {
+        failed: usize,
+        total: usize,
+    },
+    /// checks of `doctor` that failed
+    Unhealthy(usize),
+    /// the `--on-finish` command could not run or failed
+    Hook {
+        command: String,
+        message: String,
+    },
+    /// `--upload` could not post the summary
+    Upload {
+        /// none when the config has no endpoint
+        endpoint: Option<String>,
+        message: String,
+    },
+    /// the bench console could not be reached or listened for
+    Remote {
+        address: String,
+        message: String,
+    },
+    /// `--sign` could not sign an exported file
+    Sign {
+        path: PathBuf,
+        message: String,
+    },
+    /// exported files `report verify` found changed or unsigned
+    Tampered(Vec<PathBuf>),
+    /// seeds of the `fuzz` streams the analyzers panicked on
+    Panicked(Vec<u64>),
+    /// sessions of a `corpus` whose summary no longer matches the golden one
+    Regressed(Vec<PathBuf>),
+    /// text a `--script` expected that its last screen does not show
+    #[cfg(feature = "test-util")]
+    Mismatch(Vec<String>),
+}
+
+impl Error {
+    /// failed acceptance criteria stand apart from the tool failing
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::Rejected { .. } => 2,
+            _ => 1,
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Terminal(e) => write!(
+                f,
+                "could not set up the terminal, run mouse-checker in an interactive terminal ({})",
+                e
+            ),
+            Error::MouseCapture(e) => write!(
+                f,
+                "mouse capture unsupported by this terminal, try another terminal emulator ({})",
+                e
+            ),
+            Error::Clock(e) => write!(
+                f,
+                "the selected clock is not available, try `--clock instant` ({})",
+                e
+            ),
+            Error::Loopback(e) => write!(f, "uinput loopback failed: {}", e),
+            Error::Backend(e) => write!(f, "terminal i/o failed: {}", e),
+            Error::Export { path, source } => {
+                write!(f, "could not write {}: {}", path.display(), source)
+            }
+            Error::Config { path, message } => {
+                write!(f, "could not load {}: {}", path.display(), message)
+            }
+            Error::Environment(problems) => {
+                write!(
+                    f,
+                    "refusing to measure with --strict, the gaps would not be real:"
+                )?;
+                problems.iter().try_for_each(|p| write!(f, "\n  - {}", p))
+            }
+            Error::Locked(options) => write!(
+                f,
+                "operator mode locks {}, the settings of the config apply",
+                options.join(", ")
+            ),
+            Error::Rejected { failed, total } => {
+                write!(
+                    f,
+                    "the mouse failed {} of {} acceptance criteria",
+                    failed, total
+                )
+            }
+            Error::Unhealthy(failures) => {
+                write!(f, "{} checks failed, see the list above", failures)
+            }
+            Error::Hook { command, message } => {
+                write!(f, "on-finish command `{}` failed: {}", command, message)
+            }
+            Error::Upload { endpoint, message } => match endpoint {
+                Some(endpoint) => {
+                    write!(
+                        f,
+                        "could not upload the summary to {}: {}",
+                        endpoint, message
+                    )
+                }
+                None => write!(f, "could not upload the summary: {}", message),
+            },
+            Error::Remote { address, message } => {
+                write!(f, "bench console at {}: {}", address, message)
+            }
+            Error::Sign { path, message } => {
+                write!(f, "could not sign {}: {}", path.display(), message)
+            }
+            Error::Tampered(paths) => {
+                write!(f, "{} files did not verify:", paths.len())?;
+                paths
+                    .iter()
+                    .try_for_each(|p| write!(f, "\n  - {}", p.display()))
+            }
+            Error::Panicked(seeds) => {
+                let seeds = seeds.iter().map(u64::to_string).collect::<Vec<_>>();
+                write!(
+                    f,
+                    "the analyzers panicked on the streams of seeds {}, rerun one with `--seed`",
+                    seeds.join(", ")
+                )
+            }
+            Error::Regressed(paths) => {
+                write!(
+                    f,
+                    "{} sessions differ from their golden summary:",
+                    paths.len()
+                )?;
+                paths
+                    .iter()
+                    .try_for_each(|p| write!(f, "\n  - {}", p.display()))
+            }
+            #[cfg(feature = "test-util")]
+            Error::Mismatch(missing) => {
+                write!(f, "the last screen does not show:")?;
+                missing.iter().try_for_each(|m| write!(f, "\n  - {}", m))
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Terminal(e)
+            | Error::MouseCapture(e)
+            | Error::Clock(e)
+            | Error::Backend(e)
+            | Error::Loopback(e) => Some(e),
+            Error::Export { source, .. } => Some(source),
+            Error::Config { .. }
+            | Error::Environment(_)
+            | Error::Locked(_)
+            | Error::Rejected { .. }
+            | Error::Unhealthy(_)
+            | Error::Hook { .. }
+            | Error::Upload { .. }
+            | Error::Remote { .. }
+            | Error::Sign { .. }
+            | Error::Tampered(_)
+            | Error::Panicked(_)
+            | Error::Regressed(_) => None,
+            #[cfg(feature = "test-util")]
+            Error::Mismatch(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Backend(e)
+    }
+}
@@ -0,0 +1,72 @@
+use std::time::Duration;
+
+use crate::{
+    analyzer::SHORT_HOLD,
+    i18n::{self, tr, Msg},
+    session::{self, Session},
+    stats,
+};
+
+/// which rule flagged the click in memory, the values it looked at and why that matters,
+/// empty when none did; `outlier` is its flag from `Session::gap_outliers`
+pub fn click(session: &Session, index: usize, outlier: bool) -> Vec<String> {
+    let click = &session.clicks[index];
+    let mut lines = Vec::new();
+    if let Some(interval) = click.interval {
+        lines.push(i18n::fmt(
+            Msg::ExplainChatter,
+            &[
+                &i18n::duration(interval),
+                &i18n::duration(session.detect_window),
+            ],
+        ));
+        let floor = i18n::duration(session.human_floor);
+        lines.push(if interval < session.human_floor {
+            i18n::fmt(Msg::ExplainFast, &[&floor])
+        } else {
+            i18n::fmt(Msg::ExplainHuman, &[&floor])
+        });
+        lines.push(tr(Msg::ExplainChatterWhy).to_string());
+    }
+    if outlier {
+        let gaps = session.gaps().map(|g| g.as_secs_f64()).collect::<Vec<_>>();
+        if let (Some((median, mad)), Some(gap)) = (
+            stats::median_deviation(&gaps),
+            index.checked_sub(1).map(|i| gaps[i]),
+        ) {
+            let secs = Duration::from_secs_f64;
+            lines.push(i18n::fmt(
+                Msg::ExplainOutlier,
+                &[
+                    &i18n::duration(secs(gap)),
+                    &i18n::duration(secs(median)),
+                    &i18n::duration(secs((gap - median).abs())),
+                    &i18n::decimal(session::OUTLIER_MADS, 0),
+                    &i18n::duration(secs(mad)),
+                ],
+            ));
+            lines.push(tr(Msg::ExplainOutlierWhy).to_string());
+        }
+    }
+    if let Some(hold) = click.hold.filter(|h| *h < SHORT_HOLD) {
+        lines.push(i18n::fmt(
+            Msg::ExplainShortHold,
+            &[&i18n::duration(hold), &i18n::duration(SHORT_HOLD)],
+        ));
+    }
+    let stuck = session
+        .stuck
+        .iter()
+        .find(|s| s.at == click.at && s.button == click.button);
+    if let Some(stuck) = stuck {
+        lines.push(i18n::fmt(
+            Msg::ExplainStuck,
+            &[
+                &i18n::duration(stuck.held),
+                &i18n::duration(session.stuck_after),
+            ],
+        ));
+        lines.push(tr(Msg::ExplainStuckWhy).to_string());
+    }
+    lines
+}
@@ -0,0 +1,121 @@
+use crossterm::event::KeyCode;
+use ratatui::{
+    style::Style,
+    text::Span,
+    widgets::{Block, Borders},
+};
+
+use crate::theme;
+
+/// put in front of whatever has the focus, it stays visible without colours
+pub const MARKER: &str = "▶ ";
+
+/// where a key moves the focus, the same keys everywhere; the letters some views take
+/// as well are theirs since they mean other things elsewhere
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Nav {
+    Up,
+    Down,
+    Left,
+    Right,
+    /// tab, to the next element of the row
+    Next,
+    /// shift tab
+    Previous,
+    PageUp,
+    PageDown,
+    First,
+    Last,
+    /// enter, the focused element does its thing
+    Activate,
+    /// esc, out of the dialog or view
+    Back,
+}
+
+impl Nav {
+    pub fn of(code: KeyCode) -> Option<Self> {
+        Some(match code {
+            KeyCode::Up => Nav::Up,
+            KeyCode::Down => Nav::Down,
+            KeyCode::Left => Nav::Left,
+            KeyCode::Right => Nav::Right,
+            KeyCode::Tab => Nav::Next,
+            KeyCode::BackTab => Nav::Previous,
+            KeyCode::PageUp => Nav::PageUp,
+            KeyCode::PageDown => Nav::PageDown,
+            KeyCode::Home => Nav::First,
+            KeyCode::End => Nav::Last,
+            KeyCode::Enter => Nav::Activate,
+            KeyCode::Esc => Nav::Back,
+            _ => return None,
+        })
+    }
+
+    /// rows a list moves by, `page` of them for a page and to the ends for home and end
+    pub fn rows(self, page: usize) -> Option<isize> {
+        let page = page.max(1) as isize;
+        match self {
+            Nav::Up => Some(-1),
+            Nav::Down => Some(1),
+            Nav::PageUp => Some(-page),
+            Nav::PageDown => Some(page),
+            Nav::First => Some(isize::MIN / 2),
+            Nav::Last => Some(isize::MAX / 2),
+            _ => None,
+        }
+    }
+}
+
+/// the index after `at` in a row of `len` elements, wrapping at both ends
+pub fn step(at: usize, len: usize, forward: bool) -> usize {
+    if len == 0 {
+        return 0;
+    }
+    if forward {
+        (at + 1) % len
+    } else {
+        (at + len - 1) % len
+    }
+}
+
+/// rows of a bordered table with a header that fit into `height`, a page of it
+pub fn page(height: u16) -> usize {
+    height.saturating_sub(3).max(1) as usize
+}
+
+pub fn style(focused: bool) -> Style {
+    if focused {
+        theme::palette().focus
+    } else {
+        Style::default()
+    }
+}
+
+/// the title with the marker in front while focused
+pub fn title(text: &str, focused: bool) -> String {
+    if focused {
+        format!("{}{}", MARKER, text)
+    } else {
+        text.to_string()
+    }
+}
+
+/// a bordered block set apart by its border and title while it has the focus
+pub fn block(text: &str, focused: bool) -> Block<'static> {
+    Block::default()
+        .title(title(text, focused))
+        .borders(Borders::ALL)
+        .border_style(style(focused))
+}
+
+/// `[ label ]`, marked and set apart while it has the focus
+pub fn button(label: &str, focused: bool) -> Span<'static> {
+    if focused {
+        Span::styled(
+            format!("{}[ {} ]", MARKER, label),
+            theme::palette().highlight,
+        )
+    } else {
+        Span::raw(format!("  [ {} ]", label))
+    }
+}
@@ -0,0 +1,284 @@
+use std::{
+    fmt, fs,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use crossterm::event::{Event, KeyCode, MouseButton, MouseEventKind};
+use ratatui::{
+    backend::Backend,
+    layout::Alignment,
+    text::Line,
+    widgets::{Block, Borders, Paragraph},
+    Frame, Terminal,
+};
+
+use crate::{
+    error::{self, Error},
+    input,
+    json::{self, Value},
+    session,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Down(MouseButton),
+    Up(MouseButton),
+    ScrollUp,
+    ScrollDown,
+}
+
+impl Kind {
+    fn from_mouse(kind: MouseEventKind) -> Option<Self> {
+        match kind {
+            MouseEventKind::Down(button) => Some(Kind::Down(button)),
+            MouseEventKind::Up(button) => Some(Kind::Up(button)),
+            MouseEventKind::ScrollUp => Some(Kind::ScrollUp),
+            MouseEventKind::ScrollDown => Some(Kind::ScrollDown),
+            _ => None,
+        }
+    }
+
+    fn button(self) -> Option<MouseButton> {
+        match self {
+            Kind::Down(button) | Kind::Up(button) => Some(button),
+            Kind::ScrollUp | Kind::ScrollDown => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Kind::Down(_) => "down",
+            Kind::Up(_) => "up",
+            Kind::ScrollUp => "scroll-up",
+            Kind::ScrollDown => "scroll-down",
+        }
+    }
+
+    fn parse(name: &str, button: Option<MouseButton>) -> Option<Self> {
+        match (name, button) {
+            ("down", Some(button)) => Some(Kind::Down(button)),
+            ("up", Some(button)) => Some(Kind::Up(button)),
+            ("scroll-up", _) => Some(Kind::ScrollUp),
+            ("scroll-down", _) => Some(Kind::ScrollDown),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.button() {
+            Some(button) => write!(f, "{} {}", session::button_name(button), self.name()),
+            None => f.write_str(self.name()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Step {
+    /// time since the first event of the sequence
+    at: Duration,
+    kind: Kind,
+}
+
+/// clicks, releases and scrolls with their timing
+#[derive(Debug, Clone, Default)]
+pub struct Sequence {
+    steps: Vec<Step>,
+}
+
+impl Sequence {
+    fn to_json(&self) -> Value {
+        let steps = self
+            .steps
+            .iter()
+            .map(|s| {
+                json::object([
+                    ("at_us", Value::from(s.at.as_micros() as u64)),
+                    ("event", Value::from(s.kind.name())),
+                    (
+                        "button",
+                        Value::from(s.kind.button().map(session::button_name)),
+                    ),
+                ])
+            })
+            .collect::<Vec<_>>();
+        json::object([("steps", Value::Array(steps))])
+    }
+
+    fn from_json(value: &Value) -> Result<Self, String> {
+        let steps = value
+            .get("steps")
+            .and_then(Value::as_array)
+            .ok_or("missing or invalid field `steps`")?
+            .iter()
+            .enumerate()
+            .map(|(i, s)| {
+                let at = s
+                    .get("at_us")
+                    .and_then(Value::as_u64)
+                    .map(Duration::from_micros);
+                let button = s
+                    .get("button")
+                    .and_then(Value::as_str)
+                    .and_then(session::parse_button);
+                let kind = s
+                    .get("event")
+                    .and_then(Value::as_str)
+                    .and_then(|name| Kind::parse(name, button));
+                match (at, kind) {
+                    (Some(at), Some(kind)) => Ok(Step { at, kind }),
+                    _ => Err(format!("invalid step {}", i + 1)),
+                }
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(Self { steps })
+    }
+
+    pub fn load(path: &Path) -> error::Result<Self> {
+        fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| json::parse(&text))
+            .and_then(|v| Self::from_json(&v))
+            .map_err(|message| Error::Config {
+                path: path.to_path_buf(),
+                message,
+            })
+    }
+
+    pub fn save(&self, path: &Path) -> error::Result<()> {
+        fs::write(path, self.to_json().to_string_pretty()).map_err(|source| Error::Export {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// every difference to the expected sequence beyond the timing tolerance
+    pub fn differences(&self, expected: &Sequence, tolerance: Duration) -> Vec<String> {
+        let mut differences = Vec::new();
+        if self.len() != expected.len() {
+            differences.push(format!(
+                "{} events recorded, {} reproduced",
+                expected.len(),
+                self.len()
+            ));
+        }
+        for (i, (want, got)) in expected.steps.iter().zip(&self.steps).enumerate() {
+            if want.kind != got.kind {
+                differences.push(format!(
+                    "event {}: expected {}, got {}",
+                    i + 1,
+                    want.kind,
+                    got.kind
+                ));
+            } else if want.at.abs_diff(got.at) > tolerance {
+                differences.push(format!(
+                    "event {} ({}): {} ms instead of {} ms",
+                    i + 1,
+                    got.kind,
+                    got.at.as_millis(),
+                    want.at.as_millis()
+                ));
+            }
+        }
+        differences
+    }
+
+    /// largest timing difference of the matching events
+    pub fn max_offset(&self, expected: &Sequence) -> Duration {
+        expected
+            .steps
+            .iter()
+            .zip(&self.steps)
+            .map(|(want, got)| want.at.abs_diff(got.at))
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+/// capture a sequence until enter, or until `expected` events arrived, `None` when aborted
+pub fn record<B: Backend>(
+    terminal: &mut Terminal<B>,
+    expected: Option<usize>,
+) -> error::Result<Option<Sequence>> {
+    let mut sequence = Sequence::default();
+    let mut started: Option<Instant> = None;
+
+    loop {
+        terminal.draw(|f| ui(f, &sequence, expected))?;
+
+        match input::read()? {
+            Event::Mouse(event) => {
+                if let Some(kind) = Kind::from_mouse(event.kind) {
+                    let started = *started.get_or_insert_with(Instant::now);
+                    sequence.steps.push(Step {
+                        at: started.elapsed(),
+                        kind,
+                    });
+                    if expected.is_some_and(|n| sequence.len() >= n) {
+                        return Ok(Some(sequence));
+                    }
+                }
+            }
+            Event::Key(key) => match key.code {
+                KeyCode::Enter if sequence.len() > 0 => return Ok(Some(sequence)),
+                KeyCode::Esc | KeyCode::Char('q') => return Ok(None),
+                KeyCode::Char('r') => {
+                    sequence = Sequence::default();
+                    started = None;
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+}
+
+pub fn print_report(differences: &[String], max_offset: Duration, tolerance: Duration) {
+    if differences.is_empty() {
+        println!(
+            "sequence reproduced, largest timing difference {} ms (tolerance {} ms)",
+            max_offset.as_millis(),
+            tolerance.as_millis()
+        );
+        return;
+    }
+    println!("sequence differs:");
+    for difference in differences {
+        println!("  {}", difference);
+    }
+}
+
+fn ui<B: Backend>(f: &mut Frame<B>, sequence: &Sequence, expected: Option<usize>) {
+    let mut text = vec![
+        Line::from(match expected {
+            Some(n) => format!("replay the sequence, {} of {} events", sequence.len(), n),
+            None => format!("perform the sequence, {} events recorded", sequence.len()),
+        }),
+        Line::from(""),
+    ];
+    text.extend(
+        sequence
+            .steps
+            .iter()
+            .rev()
+            .take(10)
+            .map(|s| Line::from(format!("{:>8} ms  {}", s.at.as_millis(), s.kind))),
+    );
+    text.push(Line::from(""));
+    text.push(Line::from(if expected.is_some() {
+        "r: restart  enter: stop early  esc: cancel"
+    } else {
+        "r: restart  enter: save  esc: cancel"
+    }));
+
+    let p = Paragraph::new(text)
+        .block(Block::default().title("gesture").borders(Borders::ALL))
+        .alignment(Alignment::Center);
+    f.render_widget(p, f.size());
+}
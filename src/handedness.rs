@@ -0,0 +1,60 @@
+use std::process::{Command, Stdio};
+
+use crossterm::event::MouseButton;
+
+/// what a command printed, none when it could not run or failed
+fn output(program: &str, args: &[&str]) -> Option<String> {
+    let out = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    out.status
+        .success()
+        .then(|| String::from_utf8_lossy(&out.stdout).into_owned())
+}
+
+/// whether the os swapped the primary and secondary button for a left hand, none when no
+/// setting was found
+pub fn detected() -> Option<bool> {
+    if cfg!(windows) {
+        // `    SwapMouseButtons    REG_SZ    1`
+        let out = output(
+            "reg",
+            &[
+                "query",
+                r"HKCU\Control Panel\Mouse",
+                "/v",
+                "SwapMouseButtons",
+            ],
+        )?;
+        return out.split_whitespace().last().map(|value| value == "1");
+    }
+    // under x the pointer map holds the swap whatever the desktop, `1  3` is swapped
+    if std::env::var_os("DISPLAY").is_some() {
+        let map = output("xmodmap", &["-pp"]).and_then(|out| {
+            out.lines().find_map(|line| {
+                let mut fields = line.split_whitespace();
+                (fields.next()? == "1").then(|| fields.next() == Some("3"))
+            })
+        });
+        if map.is_some() {
+            return map;
+        }
+    }
+    let out = output(
+        "gsettings",
+        &["get", "org.gnome.desktop.peripherals.mouse", "left-handed"],
+    )?;
+    Some(out.trim() == "true")
+}
+
+/// the button that was pressed for the one the terminal reports
+pub fn physical(button: MouseButton, swapped: bool) -> MouseButton {
+    match button {
+        MouseButton::Left if swapped => MouseButton::Right,
+        MouseButton::Right if swapped => MouseButton::Left,
+        other => other,
+    }
+}
@@ -0,0 +1,462 @@
+use std::{
+    collections::VecDeque,
+    fs, io,
+    path::Path,
+    sync::Mutex,
+    thread,
+    time::{Duration, Instant},
+};
+
+use crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers, MouseButton, MouseEvent,
+    MouseEventKind,
+};
+use ratatui::buffer::Buffer;
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    dump,
+    error::{self, Error},
+    json::{self, Value},
+};
+
+/// screen the modes are drawn on unless the script sets one
+const SIZE: (u16, u16) = (80, 24);
+/// time the mode gets to finish the last events and draw them before the script ends it
+const END_GRACE: Duration = Duration::from_millis(500);
+
+enum Step {
+    Event(Event),
+    Wait(Duration),
+}
+
+/// a `--script` standing in for the terminal, the modes are drawn on a test backend
+struct Script {
+    steps: VecDeque<Step>,
+    size: (u16, u16),
+    /// text the last screen has to show
+    expect: Vec<String>,
+    /// when the steps ran out, the mode is ended once `END_GRACE` passed
+    ran_out: Option<Instant>,
+    ended: bool,
+    screen: Option<Buffer>,
+}
+
+static SCRIPT: Mutex<Option<Script>> = Mutex::new(None);
+
+fn button(name: &str) -> Option<MouseButton> {
+    match name {
+        "left" => Some(MouseButton::Left),
+        "right" => Some(MouseButton::Right),
+        "middle" => Some(MouseButton::Middle),
+        _ => None,
+    }
+}
+
+fn key(name: &str) -> Option<KeyEvent> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut rest = name;
+    while let Some((prefix, tail)) = rest.split_once('+').filter(|(_, tail)| !tail.is_empty()) {
+        modifiers |= match prefix {
+            "ctrl" => KeyModifiers::CONTROL,
+            "shift" => KeyModifiers::SHIFT,
+            "alt" => KeyModifiers::ALT,
+            _ => return None,
+        };
+        rest = tail;
+    }
+    let code = match rest {
+        "enter" => KeyCode::Enter,
+        "esc" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backtab" => KeyCode::BackTab,
+        "backspace" => KeyCode::Backspace,
+        "space" => KeyCode::Char(' '),
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        _ => {
+            let mut chars = rest.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => KeyCode::Char(c),
+                _ => return None,
+            }
+        }
+    };
+    Some(KeyEvent::new(code, modifiers))
+}
+
+fn mouse(kind: MouseEventKind, column: u16, row: u16) -> Step {
+    Step::Event(Event::Mouse(MouseEvent {
+        kind,
+        column,
+        row,
+        modifiers: KeyModifiers::NONE,
+    }))
+}
+
+/// the key code of its debug form, media and modifier keys are not read back
+fn key_code(text: &str) -> Option<KeyCode> {
+    if let Some(c) = text
+        .strip_prefix("Char('")
+        .and_then(|t| t.strip_suffix("')"))
+    {
+        // debug escapes quotes, backslashes and control characters
+        let c = match c {
+            "\\'" => "'",
+            "\\\\" => "\\",
+            "\\t" => "\t",
+            "\\n" => "\n",
+            "\\r" => "\r",
+            c => c,
+        };
+        let mut chars = c.chars();
+        return match (chars.next(), chars.next()) {
+            (Some(c), None) => Some(KeyCode::Char(c)),
+            _ => None,
+        };
+    }
+    if let Some(n) = text.strip_prefix("F(").and_then(|t| t.strip_suffix(')')) {
+        return n.parse().ok().map(KeyCode::F);
+    }
+    Some(match text {
+        "Backspace" => KeyCode::Backspace,
+        "Enter" => KeyCode::Enter,
+        "Left" => KeyCode::Left,
+        "Right" => KeyCode::Right,
+        "Up" => KeyCode::Up,
+        "Down" => KeyCode::Down,
+        "Home" => KeyCode::Home,
+        "End" => KeyCode::End,
+        "PageUp" => KeyCode::PageUp,
+        "PageDown" => KeyCode::PageDown,
+        "Tab" => KeyCode::Tab,
+        "BackTab" => KeyCode::BackTab,
+        "Delete" => KeyCode::Delete,
+        "Insert" => KeyCode::Insert,
+        "Null" => KeyCode::Null,
+        "Esc" => KeyCode::Esc,
+        "CapsLock" => KeyCode::CapsLock,
+        "ScrollLock" => KeyCode::ScrollLock,
+        "NumLock" => KeyCode::NumLock,
+        "PrintScreen" => KeyCode::PrintScreen,
+        "Pause" => KeyCode::Pause,
+        "Menu" => KeyCode::Menu,
+        "KeypadBegin" => KeyCode::KeypadBegin,
+        _ => return None,
+    })
+}
+
+/// the time and event of one line of a `--raw-dump`
+fn dump_line(line: &str) -> Result<(Duration, Event), String> {
+    let value = json::parse(line)?;
+    let at = value
+        .get("at_us")
+        .and_then(Value::as_u64)
+        .ok_or("no `at_us`")?;
+    let text = |name: &str| value.get(name).and_then(Value::as_str);
+    let number = |name: &str| {
+        value
+            .get(name)
+            .and_then(Value::as_u64)
+            .ok_or_else(|| format!("no `{}`", name))
+    };
+    let cell = |name: &str| {
+        number(name).and_then(|n| u16::try_from(n).map_err(|_| format!("`{}` too large", name)))
+    };
+    let bits = |name: &str| number(name).map(|n| n as u8);
+    let event = match text("type") {
+        Some("mouse") => {
+            let button = text("button").and_then(button);
+            let kind = match (text("kind"), button) {
+                (Some("down"), Some(b)) => MouseEventKind::Down(b),
+                (Some("up"), Some(b)) => MouseEventKind::Up(b),
+                (Some("drag"), Some(b)) => MouseEventKind::Drag(b),
+                (Some("moved"), _) => MouseEventKind::Moved,
+                (Some("scroll_down"), _) => MouseEventKind::ScrollDown,
+                (Some("scroll_up"), _) => MouseEventKind::ScrollUp,
+                (Some("scroll_left"), _) => MouseEventKind::ScrollLeft,
+                (Some("scroll_right"), _) => MouseEventKind::ScrollRight,
+                _ => return Err("unknown mouse event".to_string()),
+            };
+            Event::Mouse(MouseEvent {
+                kind,
+                column: cell("column")?,
+                row: cell("row")?,
+                modifiers: KeyModifiers::from_bits_truncate(bits("modifiers")?),
+            })
+        }
+        Some("key") => {
+            let code = text("code").ok_or("no `code`")?;
+            let kind = match text("kind") {
+                Some("repeat") => KeyEventKind::Repeat,
+                Some("release") => KeyEventKind::Release,
+                _ => KeyEventKind::Press,
+            };
+            Event::Key(KeyEvent {
+                code: key_code(code).ok_or_else(|| format!("key `{}` is not replayed", code))?,
+                modifiers: KeyModifiers::from_bits_truncate(bits("modifiers")?),
+                kind,
+                state: KeyEventState::from_bits_truncate(bits("state")?),
+            })
+        }
+        Some("resize") => Event::Resize(cell("columns")?, cell("rows")?),
+        Some("focus_gained") => Event::FocusGained,
+        Some("focus_lost") => Event::FocusLost,
+        Some("paste") => Event::Paste(text("text").ok_or("no `text`")?.to_string()),
+        _ => return Err("unknown event type".to_string()),
+    };
+    Ok((Duration::from_micros(at), event))
+}
+
+/// the steps of one line, `size` sets the screen instead
+fn parse_line(line: &str, size: &mut (u16, u16)) -> Result<Vec<Step>, String> {
+    let (command, rest) = line.split_once(' ').unwrap_or((line, ""));
+    let words = rest.split_whitespace().collect::<Vec<_>>();
+    let number = |word: &str| {
+        word.parse::<u16>()
+            .map_err(|_| format!("`{}` is no number", word))
+    };
+    // the button may be left out, it is the left one then
+    let at = |words: &[&str]| -> Result<(MouseButton, u16, u16), String> {
+        match words {
+            [name, column, row] => Ok((
+                button(name).ok_or_else(|| format!("unknown button `{}`", name))?,
+                number(column)?,
+                number(row)?,
+            )),
+            [column, row] => Ok((MouseButton::Left, number(column)?, number(row)?)),
+            _ => Err(format!("`{}` needs a column and a row", command)),
+        }
+    };
+
+    let steps = match command {
+        "size" => match words.as_slice() {
+            [columns, rows] => {
+                *size = (number(columns)?, number(rows)?);
+                Vec::new()
+            }
+            _ => return Err("`size` needs columns and rows".to_string()),
+        },
+        "down" | "up" | "click" | "drag" => {
+            let (button, column, row) = at(&words)?;
+            match command {
+                "down" => vec![mouse(MouseEventKind::Down(button), column, row)],
+                "up" => vec![mouse(MouseEventKind::Up(button), column, row)],
+                "drag" => vec![mouse(MouseEventKind::Drag(button), column, row)],
+                _ => vec![
+                    mouse(MouseEventKind::Down(button), column, row),
+                    mouse(MouseEventKind::Up(button), column, row),
+                ],
+            }
+        }
+        "move" => match words.as_slice() {
+            [column, row] => vec![mouse(MouseEventKind::Moved, number(column)?, number(row)?)],
+            _ => return Err("`move` needs a column and a row".to_string()),
+        },
+        "scroll" => match words.as_slice() {
+            [direction, column, row] => {
+                let kind = match *direction {
+                    "up" => MouseEventKind::ScrollUp,
+                    "down" => MouseEventKind::ScrollDown,
+                    _ => return Err(format!("unknown direction `{}`", direction)),
+                };
+                vec![mouse(kind, number(column)?, number(row)?)]
+            }
+            _ => return Err("`scroll` needs up or down, a column and a row".to_string()),
+        },
+        "key" => words
+            .iter()
+            .map(|name| {
+                key(name)
+                    .map(|k| Step::Event(Event::Key(k)))
+                    .ok_or_else(|| format!("unknown key `{}`", name))
+            })
+            .collect::<Result<_, _>>()?,
+        "type" => rest
+            .chars()
+            .map(|c| {
+                Step::Event(Event::Key(KeyEvent::new(
+                    KeyCode::Char(c),
+                    KeyModifiers::NONE,
+                )))
+            })
+            .collect(),
+        "wait" => match words.as_slice() {
+            [ms] => vec![Step::Wait(Duration::from_millis(
+                ms.parse().map_err(|_| format!("`{}` is no number", ms))?,
+            ))],
+            _ => return Err("`wait` needs milliseconds".to_string()),
+        },
+        _ => return Err(format!("unknown step `{}`", command)),
+    };
+    Ok(steps)
+}
+
+/// read the script the terminal is replaced with, `expect` is checked on the last screen
+pub fn load(path: &Path, expect: Vec<String>) -> error::Result<()> {
+    let failed = |message: String| Error::Config {
+        path: path.to_path_buf(),
+        message,
+    };
+    let bytes = fs::read(path).map_err(|e| failed(e.to_string()))?;
+    let text = if dump::is_compact(&bytes) {
+        dump::lines(&bytes).map_err(failed)?.join("\n")
+    } else {
+        String::from_utf8(bytes).map_err(|e| failed(e.to_string()))?
+    };
+    let mut script = Script {
+        steps: VecDeque::new(),
+        size: SIZE,
+        expect,
+        ran_out: None,
+        ended: false,
+        screen: None,
+    };
+    // a `--raw-dump` is replayed with the gaps it was recorded with
+    let replay = text.trim_start().starts_with('{');
+    let mut last = Duration::ZERO;
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if !replay {
+            let steps = parse_line(line, &mut script.size)
+                .map_err(|e| failed(format!("line {}: {}", i + 1, e)))?;
+            script.steps.extend(steps);
+            continue;
+        }
+        let (at, event) = dump_line(line).map_err(|e| failed(format!("line {}: {}", i + 1, e)))?;
+        match event {
+            Event::Resize(columns, rows) if script.steps.is_empty() => {
+                script.size = (columns, rows);
+            }
+            event => {
+                script.steps.push_back(Step::Wait(at.saturating_sub(last)));
+                script.steps.push_back(Step::Event(event));
+            }
+        }
+        last = at;
+    }
+    if let Ok(mut current) = SCRIPT.lock() {
+        *current = Some(script);
+    }
+    Ok(())
+}
+
+/// columns and rows of the test backend, none without a script
+pub fn size() -> Option<(u16, u16)> {
+    SCRIPT.lock().ok()?.as_ref().map(|s| s.size)
+}
+
+fn ended() -> io::Error {
+    io::Error::new(io::ErrorKind::UnexpectedEof, "the script ended")
+}
+
+impl Script {
+    /// sleep through the waits in front of the next event for up to `timeout`, whether it
+    /// is there, an error once the grace after the last step passed
+    fn next(&mut self, mut timeout: Duration) -> io::Result<bool> {
+        loop {
+            match self.steps.front_mut() {
+                Some(Step::Event(_)) => return Ok(true),
+                Some(Step::Wait(left)) => {
+                    let nap = (*left).min(timeout);
+                    thread::sleep(nap);
+                    if nap < *left {
+                        *left -= nap;
+                        return Ok(false);
+                    }
+                    timeout -= nap;
+                    self.steps.pop_front();
+                }
+                None => {
+                    let since = *self.ran_out.get_or_insert_with(Instant::now);
+                    let left = END_GRACE.saturating_sub(since.elapsed());
+                    if left.is_zero() {
+                        self.ended = true;
+                        return Err(ended());
+                    }
+                    thread::sleep(left.min(timeout));
+                    return Ok(false);
+                }
+            }
+        }
+    }
+}
+
+pub fn poll(timeout: Duration) -> Option<io::Result<bool>> {
+    let mut current = SCRIPT.lock().ok()?;
+    Some(current.as_mut()?.next(timeout))
+}
+
+pub fn read() -> Option<io::Result<Event>> {
+    let mut current = SCRIPT.lock().ok()?;
+    let script = current.as_mut()?;
+    Some(loop {
+        match script.next(Duration::MAX) {
+            Ok(true) => match script.steps.pop_front() {
+                Some(Step::Event(event)) => break Ok(event),
+                _ => continue,
+            },
+            Ok(false) => continue,
+            Err(e) => break Err(e),
+        }
+    })
+}
+
+/// the screen a mode left, checked once the run is over
+pub fn keep(screen: &Buffer) {
+    if let Some(script) = SCRIPT.lock().ok().as_mut().and_then(|s| s.as_mut()) {
+        script.screen = Some(screen.clone());
+    }
+}
+
+fn rows(screen: &Buffer) -> Vec<String> {
+    let area = screen.area;
+    (area.top()..area.bottom())
+        .map(|y| {
+            let mut row = String::new();
+            let mut x = area.left();
+            while x < area.right() {
+                let symbol = &screen.get(x, y).symbol;
+                row.push_str(symbol);
+                // a wide character covers the cells after it
+                x += symbol.width().max(1) as u16;
+            }
+            row.trim_end().to_string()
+        })
+        .collect()
+}
+
+/// the run ending with the script counts as finished, the last screen is printed and the
+/// expected text looked up in it
+pub fn settle(result: error::Result<()>) -> error::Result<()> {
+    let Some(script) = SCRIPT.lock().ok().and_then(|mut s| s.take()) else {
+        return result;
+    };
+    match result {
+        Err(Error::Backend(e)) if script.ended && e.kind() == io::ErrorKind::UnexpectedEof => {}
+        other => other?,
+    }
+    let rows = script.screen.as_ref().map(rows).unwrap_or_default();
+    for row in &rows {
+        println!("{}", row);
+    }
+    let missing = script
+        .expect
+        .into_iter()
+        .filter(|text| !rows.iter().any(|row| row.contains(text.as_str())))
+        .collect::<Vec<_>>();
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::Mismatch(missing))
+    }
+}
@@ -0,0 +1,265 @@
+use std::sync::RwLock;
+
+use crate::{
+    analyzer::{Consistency, HoldProfile},
+    diagnose::{Outcome, Test},
+    i18n::{self, tr, Msg},
+    json::{self, Value},
+    session::Session,
+};
+
+/// chatter rate that scores nothing, the score falls in a line from none to it
+const CHATTER_ZERO: f64 = 0.05;
+/// share of short or stuck presses that scores nothing
+const HOLDS_ZERO: f64 = 0.2;
+/// share of skipped, doubled or reversed notches that scores nothing
+const WHEEL_ZERO: f64 = 0.1;
+
+/// how much each part counts towards the health score, declared in the config; only the
+/// parts a run measured count, so the weights need not add up to anything
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Weights {
+    pub chatter: f64,
+    pub consistency: f64,
+    pub holds: f64,
+    pub wheel: f64,
+}
+
+impl Weights {
+    const DEFAULT: Self = Self {
+        chatter: 50.0,
+        consistency: 15.0,
+        holds: 20.0,
+        wheel: 15.0,
+    };
+
+    fn of(self, component: Component) -> f64 {
+        match component {
+            Component::Chatter => self.chatter,
+            Component::Consistency => self.consistency,
+            Component::Holds => self.holds,
+            Component::Wheel => self.wheel,
+        }
+    }
+
+    pub fn to_json(self) -> Value {
+        json::object(Component::ALL.map(|c| (c.key(), Value::from(self.of(c)))))
+    }
+
+    /// missing or negative weights fall back to the defaults
+    pub fn from_json(value: &Value) -> Self {
+        let weight = |component: Component| {
+            value
+                .get(component.key())
+                .and_then(Value::as_f64)
+                .filter(|w| *w >= 0.0)
+                .unwrap_or(Self::DEFAULT.of(component))
+        };
+        Self {
+            chatter: weight(Component::Chatter),
+            consistency: weight(Component::Consistency),
+            holds: weight(Component::Holds),
+            wheel: weight(Component::Wheel),
+        }
+    }
+}
+
+impl Default for Weights {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+static WEIGHTS: RwLock<Weights> = RwLock::new(Weights::DEFAULT);
+
+/// the weights of the config, the next score is taken with them
+pub fn set_weights(weights: Weights) {
+    *WEIGHTS.write().unwrap_or_else(|e| e.into_inner()) = weights;
+}
+
+fn weights() -> Weights {
+    *WEIGHTS.read().unwrap_or_else(|e| e.into_inner())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Component {
+    /// clicks that came again inside the detect window
+    Chatter,
+    /// the gaps of both halves of the session alike
+    Consistency,
+    /// presses too short to be a finger, or stuck
+    Holds,
+    /// notches skipped, doubled or reversed
+    Wheel,
+}
+
+impl Component {
+    const ALL: [Component; 4] = [
+        Component::Chatter,
+        Component::Consistency,
+        Component::Holds,
+        Component::Wheel,
+    ];
+
+    pub fn key(self) -> &'static str {
+        match self {
+            Component::Chatter => "chatter",
+            Component::Consistency => "consistency",
+            Component::Holds => "holds",
+            Component::Wheel => "wheel",
+        }
+    }
+
+    fn name(self) -> &'static str {
+        tr(match self {
+            Component::Chatter => Msg::HealthChatter,
+            Component::Consistency => Msg::HealthConsistency,
+            Component::Holds => Msg::HealthHolds,
+            Component::Wheel => Msg::HealthWheel,
+        })
+    }
+}
+
+/// the score of one component, 0 to 100
+#[derive(Debug, Clone)]
+pub struct Part {
+    pub component: Component,
+    pub score: f64,
+    pub weight: f64,
+}
+
+/// the weighted mean of the component scores, one number for whoever does not read the rest
+#[derive(Debug, Clone)]
+pub struct Health {
+    pub score: f64,
+    pub parts: Vec<Part>,
+}
+
+/// 100 for none, falling in a line to 0 at `zero`
+fn falling(share: f64, zero: f64) -> f64 {
+    100.0 * (1.0 - share / zero).clamp(0.0, 1.0)
+}
+
+impl Health {
+    /// none when nothing was measured or every weight is 0
+    fn new(scores: Vec<(Component, f64)>) -> Option<Self> {
+        let weights = weights();
+        let parts = scores
+            .into_iter()
+            .map(|(component, score)| Part {
+                component,
+                score,
+                weight: weights.of(component),
+            })
+            .collect::<Vec<_>>();
+        let total = parts.iter().map(|p| p.weight).sum::<f64>();
+        (total > 0.0).then(|| Self {
+            score: parts.iter().map(|p| p.score * p.weight).sum::<f64>() / total,
+            parts,
+        })
+    }
+
+    /// chatter, consistency and holds of a click test; none without clicks
+    pub fn of_session(session: &Session) -> Option<Self> {
+        let clicks = session.click_count();
+        if clicks == 0 {
+            return None;
+        }
+        let mut scores = vec![(
+            Component::Chatter,
+            falling(session.chatter_count() as f64 / clicks as f64, CHATTER_ZERO),
+        )];
+        // halves differing by no more than chance score full, twice that nothing
+        if let Some((distance, limit)) = Consistency::compare(session) {
+            let score = 100.0 * ((2.0 * limit - distance) / limit).clamp(0.0, 1.0);
+            scores.push((Component::Consistency, score));
+        }
+        if let Some(holds) = HoldProfile::new(session) {
+            let bad = holds.short + session.stuck.len();
+            let share = bad as f64 / holds.holds as f64;
+            scores.push((Component::Holds, falling(share, HOLDS_ZERO)));
+        }
+        Self::new(scores)
+    }
+
+    /// chatter of the buttons, dropouts of drag and hold and the notches of the wheel steps
+    pub fn of_diagnosis(outcomes: &[Outcome]) -> Option<Self> {
+        let share = |tests: &[Test]| {
+            let (bad, total) = outcomes
+                .iter()
+                .filter(|o| tests.contains(&o.spec.test))
+                .fold((0, 0), |(bad, total), o| (bad + o.bad, total + o.total));
+            (total > 0).then(|| bad as f64 / total as f64)
+        };
+        let scores = [
+            (
+                Component::Chatter,
+                &[Test::Left, Test::Right][..],
+                CHATTER_ZERO,
+            ),
+            (Component::Holds, &[Test::Drag, Test::Hold][..], HOLDS_ZERO),
+            (
+                Component::Wheel,
+                &[Test::Wheel, Test::Detents][..],
+                WHEEL_ZERO,
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(component, tests, zero)| {
+            share(tests).map(|share| (component, falling(share, zero)))
+        })
+        .collect();
+        Self::new(scores)
+    }
+
+    pub fn grade(&self) -> char {
+        match self.score {
+            s if s >= 90.0 => 'A',
+            s if s >= 80.0 => 'B',
+            s if s >= 70.0 => 'C',
+            s if s >= 60.0 => 'D',
+            _ => 'F',
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        json::object([
+            ("score", Value::from(self.score.round())),
+            ("grade", Value::from(self.grade().to_string())),
+            (
+                "components",
+                Value::Array(
+                    self.parts
+                        .iter()
+                        .map(|p| {
+                            json::object([
+                                ("component", Value::from(p.component.key())),
+                                ("score", Value::from(p.score.round())),
+                                ("weight", Value::from(p.weight)),
+                            ])
+                        })
+                        .collect(),
+                ),
+            ),
+        ])
+    }
+
+    /// the score and grade, then a line per component
+    pub fn describe(&self) -> Vec<String> {
+        let mut lines = vec![i18n::fmt(
+            Msg::Health,
+            &[&i18n::decimal(self.score, 0), &self.grade()],
+        )];
+        lines.extend(self.parts.iter().map(|p| {
+            i18n::fmt(
+                Msg::HealthPart,
+                &[
+                    &p.component.name(),
+                    &i18n::decimal(p.score, 0),
+                    &i18n::decimal(p.weight, 0),
+                ],
+            )
+        }));
+        lines
+    }
+}
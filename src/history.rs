@@ -0,0 +1,509 @@
+use std::path::PathBuf;
+
+use crossterm::event::{Event, KeyCode, MouseEventKind};
+use ratatui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    symbols,
+    text::Line,
+    widgets::{
+        Axis, Block, Borders, Cell, Chart, Dataset, GraphType, Paragraph, Row, Table, TableState,
+    },
+    Frame, Terminal,
+};
+
+use crate::{
+    error,
+    focus::{self, Nav},
+    i18n, input,
+    region::{self, WHEEL_ROWS},
+    session::{self, Session},
+    stats::Fit,
+    theme,
+};
+
+/// chatter rate in percent from which a switch is due for replacement
+const WORN_OUT: f64 = 1.0;
+/// standard errors of the wear trend the remaining life is given with
+const LIFE_ERRORS: f64 = 2.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SortColumn {
+    Date,
+    Device,
+    Chatter,
+    MeanInterval,
+}
+
+impl SortColumn {
+    const ALL: [SortColumn; 4] = [
+        SortColumn::Date,
+        SortColumn::Device,
+        SortColumn::Chatter,
+        SortColumn::MeanInterval,
+    ];
+
+    fn title(self) -> &'static str {
+        match self {
+            SortColumn::Date => "date",
+            SortColumn::Device => "device",
+            SortColumn::Chatter => "chatter",
+            SortColumn::MeanInterval => "mean interval",
+        }
+    }
+}
+
+enum View {
+    Table,
+    Detail(usize),
+    /// sessions of one device over time
+    Trend(Option<String>),
+}
+
+struct HistoryState {
+    all: Vec<(PathBuf, Session)>,
+    /// the sessions of `all` shown, narrowed to one operator
+    sessions: Vec<(PathBuf, Session)>,
+    /// `Some(None)` shows the sessions saved without an operator
+    operator: Option<Option<String>>,
+    sort: SortColumn,
+    descending: bool,
+    table: TableState,
+    view: View,
+    detail_scroll: u16,
+    /// where the table or the detail was drawn, the wheel scrolls it there
+    pane: Rect,
+}
+
+impl HistoryState {
+    fn new(sessions: Vec<(PathBuf, Session)>) -> Self {
+        let mut state = Self {
+            all: sessions.clone(),
+            sessions,
+            operator: None,
+            sort: SortColumn::Date,
+            descending: true,
+            table: TableState::default(),
+            view: View::Table,
+            detail_scroll: 0,
+            pane: Rect::default(),
+        };
+        state.sort();
+        if !state.sessions.is_empty() {
+            state.table.select(Some(0));
+        }
+        state
+    }
+
+    fn sort(&mut self) {
+        self.sessions.sort_by(|(_, a), (_, b)| {
+            let ord = match self.sort {
+                SortColumn::Date => a.started_at.cmp(&b.started_at),
+                SortColumn::Device => a.device.cmp(&b.device),
+                SortColumn::Chatter => a.chatter_count().cmp(&b.chatter_count()),
+                SortColumn::MeanInterval => a.mean_interval().cmp(&b.mean_interval()),
+            };
+            if self.descending {
+                ord.reverse()
+            } else {
+                ord
+            }
+        });
+    }
+
+    fn sort_by(&mut self, column: SortColumn) {
+        if self.sort == column {
+            self.descending = !self.descending;
+        } else {
+            self.sort = column;
+            self.descending = false;
+        }
+        self.sort();
+    }
+
+    /// show the sessions of the next operator, all of them after the last
+    fn next_operator(&mut self) {
+        let mut operators = self
+            .all
+            .iter()
+            .map(|(_, s)| s.operator.clone())
+            .collect::<Vec<_>>();
+        operators.sort();
+        operators.dedup();
+        self.operator = match &self.operator {
+            None => operators.into_iter().next(),
+            Some(current) => operators.into_iter().skip_while(|o| o != current).nth(1),
+        };
+        self.sessions = self
+            .all
+            .iter()
+            .filter(|(_, s)| self.operator.as_ref().is_none_or(|o| s.operator == *o))
+            .cloned()
+            .collect();
+        self.sort();
+        self.table.select((!self.sessions.is_empty()).then_some(0));
+    }
+
+    /// the wheel and the keys move the table selection or scroll the detail
+    fn scroll(&mut self, rows: isize) {
+        match self.view {
+            View::Table => self.select_offset(rows),
+            View::Detail(_) => {
+                let rows = rows.clamp(i16::MIN as isize, i16::MAX as isize) as i16;
+                self.detail_scroll = self.detail_scroll.saturating_add_signed(rows)
+            }
+            View::Trend(_) => {}
+        }
+    }
+
+    fn select_offset(&mut self, offset: isize) {
+        if self.sessions.is_empty() {
+            return;
+        }
+        let current = self.table.selected().unwrap_or(0) as isize;
+        let next = (current + offset).clamp(0, self.sessions.len() as isize - 1);
+        self.table.select(Some(next as usize));
+    }
+}
+
+pub fn run<B: Backend>(terminal: &mut Terminal<B>) -> error::Result<()> {
+    let mut state = HistoryState::new(session::list());
+
+    loop {
+        terminal.draw(|f| ui(f, &mut state))?;
+
+        let key = match input::read()? {
+            Event::Key(key) => key,
+            Event::Mouse(mouse) => {
+                let notches = match mouse.kind {
+                    MouseEventKind::ScrollUp => -1,
+                    MouseEventKind::ScrollDown => 1,
+                    _ => continue,
+                };
+                if region::hit(state.pane, mouse.column, mouse.row) {
+                    state.scroll(notches * WHEEL_ROWS);
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        if let Some(rows) =
+            Nav::of(key.code).and_then(|nav| nav.rows(focus::page(state.pane.height)))
+        {
+            state.scroll(rows);
+            continue;
+        }
+
+        match state.view {
+            View::Detail(_) => {
+                match key.code {
+                    KeyCode::Char('q') => break,
+                    KeyCode::Esc | KeyCode::Backspace => state.view = View::Table,
+                    KeyCode::Char('k') => state.scroll(-1),
+                    KeyCode::Char('j') => state.scroll(1),
+                    _ => {}
+                }
+                continue;
+            }
+            View::Trend(_) => {
+                match key.code {
+                    KeyCode::Char('q') => break,
+                    KeyCode::Esc | KeyCode::Backspace => state.view = View::Table,
+                    _ => {}
+                }
+                continue;
+            }
+            View::Table => {}
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => break,
+            KeyCode::Char('k') => state.select_offset(-1),
+            KeyCode::Char('j') => state.select_offset(1),
+            KeyCode::Enter => {
+                if let Some(idx) = state.table.selected() {
+                    state.view = View::Detail(idx);
+                    state.detail_scroll = 0;
+                }
+            }
+            KeyCode::Char('t') => {
+                if let Some(idx) = state.table.selected() {
+                    state.view = View::Trend(state.sessions[idx].1.device.clone());
+                }
+            }
+            KeyCode::Char('o') => state.next_operator(),
+            KeyCode::Char(c @ '1'..='4') => {
+                state.sort_by(SortColumn::ALL[c as usize - '1' as usize]);
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn ui<B: Backend>(f: &mut Frame<B>, state: &mut HistoryState) {
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)].as_ref())
+        .split(f.size());
+    state.pane = layout[0];
+
+    match &state.view {
+        View::Detail(idx) => {
+            detail_ui(f, &state.sessions[*idx].1, state.detail_scroll, layout[0]);
+            f.render_widget(
+                Paragraph::new("esc: back  up/down pgup/pgdn: scroll  q: quit"),
+                layout[1],
+            );
+        }
+        View::Trend(device) => {
+            trend_ui(f, &state.sessions, device.as_deref(), layout[0]);
+            f.render_widget(Paragraph::new("esc: back  q: quit"), layout[1]);
+        }
+        View::Table => {
+            table_ui(f, state, layout[0]);
+            f.render_widget(
+                Paragraph::new(
+                    "1-4: sort  enter: open  t: trend  o: operator  up/down pgup/pgdn: select  q: quit",
+                ),
+                layout[1],
+            );
+        }
+    }
+}
+
+fn table_ui<B: Backend>(f: &mut Frame<B>, state: &mut HistoryState, area: Rect) {
+    let header = Row::new(SortColumn::ALL.iter().enumerate().map(|(i, col)| {
+        let marker = match (state.sort == *col, state.descending) {
+            (true, true) => " v",
+            (true, false) => " ^",
+            _ => "",
+        };
+        Cell::from(format!("{} {}{}", i + 1, col.title(), marker))
+    }))
+    .style(Style::default().add_modifier(Modifier::BOLD));
+
+    let rows = state.sessions.iter().map(|(_, s)| {
+        Row::new([
+            session::format_timestamp(s.started_at),
+            s.device.clone().unwrap_or_else(|| "-".to_string()),
+            s.chatter_count().to_string(),
+            s.mean_interval()
+                .map(|d| format!("{} ms", d.as_millis()))
+                .unwrap_or_else(|| "-".to_string()),
+        ])
+    });
+
+    let title = match &state.operator {
+        Some(operator) => format!(
+            "history of {}",
+            operator.as_deref().unwrap_or("no operator")
+        ),
+        None if state.sessions.is_empty() => {
+            "history (no saved sessions, run with --save)".to_string()
+        }
+        None => "history".to_string(),
+    };
+
+    let table = Table::new(rows)
+        .header(header)
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .widths(&[
+            Constraint::Length(18),
+            Constraint::Min(10),
+            Constraint::Length(12),
+            Constraint::Length(18),
+        ])
+        .highlight_style(theme::palette().highlight)
+        .highlight_symbol(focus::MARKER);
+
+    f.render_stateful_widget(table, area, &mut state.table);
+}
+
+fn detail_ui<B: Backend>(f: &mut Frame<B>, session: &Session, scroll: u16, area: Rect) {
+    let mut text = vec![
+        Line::from(format!(
+            "date: {}",
+            session::format_timestamp(session.started_at)
+        )),
+        Line::from(format!(
+            "device: {}",
+            session.device.as_deref().unwrap_or("-")
+        )),
+        Line::from(format!(
+            "operator: {}",
+            session.operator.as_deref().unwrap_or("-")
+        )),
+        Line::from(format!(
+            "battery: {}",
+            session
+                .battery
+                .map(|b| format!("{}%", b))
+                .unwrap_or_else(|| "-".to_string())
+        )),
+        Line::from(format!(
+            "detect window: {} ms",
+            session.detect_window.as_millis()
+        )),
+        Line::from(format!(
+            "clicks: {}  chatter: {}  longest clean streak: {}",
+            session.clicks.len(),
+            session.chatter_count(),
+            session.clean_streaks().1
+        )),
+        Line::from(""),
+    ];
+
+    // build only the click lines that fit on screen
+    let visible = area.height.saturating_sub(2) as usize;
+    let skip = (scroll as usize).saturating_sub(text.len());
+    let header_scroll = scroll.min(text.len() as u16);
+    text.extend(session.clicks.iter().skip(skip).take(visible).map(|c| {
+        Line::from(format!(
+            "{:>10.3} s  {:<6}  {:>10}  hold {:>10}",
+            c.at.as_secs_f64(),
+            session::button_name(c.button),
+            c.shown_interval()
+                .map(i18n::duration)
+                .unwrap_or_else(|| "-".to_string()),
+            c.hold
+                .map(i18n::duration)
+                .unwrap_or_else(|| "-".to_string())
+        ))
+    }));
+
+    let p = Paragraph::new(text)
+        .block(Block::default().title("session").borders(Borders::ALL))
+        .scroll((header_scroll, 0));
+
+    f.render_widget(p, area);
+}
+
+fn trend_ui<B: Backend>(
+    f: &mut Frame<B>,
+    sessions: &[(PathBuf, Session)],
+    device: Option<&str>,
+    area: Rect,
+) {
+    let mut points = sessions
+        .iter()
+        .map(|(_, s)| s)
+        .filter(|s| s.device.as_deref() == device)
+        .collect::<Vec<_>>();
+    points.sort_by_key(|s| s.started_at);
+
+    let name = device.unwrap_or("unnamed device");
+    let Some(first) = points.first().map(|s| s.started_at) else {
+        return;
+    };
+
+    // x axis is days since the first session of the device
+    let day = |s: &Session| (s.started_at - first) as f64 / 86400.0;
+    let rate = points
+        .iter()
+        .map(|s| (day(s), s.chatter_rate() * 100.0))
+        .collect::<Vec<_>>();
+    let mean = points
+        .iter()
+        .filter_map(|s| {
+            s.mean_interval()
+                .map(|m| (day(s), m.as_secs_f64() * 1000.0))
+        })
+        .collect::<Vec<_>>();
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Length(3),
+                Constraint::Percentage(50),
+                Constraint::Percentage(50),
+            ]
+            .as_ref(),
+        )
+        .split(area);
+
+    f.render_widget(
+        Paragraph::new(remaining_life(&rate))
+            .block(Block::default().title("switch life").borders(Borders::ALL)),
+        layout[0],
+    );
+
+    let max_day = rate.last().map(|p| p.0).unwrap_or(0.0).max(1.0);
+    f.render_widget(
+        trend_chart(
+            format!("{} - chatter rate (%)", name),
+            &rate,
+            max_day,
+            100.0,
+        ),
+        layout[1],
+    );
+
+    let max_mean = mean.iter().map(|p| p.1).fold(1.0, f64::max);
+    f.render_widget(
+        trend_chart(
+            format!("{} - mean interval (ms)", name),
+            &mean,
+            max_day,
+            max_mean,
+        ),
+        layout[2],
+    );
+}
+
+/// days until the chatter rate trend reaches `WORN_OUT`, a rough projection of
+/// how long the switch lasts
+fn remaining_life(rate: &[(f64, f64)]) -> String {
+    let Some(fit) = Fit::new(rate) else {
+        return "save at least three sessions of the device to project the switch life".to_string();
+    };
+    let today = rate.last().map(|p| p.0).unwrap_or(0.0);
+    let now = fit.at(today);
+    if now >= WORN_OUT {
+        return format!(
+            "chatter is at {:.1}%, past the {:.0}% a switch is replaced at",
+            now, WORN_OUT
+        );
+    }
+    // the slower the wear the later, no wear at all never wears it out
+    let days = |slope: f64| (slope > 0.0).then(|| (WORN_OUT - now) / slope);
+    let spread = LIFE_ERRORS * fit.slope_error;
+    let (Some(soonest), Some(expected)) = (days(fit.slope + spread), days(fit.slope)) else {
+        return format!(
+            "no wear trend, chatter stays around {:.1}% (+{:.3}% per day at most)",
+            now,
+            (fit.slope + spread).max(0.0)
+        );
+    };
+    let latest = days(fit.slope - spread)
+        .map(|d| format!("{:.0}", d))
+        .unwrap_or_else(|| "never".to_string());
+    format!(
+        "{:.1}% now, {:.3}% more per day: {:.0}% in about {:.0} days ({:.0} to {})",
+        now, fit.slope, WORN_OUT, expected, soonest, latest
+    )
+}
+
+fn trend_chart(title: String, data: &[(f64, f64)], max_x: f64, max_y: f64) -> Chart<'_> {
+    let dataset = Dataset::default()
+        .marker(symbols::Marker::Braille)
+        .graph_type(GraphType::Line)
+        .data(data);
+
+    Chart::new(vec![dataset])
+        .block(Block::default().title(title).borders(Borders::ALL))
+        .x_axis(
+            Axis::default()
+                .title("days")
+                .bounds([0.0, max_x])
+                .labels(vec!["0".into(), format!("{:.0}", max_x).into()]),
+        )
+        .y_axis(
+            Axis::default()
+                .bounds([0.0, max_y])
+                .labels(vec!["0".into(), format!("{:.0}", max_y).into()]),
+        )
+}
@@ -0,0 +1,63 @@
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+use crate::{
+    error::{self, Error},
+    json::Value,
+};
+
+/// quote a value so the shell passes it as one argument
+#[cfg(not(windows))]
+fn quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', r"'\''"))
+}
+
+#[cfg(windows)]
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', "\\\""))
+}
+
+#[cfg(not(windows))]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.arg("-c").arg(command);
+    shell
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.arg("/C").arg(command);
+    shell
+}
+
+/// run `command` through the shell with `{json}` and `{path}` filled in,
+/// the json summary is also written to its stdin
+pub fn run(command: &str, summary: &Value) -> error::Result<()> {
+    let json = summary.to_string_compact();
+    let path = summary.get("path").and_then(Value::as_str).unwrap_or("");
+    let line = command
+        .replace("{json}", &quote(&json))
+        .replace("{path}", &quote(path));
+
+    let failed = |message: String| Error::Hook {
+        command: command.to_string(),
+        message,
+    };
+    let mut child = shell(&line)
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| failed(e.to_string()))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // commands that never read stdin close it early
+        let _ = writeln!(stdin, "{}", json);
+    }
+    let status = child.wait().map_err(|e| failed(e.to_string()))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(failed(format!("exited with {}", status)))
+    }
+}
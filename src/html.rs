@@ -0,0 +1,189 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    checksum,
+    error::{self, Error},
+    explain,
+    i18n::{self, tr, Msg},
+    json::Value,
+    redact,
+    session::{self, Session},
+    summary::Summary,
+    version::Build,
+};
+
+/// draws the embedded gaps on a canvas, the wheel zooms into the timeline, dragging
+/// pans and a double click shows all of it again
+const SCRIPT: &str = r##"
+const canvas = document.getElementById("chart");
+const ctx = canvas.getContext("2d");
+const pad = 40;
+// reduce rather than spreading into Math.max, long sessions overflow the stack
+const end = data.gaps.reduce((m, g) => Math.max(m, g[0]), 1);
+let view = [0, end];
+let drag = null;
+
+function draw() {
+  const w = canvas.width = canvas.clientWidth;
+  const h = canvas.height;
+  const [from, to] = view;
+  const shown = data.gaps.filter(g => g[0] >= from && g[0] <= to);
+  const top = shown.reduce((m, g) => Math.max(m, Math.min(g[1], data.window * 4)), data.window * 1.5);
+  const x = t => pad + (t - from) / (to - from) * (w - 2 * pad);
+  const y = v => h - pad - Math.min(v, top) / top * (h - 2 * pad);
+  ctx.clearRect(0, 0, w, h);
+  ctx.strokeStyle = "#000";
+  ctx.beginPath();
+  ctx.moveTo(pad, pad);
+  ctx.lineTo(pad, h - pad);
+  ctx.lineTo(w - pad, h - pad);
+  ctx.stroke();
+  ctx.fillStyle = "#000";
+  ctx.fillText((from / 1000).toFixed(1) + " s", pad, h - pad + 14);
+  ctx.textAlign = "right";
+  ctx.fillText((to / 1000).toFixed(1) + " s", w - pad, h - pad + 14);
+  ctx.fillText(top.toFixed(0) + " ms", pad - 4, pad + 4);
+  ctx.fillText("0", pad - 4, h - pad);
+  ctx.textAlign = "left";
+  ctx.setLineDash([6, 4]);
+  ctx.strokeStyle = "red";
+  ctx.beginPath();
+  ctx.moveTo(pad, y(data.window));
+  ctx.lineTo(w - pad, y(data.window));
+  ctx.stroke();
+  ctx.setLineDash([]);
+  for (const [t, gap, chatter] of shown) {
+    ctx.fillStyle = chatter ? "red" : "steelblue";
+    ctx.fillRect(x(t) - 1.5, y(gap) - 1.5, 3, 3);
+  }
+}
+
+canvas.addEventListener("wheel", e => {
+  e.preventDefault();
+  const [from, to] = view;
+  const at = from + (e.offsetX - pad) / (canvas.width - 2 * pad) * (to - from);
+  const scale = e.deltaY < 0 ? 0.8 : 1.25;
+  view = [at - (at - from) * scale, at + (to - at) * scale];
+  draw();
+});
+canvas.addEventListener("mousedown", e => drag = [e.offsetX, view]);
+window.addEventListener("mouseup", () => drag = null);
+canvas.addEventListener("mousemove", e => {
+  if (!drag) return;
+  const [start, [from, to]] = drag;
+  const shift = (start - e.offsetX) / (canvas.width - 2 * pad) * (to - from);
+  view = [from + shift, to + shift];
+  draw();
+});
+canvas.addEventListener("dblclick", () => {
+  view = [0, end];
+  draw();
+});
+window.addEventListener("resize", draw);
+draw();
+"##;
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// one html file holding the summary, the clicks and a chart to zoom into, no network
+pub fn render(session: &Session, summary: &Summary) -> String {
+    let gaps = session
+        .clicks
+        .windows(2)
+        .map(|w| {
+            Value::Array(vec![
+                Value::from(w[1].at.as_secs_f64() * 1000.0),
+                Value::from((w[1].at - w[0].at).as_secs_f64() * 1000.0),
+                Value::from(w[1].interval.is_some()),
+            ])
+        })
+        .collect::<Vec<_>>();
+    let data = crate::json::object([
+        (
+            "window",
+            Value::from(session.detect_window.as_secs_f64() * 1000.0),
+        ),
+        ("gaps", Value::Array(gaps)),
+    ]);
+    let title = format!(
+        "{} {}",
+        redact::name(session.device.as_deref()).unwrap_or_else(|| "unnamed device".to_string()),
+        session::format_zoned(session.started_at)
+    );
+    // every flagged click with the rules that fired, folded away below the summary
+    let outliers = session.gap_outliers();
+    let flagged = (0..session.clicks.len())
+        .filter_map(|i| {
+            let why = explain::click(session, i, outliers[i]);
+            (!why.is_empty()).then(|| {
+                format!(
+                    "{} {} s\n  {}",
+                    i18n::fmt(
+                        Msg::PopupClick,
+                        &[&i18n::number(session.spilled.clicks + i + 1)]
+                    ),
+                    i18n::decimal(session.clicks[i].at.as_secs_f64(), 3),
+                    why.join("\n  ")
+                )
+            })
+        })
+        .collect::<Vec<_>>();
+    let flagged = if flagged.is_empty() {
+        String::new()
+    } else {
+        format!(
+            "<details><summary>{} ({})</summary><pre>{}</pre></details>\n",
+            escape(tr(Msg::FlaggedClicks)),
+            flagged.len(),
+            escape(&flagged.join("\n\n"))
+        )
+    };
+    let build = Build::current();
+    let built = match &session.build {
+        Some(recorded) => format!(
+            "{}, recorded with {}",
+            build.describe(),
+            recorded.describe()
+        ),
+        None => format!("{}, recorded with an unknown build", build.describe()),
+    };
+    // `</` would end the script element early
+    let data = data.to_string_compact().replace("</", "<\\/");
+
+    format!(
+        "<!doctype html>\n<html>\n<head><meta charset=\"utf-8\"><meta name=\"generator\" content=\"{generator}\">\
+         <title>{title}</title>\n\
+         <style>body {{ font-family: sans-serif; margin: 2em; }} \
+         canvas {{ width: 100%; height: 360px; border: 1px solid #ccc; cursor: grab; }}</style>\n\
+         </head>\n<body>\n<h1>{title}</h1>\n<pre>{text}</pre>\n{flagged}\
+         <p>gap before every click, chatter in red, the dashed line is the detect window. \
+         scroll to zoom, drag to pan, double click to reset.</p>\n\
+         <canvas id=\"chart\" height=\"360\"></canvas>\n\
+         <p><small>{built}</small></p>\n\
+         <script>\nconst data = {data};\n{SCRIPT}</script>\n</body>\n</html>\n",
+        generator = escape(&build.describe()),
+        built = escape(&built),
+        title = escape(&title),
+        text = escape(&summary.render(crate::summary::Format::Text)),
+    )
+}
+
+/// the report next to the session file as `<stem>.html`, ending in the hash of its content
+/// and signed with `--sign`
+pub fn write(session: &Session, summary: &Summary, path: &Path) -> error::Result<PathBuf> {
+    let out = path.with_extension("html");
+    let html = checksum::seal_markup(render(session, summary));
+    fs::write(&out, html).map_err(|source| Error::Export {
+        path: out.clone(),
+        source,
+    })?;
+    checksum::sign(&out)?;
+    Ok(out)
+}
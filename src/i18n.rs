@@ -0,0 +1,872 @@
+use std::{
+    env,
+    fmt::Display,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        OnceLock,
+    },
+    time::Duration,
+};
+
+use clap::ValueEnum;
+
+/// languages of the ui, add a variant and a table below to add a translation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Lang {
+    En,
+    ZhTw,
+}
+
+/// thousands and decimal separators of numbers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Numbers {
+    /// 1,234.5
+    En,
+    /// 1.234,5
+    De,
+    /// 1 234,5
+    Fr,
+    /// 1234.5
+    Plain,
+}
+
+static LANG: OnceLock<Lang> = OnceLock::new();
+static NUMBERS: OnceLock<Numbers> = OnceLock::new();
+/// decimals of the intervals shown, `--precision` or the config
+static PRECISION: AtomicUsize = AtomicUsize::new(DEFAULT_PRECISION);
+
+pub const DEFAULT_PRECISION: usize = 1;
+pub const MAX_PRECISION: usize = 6;
+
+/// pick the language and number format from the flags or else from the locale environment
+pub fn init(lang: Option<Lang>, numbers: Option<Numbers>) {
+    let _ = LANG.set(lang.unwrap_or_else(detect_lang));
+    let _ = NUMBERS.set(numbers.unwrap_or_else(detect_numbers));
+}
+
+fn locale(category: &str) -> String {
+    ["LC_ALL", category, "LANG"]
+        .iter()
+        .filter_map(|key| env::var(key).ok())
+        .find(|value| !value.is_empty())
+        .unwrap_or_default()
+        .to_lowercase()
+        .replace('-', "_")
+}
+
+fn has_prefix(locale: &str, prefixes: &[&str]) -> bool {
+    prefixes.iter().any(|prefix| locale.starts_with(prefix))
+}
+
+fn detect_lang() -> Lang {
+    if has_prefix(&locale("LC_MESSAGES"), &["zh_tw", "zh_hk", "zh_hant"]) {
+        Lang::ZhTw
+    } else {
+        Lang::En
+    }
+}
+
+fn detect_numbers() -> Numbers {
+    let locale = locale("LC_NUMERIC");
+    if locale.is_empty() || locale == "c" || locale.starts_with("posix") {
+        Numbers::Plain
+    } else if has_prefix(
+        &locale,
+        &["de_", "nl_", "es_", "it_", "pt_", "da_", "id_", "tr_"],
+    ) {
+        Numbers::De
+    } else if has_prefix(
+        &locale,
+        &["fr_", "ru_", "pl_", "cs_", "sv_", "fi_", "nb_", "uk_"],
+    ) {
+        Numbers::Fr
+    } else {
+        Numbers::En
+    }
+}
+
+fn lang() -> Lang {
+    LANG.get().copied().unwrap_or(Lang::En)
+}
+
+fn separators() -> (Option<char>, char) {
+    match NUMBERS.get().copied().unwrap_or(Numbers::Plain) {
+        Numbers::En => (Some(','), '.'),
+        Numbers::De => (Some('.'), ','),
+        // a no-break space so the number never wraps
+        Numbers::Fr => (Some('\u{a0}'), ','),
+        Numbers::Plain => (None, '.'),
+    }
+}
+
+fn group(digits: &str, thousands: Option<char>) -> String {
+    let Some(thousands) = thousands else {
+        return digits.to_string();
+    };
+    let (sign, digits) = match digits.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", digits),
+    };
+
+    let mut text = sign.to_string();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            text.push(thousands);
+        }
+        text.push(c);
+    }
+    text
+}
+
+/// apply the number format to an integer or a float
+pub fn number(n: impl Display) -> String {
+    let text = n.to_string();
+    let (thousands, point) = separators();
+    match text.split_once('.') {
+        Some((int, frac)) => format!("{}{}{}", group(int, thousands), point, frac),
+        None => group(&text, thousands),
+    }
+}
+
+/// a float with a fixed number of decimals in the number format
+pub fn decimal(x: f64, precision: usize) -> String {
+    number(format!("{:.*}", precision, x))
+}
+
+pub fn set_precision(digits: usize) {
+    PRECISION.store(digits.min(MAX_PRECISION), Ordering::Relaxed);
+}
+
+pub fn precision() -> usize {
+    PRECISION.load(Ordering::Relaxed)
+}
+
+/// a time in the unit that reads best for it, µs for the bounces under a millisecond and s
+/// from a second on
+pub fn duration(d: Duration) -> String {
+    let precision = precision();
+    let micros = d.as_secs_f64() * 1e6;
+    let units = [(1.0, "µs"), (1e3, "ms"), (1e6, "s")];
+    // zero reads as the milliseconds the rest of the list is in, a value rounding up to 1000
+    // goes to the next unit
+    let first = usize::from(d.is_zero());
+    let (scale, unit) = units[first..]
+        .iter()
+        .copied()
+        .find(|(scale, _)| {
+            let shown = format!("{:.*}", precision, micros / scale);
+            shown.parse::<f64>().is_ok_and(|x| x < 1000.0)
+        })
+        .unwrap_or(units[2]);
+    format!("{} {}", decimal(micros / scale, precision), unit)
+}
+
+/// ids of the translated strings, `{}` in a text is filled in order by [`fmt`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Msg {
+    Clicks,
+    Chatter,
+    Outliers,
+    CleanStreak,
+    LastChatter,
+    Stats,
+    FlagChatter,
+    FlagOutlier,
+    FlagBookmark,
+    FlagKeyboard,
+    SnapshotSaved,
+    Battery,
+    DeviceBattery,
+    EventRate,
+    UsbRate,
+    ClickPrompt,
+    ColTime,
+    ColButton,
+    ColInterval,
+    ColHold,
+    ColFlags,
+    PopupClick,
+    PopupTime,
+    PopupButton,
+    PopupInterval,
+    PopupGap,
+    PopupHold,
+    PopupFlags,
+    Event,
+    WindowClosed,
+    WindowOpen,
+    DetectWindow,
+    Interval,
+    IntervalMs,
+    ChartClick,
+    Summary,
+    Saved,
+    Estimate,
+    EstimateConfirmed,
+    EstimateNeeded,
+    EstimateAbove,
+    Cps,
+    Bursts,
+    Bounces,
+    NotFollowing,
+    FilterPrompt,
+    FilterHidden,
+    SearchPrompt,
+    SearchMatches,
+    Quantized,
+    Coalesced,
+    Debounce,
+    Simulating,
+    SimulationOff,
+    SimulatedClicks,
+    SimulatedChatter,
+    WindowAdjusted,
+    Viewing,
+    CriterionChatter,
+    CriterionCps,
+    CriterionBounce,
+    CheckPass,
+    CheckFail,
+    SuggestWindow,
+    SuggestWrite,
+    Holds,
+    HoldsBounce,
+    HoldsTechnique,
+    Consistency,
+    VerdictInconsistent,
+    Macros,
+    VerdictHuman,
+    VerdictWaiting,
+    VerdictClean,
+    VerdictChatter,
+    VerdictMacro,
+    VerdictHardware,
+    PlainPrompt,
+    PlainClick,
+    PlainChatter,
+    PlainKeyboard,
+    ControlPaused,
+    ControlResumed,
+    ControlMarked,
+    ControlStopped,
+    ControlUnknown,
+    Pruned,
+    Recover,
+    Resumed,
+    NoMouseTitle,
+    NoMouse,
+    NoMouseTerminal,
+    NoMouseTmux,
+    NoMouseKeys,
+    ConfigReloaded,
+    SettingsTitle,
+    SettingsKeys,
+    SettingsSaved,
+    SettingWindow,
+    SettingFloor,
+    SettingConfidence,
+    SettingTarget,
+    SettingNewestFirst,
+    SettingFollow,
+    SettingTheme,
+    On,
+    Off,
+    ConfirmTitle,
+    ConfirmKeys,
+    ConfirmQuit,
+    ConfirmStartOver,
+    ChatterToast,
+    ChartWindow,
+    PopupWallClock,
+    PopupPosition,
+    PopupCell,
+    PopupModifiers,
+    PopupNotes,
+    PopupContext,
+    Records,
+    RecordCps,
+    RecordDouble,
+    RecordStreak,
+    Backlog,
+    BacklogSummary,
+    ClockJumps,
+    Suspended,
+    Uploaded,
+    Quirks,
+    Captured,
+    Released,
+    CaptureOn,
+    CaptureOff,
+    SettingPrecision,
+    Timeline,
+    TimelineRow,
+    TimelineSingle,
+    WindowClean,
+    WindowDoubleClick,
+    WindowChatter,
+    Agreement,
+    AgreementMissed,
+    MarkedOnePress,
+    NoWindow,
+    BlindProgress,
+    BlindHint,
+    BlindDone,
+    ButtonsSwapped,
+    Fatigue,
+    FatigueFinger,
+    FatigueSwitch,
+    FatigueUnclear,
+    Segment,
+    BreakTitle,
+    BreakText,
+    BreakKeys,
+    SegmentStarted,
+    MiniWaiting,
+    MiniLast,
+    MiniCounts,
+    StuckButtons,
+    VerdictStuck,
+    Stuck,
+    StuckReleased,
+    Chords,
+    VerdictChord,
+    Actuations,
+    ActuationLatency,
+    VerdictMissed,
+    ControlActuated,
+    ActuationOutcome,
+    ActuationMissed,
+    Loopback,
+    PopupShare,
+    ResultsTitle,
+    ResultsVerdict,
+    ResultsChart,
+    ResultsKeys,
+    ResultsSaved,
+    ResultsExported,
+    ResultsCopied,
+    ConfirmLeave,
+    ConfirmLeaveSaving,
+    ConfirmLeaveUnsaved,
+    Restarted,
+    Before,
+    Baseline,
+    Health,
+    HealthPart,
+    HealthChatter,
+    HealthConsistency,
+    HealthHolds,
+    HealthWheel,
+    ExplainTitle,
+    ExplainHint,
+    ExplainNothing,
+    ExplainChatter,
+    ExplainFast,
+    ExplainHuman,
+    ExplainChatterWhy,
+    ExplainOutlier,
+    ExplainOutlierWhy,
+    ExplainShortHold,
+    ExplainStuck,
+    ExplainStuckWhy,
+    ExplainCheckChatter,
+    ExplainCheckCps,
+    ExplainCheckBounce,
+    FlaggedClicks,
+    Smoothed,
+    BadDuration,
+    DurationZero,
+    SettingsDuration,
+    RecordedWith,
+    BuildDiffers,
+    UpdateAvailable,
+    UpdateDetectors,
+    Yes,
+    No,
+    Identity,
+}
+
+pub fn tr(msg: Msg) -> &'static str {
+    match lang() {
+        Lang::En => en(msg),
+        Lang::ZhTw => zh_tw(msg),
+    }
+}
+
+/// translate `msg` and fill its placeholders with `args`
+pub fn fmt(msg: Msg, args: &[&dyn Display]) -> String {
+    let mut args = args.iter();
+    let mut parts = tr(msg).split("{}");
+    let mut text = parts.next().unwrap_or_default().to_string();
+    for part in parts {
+        if let Some(arg) = args.next() {
+            text.push_str(&arg.to_string());
+        }
+        text.push_str(part);
+    }
+    text
+}
+
+fn en(msg: Msg) -> &'static str {
+    match msg {
+        Msg::Clicks => "clicks: {}",
+        Msg::Chatter => "chatter: {}",
+        Msg::Outliers => "outliers: {}",
+        Msg::CleanStreak => "clean streak: {} (best {})",
+        Msg::LastChatter => "last chatter: {} s ago",
+        Msg::Stats => "stats",
+        Msg::FlagChatter => "chatter",
+        Msg::FlagOutlier => "outlier",
+        Msg::FlagBookmark => "bookmark",
+        Msg::FlagKeyboard => "keyboard",
+        Msg::SnapshotSaved => "screen saved to {}",
+        Msg::WindowAdjusted => "detect window {} ms: {} chatter, {} before",
+        Msg::Viewing => "viewing {}, read only",
+        Msg::Battery => "battery {}%",
+        Msg::DeviceBattery => "{} (battery {}%)",
+        Msg::EventRate => "event rate: {} Hz",
+        Msg::UsbRate => " (usb: {} Hz)",
+        Msg::ClickPrompt => "please click the mouse! (or press space without mouse support)",
+        Msg::ColTime => "time",
+        Msg::ColButton => "button",
+        Msg::ColInterval => "interval",
+        Msg::ColHold => "hold",
+        Msg::ColFlags => "flags",
+        Msg::PopupClick => "click: {}",
+        Msg::PopupTime => "time: {} s",
+        Msg::PopupButton => "button: {}",
+        Msg::PopupInterval => "interval: {}",
+        Msg::PopupGap => "gap to previous: {}",
+        Msg::PopupHold => "hold: {}",
+        Msg::PopupFlags => "flags: {}",
+        Msg::Event => "event",
+        Msg::WindowClosed => "window closed, the next click starts a new group",
+        Msg::WindowOpen => "{} s until the next click starts a new group",
+        Msg::DetectWindow => "detect window",
+        Msg::Interval => "interval",
+        Msg::IntervalMs => "interval (ms, g: detect windows, a: smooth)",
+        Msg::ChartClick => "click",
+        Msg::Summary => "{} clicks, {} chatter, longest clean streak {} clicks",
+        Msg::Saved => "session saved to {}",
+        Msg::Estimate => "chatter {}% ({}% ci {}-{}%)",
+        Msg::EstimateConfirmed => ", below {}% confirmed",
+        Msg::EstimateNeeded => ", {} more clicks to confirm below {}%",
+        Msg::EstimateAbove => ", above {}%",
+        Msg::Cps => "cps: {} (peak {})",
+        Msg::Bursts => "bursts: {} (longest {})",
+        Msg::Bounces => "press bounce: {}, release bounce: {}",
+        Msg::NotFollowing => " | not following, f to follow",
+        Msg::FilterPrompt => "filter (left, right, middle, flagged, all): {}_",
+        Msg::FilterHidden => " | filter {}, {} hidden",
+        Msg::SearchPrompt => "search (interval<20, hold>300, button=right): {}_",
+        Msg::SearchMatches => " | search {}: {} matches, n/N to jump",
+        Msg::Macros => "macro-like trains: {} (longest {} clicks, last at {} s), {} chatter inside them is not the switch",
+        Msg::VerdictWaiting => "no clicks yet",
+        Msg::VerdictClean => "no chatter detected in {} clicks",
+        Msg::VerdictChatter => "CHATTER: {} events, worst {} ms",
+        Msg::VerdictMacro => "MACRO-LIKE: {} evenly spaced trains",
+        Msg::VerdictHuman => "all {} chatter at or above {} ms, fast but humanly plausible",
+        Msg::VerdictHardware => "{} chatter below {} ms, too fast for a hand: switch fault ({} humanly plausible)",
+        Msg::Quantized => "timestamps move in {} µs steps of the {} clock, shorter chatter is lost",
+        Msg::CriterionChatter => "chatter per 1000 clicks",
+        Msg::CriterionCps => "peak clicks per second",
+        Msg::CriterionBounce => "99th percentile chatter interval in ms",
+        Msg::CheckPass => "pass: {} {} (limit {})",
+        Msg::CheckFail => "FAIL: {} {} (limit {})",
+        Msg::Debounce => "set the firmware debounce to at least {} ms to swallow these bounces",
+        Msg::Simulating => "simulating a {} ms firmware debounce, raw / debounced",
+        Msg::SimulationOff => "debounce simulation off",
+        Msg::SimulatedClicks => "clicks: {} / {}",
+        Msg::SimulatedChatter => "chatter: {} / {} ({} / {})",
+        Msg::SuggestWindow => "clean run, your fastest gap was {} ms: a {} ms detect window still leaves your own clicking alone",
+        Msg::SuggestWrite => "write the {} ms detect window to {}? [y/N] ",
+        Msg::Holds => "holds: median {} ms, a tenth under {} ms, {} shorter than {} ms",
+        Msg::HoldsBounce => "chatter held {} ms, too short for a finger: the switch bounces",
+        Msg::HoldsTechnique => "chatter held {} ms, not much shorter than the usual {} ms: real presses, likely technique",
+        Msg::Consistency => "halves of the session: ks distance {} (limit {})",
+        Msg::VerdictInconsistent => "CONDITIONS CHANGED: the halves disagree (ks {}), totals are untrustworthy",
+        Msg::Coalesced => "the terminal coalesces events into {} ms frames, that is the measurement floor",
+        Msg::PlainPrompt => "click the mouse or press space, press q to quit",
+        Msg::PlainClick => "click {}: {} button at {} s",
+        Msg::PlainChatter => ", chatter {} after the first click",
+        Msg::PlainKeyboard => ", made with the space key",
+        Msg::ControlPaused => "paused, clicks are not counted",
+        Msg::ControlResumed => "resumed",
+        Msg::ControlMarked => "marked at {} s: {}",
+        Msg::ControlStopped => "stopped",
+        Msg::ControlUnknown => {
+            "unknown command `{}`, use pause, resume, mark <note>, stats or stop"
+        }
+        Msg::Pruned => "{} old sessions removed, they were past the retention of the config",
+        Msg::Recover => "the click test of {} with {} clicks did not finish, save it into the history? [y/N] ",
+        Msg::Resumed => "resumed the session of {}, {} clicks so far",
+        Msg::NoMouseTitle => "no mouse input",
+        Msg::NoMouse => {
+            "no mouse event arrived in {} s, this terminal may not report the mouse and nothing is recorded"
+        }
+        Msg::NoMouseTerminal => {
+            "try another terminal emulator, such as kitty, alacritty, wezterm or windows terminal"
+        }
+        Msg::NoMouseTmux => {
+            "in tmux or screen turn the mouse on (`set -g mouse on`), over ssh the local terminal has to report it"
+        }
+        Msg::NoMouseKeys => {
+            "space clicks with the keyboard, enter waits again, esc hides this, q quits"
+        }
+        Msg::ConfigReloaded => "config reloaded",
+        Msg::SettingsTitle => "settings",
+        Msg::SettingsKeys => "↑↓ or tab choose, ←→ change or type, enter saves to the config, esc closes",
+        Msg::SettingsSaved => "settings saved to {}",
+        Msg::SettingWindow => "detect window",
+        Msg::SettingFloor => "human floor",
+        Msg::SettingConfidence => "confidence",
+        Msg::SettingTarget => "target chatter rate",
+        Msg::SettingNewestFirst => "newest click on top",
+        Msg::SettingFollow => "follow new clicks",
+        Msg::SettingTheme => "theme",
+        Msg::On => "on",
+        Msg::Off => "off",
+        Msg::ConfirmTitle => "confirm",
+        Msg::ConfirmKeys => "y: yes, n or esc: no, tab or ←→ moves, enter takes the marked one",
+        Msg::ConfirmQuit => "quit? the {} clicks of this test are not saved, --save keeps them",
+        Msg::ConfirmStartOver => "start over? the {} clicks so far are dropped",
+        Msg::ChatterToast => "chatter detected, {} into the window",
+        Msg::ChartWindow => "clicks {}-{}: {} chatter, fastest {}",
+        Msg::PopupWallClock => "wall clock: {} utc",
+        Msg::PopupPosition => "position: {}",
+        Msg::PopupCell => "column {}, row {}",
+        Msg::PopupModifiers => "modifiers: {}",
+        Msg::PopupNotes => "notes: {}",
+        Msg::PopupContext => "clicks around it:",
+        Msg::Records => "best: {} cps, double {} ms, streak {}",
+        Msg::RecordCps => "new record: {} clicks in one second",
+        Msg::RecordDouble => "new record: double click in {} ms",
+        Msg::RecordStreak => "new record: {} clicks without chatter",
+        Msg::Backlog => "behind on {} events, {} frames skipped",
+        Msg::BacklogSummary => "{} events arrived faster than they were drawn, {} frames were skipped and {} drawn with older stats, no click was dropped but their timestamps may be late",
+        Msg::ClockJumps => "the recording clock went back {} times and jumped ahead {} times, the clicks after were moved to continue just past the detect window",
+        Msg::Suspended => "suspended for {} s, the next click opens a new detect window",
+        Msg::Uploaded => "summary uploaded to {}",
+        Msg::Quirks => "terminal quirks worked around: {}",
+        Msg::Captured => "mouse captured again",
+        Msg::Released => "mouse released, select and copy as usual until c captures it again",
+        Msg::CaptureOn => "mouse captured, c frees it",
+        Msg::CaptureOff => "mouse free, c captures it",
+        Msg::SettingPrecision => "interval decimals",
+        Msg::Timeline => "detect windows (g: intervals)",
+        Msg::TimelineRow => "#{}  {} clicks in {}",
+        Msg::TimelineSingle => "#{}  1 click",
+        Msg::WindowClean => "clean",
+        Msg::WindowDoubleClick => "double click",
+        Msg::WindowChatter => "chatter",
+        Msg::Agreement => "{} windows marked as one press, {} of them clicked again and {} of those called chatter",
+        Msg::AgreementMissed => "{} marked windows read as a double click, a human floor of {} ms is too low for this switch",
+        Msg::MarkedOnePress => "window of click #{} marked as one press",
+        Msg::NoWindow => "no click to mark yet",
+        Msg::BlindProgress => "click {} of {}",
+        Msg::BlindHint => "blind test, the results show once the last click is in",
+        Msg::BlindDone => "blind test done, here are the results",
+        Msg::ButtonsSwapped => "the os swaps the buttons, left and right are the buttons pressed",
+        Msg::Fatigue => "{} first to last quarter: press gap {} to {}, hold {} to {}, chatter {}% to {}%",
+        Msg::FatigueFinger => "{} pressed every {} before the rest break and every {} after it, a tired finger",
+        Msg::FatigueSwitch => "{} chatters more and kept on after the rest break, the switch is wearing",
+        Msg::FatigueUnclear => "{} drifted, rest a minute or mark a break and go on to tell a tired finger from a wearing switch",
+        Msg::Segment => "segment {}: {} clicks, {} chatter ({}%), median gap {}",
+        Msg::BreakTitle => "break",
+        Msg::BreakText => "segment {} is done after {} minutes, rest your hand and look away from the screen; resting for {}",
+        Msg::BreakKeys => "enter: start segment {}  q: quit",
+        Msg::SegmentStarted => "segment {} started",
+        Msg::MiniWaiting => "waiting for the first click",
+        Msg::MiniLast => "last {}: {}",
+        Msg::MiniCounts => "{} clicks, {} chatter ({}%)",
+        Msg::StuckButtons => "stuck presses: {} (longest {})",
+        Msg::VerdictStuck => "STUCK: {} held {} with no release",
+        Msg::Stuck => "{} button stuck, down for {} with no release",
+        Msg::StuckReleased => "{} button came up after {}",
+        Msg::Chords => "chords: {} ({} pressed together, fastest {} apart)",
+        Msg::VerdictChord => "CROSSTALK: {} went down {} after {} with it held",
+        Msg::Actuations => "actuations: {} ({} missed, {} extra clicks)",
+        Msg::ActuationLatency => "actuation to click: {} median, {} worst",
+        Msg::VerdictMissed => "MISSED: {} of {} actuations gave no click",
+        Msg::ControlActuated => "actuation {} at {} s",
+        Msg::ActuationOutcome => "actuation {}: {} clicks, first after {}",
+        Msg::ActuationMissed => "actuation {}: missed",
+        Msg::Loopback => "input stack round trip: {}, taken off the actuation latencies",
+        Msg::PopupShare => "{}% across, {}% down",
+        Msg::ResultsTitle => "results",
+        Msg::ResultsVerdict => "verdict",
+        Msg::ResultsChart => "chart",
+        Msg::ResultsKeys => "left/right: page  s: save  e: report  y: copy  n: new test  b: new test with this one as baseline  esc: back  q: leave",
+        Msg::ResultsSaved => "saved to {}",
+        Msg::ResultsExported => "report written to {}",
+        Msg::ResultsCopied => "summary sent to the clipboard",
+        Msg::ConfirmLeave => "leave? the session is saved at {}",
+        Msg::ConfirmLeaveSaving => "leave? the session is saved on the way out",
+        Msg::ConfirmLeaveUnsaved => "leave? the {} clicks of this test are not saved, s saves them",
+        Msg::Restarted => "new test with the same settings",
+        Msg::Before => "baseline: {} clicks, {}% chatter, now {}%",
+        Msg::Baseline => "baseline",
+        Msg::Health => "health: {}/100, grade {}",
+        Msg::HealthPart => "  {}: {}/100, weight {}",
+        Msg::HealthChatter => "chatter",
+        Msg::HealthConsistency => "consistency",
+        Msg::HealthHolds => "holds",
+        Msg::HealthWheel => "wheel",
+        Msg::ExplainTitle => "why it was flagged:",
+        Msg::ExplainHint => "x: why it was flagged",
+        Msg::ExplainNothing => "no rule flagged this click",
+        Msg::ExplainChatter => "chatter: it came {} after the click that opened the window, inside the {} detect window",
+        Msg::ExplainFast => "that is faster than the {} human floor, no hand clicks again that fast, so the switch is blamed",
+        Msg::ExplainHuman => "that is slower than the {} human floor, so it may be a double click on purpose",
+        Msg::ExplainChatterWhy => "why it matters: a chattering switch turns one click into two, opens things twice and drops drags",
+        Msg::ExplainOutlier => "outlier: its gap of {} is {} off the median gap of {}, more than {} times the usual spread of {}",
+        Msg::ExplainOutlierWhy => "why it matters: a gap out of rhythm is a missed press, a stall of the terminal or a pause, not chatter",
+        Msg::ExplainShortHold => "short hold: down for only {}, under the {} a finger takes, typical of a bouncing contact",
+        Msg::ExplainStuck => "stuck: held {}, past the {} stuck timeout",
+        Msg::ExplainStuckWhy => "why it matters: a button that does not let go turns clicks into drags",
+        Msg::ExplainCheckChatter => "{} chatter per 1000 clicks is over the limit of {}, about one click in every {} came twice",
+        Msg::ExplainCheckCps => "at most {} clicks fell into one second, under the {} required, the switch cannot keep up with fast clicking",
+        Msg::ExplainCheckBounce => "99% of the chatter came within {} ms, over the limit of {} ms, the contact keeps bouncing longer than a firmware debounce covers",
+        Msg::FlaggedClicks => "flagged clicks",
+        Msg::Smoothed => "interval, mean of {} clicks",
+        Msg::BadDuration => "`{}` is no duration, try 250ms, 0.25s or 1/4s",
+        Msg::DurationZero => "has to be longer than 0",
+        Msg::SettingsDuration => "type a duration like 250ms, 0.25s or 1/4s, without a unit in {}",
+        Msg::RecordedWith => "recorded with {}, analyzed with {}; detectors change between versions, compare verdicts of one build",
+        Msg::BuildDiffers => "the builds differ, {} against {}; the verdicts may differ by the detectors alone",
+        Msg::UpdateAvailable => "mouse-checker {} is out, this is {}",
+        Msg::UpdateDetectors => "mouse-checker {} is out with detector fixes, this is {}; its verdicts may differ from these",
+        Msg::Yes => "yes",
+        Msg::No => "no",
+        Msg::Identity => "mouse: {}",
+    }
+}
+
+fn zh_tw(msg: Msg) -> &'static str {
+    match msg {
+        Msg::Clicks => "點擊: {}",
+        Msg::Chatter => "連點: {}",
+        Msg::Outliers => "離群值: {}",
+        Msg::CleanStreak => "連續正常: {} (最佳 {})",
+        Msg::LastChatter => "上次連點: {} 秒前",
+        Msg::Stats => "統計",
+        Msg::FlagChatter => "連點",
+        Msg::FlagOutlier => "離群",
+        Msg::FlagBookmark => "書籤",
+        Msg::FlagKeyboard => "鍵盤",
+        Msg::SnapshotSaved => "畫面已儲存至 {}",
+        Msg::WindowAdjusted => "偵測時間 {} ms: {} 次連點，原為 {} 次",
+        Msg::Viewing => "檢視 {}，唯讀",
+        Msg::Battery => "電量 {}%",
+        Msg::DeviceBattery => "{} (電量 {}%)",
+        Msg::EventRate => "事件頻率: {} Hz",
+        Msg::UsbRate => " (usb: {} Hz)",
+        Msg::ClickPrompt => "請點擊滑鼠! (不支援滑鼠時可按空白鍵)",
+        Msg::ColTime => "時間",
+        Msg::ColButton => "按鍵",
+        Msg::ColInterval => "間隔",
+        Msg::ColHold => "按住",
+        Msg::ColFlags => "標記",
+        Msg::PopupClick => "點擊: {}",
+        Msg::PopupTime => "時間: {} 秒",
+        Msg::PopupButton => "按鍵: {}",
+        Msg::PopupInterval => "間隔: {}",
+        Msg::PopupGap => "與前次間隔: {}",
+        Msg::PopupHold => "按住: {}",
+        Msg::PopupFlags => "標記: {}",
+        Msg::Event => "事件",
+        Msg::WindowClosed => "偵測視窗已關閉，下一次點擊開始新的一組",
+        Msg::WindowOpen => "{} 秒後下一次點擊開始新的一組",
+        Msg::DetectWindow => "偵測視窗",
+        Msg::Interval => "間隔",
+        Msg::IntervalMs => "間隔 (ms，g: 偵測區間，a: 平滑)",
+        Msg::ChartClick => "點擊",
+        Msg::Summary => "{} 次點擊，{} 次連點，最長連續正常 {} 次",
+        Msg::Saved => "紀錄已儲存至 {}",
+        Msg::Estimate => "連點率 {}% ({}% 信賴區間 {}-{}%)",
+        Msg::EstimateConfirmed => "，已確認低於 {}%",
+        Msg::EstimateNeeded => "，還需 {} 次點擊以確認低於 {}%",
+        Msg::EstimateAbove => "，高於 {}%",
+        Msg::Cps => "每秒點擊: {} (最高 {})",
+        Msg::Bursts => "連發: {} (最長 {})",
+        Msg::Bounces => "按下彈跳: {}，放開彈跳: {}",
+        Msg::NotFollowing => " | 未跟隨，按 f 跟隨",
+        Msg::FilterPrompt => "篩選 (left, right, middle, flagged, all): {}_",
+        Msg::FilterHidden => " | 篩選 {}，隱藏 {} 筆",
+        Msg::SearchPrompt => "搜尋 (interval<20, hold>300, button=right): {}_",
+        Msg::SearchMatches => " | 搜尋 {}: {} 筆符合，n/N 跳轉",
+        Msg::Macros => "疑似巨集連發: {} 段 (最長 {} 次，最近於 {} 秒)，其中 {} 次連點非開關問題",
+        Msg::VerdictWaiting => "尚未點擊",
+        Msg::VerdictClean => "{} 次點擊未偵測到連點",
+        Msg::VerdictChatter => "連點: {} 次，最快 {} ms",
+        Msg::VerdictMacro => "疑似巨集: {} 段等間隔連發",
+        Msg::VerdictHuman => "全部 {} 次連點皆不低於 {} ms，快但仍可能是人為",
+        Msg::VerdictHardware => "{} 次連點低於 {} ms，人手無法達到: 開關故障 ({} 次可能是人為)",
+        Msg::Quantized => "時間戳以 {} µs 為單位 ({} 時鐘)，更短的連點無法量測",
+        Msg::CriterionChatter => "每 1000 次點擊的連點",
+        Msg::CriterionCps => "每秒最高點擊",
+        Msg::CriterionBounce => "連點間隔第 99 百分位 (ms)",
+        Msg::CheckPass => "通過: {} {} (限制 {})",
+        Msg::CheckFail => "未通過: {} {} (限制 {})",
+        Msg::Debounce => "將韌體防彈跳設為至少 {} ms 即可濾掉這些彈跳",
+        Msg::Simulating => "模擬 {} ms 的韌體防彈跳，原始 / 防彈跳後",
+        Msg::SimulationOff => "已關閉防彈跳模擬",
+        Msg::SimulatedClicks => "點擊: {} / {}",
+        Msg::SimulatedChatter => "連點: {} / {} ({} / {})",
+        Msg::SuggestWindow => "沒有連點，最快的間隔為 {} ms: {} ms 的偵測時間不會誤判你自己的點擊",
+        Msg::SuggestWrite => "將 {} ms 的偵測時間寫入 {}? [y/N] ",
+        Msg::Holds => "按住時間: 中位數 {} ms，一成低於 {} ms，{} 次短於 {} ms",
+        Msg::HoldsBounce => "連點只按住 {} ms，手指不可能這麼快: 開關彈跳",
+        Msg::HoldsTechnique => "連點按住 {} ms，不比一般的 {} ms 短多少: 確實按下，可能是手法",
+        Msg::Consistency => "前後半段: ks 距離 {} (上限 {})",
+        Msg::VerdictInconsistent => "條件改變: 前後半段不一致 (ks {})，總計不可信",
+        Msg::Coalesced => "終端機把事件合併成 {} ms 的影格，這就是量測下限",
+        Msg::PlainPrompt => "請點擊滑鼠或按空白鍵，按 q 結束",
+        Msg::PlainClick => "點擊 {}: {} 鍵，於 {} 秒",
+        Msg::PlainChatter => "，連點，距第一次點擊 {}",
+        Msg::PlainKeyboard => "，以空白鍵模擬",
+        Msg::ControlPaused => "已暫停，不計點擊",
+        Msg::ControlResumed => "已繼續",
+        Msg::ControlMarked => "已標記於 {} 秒: {}",
+        Msg::ControlStopped => "已停止",
+        Msg::ControlUnknown => "未知的指令 `{}`，可用 pause、resume、mark <備註>、stats 或 stop",
+        Msg::Pruned => "已移除 {} 筆超出設定保留期限的舊紀錄",
+        Msg::Recover => "{} 的點擊測試 ({} 次點擊) 未正常結束，要存入紀錄嗎? [y/N] ",
+        Msg::Resumed => "繼續 {} 的紀錄，目前 {} 次點擊",
+        Msg::NoMouseTitle => "沒有滑鼠輸入",
+        Msg::NoMouse => "{} 秒內沒有收到任何滑鼠事件，這個終端機可能不回報滑鼠，目前沒有任何紀錄",
+        Msg::NoMouseTerminal => {
+            "請改用其他終端機，例如 kitty、alacritty、wezterm 或 windows terminal"
+        }
+        Msg::NoMouseTmux => {
+            "在 tmux 或 screen 中請開啟滑鼠 (`set -g mouse on`)，透過 ssh 時本機終端機必須回報滑鼠"
+        }
+        Msg::NoMouseKeys => "空白鍵以鍵盤點擊，enter 繼續等待，esc 隱藏，q 離開",
+        Msg::ConfigReloaded => "已重新載入設定",
+        Msg::SettingsTitle => "設定",
+        Msg::SettingsKeys => "↑↓ 或 tab 選擇，←→ 調整或直接輸入，enter 存入設定檔，esc 關閉",
+        Msg::SettingsSaved => "設定已儲存至 {}",
+        Msg::SettingWindow => "偵測區間",
+        Msg::SettingFloor => "人手下限",
+        Msg::SettingConfidence => "信賴水準",
+        Msg::SettingTarget => "目標連點率",
+        Msg::SettingNewestFirst => "最新點擊在上",
+        Msg::SettingFollow => "跟隨新點擊",
+        Msg::SettingTheme => "主題",
+        Msg::On => "開",
+        Msg::Off => "關",
+        Msg::ConfirmTitle => "確認",
+        Msg::ConfirmKeys => "y: 是，n 或 esc: 否，tab 或 ←→ 移動，enter 選擇標示的按鈕",
+        Msg::ConfirmQuit => "要離開嗎? 這次測試的 {} 次點擊不會儲存，加上 --save 可保留",
+        Msg::ConfirmStartOver => "要重新開始嗎? 目前的 {} 次點擊將被捨棄",
+        Msg::ChatterToast => "偵測到連點，距區間開始 {}",
+        Msg::ChartWindow => "第 {}-{} 次點擊: {} 次連點，最快 {}",
+        Msg::PopupWallClock => "實際時間: {} utc",
+        Msg::PopupPosition => "位置: {}",
+        Msg::PopupCell => "第 {} 欄，第 {} 列",
+        Msg::PopupModifiers => "組合鍵: {}",
+        Msg::PopupNotes => "備註: {}",
+        Msg::PopupContext => "前後的點擊:",
+        Msg::Records => "最佳: {} cps,雙擊 {} ms,連續 {}",
+        Msg::RecordCps => "新紀錄: 一秒內 {} 次點擊",
+        Msg::RecordDouble => "新紀錄: 雙擊 {} ms",
+        Msg::RecordStreak => "新紀錄: 連續 {} 次點擊沒有連點",
+        Msg::Backlog => "落後 {} 個事件,略過 {} 幀",
+        Msg::BacklogSummary => "{} 個事件來得比畫面更新快,略過了 {} 幀,{} 幀用了較舊的統計,沒有遺失任何點擊,但時間戳可能偏晚",
+        Msg::ClockJumps => "錄製時的時鐘倒退了 {} 次、往前跳了 {} 次,之後的點擊已移到緊接在偵測視窗之後",
+        Msg::Suspended => "系統休眠了 {} 秒,下一次點擊會開啟新的偵測視窗",
+        Msg::Uploaded => "摘要已上傳到 {}",
+        Msg::Quirks => "已處理的終端機異常: {}",
+        Msg::Captured => "已重新擷取滑鼠",
+        Msg::Released => "已釋放滑鼠，可照常選取與複製，按 c 重新擷取",
+        Msg::CaptureOn => "已擷取滑鼠，按 c 釋放",
+        Msg::CaptureOff => "滑鼠已釋放，按 c 擷取",
+        Msg::SettingPrecision => "間隔小數位數",
+        Msg::Timeline => "偵測區間 (g: 間隔)",
+        Msg::TimelineRow => "#{}  {} 次點擊，歷時 {}",
+        Msg::TimelineSingle => "#{}  1 次點擊",
+        Msg::WindowClean => "正常",
+        Msg::WindowDoubleClick => "雙擊",
+        Msg::WindowChatter => "連點",
+        Msg::Agreement => "{} 個區間標為只按一次，其中 {} 個多出點擊，{} 個判定為連點",
+        Msg::AgreementMissed => "{} 個標記的區間被當成雙擊，人手下限 {} ms 對這個開關太低",
+        Msg::MarkedOnePress => "已將第 {} 次點擊的區間標為只按一次",
+        Msg::NoWindow => "還沒有可標記的點擊",
+        Msg::BlindProgress => "第 {} 次點擊，共 {} 次",
+        Msg::BlindHint => "盲測中，最後一次點擊後才顯示結果",
+        Msg::BlindDone => "盲測完成，以下是結果",
+        Msg::ButtonsSwapped => "系統對調了左右鍵，記錄的是實際按下的按鍵",
+        Msg::Fatigue => "{} 前四分之一到最後四分之一: 按壓間隔 {} 到 {}，按住 {} 到 {}，連擊 {}% 到 {}%",
+        Msg::FatigueFinger => "{} 休息前每 {} 按一次，休息後每 {} 按一次，是手指累了",
+        Msg::FatigueSwitch => "{} 連擊變多，休息後仍持續，微動開關正在老化",
+        Msg::FatigueUnclear => "{} 有漂移，休息一分鐘或標記休息後繼續，以分辨手指疲勞或開關老化",
+        Msg::Segment => "第 {} 段: {} 次點擊，{} 次連擊 ({}%)，間隔中位數 {}",
+        Msg::BreakTitle => "休息",
+        Msg::BreakText => "第 {} 段在 {} 分鐘後結束，讓手休息並將視線移開螢幕；已休息 {}",
+        Msg::BreakKeys => "enter: 開始第 {} 段  q: 離開",
+        Msg::SegmentStarted => "第 {} 段開始",
+        Msg::MiniWaiting => "等待第一次點擊",
+        Msg::MiniLast => "最後 {}: {}",
+        Msg::MiniCounts => "{} 次點擊，{} 次連擊 ({}%)",
+        Msg::StuckButtons => "卡住的按壓: {} 次 (最久 {})",
+        Msg::VerdictStuck => "卡鍵: {} 按住 {} 未放開",
+        Msg::Stuck => "{} 鍵卡住，已按下 {} 未放開",
+        Msg::StuckReleased => "{} 鍵在 {} 後彈起",
+        Msg::Chords => "同時按壓: {} 次 ({} 次幾乎同時, 最快相隔 {})",
+        Msg::VerdictChord => "串擾: {} 在 {} 後按下, 當時 {} 仍按住",
+        Msg::Actuations => "致動: {} 次 ({} 次漏失, {} 次多餘點擊)",
+        Msg::ActuationLatency => "致動到點擊: 中位數 {}, 最慢 {}",
+        Msg::VerdictMissed => "漏失: {} / {} 次致動沒有點擊",
+        Msg::ControlActuated => "第 {} 次致動於 {} 秒",
+        Msg::ActuationOutcome => "第 {} 次致動: {} 次點擊, 第一次在 {} 後",
+        Msg::ActuationMissed => "第 {} 次致動: 漏失",
+        Msg::Loopback => "輸入堆疊往返: {}, 已從致動延遲中扣除",
+        Msg::PopupShare => "橫向 {}%，縱向 {}%",
+        Msg::ResultsTitle => "結果",
+        Msg::ResultsVerdict => "判定",
+        Msg::ResultsChart => "圖表",
+        Msg::ResultsKeys => "左/右: 換頁  s: 儲存  e: 報告  y: 複製  n: 新測試  b: 新測試並以此次為基準  esc: 返回  q: 離開",
+        Msg::ResultsSaved => "已儲存至 {}",
+        Msg::ResultsExported => "報告已寫入 {}",
+        Msg::ResultsCopied => "摘要已送到剪貼簿",
+        Msg::ConfirmLeave => "要離開嗎? 這次測試已儲存於 {}",
+        Msg::ConfirmLeaveSaving => "要離開嗎? 這次測試會在離開時儲存",
+        Msg::ConfirmLeaveUnsaved => "要離開嗎? 這次測試的 {} 次點擊未儲存，按 s 可儲存",
+        Msg::Restarted => "以相同設定開始新測試",
+        Msg::Before => "基準: {} 次點擊，連點 {}%，現在 {}%",
+        Msg::Baseline => "基準",
+        Msg::Health => "健康度: {}/100，等級 {}",
+        Msg::HealthPart => "  {}: {}/100，權重 {}",
+        Msg::HealthChatter => "連點",
+        Msg::HealthConsistency => "一致性",
+        Msg::HealthHolds => "按壓",
+        Msg::HealthWheel => "滾輪",
+        Msg::ExplainTitle => "標記原因:",
+        Msg::ExplainHint => "x: 標記原因",
+        Msg::ExplainNothing => "沒有規則標記此點擊",
+        Msg::ExplainChatter => "連點: 在開啟視窗的點擊後 {} 出現，落在 {} 的偵測視窗內",
+        Msg::ExplainFast => "比 {} 的人手下限還快，人手無法這麼快再點一次，因此歸咎於微動開關",
+        Msg::ExplainHuman => "比 {} 的人手下限慢，可能是刻意的雙擊",
+        Msg::ExplainChatterWhy => "影響: 連點的開關會把一次點擊變成兩次，重複開啟東西並中斷拖曳",
+        Msg::ExplainOutlier => "離群: 間隔 {} 與中位數 {} 相差 {}，超過 {} 倍的一般離散度 {}",
+        Msg::ExplainOutlierWhy => "影響: 節奏異常的間隔代表漏按、終端停頓或暫停，而不是連點",
+        Msg::ExplainShortHold => "按壓過短: 只按下 {}，低於手指所需的 {}，常見於彈跳的接點",
+        Msg::ExplainStuck => "卡住: 按住 {}，超過 {} 的卡住門檻",
+        Msg::ExplainStuckWhy => "影響: 無法放開的按鍵會把點擊變成拖曳",
+        Msg::ExplainCheckChatter => "每 1000 次點擊 {} 次連點，超過上限 {}，約每 {} 次點擊就有一次變成兩次",
+        Msg::ExplainCheckCps => "一秒內最多 {} 次點擊，低於要求的 {}，開關跟不上快速點擊",
+        Msg::ExplainCheckBounce => "99% 的連點在 {} ms 內，超過上限 {} ms，接點彈跳比韌體去彈跳涵蓋的還久",
+        Msg::FlaggedClicks => "被標記的點擊",
+        Msg::Smoothed => "間隔，{} 次點擊的平均",
+        Msg::BadDuration => "`{}` 不是時間長度，試試 250ms、0.25s 或 1/4s",
+        Msg::DurationZero => "必須大於 0",
+        Msg::SettingsDuration => "輸入時間長度，例如 250ms、0.25s 或 1/4s，只有數字時單位為 {}",
+        Msg::RecordedWith => "以 {} 錄製，以 {} 分析；偵測器會隨版本改變，請比較同一版本的判定",
+        Msg::BuildDiffers => "版本不同，{} 對 {}；判定可能只因偵測器不同而有差異",
+        Msg::UpdateAvailable => "mouse-checker {} 已發布，目前為 {}",
+        Msg::UpdateDetectors => "mouse-checker {} 已發布並修正了偵測器，目前為 {}；其判定可能與這裡不同",
+        Msg::Yes => "是",
+        Msg::No => "否",
+        Msg::Identity => "滑鼠：{}",
+    }
+}
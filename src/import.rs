@@ -0,0 +1,276 @@
+use std::{fs, path::Path, time::Duration};
+
+use clap::ValueEnum;
+use crossterm::event::{KeyModifiers, MouseButton};
+
+use crate::{
+    error::{self, Error},
+    session::{Click, Session},
+};
+
+/// unix timestamps in seconds are past this, smaller ones are relative
+const EPOCH_SECS: f64 = 1e9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Unit {
+    S,
+    Ms,
+    Us,
+}
+
+impl Unit {
+    /// guess from a header like `time_ms` or `timestamp (s)`
+    fn from_header(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase();
+        let suffix = name.trim_end_matches([')', ']']);
+        if suffix.ends_with("us") || suffix.ends_with("µs") || suffix.contains("micro") {
+            Some(Unit::Us)
+        } else if suffix.ends_with("ms") || suffix.contains("milli") {
+            Some(Unit::Ms)
+        } else if suffix.ends_with("(s") || suffix.ends_with("_s") || suffix.ends_with("sec") {
+            Some(Unit::S)
+        } else {
+            None
+        }
+    }
+
+    fn secs(self, value: f64) -> f64 {
+        match self {
+            Unit::S => value,
+            Unit::Ms => value / 1e3,
+            Unit::Us => value / 1e6,
+        }
+    }
+}
+
+/// button names of the usual loggers, numbers are the dom `MouseEvent.button`
+fn parse_button(value: &str) -> Option<MouseButton> {
+    match value.to_ascii_lowercase().as_str() {
+        "left" | "l" | "0" | "button1" | "lmb" => Some(MouseButton::Left),
+        "middle" | "m" | "1" | "button2" | "mmb" => Some(MouseButton::Middle),
+        "right" | "r" | "2" | "button3" | "rmb" => Some(MouseButton::Right),
+        _ => None,
+    }
+}
+
+/// whether an event column value is a release, rows without one are presses
+fn is_release(value: &str) -> bool {
+    matches!(
+        value.to_ascii_lowercase().as_str(),
+        "up" | "release" | "released" | "mouseup" | "buttonup"
+    )
+}
+
+fn split(line: &str, separator: char) -> Vec<String> {
+    line.split(separator)
+        .map(|f| f.trim().trim_matches('"').trim().to_string())
+        .collect()
+}
+
+/// read a click logger csv with a timestamp and a button column into a session
+pub fn read(
+    path: &Path,
+    detect_window: Duration,
+    device: Option<String>,
+    unit: Option<Unit>,
+) -> error::Result<Session> {
+    let failed = |message: String| Error::Config {
+        path: path.to_path_buf(),
+        message,
+    };
+    let text = fs::read_to_string(path).map_err(|e| failed(e.to_string()))?;
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty());
+
+    let (_, header) = lines
+        .next()
+        .ok_or_else(|| failed("empty file".to_string()))?;
+    let separator = [',', ';', '\t']
+        .into_iter()
+        .max_by_key(|s| header.matches(*s).count())
+        .unwrap_or(',');
+    let columns = split(header, separator)
+        .into_iter()
+        .map(|c| c.to_ascii_lowercase())
+        .collect::<Vec<_>>();
+    // short names only match whole, `t` is no prefix of `type`
+    let find = |names: &[&str]| {
+        names.iter().find_map(|n| {
+            columns
+                .iter()
+                .position(|c| c == n || (n.len() > 2 && c.starts_with(n)))
+        })
+    };
+    let time = find(&["timestamp", "time", "ms", "us", "t", "at"])
+        .ok_or_else(|| failed("no timestamp column in the header".to_string()))?;
+    let button = find(&["button", "btn", "key"])
+        .ok_or_else(|| failed("no button column in the header".to_string()))?;
+    let event = find(&["event", "type", "action", "state"]);
+    let unit = unit
+        .or_else(|| Unit::from_header(&columns[time]))
+        .unwrap_or(Unit::Ms);
+
+    let mut rows = Vec::new();
+    for (number, line) in lines {
+        let fields = split(line, separator);
+        let line_failed = |what: &str| failed(format!("line {}: {}", number + 1, what));
+        let at = fields
+            .get(time)
+            .and_then(|f| f.parse::<f64>().ok())
+            .filter(|t| t.is_finite() && *t >= 0.0)
+            .ok_or_else(|| line_failed("invalid timestamp"))?;
+        let button = fields
+            .get(button)
+            .and_then(|f| parse_button(f))
+            .ok_or_else(|| line_failed("unknown button"))?;
+        let release = event
+            .and_then(|e| fields.get(e))
+            .is_some_and(|e| is_release(e));
+        rows.push((unit.secs(at), button, release));
+    }
+    rows.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let first = rows.first().map(|r| r.0).unwrap_or_default();
+    let mut session = Session::new(device, detect_window);
+    if first >= EPOCH_SECS {
+        session.started_at = first as u64;
+    }
+
+    // the same detect windows as a live capture
+    let mut window: Option<Duration> = None;
+    for (secs, button, release) in rows {
+        let at = Duration::try_from_secs_f64(secs - first)
+            .map_err(|_| failed(format!("timestamp {} is too far from the first one", secs)))?;
+        if release {
+            if let Some(click) = session
+                .clicks
+                .iter_mut()
+                .rev()
+                .find(|c| c.button == button)
+                .filter(|c| c.hold.is_none())
+            {
+                click.hold = Some(at.saturating_sub(click.at));
+            }
+            continue;
+        }
+        let interval = match window {
+            Some(start) if at - start <= detect_window => Some(at - start),
+            _ => {
+                window = Some(at);
+                None
+            }
+        };
+        session.clicks.push(Click {
+            at,
+            interval,
+            button,
+            hold: None,
+            bookmarked: false,
+            keyboard: false,
+            position: None,
+            modifiers: KeyModifiers::NONE,
+        });
+    }
+    // the rows are sorted, a logger's machine may still have slept between two
+    session.correct_jumps();
+    Ok(session)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use super::*;
+
+    /// the session read from a csv file holding `text`
+    fn import(name: &str, text: &str, unit: Option<Unit>) -> error::Result<Session> {
+        let path = env::temp_dir().join(format!("mouse-checker-import-{}-{}", process::id(), name));
+        fs::write(&path, text).unwrap();
+        let session = read(&path, Duration::from_millis(50), None, unit);
+        let _ = fs::remove_file(&path);
+        session
+    }
+
+    fn millis(d: Option<Duration>) -> Option<u128> {
+        d.map(|d| d.as_millis())
+    }
+
+    #[test]
+    fn units_from_the_header() {
+        assert_eq!(Unit::from_header("time_ms"), Some(Unit::Ms));
+        assert_eq!(Unit::from_header("Timestamp (us)"), Some(Unit::Us));
+        assert_eq!(Unit::from_header("timestamp (s)"), Some(Unit::S));
+        assert_eq!(Unit::from_header("t_sec"), Some(Unit::S));
+        assert_eq!(Unit::from_header("timestamp"), None);
+    }
+
+    #[test]
+    fn presses_and_releases_become_clicks() {
+        // out of order, the release gives the hold of its press
+        let session = import(
+            "events",
+            "time_ms,button,event\n1030,left,down\n1000,left,down\n\n1020,left,up\n1100,\"R\",down\n",
+            None,
+        )
+        .unwrap();
+        let clicks = session
+            .clicks
+            .iter()
+            .map(|c| {
+                (
+                    c.at.as_millis(),
+                    c.button,
+                    millis(c.interval),
+                    millis(c.hold),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            clicks,
+            [
+                (0, MouseButton::Left, None, Some(20)),
+                (30, MouseButton::Left, Some(30), None),
+                (100, MouseButton::Right, None, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn unix_seconds_start_the_session() {
+        let session = import(
+            "unix",
+            "timestamp (s);btn\n1700000000.25;0\n1700000000.28125;0\n",
+            None,
+        )
+        .unwrap();
+        assert_eq!(session.started_at, 1_700_000_000);
+        // exact in binary, a thirty second of a second
+        assert_eq!(
+            session.clicks[1].interval,
+            Some(Duration::from_micros(31_250))
+        );
+        // a unit given on the command line wins over the header
+        let session = import("given", "time\tkey\n0\tl\n20000\tl\n", Some(Unit::Us)).unwrap();
+        assert_eq!(millis(session.clicks[1].interval), Some(20));
+    }
+
+    #[test]
+    fn bad_files_fail_with_the_line() {
+        for (text, problem) in [
+            ("", "empty file"),
+            ("when,button\n", "no timestamp column"),
+            ("time,type\n", "no button column"),
+            (
+                "time,button\n0,left\nsoon,left\n",
+                "line 3: invalid timestamp",
+            ),
+            ("time,button\n-5,left\n", "line 2: invalid timestamp"),
+            ("time,button\n0,thumb\n", "line 2: unknown button"),
+        ] {
+            let message = import("bad", text, None).unwrap_err().to_string();
+            assert!(message.contains(problem), "{:?}: {}", text, message);
+        }
+    }
+}
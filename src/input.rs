@@ -0,0 +1,35 @@
+use std::{io, time::Duration};
+
+use crossterm::event::{self, Event};
+
+use crate::{dump, quirks};
+
+/// whether an event is waiting, the terminal's or the next of a `--script`
+fn poll_source(timeout: Duration) -> io::Result<bool> {
+    #[cfg(feature = "test-util")]
+    if let Some(ready) = crate::harness::poll(timeout) {
+        return ready;
+    }
+    event::poll(timeout)
+}
+
+fn read_source() -> io::Result<Event> {
+    #[cfg(feature = "test-util")]
+    if let Some(event) = crate::harness::read() {
+        return event.inspect(dump::record);
+    }
+    event::read().inspect(dump::record)
+}
+
+/// whether an event is waiting, one the quirks layer holds back included
+pub fn poll(timeout: Duration) -> io::Result<bool> {
+    if quirks::pending() {
+        return Ok(true);
+    }
+    poll_source(timeout)
+}
+
+/// the next event, blocking until one arrives, with the terminal's quirks worked around
+pub fn read() -> io::Result<Event> {
+    quirks::read(read_source, poll_source)
+}
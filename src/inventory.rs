@@ -0,0 +1,166 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use crate::{
+    diagnose::{self, Outcome},
+    error::{self, Error},
+    session,
+};
+
+const HEADER: &str = "tested_at,label,device,verdict,components";
+
+/// one tested unit in the ledger
+pub struct Entry {
+    /// unix timestamp in seconds
+    pub tested_at: u64,
+    pub label: String,
+    pub device: Option<String>,
+    pub verdict: String,
+    /// verdict of every component, `left button: healthy; wheel: failing`
+    pub components: String,
+}
+
+impl Entry {
+    pub fn new(label: &str, device: Option<String>, outcomes: &[Outcome]) -> Self {
+        Self {
+            tested_at: session::unix_now(),
+            // one unit per line of the ledger
+            label: label.replace(['\r', '\n'], " "),
+            device,
+            verdict: diagnose::overall(outcomes).name().to_string(),
+            components: outcomes
+                .iter()
+                .map(|o| format!("{}: {}", o.spec.test.component(), o.verdict().name()))
+                .collect::<Vec<_>>()
+                .join("; "),
+        }
+    }
+
+    fn to_csv(&self) -> String {
+        [
+            self.tested_at.to_string(),
+            self.label.clone(),
+            self.device.clone().unwrap_or_default(),
+            self.verdict.clone(),
+            self.components.clone(),
+        ]
+        .iter()
+        .map(|f| quote(f))
+        .collect::<Vec<_>>()
+        .join(",")
+    }
+
+    fn from_csv(line: &str) -> Option<Self> {
+        let mut fields = split(line).into_iter();
+        Some(Self {
+            tested_at: fields.next()?.parse().ok()?,
+            label: fields.next()?,
+            device: fields.next().filter(|d| !d.is_empty()),
+            verdict: fields.next()?,
+            components: fields.next().unwrap_or_default(),
+        })
+    }
+}
+
+/// the ledger every tested unit is appended to
+pub fn path() -> PathBuf {
+    session::data_dir().join("inventory.csv")
+}
+
+pub fn append(entry: &Entry) -> error::Result<PathBuf> {
+    let path = path();
+    let write = || -> io::Result<()> {
+        fs::create_dir_all(session::data_dir())?;
+        let new = !path.exists();
+        let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+        if new {
+            writeln!(file, "{}", HEADER)?;
+        }
+        writeln!(file, "{}", entry.to_csv())
+    };
+    write().map_err(|source| Error::Export {
+        path: path.clone(),
+        source,
+    })?;
+    Ok(path)
+}
+
+/// every unit of the ledger in the order tested, empty without a ledger
+pub fn list() -> error::Result<Vec<Entry>> {
+    let path = path();
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(Error::Config {
+                path,
+                message: e.to_string(),
+            })
+        }
+    };
+    text.lines()
+        .enumerate()
+        .skip(1)
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(number, line)| {
+            Entry::from_csv(line).ok_or_else(|| Error::Config {
+                path: path.clone(),
+                message: format!("line {}: invalid entry", number + 1),
+            })
+        })
+        .collect()
+}
+
+pub fn print_list(entries: &[Entry]) {
+    if entries.is_empty() {
+        println!("no units tested yet, run `mouse-checker inventory test <label>`");
+        return;
+    }
+    println!(
+        "{:<18}{:<16}{:<16}{:<10}components",
+        "date", "label", "device", "verdict"
+    );
+    for entry in entries {
+        println!(
+            "{:<18}{:<16}{:<16}{:<10}{}",
+            session::format_timestamp(entry.tested_at),
+            entry.label,
+            entry.device.as_deref().unwrap_or("-"),
+            entry.verdict,
+            entry.components
+        );
+    }
+}
+
+/// a csv field, quoted when it holds a separator or a quote
+fn quote(field: &str) -> String {
+    if field.contains([',', '"']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn split(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            // a doubled quote inside quotes is a literal one
+            ('"', true) if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            ('"', _) => quoted = !quoted,
+            (',', false) => fields.push(std::mem::take(&mut field)),
+            (c, _) => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
@@ -0,0 +1,298 @@
+use std::{
+    fs,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use crossterm::event::{Event, KeyCode, MouseButton, MouseEventKind};
+use ratatui::{
+    backend::Backend,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Gauge, Paragraph},
+    Frame, Terminal,
+};
+
+use crate::{
+    error::{self, Error},
+    input,
+    json::{self, Value},
+    session,
+};
+
+/// cps shown as a full gauge
+const MAX_CPS: f64 = 20.0;
+const KEEP_SCORES: usize = 10;
+/// hits needed before the technique is classified
+const MIN_CLASSIFY_HITS: usize = 10;
+
+#[derive(Debug, Clone, Copy)]
+struct Hit {
+    /// time since the first click of the round
+    at: Duration,
+    button: MouseButton,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Technique {
+    Regular,
+    Jitter,
+    Butterfly,
+    DragClick,
+}
+
+impl Technique {
+    fn name(self) -> &'static str {
+        match self {
+            Technique::Regular => "regular",
+            Technique::Jitter => "jitter",
+            Technique::Butterfly => "butterfly",
+            Technique::DragClick => "drag click",
+        }
+    }
+}
+
+/// guess the clicking technique from the interval pattern of a round
+fn classify(hits: &[Hit]) -> Option<Technique> {
+    if hits.len() < MIN_CLASSIFY_HITS {
+        return None;
+    }
+
+    let intervals = hits
+        .windows(2)
+        .map(|w| (w[1].at - w[0].at).as_secs_f64() * 1000.0)
+        .collect::<Vec<_>>();
+    let n = intervals.len() as f64;
+    let mean = intervals.iter().sum::<f64>() / n;
+    let sd = (intervals.iter().map(|i| (i - mean).powi(2)).sum::<f64>() / n).sqrt();
+    let cv = if mean > 0.0 { sd / mean } else { 0.0 };
+
+    // drag clicking rubs the button into bursts of very short bounces
+    let very_short = intervals.iter().filter(|i| **i < 15.0).count() as f64 / n;
+    if very_short > 0.3 {
+        return Some(Technique::DragClick);
+    }
+
+    // butterfly uses two fingers, either on two buttons or on one button
+    // where it shows as alternating short and long gaps
+    let switches = hits
+        .windows(2)
+        .filter(|w| w[0].button != w[1].button)
+        .count() as f64;
+    let zigzag = intervals
+        .windows(3)
+        .filter(|w| (w[1] - w[0]).signum() != (w[2] - w[1]).signum())
+        .count() as f64
+        / (n - 2.0).max(1.0);
+    if switches / n > 0.4 || (zigzag > 0.7 && cv > 0.35) {
+        return Some(Technique::Butterfly);
+    }
+
+    // jitter clicking vibrates one finger at a high and very even rate
+    if mean < 110.0 && cv < 0.25 {
+        return Some(Technique::Jitter);
+    }
+
+    Some(Technique::Regular)
+}
+
+struct KohiState {
+    window: Duration,
+    started: Option<Instant>,
+    hits: Vec<Hit>,
+    best: Vec<f64>,
+    /// score of the finished round
+    score: Option<f64>,
+}
+
+impl KohiState {
+    fn new(window: Duration) -> Self {
+        Self {
+            window,
+            started: None,
+            hits: Vec::new(),
+            best: load_scores(),
+            score: None,
+        }
+    }
+
+    fn restart(&mut self) {
+        self.started = None;
+        self.hits.clear();
+        self.score = None;
+    }
+
+    fn on_click(&mut self, button: MouseButton) {
+        if self.score.is_some() {
+            return;
+        }
+        let started = *self.started.get_or_insert_with(Instant::now);
+        self.hits.push(Hit {
+            at: started.elapsed(),
+            button,
+        });
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.started
+            .map(|s| s.elapsed().min(self.window))
+            .unwrap_or_default()
+    }
+
+    fn cps(&self) -> f64 {
+        // a single early click would otherwise read as thousands of cps
+        let secs = self.elapsed().max(Duration::from_secs(1)).as_secs_f64();
+        self.hits.len() as f64 / secs
+    }
+
+    /// finish the round once the window is over
+    fn tick(&mut self) {
+        if self.score.is_none() && self.elapsed() >= self.window {
+            let score = self.hits.len() as f64 / self.window.as_secs_f64();
+            self.score = Some(score);
+            // a score of no window is no number and would break the json of the table
+            if !score.is_finite() {
+                return;
+            }
+            self.best.push(score);
+            self.best.sort_by(|a, b| b.total_cmp(a));
+            self.best.truncate(KEEP_SCORES);
+            // a failed write only loses the high score table
+            let _ = save_scores(&self.best);
+        }
+    }
+}
+
+pub fn run<B: Backend>(terminal: &mut Terminal<B>, window: Duration) -> error::Result<()> {
+    let mut state = KohiState::new(window);
+
+    loop {
+        state.tick();
+        terminal.draw(|f| ui(f, &state))?;
+
+        if !input::poll(Duration::from_millis(50))? {
+            continue;
+        }
+
+        match input::read()? {
+            Event::Mouse(event) => {
+                if let MouseEventKind::Down(button) = event.kind {
+                    state.on_click(button);
+                }
+            }
+            Event::Key(key) => match key.code {
+                KeyCode::Char('q') => break,
+                KeyCode::Char('r') => state.restart(),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn ui<B: Backend>(f: &mut Frame<B>, state: &KohiState) {
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Length(3),
+                Constraint::Length(3),
+                Constraint::Min(5),
+                Constraint::Length(KEEP_SCORES as u16 + 2),
+                Constraint::Length(1),
+            ]
+            .as_ref(),
+        )
+        .split(f.size());
+
+    let cps = state.score.unwrap_or_else(|| state.cps());
+    let gauge = Gauge::default()
+        .block(Block::default().title("cps").borders(Borders::ALL))
+        .ratio((cps / MAX_CPS).clamp(0.0, 1.0))
+        .label(format!("{:.1} CPS", cps));
+    f.render_widget(gauge, layout[0]);
+
+    let remaining = state.window - state.elapsed();
+    let gauge = Gauge::default()
+        .block(Block::default().title("time left").borders(Borders::ALL))
+        .ratio(remaining.as_secs_f64() / state.window.as_secs_f64().max(f64::EPSILON))
+        .label(format!("{:.1} s", remaining.as_secs_f64()));
+    f.render_widget(gauge, layout[1]);
+
+    let big = Style::default().add_modifier(Modifier::BOLD);
+    let mut text = match (state.score, state.started) {
+        (Some(score), _) => vec![
+            Line::from("time's up!"),
+            Line::from(""),
+            Line::from(Span::styled(format!("{:.2} CPS", score), big)),
+            Line::from(format!("{} clicks", state.hits.len())),
+        ],
+        (None, Some(_)) => vec![
+            Line::from(format!(
+                "{:.1} s left",
+                (state.window - state.elapsed()).as_secs_f64()
+            )),
+            Line::from(""),
+            Line::from(Span::styled(format!("{} clicks", state.hits.len()), big)),
+        ],
+        (None, None) => vec![Line::from(format!(
+            "click as fast as you can for {} seconds, the first click starts the timer",
+            state.window.as_secs_f64()
+        ))],
+    };
+
+    if let Some(technique) = classify(&state.hits) {
+        text.push(Line::from(""));
+        text.push(Line::from(format!("technique: {}", technique.name())));
+    }
+
+    let p = Paragraph::new(text)
+        .block(
+            Block::default()
+                .title("kohi click test")
+                .borders(Borders::ALL),
+        )
+        .alignment(Alignment::Center);
+    f.render_widget(p, layout[2]);
+
+    let best = state
+        .best
+        .iter()
+        .enumerate()
+        .map(|(i, score)| Line::from(format!("{:>2}. {:.2} CPS", i + 1, score)))
+        .collect::<Vec<_>>();
+    let p = Paragraph::new(best)
+        .block(Block::default().title("best").borders(Borders::ALL))
+        .alignment(Alignment::Center);
+    f.render_widget(p, layout[3]);
+
+    f.render_widget(Paragraph::new("r: restart  q: quit"), layout[4]);
+}
+
+fn scores_path() -> PathBuf {
+    session::data_dir().join("kohi.json")
+}
+
+fn load_scores() -> Vec<f64> {
+    fs::read_to_string(scores_path())
+        .ok()
+        .and_then(|text| json::parse(&text).ok())
+        .and_then(|v| {
+            v.get("best")
+                .and_then(Value::as_array)
+                .map(|a| a.iter().filter_map(Value::as_f64).collect())
+        })
+        .unwrap_or_default()
+}
+
+fn save_scores(best: &[f64]) -> error::Result<()> {
+    let path = scores_path();
+    let value = json::object([("best", Value::from(best.to_vec()))]);
+    path.parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write(&path, value.to_string_pretty()))
+        .map_err(|source| Error::Export { path, source })
+}
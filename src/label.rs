@@ -0,0 +1,72 @@
+use std::{fs::OpenOptions, io::Write, path::Path};
+
+use clap::ValueEnum;
+
+use crate::{
+    diagnose::{self, Outcome},
+    error::{self, Error},
+    session,
+};
+
+/// dots between the lines of a zpl label, fits a 203 dpi printer
+const ZPL_LINE: usize = 30;
+
+/// what the label for a tested unit is printed as
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum LabelFormat {
+    /// a few short lines for a receipt or plain text printer
+    #[default]
+    Text,
+    /// zebra programming language for thermal label printers
+    Zpl,
+}
+
+/// unit, date, verdict and one line per component, as short as the label is
+fn lines(unit: &str, outcomes: &[Outcome]) -> Vec<String> {
+    let mut lines = vec![
+        unit.to_string(),
+        session::format_timestamp(session::unix_now()),
+        diagnose::overall(outcomes).name().to_uppercase(),
+    ];
+    lines.extend(outcomes.iter().map(|o| {
+        format!(
+            "{} {} {}/{}",
+            o.spec.test.component(),
+            o.verdict().name(),
+            o.bad,
+            o.total
+        )
+    }));
+    lines
+}
+
+pub fn render(unit: &str, outcomes: &[Outcome], format: LabelFormat) -> String {
+    let lines = lines(unit, outcomes);
+    match format {
+        LabelFormat::Text => lines.join("\n") + "\n",
+        LabelFormat::Zpl => {
+            let mut zpl = String::from("^XA\n^CF0,24\n");
+            for (i, line) in lines.iter().enumerate() {
+                // ^ and ~ start commands, they cannot appear in field data
+                let line = line.replace(['^', '~'], " ");
+                zpl.push_str(&format!("^FO20,{}^FD{}^FS\n", 20 + i * ZPL_LINE, line));
+            }
+            zpl.push_str("^XZ\n");
+            zpl
+        }
+    }
+}
+
+/// write the label to a file or straight to a printer device like /dev/usb/lp0
+pub fn write(path: &Path, label: &str) -> error::Result<()> {
+    OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)
+        .and_then(|mut out| out.write_all(label.as_bytes()))
+        .map_err(|source| Error::Export {
+            path: path.to_path_buf(),
+            source,
+        })
+}
@@ -0,0 +1,198 @@
+use std::{io, time::Duration};
+
+use crate::{
+    clock::Clock,
+    error::{self, Error},
+};
+
+/// synthetic clicks `--calibrate` times
+pub const CLICKS: usize = 20;
+/// time the desktop gets to pick up a new device
+#[cfg(all(feature = "uinput", target_os = "linux"))]
+pub const SETTLE: Duration = Duration::from_secs(1);
+
+#[cfg(all(feature = "uinput", target_os = "linux"))]
+pub use device::Device;
+
+#[cfg(all(feature = "uinput", target_os = "linux"))]
+mod device {
+    use crossterm::event::MouseButton;
+    use std::{
+        fs::{File, OpenOptions},
+        io::{self, Write},
+        mem,
+        os::unix::{fs::OpenOptionsExt, io::AsRawFd},
+        slice,
+    };
+
+    const EV_SYN: u16 = 0x00;
+    const EV_KEY: u16 = 0x01;
+    const EV_REL: u16 = 0x02;
+    const SYN_REPORT: u16 = 0;
+    const REL_X: u16 = 0x00;
+    const REL_Y: u16 = 0x01;
+    const BTN_LEFT: u16 = 0x110;
+    const BTN_RIGHT: u16 = 0x111;
+    const BTN_MIDDLE: u16 = 0x112;
+    const BUS_VIRTUAL: u16 = 0x06;
+
+    // _IOW('U', 100..102, int) and _IO('U', 1..2) of linux/uinput.h
+    const UI_SET_EVBIT: u32 = 0x4004_5564;
+    const UI_SET_KEYBIT: u32 = 0x4004_5565;
+    const UI_SET_RELBIT: u32 = 0x4004_5566;
+    const UI_DEV_CREATE: u32 = 0x5501;
+    const UI_DEV_DESTROY: u32 = 0x5502;
+
+    const UINPUT: &str = "/dev/uinput";
+    const NAME: &str = "mouse-checker loopback";
+
+    /// a virtual mouse with three buttons, gone again when dropped
+    pub struct Device(File);
+
+    fn ioctl(file: &File, request: u32, value: u16) -> io::Result<()> {
+        // SAFETY: the requests take an int argument or none at all
+        let result =
+            unsafe { libc::ioctl(file.as_raw_fd(), request as _, libc::c_int::from(value)) };
+        if result < 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    /// the bytes of a plain c struct as the kernel reads it
+    fn bytes<T>(value: &T) -> &[u8] {
+        // SAFETY: only used on the zero padded libc structs below
+        unsafe { slice::from_raw_parts((value as *const T).cast::<u8>(), mem::size_of::<T>()) }
+    }
+
+    impl Device {
+        pub fn new() -> io::Result<Self> {
+            let file = OpenOptions::new()
+                .write(true)
+                .custom_flags(libc::O_NONBLOCK)
+                .open(UINPUT)
+                .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", UINPUT, e)))?;
+            ioctl(&file, UI_SET_EVBIT, EV_KEY)?;
+            for button in [BTN_LEFT, BTN_RIGHT, BTN_MIDDLE] {
+                ioctl(&file, UI_SET_KEYBIT, button)?;
+            }
+            // without relative axes the desktop does not take it for a mouse
+            ioctl(&file, UI_SET_EVBIT, EV_REL)?;
+            ioctl(&file, UI_SET_RELBIT, REL_X)?;
+            ioctl(&file, UI_SET_RELBIT, REL_Y)?;
+
+            // SAFETY: all zero is a valid uinput_user_dev
+            let mut setup: libc::uinput_user_dev = unsafe { mem::zeroed() };
+            for (c, b) in setup.name.iter_mut().zip(NAME.bytes()) {
+                *c = b as libc::c_char;
+            }
+            setup.id.bustype = BUS_VIRTUAL;
+            setup.id.vendor = 1;
+            setup.id.product = 1;
+            (&file).write_all(bytes(&setup))?;
+            ioctl(&file, UI_DEV_CREATE, 0)?;
+            Ok(Self(file))
+        }
+
+        fn emit(&self, kind: u16, code: u16, value: i32) -> io::Result<()> {
+            let event = libc::input_event {
+                time: libc::timeval {
+                    tv_sec: 0,
+                    tv_usec: 0,
+                },
+                type_: kind,
+                code,
+                value,
+            };
+            (&self.0).write_all(bytes(&event))
+        }
+
+        pub fn press(&self, button: MouseButton, down: bool) -> io::Result<()> {
+            let code = match button {
+                MouseButton::Left => BTN_LEFT,
+                MouseButton::Right => BTN_RIGHT,
+                MouseButton::Middle => BTN_MIDDLE,
+            };
+            self.emit(EV_KEY, code, i32::from(down))?;
+            self.emit(EV_SYN, SYN_REPORT, 0)
+        }
+    }
+
+    impl Drop for Device {
+        fn drop(&mut self) {
+            let _ = ioctl(&self.0, UI_DEV_DESTROY, 0);
+        }
+    }
+}
+
+/// median time from a synthetic press of a uinput device until the terminal reports it,
+/// the pointer has to rest over the terminal window since the clicks land where it is
+#[cfg(all(feature = "uinput", target_os = "linux"))]
+pub fn calibrate(clock: Clock, clicks: usize) -> error::Result<Duration> {
+    use std::thread;
+
+    use crossterm::event::{Event, MouseButton, MouseEventKind};
+
+    use crate::{input, selftest, terminal::CaptureGuard};
+
+    /// a press not reported by then went to another window
+    const TIMEOUT: Duration = Duration::from_millis(500);
+    const SPACING: Duration = Duration::from_millis(100);
+
+    let device = Device::new().map_err(Error::Loopback)?;
+    let _guard = CaptureGuard::new()?;
+    thread::sleep(SETTLE);
+    let drain = || -> io::Result<()> {
+        while input::poll(Duration::ZERO)? {
+            input::read()?;
+        }
+        Ok(())
+    };
+    drain()?;
+
+    let mut trips = Vec::with_capacity(clicks);
+    for _ in 0..clicks {
+        let start = clock.now();
+        device
+            .press(MouseButton::Left, true)
+            .map_err(Error::Loopback)?;
+        while let Some(left) = TIMEOUT.checked_sub(clock.now().saturating_sub(start)) {
+            if !input::poll(left)? {
+                break;
+            }
+            if let Event::Mouse(event) = input::read()? {
+                if event.kind == MouseEventKind::Down(MouseButton::Left) {
+                    trips.push(clock.now().saturating_sub(start));
+                    break;
+                }
+            }
+        }
+        device
+            .press(MouseButton::Left, false)
+            .map_err(Error::Loopback)?;
+        thread::sleep(SPACING);
+        drain()?;
+    }
+    let (median, ..) = selftest::spread(&trips).ok_or_else(|| {
+        Error::Loopback(io::Error::new(
+            io::ErrorKind::TimedOut,
+            "no synthetic click reached the terminal, keep the pointer over its window",
+        ))
+    })?;
+    Ok(median)
+}
+
+#[cfg(not(all(feature = "uinput", target_os = "linux")))]
+pub fn calibrate(_: Clock, _: usize) -> error::Result<Duration> {
+    Err(unsupported())
+}
+
+/// the error of everything needing a uinput device in a build without one
+#[cfg(not(all(feature = "uinput", target_os = "linux")))]
+pub fn unsupported() -> Error {
+    Error::Loopback(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "needs a linux build with the uinput feature",
+    ))
+}
@@ -1,157 +1,1354 @@
+mod accel;
+mod acceptance;
+mod analyzer;
+mod app;
+mod battery;
+#[cfg(feature = "cast")]
+mod cast;
+mod checksum;
+mod clock;
+mod config;
+mod console;
+mod control;
+mod corpus;
+mod dashboard;
+mod diagnose;
+mod dialog;
+mod diff;
+mod doctor;
+mod drag;
+mod dump;
+mod environment;
+mod error;
+mod explain;
+mod focus;
+mod gesture;
+mod handedness;
+#[cfg(feature = "test-util")]
+mod harness;
+mod health;
+mod history;
+mod hook;
+mod html;
+mod i18n;
+mod import;
+mod input;
+mod inventory;
+mod json;
+mod kohi;
+mod label;
+mod loopback;
+mod paced;
+mod pane;
+mod pipeline;
+mod plain;
+mod query;
+mod quirks;
+mod records;
+mod redact;
+mod region;
+mod remote;
+mod report;
+mod retention;
+mod rma;
+mod selftest;
+mod session;
+mod setup;
+mod snapping;
+mod snapshot;
+mod stats;
+mod stream;
+mod stress;
+mod summary;
+mod svg;
+mod tally;
+mod template;
+mod terminal;
+mod theme;
+mod timeline;
+mod update;
+mod upload;
+mod usb;
+mod version;
+mod wizard;
+mod zip;
+
 use std::{
-    io,
-    time::{Duration, Instant},
+    fs,
+    io::{self, IsTerminal, Write},
+    path::{Path, PathBuf},
+    process,
+    time::Duration,
 };
 
-use clap::{command, Parser};
-use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, MouseEventKind},
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-};
-use ratatui::{
-    backend::{Backend, CrosstermBackend},
-    layout::{Alignment, Constraint, Direction, Layout},
-    text::Line,
-    widgets::{Block, Borders, Padding, Paragraph, Wrap},
-    Frame, Terminal,
-};
+use app::{run_app, AppState};
+use clap::{Parser, Subcommand};
+use clock::Clock;
+use config::Config;
+use control::Listener;
+use diagnose::Plan;
+use i18n::{Lang, Msg, Numbers};
+use label::LabelFormat;
+use records::Records;
+use session::Session;
+use stats::Estimate;
+use summary::{Format, Summary};
+use template::Template;
+use terminal::TerminalGuard;
+use theme::Theme;
 
 #[derive(Parser, Debug, Clone)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// detect sec time for mouse click
-    #[arg(short, long, default_value_t = 1.0)]
-    sec: f32,
+    /// detect sec time for mouse click, defaults to the setup; a unit like `250ms` or
+    /// `1/4s` may be given
+    #[arg(short, long, value_parser = session::parse_secs)]
+    sec: Option<Duration>,
+
+    /// detect window in milliseconds, wins over `--sec`
+    #[arg(short, long, value_parser = session::parse_ms)]
+    millisecond: Option<Duration>,
+
+    /// save the session into the history when quitting
+    #[arg(long)]
+    save: bool,
+
+    /// with the saved session also write its interval chart and histogram as svg
+    #[arg(long)]
+    svg: bool,
+
+    /// confidence level in percent of the chatter estimate, defaults to the setup
+    #[arg(long)]
+    confidence: Option<f64>,
+
+    /// chatter rate in percent the mouse should stay below, defaults to the setup
+    #[arg(long)]
+    target_rate: Option<f64>,
+
+    /// source of the click timestamps
+    #[arg(long, value_enum, default_value_t = clock::Source::Instant)]
+    clock: clock::Source,
+
+    /// fastest double click in milliseconds a hand manages, faster chatter is
+    /// blamed on the switch, defaults to the setup
+    #[arg(long, value_parser = session::parse_ms)]
+    human_floor: Option<Duration>,
+
+    /// milliseconds a button may stay down before it is flagged as stuck
+    #[arg(long, value_name = "MS", value_parser = session::parse_ms)]
+    stuck_after: Option<Duration>,
+
+    /// a saved session the click test is held against, its gaps drawn behind the live ones
+    /// and its chatter rate next to the live one
+    #[arg(long, value_name = "FILE")]
+    baseline_session: Option<PathBuf>,
+
+    /// leave the click test straight from q instead of showing the results screen first
+    #[arg(long)]
+    no_results: bool,
+
+    /// before the click test, time synthetic clicks of a uinput device through the input
+    /// stack and take that baseline off the actuation latencies; the pointer has to rest
+    /// over this window as the clicks land where it is
+    #[arg(long)]
+    calibrate: bool,
+
+    /// milliseconds after an `actuate` command of a rig its clicks may arrive
+    #[arg(long, value_name = "MS", value_parser = session::parse_ms)]
+    actuation_window: Option<Duration>,
+
+    /// firmware debounce in milliseconds the d key simulates during the click test
+    #[arg(long, default_value = "10", value_parser = session::parse_ms)]
+    simulate_debounce: Duration,
+
+    /// the os swaps the buttons for a left hand, record the button pressed instead of the
+    /// one reported; detected from the os settings where they can be read
+    #[arg(long)]
+    swap_buttons: bool,
+
+    /// collect this many clicks with only the progress shown, the numbers would change how
+    /// you click; all of them show once the last click is in
+    #[arg(long, value_name = "CLICKS")]
+    blind: Option<usize>,
+
+    /// pause the click test for a rest every this many minutes, the session is split into
+    /// segments at the breaks
+    #[arg(long, value_name = "MINUTES")]
+    break_every: Option<u64>,
+
+    /// write every terminal event as it arrived into this file, a json object per line,
+    /// before the analysis or any quirk touches it
+    #[arg(long, value_name = "FILE")]
+    raw_dump: Option<PathBuf>,
+
+    /// write the `--raw-dump` in a compact binary form, a tenth of the size of the json;
+    /// `convert-dump` turns it back into json lines
+    #[arg(long, requires = "raw_dump")]
+    compact_dump: bool,
+
+    /// draw in the screen as it is instead of the alternate screen, the last frame stays
+    /// in the scrollback after the exit
+    #[arg(long)]
+    no_altscreen: bool,
+
+    /// a three line widget with the last interval, the counts and the verdict drawn inline
+    /// instead of the full screen, for a corner pane
+    #[arg(long)]
+    mini: bool,
+
+    /// record the click test as an asciinema cast file
+    #[cfg(feature = "cast")]
+    #[arg(long)]
+    cast: Option<PathBuf>,
+
+    /// drive the mode with the steps of this file instead of the terminal, drawing it on a
+    /// test backend and printing the last screen
+    #[cfg(feature = "test-util")]
+    #[arg(long)]
+    script: Option<PathBuf>,
+
+    /// text the last screen of the script has to show, may be given several times
+    #[cfg(feature = "test-util")]
+    #[arg(long, requires = "script")]
+    expect: Vec<String>,
+
+    /// how the summary at the end of the click test is printed
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    /// lay the summary out with a jinja style template instead, it sees the fields of
+    /// the json summary and the sentences of the text one as `text`
+    #[arg(long)]
+    template: Option<PathBuf>,
+
+    /// shell command run with each finished session, `{json}` and `{path}`
+    /// are replaced with the summary and the saved session file
+    #[arg(long)]
+    on_finish: Option<String>,
+
+    /// post the json summary of each finished session to the paste endpoint of the config
+    /// and print the link, the saved file path and the operator are left out
+    #[arg(long)]
+    upload: bool,
+
+    /// device name recorded with the saved session, defaults to the setup
+    #[arg(long)]
+    device: Option<String>,
+
+    /// who runs the test, recorded with the saved session, defaults to the os user
+    #[arg(long)]
+    operator_name: Option<String>,
+
+    /// language of the ui, detected from the locale by default
+    #[arg(long, value_enum)]
+    lang: Option<Lang>,
+
+    /// separators of numbers, detected from the locale by default
+    #[arg(long, value_enum)]
+    numbers: Option<Numbers>,
+
+    /// show dates and times in utc instead of the local time zone
+    #[arg(long)]
+    utc: bool,
+
+    /// how the first click of a detect window shows in the list and the interval stats,
+    /// without an interval or 0 ms into the window like before
+    #[arg(long, value_enum, default_value_t = session::Baseline::FirstClick)]
+    baseline: session::Baseline,
+
+    /// leave the user, the host, serial numbers and the dirs of paths out of the summary,
+    /// the reports and the exports so they can be shared
+    #[arg(long)]
+    redact: bool,
+
+    /// work around a terminal quirk that is not detected, on top of the detected ones
+    #[arg(long, value_enum)]
+    quirk: Vec<quirks::Quirk>,
+
+    /// pass the mouse events on as the terminal reports them, without working around its
+    /// detected quirks
+    #[arg(long)]
+    no_quirks: bool,
+
+    /// sign saved sessions and exported reports with this minisign secret key, made with
+    /// `minisign -G -W` as there is no terminal to ask for a password on
+    #[arg(long, value_name = "KEY")]
+    sign: Option<PathBuf>,
+
+    /// decimals of the intervals shown, the config ones by default
+    #[arg(long, value_name = "DIGITS", value_parser = clap::value_parser!(u8).range(0..=i18n::MAX_PRECISION as i64))]
+    precision: Option<u8>,
+
+    /// colors of the ui, high-contrast and mono mark flagged rows with symbols, the
+    /// config one by default
+    #[arg(long, value_enum)]
+    theme: Option<Theme>,
+
+    /// write a label for the diagnosed unit to this file or printer device
+    #[arg(long)]
+    label_out: Option<PathBuf>,
+
+    /// what the label is written as
+    #[arg(long, value_enum, default_value_t = LabelFormat::Text)]
+    label_format: LabelFormat,
+
+    /// print one line per click of the click test instead of the tui, for screen readers. with
+    /// stdin piped it also reads `pause`, `resume`, `mark <note>`, `actuate [button]` and
+    /// `stop` from it
+    #[arg(long)]
+    plain: bool,
+
+    /// keep adding to a saved session, `last` for the newest of the device, saved when quitting
+    #[arg(long, value_name = "last|FILE")]
+    resume: Option<String>,
+
+    /// take the commands of `--plain` and a `stats` query over a unix socket at this path,
+    /// during the click test
+    #[arg(long, value_name = "PATH")]
+    control_socket: Option<PathBuf>,
+
+    /// forward the clicks of the click test to the bench console at this address, which may
+    /// also pause, resume and stop it
+    #[arg(long, value_name = "HOST[:PORT]")]
+    agent: Option<String>,
+
+    /// name the bench console shows the station under, the host name by default
+    #[arg(long)]
+    station: Option<String>,
 
-    #[arg(short, long, default_value_t = 0)]
-    millisecond: u64,
+    /// refuse to run where the terminal or a multiplexer is known to distort the timing
+    #[arg(long)]
+    strict: bool,
+
+    /// for shared benches: the settings and test plan of the config cannot be changed,
+    /// only the click test, diagnose and inventory run and sessions are always saved
+    #[arg(long)]
+    operator: bool,
+
+    /// no flashing cues and charts updating at most once per second, `m` toggles it
+    #[arg(long)]
+    reduced_motion: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
 }
 
-struct AppState {
-    event: Vec<Duration>,
-    duration: Duration,
-    instant: Instant,
+impl Args {
+    /// whether the session ends up in the data dir
+    fn keeps_session(&self) -> bool {
+        self.save || self.operator || self.resume.is_some()
+    }
 }
 
-impl AppState {
-    fn new(duration: Duration) -> Self {
-        Self {
-            event: Vec::new(),
-            duration,
-            instant: Instant::now(),
+#[derive(Subcommand, Debug, Clone)]
+enum ReportCommand {
+    /// pool sessions of the same device into one estimate
+    Merge {
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+    },
+    /// render the interval chart and histogram of saved sessions as svg files next to them
+    Svg {
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+    },
+    /// write a single html file next to each saved session, the summary and a chart to zoom into
+    Html {
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+    },
+    /// summarize every session saved on one day
+    Daily {
+        /// yyyy-mm-dd in the local time zone or utc with `--utc`, today or yesterday
+        #[arg(long, default_value = "today", value_parser = session::parse_day)]
+        date: session::Day,
+        #[arg(long, value_enum, default_value_t = report::Output::Text)]
+        output: report::Output,
+    },
+    /// check saved sessions and exported reports were not changed since they were written
+    Verify {
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+        /// minisign public key the `.minisig` next to each file has to match
+        #[arg(long)]
+        pubkey: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+enum InventoryCommand {
+    /// run the diagnostic suite on one unit and append its verdict to the ledger
+    Test {
+        /// id or label of the unit, e.g. its serial number
+        label: String,
+        /// toml file with the ordered `[[step]]` list to run instead of the default
+        #[arg(long)]
+        plan: Option<PathBuf>,
+        /// leave inertial scroll events trailing a flick out of the wheel steps
+        #[arg(long)]
+        drop_momentum: bool,
+    },
+    /// print every unit of the ledger
+    List,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+enum GestureCommand {
+    /// record a sequence of clicks, releases and scrolls into a file
+    Record { file: PathBuf },
+    /// check the device reproduces a recorded sequence
+    Verify {
+        file: PathBuf,
+        /// allowed timing difference per event in milliseconds
+        #[arg(long, default_value_t = 10)]
+        tolerance: u64,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+enum Command {
+    /// choose the device and thresholds, runs on the first launch
+    Setup,
+    /// browse saved sessions
+    History,
+    /// test every switch, the wheel, drag and hold and report their health
+    Diagnose {
+        /// toml file with the ordered `[[step]]` list to run instead of the default
+        #[arg(long)]
+        plan: Option<PathBuf>,
+        /// leave inertial scroll events trailing a flick out of the wheel steps
+        #[arg(long)]
+        drop_momentum: bool,
+    },
+    /// run the diagnosis and bundle its sessions, html reports, raw events, device and
+    /// environment into one zip with a cover summary for a warranty claim
+    Rma {
+        /// toml file with the ordered `[[step]]` list to run instead of the default
+        #[arg(long)]
+        plan: Option<PathBuf>,
+        /// leave inertial scroll events trailing a flick out of the wheel steps
+        #[arg(long)]
+        drop_momentum: bool,
+        /// the zip to write, `rma-<device>-<date>.zip` by default
+        #[arg(long, short, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+    /// test units one after another and keep a ledger of their verdicts
+    Inventory {
+        #[command(subcommand)]
+        action: InventoryCommand,
+    },
+    /// run the same click test over the usb cable and the wireless dongle
+    WiredVsWireless {
+        /// clicks per phase
+        #[arg(long, default_value_t = 30)]
+        clicks: usize,
+    },
+    /// kohi style click speed challenge
+    Kohi {
+        /// length of a round in seconds unless a unit is given
+        #[arg(long, default_value = "10", value_parser = session::parse_positive_secs)]
+        time: Duration,
+    },
+    /// count the clicks of each drag click swipe
+    Drag {
+        /// milliseconds without clicks that end a burst
+        #[arg(long, default_value_t = 150)]
+        gap: u64,
+    },
+    /// compare slow and fast swipes over one distance to find pointer acceleration
+    Accel {
+        /// swipes at each pace
+        #[arg(long, default_value_t = 3)]
+        swipes: usize,
+    },
+    /// draw freehand diagonals to find out whether the firmware straightens them
+    Snapping,
+    /// click on every cue to find missed and doubled registrations
+    Paced {
+        /// milliseconds between cues
+        #[arg(long, default_value_t = 1000, value_parser = clap::value_parser!(u64).range(1..))]
+        cadence: u64,
+
+        /// number of cues
+        #[arg(long, default_value_t = 30)]
+        cues: usize,
+
+        /// do not ring the terminal bell on cues
+        #[arg(long)]
+        no_bell: bool,
+    },
+    /// record input sequences and verify a device fires them the same way
+    Gesture {
+        #[command(subcommand)]
+        action: GestureCommand,
+    },
+    /// work with saved session files
+    Report {
+        #[command(subcommand)]
+        action: ReportCommand,
+    },
+    /// convert a click logger csv with timestamp and button columns into a saved session
+    Import {
+        file: PathBuf,
+        /// unit of the timestamps, guessed from the header and milliseconds otherwise
+        #[arg(long, value_enum)]
+        unit: Option<import::Unit>,
+    },
+    /// run the analyzers over a saved session again with other settings
+    Reanalyze {
+        file: PathBuf,
+        /// detect window in milliseconds, defaults to the one the session was recorded with
+        #[arg(long, value_parser = session::parse_ms)]
+        chatter_threshold: Option<Duration>,
+    },
+    /// open a saved session in the tui without capturing any input
+    View { file: PathBuf },
+    /// two saved sessions side by side with the changes of the second highlighted, `-` reads
+    /// one piped to stdin
+    #[command(alias = "compare")]
+    Diff { baseline: PathBuf, other: PathBuf },
+    /// check saved session files against the current schema
+    Validate {
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+    },
+    /// check the terminal, the mouse, the clocks, the config and the storage, printing a checklist
+    Doctor {
+        /// leave out the check asking to move the mouse
+        #[arg(long)]
+        no_mouse: bool,
+    },
+    /// measure the latency the terminal and event pipeline add on their own
+    SelfTest {
+        /// number of round trips and idle polls
+        #[arg(long, default_value_t = 200)]
+        samples: usize,
+        /// instead send known click patterns through a uinput device and check the click
+        /// test records them as sent; the pointer has to rest over this window
+        #[arg(long)]
+        inject: bool,
+    },
+    /// flood the click test with simulated events and report how it keeps up
+    Stress {
+        /// presses and releases sent
+        #[arg(long, default_value_t = 5000)]
+        events: usize,
+        /// events a second
+        #[arg(long, default_value_t = 1000)]
+        rate: u32,
+        /// every nth press arrives twice, 0 sends no duplicates
+        #[arg(long, default_value_t = 10)]
+        duplicate_every: usize,
+    },
+    /// watch the click tests of the stations started with `--agent`, printing their log
+    /// with `--plain`
+    Console {
+        /// address to listen on, 0.0.0.0 for the stations on other machines
+        #[arg(long, default_value_t = format!("127.0.0.1:{}", remote::PORT))]
+        listen: String,
+    },
+    /// a card for each station started with `--agent`, enter opens one
+    Dashboard {
+        /// addresses to listen on, repeated for several networks
+        #[arg(long, default_values_t = [format!("127.0.0.1:{}", remote::PORT)])]
+        listen: Vec<String>,
+    },
+    /// run the analyzers over a directory of saved sessions and compare each summary with
+    /// the golden `.golden.json` stored next to it
+    Corpus {
+        dir: PathBuf,
+        /// store the summaries of today as the golden ones
+        #[arg(long)]
+        bless: bool,
+    },
+    /// feed the analyzers event streams in orders no mouse sends and report any that panic
+    Fuzz {
+        /// streams sent
+        #[arg(long, default_value_t = 10000)]
+        runs: u64,
+        /// seed of the first stream, the next ones count up from it
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+    },
+    /// turn a compact `--raw-dump` into json lines, or json lines into a compact dump
+    ConvertDump { input: PathBuf, output: PathBuf },
+}
+
+/// options and commands given that operator mode does not allow
+fn locked_options(args: &Args) -> Vec<String> {
+    let mut locked = Vec::new();
+    let mut lock = |set: bool, option: &str| {
+        if set {
+            locked.push(option.to_string());
         }
+    };
+    lock(args.sec.is_some(), "--sec");
+    lock(args.millisecond.is_some(), "--millisecond");
+    lock(args.confidence.is_some(), "--confidence");
+    lock(args.target_rate.is_some(), "--target-rate");
+    lock(args.human_floor.is_some(), "--human-floor");
+    lock(args.clock != clock::Source::Instant, "--clock");
+    lock(args.device.is_some(), "--device");
+    match &args.command {
+        None
+        | Some(Command::Inventory {
+            action: InventoryCommand::List,
+        }) => {}
+        Some(
+            Command::Diagnose { plan, .. }
+            | Command::Rma { plan, .. }
+            | Command::Inventory {
+                action: InventoryCommand::Test { plan, .. },
+            },
+        ) => lock(plan.is_some(), "--plan"),
+        Some(_) => lock(true, "commands other than diagnose, rma and inventory"),
     }
+    locked
+}
 
-    fn reset(&mut self) {
-        self.instant = Instant::now();
-        self.event.clear();
+/// the plan given on the command line, else the one in the config, else the default
+fn load_plan(plan: Option<&Path>, config: &Config) -> error::Result<Plan> {
+    match plan.or(config.plan.as_deref()) {
+        Some(path) => Plan::load(path),
+        None => Ok(Plan::default()),
     }
+}
 
-    fn on_click(&mut self) {
-        if self.event.is_empty() {
-            self.instant = Instant::now();
-        }
+/// a line for the user, on stderr when stdout belongs to a machine format
+fn say(plain_text: bool, line: &str) {
+    if plain_text {
+        println!("{}", line);
+    } else {
+        eprintln!("{}", line);
+    }
+}
 
-        let mut dur = if self.event.is_empty() {
-            Duration::from_secs(0)
-        } else {
-            self.instant.elapsed()
-        };
+/// ask whether the suggested detect window goes into the config
+fn offer_window(mut config: Config, window: Duration) -> error::Result<()> {
+    print!(
+        "{}",
+        i18n::fmt(
+            Msg::SuggestWrite,
+            &[&i18n::number(window.as_millis()), &config::path().display()]
+        )
+    );
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    if matches!(answer.trim(), "y" | "Y" | "yes") {
+        config.detect_window = window;
+        let path = config.save()?;
+        println!("config saved to {}", path.display());
+    }
+    Ok(())
+}
 
-        if dur > self.duration {
-            self.reset();
-            dur = Duration::from_secs(0);
-        }
+/// the session `--resume` names and its file, `last` is the newest one of the device or
+/// of any; of the sessions started in the same second the one written last
+fn resumed(which: &str, device: Option<&str>) -> error::Result<(PathBuf, Session)> {
+    if which != "last" {
+        let path = PathBuf::from(which);
+        return Session::load(&path).map(|session| (path, session));
+    }
+    let mut saved = session::list();
+    if device.is_some() {
+        saved.retain(|(_, s)| s.device.as_deref() == device);
+    }
+    saved
+        .into_iter()
+        .max_by_key(|(path, s)| {
+            let written = fs::metadata(path).and_then(|m| m.modified()).ok();
+            // `<started_at>_<n>.json` came after `<started_at>.json`
+            let n = path
+                .file_stem()
+                .and_then(|stem| stem.to_str()?.split_once('_')?.1.parse::<u32>().ok())
+                .unwrap_or(1);
+            (s.started_at, written, n)
+        })
+        .ok_or_else(|| error::Error::Config {
+            path: session::sessions_dir(),
+            message: match device {
+                Some(device) => format!("no saved session of {} to resume", device),
+                None => "no saved session to resume".to_string(),
+            },
+        })
+}
 
-        self.event.push(dur);
+/// save or drop the click tests a crash left checkpointed
+fn offer_recovery() -> error::Result<()> {
+    for left in session::left_over() {
+        print!(
+            "{}",
+            i18n::fmt(
+                Msg::Recover,
+                &[
+                    &session::format_timestamp(left.session.started_at),
+                    &i18n::number(left.session.click_count())
+                ]
+            )
+        );
+        io::stdout().flush()?;
+        let mut answer = String::new();
+        io::stdin().read_line(&mut answer)?;
+        if matches!(answer.trim(), "y" | "Y" | "yes") {
+            let path = left.session.save()?;
+            println!("{}", i18n::fmt(Msg::Saved, &[&path.display()]));
+        }
+        left.discard();
     }
+    Ok(())
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
+fn main() {
     let args = Args::parse();
-    let detect_duration = if args.millisecond == 0 {
-        Duration::from_secs_f32(args.sec)
+    #[cfg(feature = "test-util")]
+    let result = match &args.script {
+        Some(path) => {
+            harness::load(path, args.expect.clone()).and_then(|_| harness::settle(run(args)))
+        }
+        None => run(args),
+    };
+    #[cfg(not(feature = "test-util"))]
+    let result = run(args);
+    update::finish();
+    if let Err(e) = result {
+        eprintln!("error: {}", e);
+        process::exit(e.exit_code());
+    }
+}
+
+fn run(args: Args) -> error::Result<()> {
+    i18n::init(args.lang, args.numbers);
+    session::set_utc(args.utc);
+    session::set_baseline(args.baseline);
+    redact::set(args.redact);
+    checksum::set_key(args.sign.clone());
+    quirks::init(&args.quirk, !args.no_quirks);
+    theme::init(args.theme.unwrap_or_default(), args.reduced_motion);
+    if let Some(precision) = args.precision {
+        i18n::set_precision(usize::from(precision));
+    }
+    if let Some(path) = &args.raw_dump {
+        dump::open(path, args.compact_dump)?;
+    }
+    // the package of a claim holds the raw events whether asked for or not
+    let rma_dump = match &args.command {
+        Some(Command::Rma { .. }) if args.raw_dump.is_none() => Some(rma::open_dump()?),
+        _ => None,
+    };
+
+    if args.operator {
+        let locked = locked_options(&args);
+        if !locked.is_empty() {
+            return Err(error::Error::Locked(locked));
+        }
+    }
+
+    // a broken config is one of the findings
+    if let Some(Command::Doctor { no_mouse }) = args.command {
+        return doctor::run(!no_mouse);
+    }
+
+    let inline = if args.mini && args.command.is_none() {
+        Some(app::MINI_ROWS)
     } else {
-        Duration::from_millis(args.millisecond)
+        args.no_altscreen.then_some(u16::MAX)
     };
+    let mut config = Config::load()?;
+    let first_run = config.is_none() && args.command.is_none() && !args.plain && !args.operator;
+    if first_run || matches!(args.command, Some(Command::Setup)) {
+        let mut guard = TerminalGuard::new(inline)?;
+        // a skipped first setup keeps the defaults instead of asking again
+        let result = setup::run(&mut *guard)?.or_else(|| first_run.then(Config::default));
+        drop(guard);
+        if let Some(result) = result {
+            let path = result.save()?;
+            println!("config saved to {}", path.display());
+            config = Some(result);
+        }
+        if !first_run {
+            return Ok(());
+        }
+    }
 
-    // setup terminal
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+    let config = config.unwrap_or_default();
+    health::set_weights(config.health);
+    if config.update_check {
+        if let Some(note) = update::check() {
+            eprintln!("{}", note);
+        }
+    }
+    // found out before the test instead of after it
+    if args.upload && config.upload_url.is_none() {
+        return Err(error::Error::Upload {
+            endpoint: None,
+            message: "set `upload_url` in the config to the paste endpoint to post to".to_string(),
+        });
+    }
+    if args.theme.is_none() {
+        theme::set(config.theme);
+    }
+    if args.precision.is_none() {
+        i18n::set_precision(config.precision);
+    }
+    let detect_duration = args
+        .millisecond
+        .or(args.sec)
+        .unwrap_or(config.detect_window);
+    let confidence = args.confidence.unwrap_or(config.confidence) / 100.0;
+    let target_rate = args.target_rate.unwrap_or(config.target_rate) / 100.0;
+    let device = args.device.clone().or(config.device.clone());
+    let human_floor = args.human_floor.unwrap_or(config.human_floor);
+
+    let clock = Clock::new(args.clock)?;
+    let resumed = match &args.resume {
+        Some(which) if args.command.is_none() => Some(resumed(which, device.as_deref())?),
+        _ => None,
+    };
+    let loopback = if args.calibrate && args.command.is_none() {
+        println!("timing synthetic clicks, keep the pointer over this window");
+        let baseline = loopback::calibrate(clock, loopback::CLICKS)?;
+        println!("{}", i18n::fmt(Msg::Loopback, &[&i18n::duration(baseline)]));
+        Some(baseline)
+    } else {
+        None
+    };
+    let baseline = match &args.baseline_session {
+        Some(path) if args.command.is_none() => Some(Session::load(path)?),
+        _ => None,
+    };
+    let new_app = || -> error::Result<AppState> {
+        let mut app = AppState::new(detect_duration, device.clone());
+        app.set_clock(clock);
+        app.set_human_floor(human_floor);
+        app.set_stuck_after(args.stuck_after.unwrap_or(session::STUCK_AFTER));
+        app.set_loopback(loopback);
+        app.set_baseline(baseline.clone());
+        app.set_actuation_window(args.actuation_window.unwrap_or(session::ACTUATION_WINDOW));
+        app.set_debounce(args.simulate_debounce);
+        app.set_blind(args.blind);
+        app.set_mini(args.mini);
+        app.set_break_every(args.break_every.map(|m| Duration::from_secs(m * 60)));
+        app.set_swapped(args.swap_buttons || handedness::detected().unwrap_or(false));
+        app.set_list_order(config.newest_first, config.follow);
+        app.set_chart(config.chart_smoothing, config.chart_downsample);
+        app.set_locked(args.operator);
+        app.set_saving(args.keeps_session());
+        app.set_results_screen(!args.no_results);
+        app.set_records(Records::load(device.as_deref()));
+        app.confidence = confidence;
+        app.target_rate = target_rate;
+        let listener = Listener::new(
+            args.control_socket.as_deref(),
+            args.plain,
+            args.agent.is_some(),
+        )?;
+        if let (Some(address), Some(listener)) = (&args.agent, &listener) {
+            let hello = remote::Message::Hello {
+                version: remote::VERSION,
+                station: args
+                    .station
+                    .clone()
+                    .or_else(redact::hostname)
+                    .unwrap_or_else(|| "station".to_string()),
+                device: device.clone(),
+                detect_window: detect_duration,
+            };
+            let agent = remote::Agent::connect(address, hello, listener.sender())?;
+            app.set_agent(Some(agent));
+        }
+        app.set_control(listener);
+        if let Some((path, session)) = &resumed {
+            app.resume(session.clone(), path);
+        }
+        app.watch_config(config::Watch::new(config::Pinned {
+            detect_window: args.millisecond.is_some() || args.sec.is_some(),
+            confidence: args.confidence.is_some(),
+            target_rate: args.target_rate.is_some(),
+            human_floor: args.human_floor.is_some(),
+            theme: args.theme.is_some(),
+        }));
+        Ok(app)
+    };
+
+    match &args.command {
+        Some(Command::Report { action }) => {
+            return match action {
+                ReportCommand::Merge { files } => report::merge(files, confidence, target_rate),
+                ReportCommand::Svg { files } => files.iter().try_for_each(|file| {
+                    for path in svg::write(&Session::load(file)?, file)? {
+                        println!("{}", path.display());
+                    }
+                    Ok(())
+                }),
+                ReportCommand::Html { files } => files.iter().try_for_each(|file| {
+                    let session = Session::load(file)?;
+                    let estimate = Estimate::new(
+                        session.chatter_count(),
+                        session.click_count(),
+                        confidence,
+                        target_rate,
+                    );
+                    let summary = Summary::new(&session, estimate)
+                        .with_path(Some(file))
+                        .with_acceptance(&config.acceptance, &session);
+                    println!("{}", html::write(&session, &summary, file)?.display());
+                    Ok(())
+                }),
+                ReportCommand::Daily { date, output } => report::daily(&report::Daily {
+                    day: *date,
+                    criteria: config.acceptance.clone(),
+                    confidence,
+                    target: target_rate,
+                    output: *output,
+                }),
+                ReportCommand::Verify { files, pubkey } => {
+                    checksum::verify(files, pubkey.as_deref())
+                }
+            }
+        }
+        Some(Command::Validate { files }) => return report::validate(files),
+        Some(Command::Fuzz { runs, seed }) => return stream::run(*runs, *seed),
+        Some(Command::ConvertDump { input, output }) => return dump::convert(input, output),
+        Some(Command::Console { listen }) if args.plain => return console::run_plain(listen),
+        Some(Command::Inventory {
+            action: InventoryCommand::List,
+        }) => {
+            inventory::print_list(&inventory::list()?);
+            return Ok(());
+        }
+        Some(Command::Corpus { dir, bless }) => {
+            return corpus::run(dir, *bless, confidence, target_rate)
+        }
+        Some(Command::Reanalyze {
+            file,
+            chatter_threshold,
+        }) => {
+            let settings = report::Reanalysis {
+                detect_window: *chatter_threshold,
+                human_floor: args.human_floor,
+                confidence,
+                target: target_rate,
+                criteria: config.acceptance.clone(),
+                format: args.format,
+                template: args.template.as_deref().map(Template::load).transpose()?,
+            };
+            return report::reanalyze(file, &settings);
+        }
+        Some(Command::Import { file, unit }) => {
+            let mut session = import::read(file, detect_duration, device.clone(), *unit)?;
+            session.human_floor = human_floor;
+            if let Some(name) = &args.operator_name {
+                session.operator = Some(name.clone());
+            }
+            let path = session.save()?;
+            println!(
+                "imported {} clicks to {}",
+                i18n::number(session.click_count()),
+                path.display()
+            );
+            let removed = config.retention.prune()?;
+            if removed > 0 {
+                println!("{}", i18n::fmt(Msg::Pruned, &[&i18n::number(removed)]));
+            }
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    if let Some(Command::SelfTest { samples, inject }) = args.command {
+        if inject {
+            println!("injecting click patterns, keep the pointer over this window");
+            let results = selftest::inject(clock)?;
+            for result in &results {
+                match result.problems.as_slice() {
+                    [] => println!("{}: ok", result.name),
+                    problems => {
+                        println!("{}: failed", result.name);
+                        for problem in problems {
+                            println!("  {}", problem);
+                        }
+                    }
+                }
+            }
+            let failed = results.iter().filter(|r| !r.problems.is_empty()).count();
+            if failed > 0 {
+                return Err(error::Error::Unhealthy(failed));
+            }
+            return Ok(());
+        }
+        println!("measuring the event pipeline, keep the mouse still");
+        selftest::print_report(&selftest::run(clock, samples)?);
+        return Ok(());
+    }
+
+    if args.strict {
+        let problems = environment::timing_problems();
+        if !problems.is_empty() {
+            return Err(error::Error::Environment(problems));
+        }
+    }
 
-    let app = AppState::new(detect_duration);
     // run application
-    run_app(&mut terminal, app)?;
+    let mut sessions = Vec::new();
+    let mut comparison = None;
+    let mut capture = None;
+    let mut paced_report = None;
+    let mut strokes = None;
+    let mut acceleration = None;
+    let mut diagnosis = None;
+    // the zip `rma` bundles the diagnosis into
+    let mut package = None;
+    // label of the unit the diagnosis goes into the inventory for
+    let mut unit = None;
+    let mut verification = None;
+    let mut stress_report = None;
 
-    // restore terminal
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
+    if args.command.is_none() && io::stdin().is_terminal() {
+        offer_recovery()?;
+    }
+    if args.plain && args.command.is_none() {
+        capture = Some(plain::run(new_app()?)?);
+    } else {
+        // the terminal is restored when the guard drops, also on errors
+        let mut guard = TerminalGuard::new(inline)?;
+        let terminal = &mut *guard;
 
-    Ok(())
-}
+        match &args.command {
+            Some(
+                Command::Setup
+                | Command::Report { .. }
+                | Command::Validate { .. }
+                | Command::Reanalyze { .. }
+                | Command::Import { .. }
+                | Command::Fuzz { .. }
+                | Command::ConvertDump { .. }
+                | Command::Corpus { .. }
+                | Command::Doctor { .. }
+                | Command::SelfTest { .. },
+            ) => {}
+            Some(Command::History) => history::run(terminal)?,
+            Some(Command::Console { listen }) => console::run(terminal, listen)?,
+            Some(Command::Dashboard { listen }) => {
+                dashboard::run(terminal, listen, confidence, target_rate)?
+            }
+            Some(Command::Diff { baseline, other }) => diff::run(terminal, baseline, other)?,
+            Some(Command::View { file }) => {
+                run_app(terminal, AppState::view(Session::load(file)?, file))?;
+            }
+            Some(Command::Diagnose {
+                plan,
+                drop_momentum,
+            }) => {
+                let plan = load_plan(plan.as_deref(), &config)?;
+                diagnosis = diagnose::run(terminal, detect_duration, &plan, *drop_momentum)?;
+            }
+            Some(Command::Rma {
+                plan,
+                drop_momentum,
+                output,
+            }) => {
+                let plan = load_plan(plan.as_deref(), &config)?;
+                diagnosis = diagnose::run(terminal, detect_duration, &plan, *drop_momentum)?;
+                dump::close();
+                package = Some(
+                    output
+                        .clone()
+                        .unwrap_or_else(|| rma::default_path(device.as_deref())),
+                );
+            }
+            Some(Command::Inventory {
+                action:
+                    InventoryCommand::Test {
+                        label,
+                        plan,
+                        drop_momentum,
+                    },
+            }) => {
+                let plan = load_plan(plan.as_deref(), &config)?;
+                diagnosis = diagnose::run(terminal, detect_duration, &plan, *drop_momentum)?;
+                unit = Some(label.clone());
+            }
+            Some(Command::Inventory { .. }) => {}
+            Some(Command::WiredVsWireless { clicks }) => {
+                comparison = wizard::run(terminal, detect_duration, device.as_deref(), *clicks)?;
+            }
+            Some(Command::Gesture { action }) => match action {
+                GestureCommand::Record { file } => {
+                    if let Some(sequence) = gesture::record(terminal, None)? {
+                        sequence.save(file)?;
+                    }
+                }
+                GestureCommand::Verify { file, tolerance } => {
+                    let expected = gesture::Sequence::load(file)?;
+                    let tolerance = Duration::from_millis(*tolerance);
+                    if let Some(actual) = gesture::record(terminal, Some(expected.len()))? {
+                        verification = Some((
+                            actual.differences(&expected, tolerance),
+                            actual.max_offset(&expected),
+                            tolerance,
+                        ));
+                    }
+                }
+            },
+            Some(Command::Kohi { time }) => {
+                kohi::run(terminal, *time)?;
+            }
+            Some(Command::Drag { gap }) => {
+                drag::run(terminal, Duration::from_millis(*gap))?;
+            }
+            Some(Command::Snapping) => strokes = snapping::run(terminal)?,
+            Some(Command::Stress {
+                events,
+                rate,
+                duplicate_every,
+            }) => {
+                let mut app = AppState::new(detect_duration, None);
+                app.set_clock(clock);
+                stress_report = Some(stress::run(
+                    terminal,
+                    app,
+                    clock,
+                    *events,
+                    *rate,
+                    *duplicate_every,
+                )?);
+            }
+            Some(Command::Accel { swipes }) => {
+                acceleration = accel::run(terminal, (*swipes).max(1))?;
+            }
+            Some(Command::Paced {
+                cadence,
+                cues,
+                no_bell,
+            }) => {
+                paced_report =
+                    paced::run(terminal, Duration::from_millis(*cadence), *cues, !no_bell)?;
+            }
+            None => {
+                #[allow(unused_mut)]
+                let mut app = new_app()?;
+                #[cfg(feature = "cast")]
+                if let Some(path) = &args.cast {
+                    app.cast = Some(cast::Recorder::create(path)?);
+                }
+                capture = Some(run_app(terminal, app)?);
+            }
+        }
+    }
+
+    // the click test is summarized once its session is saved
+    let summarized = capture.is_some();
+    let (capture, saved_to) = capture.unzip();
+    let saved_to = saved_to.flatten();
+    sessions.extend(capture);
+
+    if let Some(outcomes) = &diagnosis {
+        diagnose::print_report(outcomes);
+        if let Some(path) = &args.label_out {
+            let name = unit
+                .as_deref()
+                .or(device.as_deref())
+                .unwrap_or("unnamed unit");
+            label::write(path, &label::render(name, outcomes, args.label_format))?;
+            println!("label written to {}", path.display());
+        }
+        if let Some(label) = unit {
+            let entry = inventory::Entry::new(&label, device.clone(), outcomes);
+            let path = inventory::append(&entry)?;
+            println!(
+                "{} recorded as {} in {}",
+                label,
+                entry.verdict,
+                path.display()
+            );
+        }
+        if let Some(path) = &package {
+            let evidence = rma::Evidence {
+                device: device.as_deref(),
+                dump: args.raw_dump.as_deref().or(rma_dump.as_deref()),
+                confidence,
+                target: target_rate,
+            };
+            let path = rma::write(path, outcomes, &evidence)?;
+            println!("evidence for the claim written to {}", path.display());
+        }
+    }
+    if let Some(path) = &rma_dump {
+        let _ = std::fs::remove_file(path);
+    }
+
+    if let Some((differences, max_offset, tolerance)) = verification {
+        gesture::print_report(&differences, max_offset, tolerance);
+    }
+
+    if let Some(report) = paced_report {
+        paced::print_report(&report);
+    }
+
+    if let Some(strokes) = strokes {
+        snapping::print_report(&strokes);
+    }
+
+    if let Some(report) = stress_report {
+        stress::print_report(&report);
+    }
+
+    if let Some(report) = acceleration {
+        accel::print_report(&report);
+    }
 
-fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: AppState) -> io::Result<()> {
-    loop {
-        terminal.draw(|f| ui(f, &mut app))?;
+    if let Some(results) = comparison {
+        wizard::print_comparison(&results);
+        sessions.extend(results.into_iter().map(|r| r.session));
+    }
+
+    if let Some(name) = &args.operator_name {
+        sessions
+            .iter_mut()
+            .for_each(|s| s.operator = Some(name.clone()));
+    }
 
-        match event::read()? {
-            Event::Mouse(event) => {
-                if let MouseEventKind::Down(_) = event.kind {
-                    app.on_click();
+    let template = args.template.as_deref().map(Template::load).transpose()?;
+    // a template or machine format owns stdout
+    let plain_text = args.format == Format::Text && template.is_none();
+    let mut suggestion = None;
+    let mut rejected = None;
+    for (i, session) in sessions.iter().enumerate() {
+        let keep = args.keeps_session();
+        let path = if keep && session.click_count() > 0 {
+            // a session resumed or saved from the results goes back into its file
+            Some(match saved_to.as_deref().filter(|_| summarized && i == 0) {
+                Some(path) => {
+                    session.save_to(path)?;
+                    path.to_path_buf()
+                }
+                None => session.save()?,
+            })
+        } else {
+            None
+        };
+        let estimate = Estimate::new(
+            session.chatter_count(),
+            session.click_count(),
+            confidence,
+            target_rate,
+        );
+        let summary = Summary::new(session, estimate)
+            .with_path(path.as_deref())
+            .with_acceptance(&config.acceptance, session);
+        if summarized && i == 0 {
+            match &template {
+                Some(template) => println!("{}", template.render(&summary.template_data())),
+                None => println!("{}", summary.render(args.format)),
+            }
+            suggestion = summary.suggestion.map(|(window, _)| window);
+            rejected = Some(acceptance::verdict(&summary.acceptance));
+            let mut records = Records::load(session.device.as_deref());
+            let beaten = records.improve(&Records::of(session));
+            // like the kohi scores, records that cannot be written are not worth failing over
+            if !beaten.is_empty() && records.save(session.device.as_deref()).is_ok() {
+                for line in beaten {
+                    say(plain_text, &line);
                 }
             }
-            Event::Key(event) => {
-                if event.code == KeyCode::Char('q') {
-                    break;
+        }
+        if let Some(path) = &path {
+            let mut saved = vec![i18n::fmt(Msg::Saved, &[&path.display()])];
+            if args.svg {
+                // the saved file also holds the clicks spilled to disk
+                for chart in svg::write(&Session::load(path)?, path)? {
+                    saved.push(i18n::fmt(Msg::Saved, &[&chart.display()]));
                 }
             }
-            _ => {}
+            // keep stdout parseable for the machine formats
+            for line in saved {
+                say(plain_text, &line);
+            }
+        }
+        if let Some(command) = &args.on_finish {
+            hook::run(command, &summary.to_json())?;
+        }
+        if let Some(endpoint) = config.upload_url.as_deref().filter(|_| args.upload) {
+            let text = i18n::fmt(
+                Msg::Uploaded,
+                &[&upload::run(endpoint, &summary.to_json())?],
+            );
+            say(plain_text, &text);
+        }
+    }
+    sessions.iter().for_each(Session::discard_spill);
+    sessions.iter().for_each(Session::discard_checkpoint);
+    if args.keeps_session() {
+        let removed = config.retention.prune()?;
+        if removed > 0 {
+            let text = i18n::fmt(Msg::Pruned, &[&i18n::number(removed)]);
+            say(plain_text, &text);
+        }
+    }
+
+    if let Some(window) = suggestion.filter(|w| *w != config.detect_window) {
+        if plain_text && io::stdin().is_terminal() && !args.operator {
+            offer_window(config, window)?;
         }
     }
 
+    if let Some(rejected) = rejected {
+        rejected?;
+    }
+
     Ok(())
 }
 
-fn ui<B: Backend>(f: &mut Frame<B>, app: &mut AppState) {
-    let size = f.size();
+#[cfg(test)]
+mod tests {
+    use std::{env, thread};
 
-    let layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([Constraint::Percentage(100)].as_ref())
-        .split(size);
+    use crossterm::event::{KeyModifiers, MouseButton};
 
-    let text = if app.event.is_empty() {
-        vec![Line::from("please click the mouse!")]
-    } else {
-        app.event
-            .iter()
-            .map(|dur| Line::from(format!("{} ms", dur.as_millis())))
-            .collect::<Vec<_>>()
-    };
+    use super::*;
+    use crate::session::Click;
 
-    let padding_top = (size.height - (2 + app.event.len() as u16)) / 2;
-    let block = Block::default()
-        .padding(Padding {
-            top: padding_top,
-            bottom: 0,
-            left: 0,
-            right: 0,
-        })
-        .borders(Borders::ALL);
+    fn click(session: &mut Session) {
+        let at = session.clicks.last().map_or(Duration::ZERO, |c| c.at) + Duration::from_secs(1);
+        session.clicks.push(Click {
+            at,
+            interval: None,
+            button: MouseButton::Left,
+            hold: None,
+            bookmarked: false,
+            keyboard: false,
+            position: None,
+            modifiers: KeyModifiers::NONE,
+        });
+    }
+
+    #[test]
+    fn a_resumed_session_is_saved_back_and_resumed_again() {
+        let dir = env::temp_dir().join(format!("mouse-checker-resume-{}", process::id()));
+        env::set_var("XDG_DATA_HOME", &dir);
+        let mut session = Session::new(None, Duration::from_millis(100));
+        click(&mut session);
+        let first = session.save().unwrap();
+        // another session started in the same second
+        let second = session.save().unwrap();
+        assert_ne!(first, second);
 
-    let p = Paragraph::new(text)
-        .block(block)
-        .alignment(Alignment::Center)
-        .wrap(Wrap { trim: true });
+        let (path, mut again) = resumed("last", None).unwrap();
+        assert_eq!(path, second);
+        click(&mut again);
+        again.save_to(&path).unwrap();
+        let (path, again) = resumed("last", None).unwrap();
+        assert_eq!((path, again.click_count()), (second, 2));
 
-    f.render_widget(p, layout[0]);
+        let (path, mut again) = resumed(first.to_str().unwrap(), None).unwrap();
+        click(&mut again);
+        thread::sleep(Duration::from_millis(20));
+        again.save_to(&path).unwrap();
+        let (path, again) = resumed("last", None).unwrap();
+        assert_eq!((path, again.click_count()), (first, 2));
+        assert_eq!(session::list().len(), 2);
+        let _ = fs::remove_dir_all(dir);
+    }
 }
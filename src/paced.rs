@@ -0,0 +1,214 @@
+use std::{
+    io::{self, Write},
+    time::{Duration, Instant},
+};
+
+use crossterm::event::{Event, KeyCode, MouseEventKind};
+use ratatui::{
+    backend::Backend,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::Style,
+    text::Line,
+    widgets::{Block, Borders, Gauge, Paragraph},
+    Frame, Terminal,
+};
+
+use crate::{error, i18n, input, theme};
+
+/// how long a cue stays highlighted
+const FLASH: Duration = Duration::from_millis(150);
+
+struct PacedState {
+    cadence: Duration,
+    cues: usize,
+    started: Option<Instant>,
+    /// click times since the first cue
+    clicks: Vec<Duration>,
+    bell: bool,
+}
+
+pub struct PacedReport {
+    pub missed: Vec<usize>,
+    pub doubled: Vec<usize>,
+    /// mean offset of the matched clicks to their cue
+    pub mean_offset: Option<Duration>,
+}
+
+impl PacedState {
+    fn elapsed(&self) -> Option<Duration> {
+        self.started.map(|s| s.elapsed())
+    }
+
+    /// index of the latest cue so far
+    fn cue(&self) -> Option<usize> {
+        self.elapsed()
+            .map(|e| (e.as_micros() / self.cadence.as_micros()) as usize)
+            .filter(|c| *c < self.cues)
+    }
+
+    fn finished(&self) -> bool {
+        // wait half a cadence after the last cue for late clicks
+        self.elapsed()
+            .is_some_and(|e| e > (self.cadence * self.cues as u32).saturating_sub(self.cadence / 2))
+    }
+
+    fn report(&self) -> PacedReport {
+        let mut hits = vec![Vec::new(); self.cues];
+        for click in &self.clicks {
+            let cadence = self.cadence.as_secs_f64();
+            let cue = (click.as_secs_f64() / cadence).round() as usize;
+            if let Some(hit) = hits.get_mut(cue) {
+                hit.push(click.as_secs_f64() - cue as f64 * cadence);
+            }
+        }
+
+        let offsets = hits
+            .iter()
+            .filter_map(|h| h.first())
+            .map(|o| o.abs())
+            .collect::<Vec<_>>();
+
+        PacedReport {
+            missed: (0..self.cues).filter(|c| hits[*c].is_empty()).collect(),
+            doubled: (0..self.cues).filter(|c| hits[*c].len() > 1).collect(),
+            mean_offset: (!offsets.is_empty()).then(|| {
+                Duration::from_secs_f64(offsets.iter().sum::<f64>() / offsets.len() as f64)
+            }),
+        }
+    }
+}
+
+pub fn run<B: Backend>(
+    terminal: &mut Terminal<B>,
+    cadence: Duration,
+    cues: usize,
+    bell: bool,
+) -> error::Result<Option<PacedReport>> {
+    let mut state = PacedState {
+        cadence,
+        cues,
+        started: None,
+        clicks: Vec::new(),
+        bell,
+    };
+    let mut rung = None;
+
+    loop {
+        if state.finished() {
+            return Ok(Some(state.report()));
+        }
+
+        let cue = state.cue();
+        if state.bell && cue.is_some() && cue != rung {
+            rung = cue;
+            io::stdout().write_all(b"\x07")?;
+            io::stdout().flush()?;
+        }
+
+        terminal.draw(|f| ui(f, &state))?;
+
+        if !input::poll(Duration::from_millis(10))? {
+            continue;
+        }
+
+        match input::read()? {
+            Event::Mouse(event) => {
+                if let (MouseEventKind::Down(_), Some(elapsed)) = (event.kind, state.elapsed()) {
+                    state.clicks.push(elapsed);
+                }
+            }
+            Event::Key(key) => match key.code {
+                KeyCode::Char('q') => return Ok(None),
+                KeyCode::Char('m') => theme::toggle_reduced_motion(),
+                KeyCode::Enter if state.started.is_none() => {
+                    state.started = Some(Instant::now());
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+}
+
+fn ui<B: Backend>(f: &mut Frame<B>, state: &PacedState) {
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Length(3),
+                Constraint::Min(3),
+                Constraint::Length(1),
+            ]
+            .as_ref(),
+        )
+        .split(f.size());
+
+    let total = state.cadence * state.cues as u32;
+    let elapsed = state.elapsed().unwrap_or_default().min(total);
+    let gauge = Gauge::default()
+        .block(Block::default().title("time left").borders(Borders::ALL))
+        .ratio(1.0 - elapsed.as_secs_f64() / total.as_secs_f64().max(f64::EPSILON))
+        .label(format!("{:.1} s", (total - elapsed).as_secs_f64()));
+    f.render_widget(gauge, layout[0]);
+
+    let flashing = !theme::reduced_motion()
+        && state
+            .elapsed()
+            .is_some_and(|e| e.as_micros() % state.cadence.as_micros() < FLASH.as_micros());
+
+    let (text, style) = match state.cue() {
+        None => (
+            vec![
+                Line::from(format!(
+                    "click once on every cue, {} cues every {} ms",
+                    state.cues,
+                    state.cadence.as_millis()
+                )),
+                Line::from("press enter to start"),
+            ],
+            Style::default(),
+        ),
+        Some(cue) if flashing => (
+            vec![Line::from(format!("CLICK {}/{}", cue + 1, state.cues))],
+            theme::palette().flash,
+        ),
+        Some(cue) => (
+            vec![Line::from(format!("{}/{}", cue + 1, state.cues))],
+            Style::default(),
+        ),
+    };
+
+    let p = Paragraph::new(text)
+        .style(style)
+        .block(Block::default().title("paced test").borders(Borders::ALL))
+        .alignment(Alignment::Center);
+    f.render_widget(p, layout[1]);
+
+    f.render_widget(Paragraph::new("m: reduced motion  q: abort"), layout[2]);
+}
+
+pub fn print_report(report: &PacedReport) {
+    let cues = |list: &[usize]| {
+        list.iter()
+            .map(|c| (c + 1).to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    println!(
+        "missed cues: {} [{}]",
+        report.missed.len(),
+        cues(&report.missed)
+    );
+    println!(
+        "double registrations: {} [{}]",
+        report.doubled.len(),
+        cues(&report.doubled)
+    );
+    if let Some(offset) = report.mean_offset {
+        println!(
+            "mean offset to cue: {} ms",
+            i18n::number(offset.as_millis())
+        );
+    }
+}
@@ -0,0 +1,182 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    widgets::Block,
+};
+
+use crate::{
+    focus,
+    json::{self, Value},
+};
+
+/// share of the left column the event list starts with, the chart gets the rest
+const LIST_PERCENT: u16 = 60;
+/// the list and chart split moves by this much
+const PERCENT_STEP: u16 = 5;
+const PERCENT_RANGE: (u16, u16) = (20, 80);
+const STATS_WIDTH: u16 = 32;
+const WIDTH_STEP: u16 = 4;
+const WIDTH_RANGE: (u16, u16) = (20, 64);
+
+/// the panes of the click test, tab moves the focus between them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pane {
+    List,
+    Chart,
+    Stats,
+}
+
+impl Pane {
+    pub const ALL: [Pane; 3] = [Pane::List, Pane::Chart, Pane::Stats];
+
+    fn name(self) -> &'static str {
+        match self {
+            Pane::List => "list",
+            Pane::Chart => "chart",
+            Pane::Stats => "stats",
+        }
+    }
+}
+
+/// where the panes go this frame, none for the ones hidden behind a maximized pane
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Areas {
+    pub gauge: Rect,
+    pub list: Option<Rect>,
+    pub chart: Option<Rect>,
+    pub stats: Option<Rect>,
+}
+
+/// the focused pane and the sizes the panes were given, kept while one is maximized
+#[derive(Debug, Clone)]
+pub struct Panes {
+    pub focus: Pane,
+    pub list_percent: u16,
+    pub stats_width: u16,
+    /// the focused pane fills the screen below the gauge
+    pub maximized: bool,
+}
+
+impl Default for Panes {
+    fn default() -> Self {
+        Self {
+            focus: Pane::List,
+            list_percent: LIST_PERCENT,
+            stats_width: STATS_WIDTH,
+            maximized: false,
+        }
+    }
+}
+
+fn nudge(value: u16, step: u16, grow: bool, (min, max): (u16, u16)) -> u16 {
+    let value = if grow {
+        value.saturating_add(step)
+    } else {
+        value.saturating_sub(step)
+    };
+    value.clamp(min, max)
+}
+
+impl Panes {
+    pub fn to_json(&self) -> Value {
+        json::object([
+            ("focus", Value::from(self.focus.name())),
+            ("list_percent", Value::from(self.list_percent as u64)),
+            ("stats_width", Value::from(self.stats_width as u64)),
+            ("maximized", Value::from(self.maximized)),
+        ])
+    }
+
+    /// sizes out of range are brought back into it
+    pub fn from_json(value: &Value) -> Self {
+        let default = Self::default();
+        let size = |key, (min, max): (u16, u16), fallback| {
+            value
+                .get(key)
+                .and_then(Value::as_u64)
+                .map_or(fallback, |v| v.clamp(min as u64, max as u64) as u16)
+        };
+        Self {
+            focus: value
+                .get("focus")
+                .and_then(Value::as_str)
+                .and_then(|name| Pane::ALL.into_iter().find(|p| p.name() == name))
+                .unwrap_or(default.focus),
+            list_percent: size("list_percent", PERCENT_RANGE, default.list_percent),
+            stats_width: size("stats_width", WIDTH_RANGE, default.stats_width),
+            maximized: value
+                .get("maximized")
+                .and_then(Value::as_bool)
+                .unwrap_or(default.maximized),
+        }
+    }
+
+    pub fn cycle(&mut self, forward: bool) {
+        let at = Pane::ALL.iter().position(|p| *p == self.focus).unwrap_or(0);
+        self.focus = Pane::ALL[focus::step(at, Pane::ALL.len(), forward)];
+    }
+
+    /// make the focused pane bigger or smaller, its neighbours take up the rest
+    pub fn resize(&mut self, grow: bool) {
+        match self.focus {
+            Pane::List => {
+                self.list_percent = nudge(self.list_percent, PERCENT_STEP, grow, PERCENT_RANGE)
+            }
+            Pane::Chart => {
+                self.list_percent = nudge(self.list_percent, PERCENT_STEP, !grow, PERCENT_RANGE)
+            }
+            Pane::Stats => {
+                self.stats_width = nudge(self.stats_width, WIDTH_STEP, grow, WIDTH_RANGE)
+            }
+        }
+    }
+
+    pub fn toggle_maximized(&mut self) {
+        self.maximized = !self.maximized;
+    }
+
+    pub fn split(&self, area: Rect) -> Areas {
+        if self.maximized {
+            let rows = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(3), Constraint::Min(0)].as_ref())
+                .split(area);
+            let mut areas = Areas {
+                gauge: rows[0],
+                ..Areas::default()
+            };
+            match self.focus {
+                Pane::List => areas.list = Some(rows[1]),
+                Pane::Chart => areas.chart = Some(rows[1]),
+                Pane::Stats => areas.stats = Some(rows[1]),
+            }
+            return areas;
+        }
+
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(0), Constraint::Length(self.stats_width)].as_ref())
+            .split(area);
+        let left = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(
+                [
+                    Constraint::Length(3),
+                    Constraint::Percentage(self.list_percent),
+                    Constraint::Percentage(100 - self.list_percent),
+                ]
+                .as_ref(),
+            )
+            .split(columns[0]);
+        Areas {
+            gauge: left[0],
+            list: Some(left[1]),
+            chart: Some(left[2]),
+            stats: Some(columns[1]),
+        }
+    }
+
+    /// the block of a pane, its border and title set apart while it has the focus
+    pub fn block(&self, pane: Pane, title: &str) -> Block<'static> {
+        focus::block(title, pane == self.focus)
+    }
+}
@@ -0,0 +1,165 @@
+use std::{
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc, Condvar, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use crossterm::event::MouseButton;
+use ratatui::text::Line;
+
+use crate::{
+    analyzer::{Context, Registry},
+    session::{Click, Session, Stuck},
+};
+
+/// how long a frame waits for the lines of its own time before it draws the last ones
+const FRESH_WAIT: Duration = Duration::from_millis(2);
+
+/// what happened to the session, in order
+enum Job {
+    Click(Click),
+    Release(MouseButton, Duration),
+    /// the stuck presses found so far and how long they were held
+    Stuck(Vec<Stuck>),
+    /// the session changed in a way no event tells, the analyzers see all of it again
+    /// when `replay` is set
+    Replace(Box<Session>, bool),
+    /// lines are wanted for this time since the session started
+    Frame(u64, Duration),
+}
+
+/// the stats lines and banner verdicts of the analyzers at one frame
+#[derive(Default, Clone)]
+pub struct Snapshot {
+    frame: u64,
+    pub lines: Vec<Line<'static>>,
+    pub banner: Vec<Line<'static>>,
+    /// the lines are of an earlier frame
+    pub stale: bool,
+}
+
+/// the analyzers on a worker thread with a copy of the session, so the statistics they
+/// work out never hold up timestamping a click or drawing a frame
+pub struct Pipeline {
+    jobs: Sender<Job>,
+    snapshot: Arc<(Mutex<Snapshot>, Condvar)>,
+    frame: u64,
+}
+
+impl Pipeline {
+    pub fn new(session: &Session) -> Self {
+        let (jobs, rx) = mpsc::channel();
+        let snapshot = Arc::new((Mutex::new(Snapshot::default()), Condvar::new()));
+        let published = Arc::clone(&snapshot);
+        let session = session.clone();
+        thread::spawn(move || work(rx, session, &published));
+        Self {
+            jobs,
+            snapshot,
+            frame: 0,
+        }
+    }
+
+    fn send(&self, job: Job) {
+        // the worker only stops once the pipeline is dropped
+        let _ = self.jobs.send(job);
+    }
+
+    pub fn click(&self, click: Click) {
+        self.send(Job::Click(click));
+    }
+
+    pub fn release(&self, button: MouseButton, now: Duration) {
+        self.send(Job::Release(button, now));
+    }
+
+    /// the stuck presses were found or held on for longer, the rest of the session is as it
+    /// was
+    pub fn stuck(&self, stuck: &[Stuck]) {
+        self.send(Job::Stuck(stuck.to_vec()));
+    }
+
+    /// the session was edited, bookmarks, marks, settings or clicks spilled to disk
+    pub fn replace(&self, session: &Session) {
+        self.send(Job::Replace(Box::new(session.clone()), false));
+    }
+
+    /// the clicks were classified again or swapped for others
+    pub fn replay(&self, session: &Session) {
+        self.send(Job::Replace(Box::new(session.clone()), true));
+    }
+
+    /// the lines for a frame at `now`, the ones of an earlier frame while the analyzers
+    /// are still busy
+    pub fn frame(&mut self, now: Duration) -> Snapshot {
+        self.frame += 1;
+        self.send(Job::Frame(self.frame, now));
+        let (lock, fresh) = &*self.snapshot;
+        let Ok(snapshot) = lock.lock() else {
+            return Snapshot {
+                stale: true,
+                ..Snapshot::default()
+            };
+        };
+        match fresh.wait_timeout_while(snapshot, FRESH_WAIT, |s| s.frame < self.frame) {
+            Ok((snapshot, _)) => Snapshot {
+                stale: snapshot.frame < self.frame,
+                ..snapshot.clone()
+            },
+            Err(_) => Snapshot {
+                stale: true,
+                ..Snapshot::default()
+            },
+        }
+    }
+}
+
+fn work(jobs: Receiver<Job>, mut session: Session, published: &(Mutex<Snapshot>, Condvar)) {
+    let mut registry = Registry::replay(&session);
+    while let Ok(job) = jobs.recv() {
+        // only the newest of the frames queued up is worked out
+        let mut frame = None;
+        for job in std::iter::once(job).chain(jobs.try_iter()) {
+            match job {
+                Job::Click(click) => {
+                    registry.on_click(&click);
+                    session.clicks.push(click);
+                }
+                Job::Release(button, now) => {
+                    registry.on_release(button);
+                    session.release(button, now);
+                }
+                Job::Stuck(stuck) => session.stuck = stuck,
+                Job::Replace(replaced, replay) => {
+                    session = *replaced;
+                    if replay {
+                        registry = Registry::replay(&session);
+                    }
+                }
+                Job::Frame(id, now) => frame = Some((id, now)),
+            }
+        }
+
+        let Some((id, now)) = frame else { continue };
+        let outliers = session.gap_outliers();
+        let cx = Context {
+            session: &session,
+            outliers: &outliers,
+            now,
+        };
+        let snapshot = Snapshot {
+            frame: id,
+            lines: registry.lines(&cx),
+            banner: registry.banner(&cx),
+            stale: false,
+        };
+        let (lock, fresh) = published;
+        if let Ok(mut published) = lock.lock() {
+            *published = snapshot;
+            fresh.notify_all();
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use std::{
+    io::{self, Write},
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use crossterm::event::{Event, KeyCode, MouseEventKind};
+
+use crate::{
+    app::{self, AppState},
+    control, error,
+    i18n::{self, tr, Msg},
+    input,
+    session::{self, Session},
+    terminal::CaptureGuard,
+};
+
+/// time between two stat summaries
+const SUMMARY_EVERY: Duration = Duration::from_secs(10);
+/// longest wait for input before the loop looks around again
+const RECHECK: Duration = Duration::from_secs(1);
+
+/// raw mode needs an explicit carriage return
+fn line(text: &str) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    write!(stdout, "{}\r\n", text)?;
+    stdout.flush()
+}
+
+fn print_click(app: &AppState) -> io::Result<()> {
+    if let Some((done, total)) = app.blind() {
+        return line(&i18n::fmt(
+            Msg::BlindProgress,
+            &[&i18n::number(done), &i18n::number(total)],
+        ));
+    }
+    let Some(click) = app.session.clicks.last() else {
+        return Ok(());
+    };
+    let mut text = i18n::fmt(
+        Msg::PlainClick,
+        &[
+            &i18n::number(app.session.click_count()),
+            &session::button_name(click.button),
+            &i18n::decimal(click.at.as_secs_f64(), 3),
+        ],
+    );
+    if let Some(interval) = click.interval {
+        text.push_str(&i18n::fmt(Msg::PlainChatter, &[&i18n::duration(interval)]));
+    }
+    if click.keyboard {
+        text.push_str(tr(Msg::PlainKeyboard));
+    }
+    line(&text)
+}
+
+/// the click just made, with the results when it ended a blind test
+fn clicked(app: &AppState, blind: bool) -> io::Result<()> {
+    print_click(app)?;
+    if blind && app.blind().is_none() {
+        line(tr(Msg::BlindDone))?;
+        line(&summary(app))?;
+    }
+    Ok(())
+}
+
+fn summary(app: &AppState) -> String {
+    format!(
+        "{}, {}. {}",
+        i18n::fmt(Msg::Clicks, &[&i18n::number(app.session.click_count())]),
+        i18n::fmt(Msg::Chatter, &[&i18n::number(app.session.chatter_count())]),
+        app.estimate().describe()
+    )
+}
+
+/// run the click test with one line of text per click instead of the tui
+pub fn run(mut app: AppState) -> error::Result<(Session, Option<PathBuf>)> {
+    let _guard = CaptureGuard::new()?;
+    line(tr(Msg::PlainPrompt))?;
+    if let Ok((columns, rows)) = crossterm::terminal::size() {
+        app.on_resize(columns, rows);
+    }
+
+    let mut next_summary = Instant::now() + SUMMARY_EVERY;
+    let mut summarized = 0;
+
+    loop {
+        if let Some(e) = app.autosave() {
+            line(&e)?;
+        }
+        if let Some(suspended) = app.watch_suspend() {
+            line(&suspended)?;
+        }
+        for stuck in app.watch_stuck() {
+            line(&stuck)?;
+        }
+        if let Some(reloaded) = app.reload_config() {
+            line(&reloaded)?;
+        }
+        if app.mouse_missing() {
+            for help in app::no_mouse_help() {
+                line(&help)?;
+            }
+            app.dismiss_probe();
+        }
+        let (answers, stop) = app.run_controls();
+        for answer in answers {
+            line(&answer)?;
+        }
+        if stop {
+            break;
+        }
+
+        let now = Instant::now();
+        if now >= next_summary {
+            if app.session.click_count() != summarized && app.blind().is_none() {
+                summarized = app.session.click_count();
+                line(&summary(&app))?;
+            }
+            next_summary = now + SUMMARY_EVERY;
+        }
+
+        // the config watch and the mouse probe look about once a second
+        let mut wait = next_summary.saturating_duration_since(now).min(RECHECK);
+        if app.controlled() {
+            wait = wait.min(control::POLL);
+        }
+        if !input::poll(wait)? {
+            continue;
+        }
+
+        match input::read()? {
+            Event::Mouse(_) if app.paused() => {}
+            Event::Mouse(event) => {
+                app.mouse_arrived();
+                match event.kind {
+                    MouseEventKind::Down(_) => {
+                        let blind = app.blind().is_some();
+                        app.on_click(event);
+                        clicked(&app, blind)?;
+                    }
+                    MouseEventKind::Up(button) => app.on_release(button),
+                    MouseEventKind::Moved | MouseEventKind::Drag(_) => app.on_move(),
+                    _ => {}
+                }
+            }
+            Event::Key(key) if key.code == KeyCode::Char('q') => break,
+            Event::Key(key) if key.code == KeyCode::Char('c') => {
+                let done = app.toggle_capture();
+                line(&done)?;
+            }
+            Event::Key(key) if key.code == KeyCode::Char('i') => {
+                let done = app.mark_one_press();
+                line(&done)?;
+            }
+            Event::Key(key) if key.code == KeyCode::Char(' ') && !app.paused() => {
+                let blind = app.blind().is_some();
+                app.on_key_click();
+                clicked(&app, blind)?;
+            }
+            Event::Resize(columns, rows) => app.on_resize(columns, rows),
+            _ => {}
+        }
+    }
+
+    Ok(app.finish())
+}
@@ -0,0 +1,84 @@
+use crossterm::event::MouseButton;
+
+use crate::session::{self, Click};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cmp {
+    Less,
+    LessEq,
+    Eq,
+    GreaterEq,
+    Greater,
+}
+
+impl Cmp {
+    fn test(self, a: f64, b: f64) -> bool {
+        match self {
+            Cmp::Less => a < b,
+            Cmp::LessEq => a <= b,
+            Cmp::Eq => a == b,
+            Cmp::GreaterEq => a >= b,
+            Cmp::Greater => a > b,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Term {
+    /// milliseconds
+    Interval(Cmp, f64),
+    /// milliseconds, clicks without a release never match
+    Hold(Cmp, f64),
+    Button(MouseButton),
+}
+
+/// space separated conditions a click has to meet all of, like `interval<20 button=right`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    pub text: String,
+    terms: Vec<Term>,
+}
+
+impl Query {
+    /// `None` for an empty or malformed query
+    pub fn parse(text: &str) -> Option<Self> {
+        // longer operators first so `<=` isn't read as `<`
+        const OPS: [(&str, Cmp); 5] = [
+            ("<=", Cmp::LessEq),
+            (">=", Cmp::GreaterEq),
+            ("<", Cmp::Less),
+            (">", Cmp::Greater),
+            ("=", Cmp::Eq),
+        ];
+
+        let terms = text
+            .split_whitespace()
+            .map(|term| {
+                let (at, op, cmp) = OPS
+                    .iter()
+                    .find_map(|(op, cmp)| term.find(op).map(|at| (at, *op, *cmp)))?;
+                let (field, value) = (&term[..at], &term[at + op.len()..]);
+                match field {
+                    "interval" => value.parse().ok().map(|v| Term::Interval(cmp, v)),
+                    "hold" => value.parse().ok().map(|v| Term::Hold(cmp, v)),
+                    "button" if cmp == Cmp::Eq => session::parse_button(value).map(Term::Button),
+                    _ => None,
+                }
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        (!terms.is_empty()).then(|| Self {
+            text: text.trim().to_string(),
+            terms,
+        })
+    }
+
+    pub fn matches(&self, click: &Click) -> bool {
+        let ms = |d: std::time::Duration| d.as_secs_f64() * 1000.0;
+        self.terms.iter().all(|term| match *term {
+            Term::Interval(cmp, v) => click.shown_interval().is_some_and(|i| cmp.test(ms(i), v)),
+            Term::Hold(cmp, v) => click.hold.is_some_and(|h| cmp.test(ms(h), v)),
+            Term::Button(button) => click.button == button,
+        })
+    }
+}
@@ -0,0 +1,161 @@
+use std::{collections::VecDeque, env, io, sync::Mutex, time::Duration};
+
+use clap::ValueEnum;
+use crossterm::event::{Event, MouseButton, MouseEvent, MouseEventKind};
+
+/// a terminal reporting the mouse in a way the tests have to work around
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Quirk {
+    /// windows terminal and conpty hand the input over in batches, the motion of a batch is
+    /// merged into its last move and gaps inside a batch are the time it took to read them
+    Coalescing,
+    /// a press arrives while its button is still held, the release it missed is added
+    MissingRelease,
+    /// urxvt without the sgr protocol reports releases without their button, they are
+    /// given the button held last
+    AnonymousRelease,
+}
+
+impl Quirk {
+    pub fn name(self) -> &'static str {
+        match self {
+            Quirk::Coalescing => "coalescing",
+            Quirk::MissingRelease => "missing-release",
+            Quirk::AnonymousRelease => "anonymous-release",
+        }
+    }
+}
+
+struct Layer {
+    /// none with `--no-quirks`, the events pass unchanged
+    enabled: bool,
+    active: Vec<Quirk>,
+    /// buttons pressed and not released, the last pressed last
+    held: Vec<MouseButton>,
+    /// events read ahead or added, handed out before the next one is read
+    queue: VecDeque<Event>,
+}
+
+static LAYER: Mutex<Layer> = Mutex::new(Layer {
+    enabled: true,
+    active: Vec::new(),
+    held: Vec::new(),
+    queue: VecDeque::new(),
+});
+
+/// the quirks `$TERM` and the environment tell of
+fn detected() -> Vec<Quirk> {
+    let mut quirks = Vec::new();
+    if cfg!(windows) || env::var_os("WT_SESSION").is_some() {
+        quirks.push(Quirk::Coalescing);
+    }
+    if env::var("TERM").is_ok_and(|term| term.starts_with("rxvt")) {
+        quirks.push(Quirk::AnonymousRelease);
+    }
+    quirks
+}
+
+/// turn on the detected quirks and the `--quirk` ones, none at all with `--no-quirks`; a
+/// missing release is also found out while the test runs
+pub fn init(forced: &[Quirk], enabled: bool) {
+    if let Ok(mut layer) = LAYER.lock() {
+        layer.enabled = enabled;
+        layer.active = if enabled { detected() } else { Vec::new() };
+        for quirk in forced {
+            if !layer.active.contains(quirk) {
+                layer.active.push(*quirk);
+            }
+        }
+    }
+}
+
+/// names of the quirks worked around so far, for the exports
+pub fn active() -> Vec<String> {
+    LAYER
+        .lock()
+        .map(|layer| layer.active.iter().map(|q| q.name().to_string()).collect())
+        .unwrap_or_default()
+}
+
+/// whether an event is waiting in the layer
+pub fn pending() -> bool {
+    LAYER.lock().is_ok_and(|layer| !layer.queue.is_empty())
+}
+
+fn is_motion(event: &Event) -> bool {
+    matches!(
+        event,
+        Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Moved | MouseEventKind::Drag(_),
+            ..
+        })
+    )
+}
+
+impl Layer {
+    fn on(&self, quirk: Quirk) -> bool {
+        self.active.contains(&quirk)
+    }
+
+    fn apply(&mut self, event: Event) -> Event {
+        let Event::Mouse(mut mouse) = event else {
+            return event;
+        };
+        match mouse.kind {
+            MouseEventKind::Down(button) => {
+                if self.held.contains(&button) {
+                    if !self.on(Quirk::MissingRelease) {
+                        self.active.push(Quirk::MissingRelease);
+                    }
+                    self.queue.push_front(Event::Mouse(mouse));
+                    self.held.retain(|b| *b != button);
+                    mouse.kind = MouseEventKind::Up(button);
+                    return Event::Mouse(mouse);
+                }
+                self.held.push(button);
+            }
+            MouseEventKind::Up(button) => {
+                let button = match self.held.last() {
+                    Some(last) if self.on(Quirk::AnonymousRelease) => *last,
+                    _ => button,
+                };
+                self.held.retain(|b| *b != button);
+                mouse.kind = MouseEventKind::Up(button);
+            }
+            _ => {}
+        }
+        Event::Mouse(mouse)
+    }
+}
+
+/// the next event with the workarounds of the active quirks applied, `read` and `poll` get
+/// it from the terminal or a script
+pub fn read(
+    read: impl Fn() -> io::Result<Event>,
+    poll: impl Fn(Duration) -> io::Result<bool>,
+) -> io::Result<Event> {
+    let queued = LAYER.lock().ok().and_then(|mut l| l.queue.pop_front());
+    let mut event = match queued {
+        Some(event) => event,
+        None => read()?,
+    };
+    let Ok(mut layer) = LAYER.lock() else {
+        return Ok(event);
+    };
+    if !layer.enabled {
+        return Ok(event);
+    }
+    if layer.on(Quirk::Coalescing) && layer.queue.is_empty() {
+        // a move followed right away by another one is dropped for it
+        while is_motion(&event) && poll(Duration::ZERO)? {
+            let next = read()?;
+            if is_motion(&next) {
+                event = next;
+            } else {
+                layer.queue.push_back(next);
+                break;
+            }
+        }
+    }
+    Ok(layer.apply(event))
+}
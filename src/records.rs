@@ -0,0 +1,151 @@
+use std::{collections::BTreeMap, fs, path::PathBuf, time::Duration};
+
+use crate::{
+    error::{self, Error},
+    i18n::{self, Msg},
+    json::{self, Value},
+    session::{self, Session},
+};
+
+/// key of the sessions run without a device name
+const UNNAMED: &str = "-";
+
+/// the best results of the click test on one device, each kept until a session beats it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Records {
+    /// most clicks inside one second
+    pub peak_cps: usize,
+    /// shortest gap between two clicks of a button that is neither chatter nor faster than
+    /// the human floor
+    pub fastest_double: Option<Duration>,
+    /// longest run of clicks without chatter
+    pub clean_streak: usize,
+}
+
+fn path() -> PathBuf {
+    session::data_dir().join("records.json")
+}
+
+fn key(device: Option<&str>) -> &str {
+    device.unwrap_or(UNNAMED)
+}
+
+/// the records of every device by name
+fn load_all() -> BTreeMap<String, Value> {
+    match fs::read_to_string(path()).map(|text| json::parse(&text)) {
+        Ok(Ok(Value::Object(devices))) => devices,
+        _ => BTreeMap::new(),
+    }
+}
+
+impl Records {
+    /// what the clicks in memory reach, clicks spilled to disk only count for the streak
+    pub fn of(session: &Session) -> Self {
+        let mut peak_cps = 0;
+        let mut start = 0;
+        for (end, click) in session.clicks.iter().enumerate() {
+            while click.at.saturating_sub(session.clicks[start].at) > Duration::from_secs(1) {
+                start += 1;
+            }
+            peak_cps = peak_cps.max(end - start + 1);
+        }
+        let fastest_double = session
+            .clicks
+            .windows(2)
+            .filter(|w| w[0].button == w[1].button && w[1].interval.is_none())
+            .map(|w| w[1].at.saturating_sub(w[0].at))
+            .filter(|gap| *gap >= session.human_floor)
+            .min();
+        Self {
+            peak_cps,
+            fastest_double,
+            clean_streak: session.clean_streaks().1,
+        }
+    }
+
+    fn to_json(self) -> Value {
+        json::object([
+            ("peak_cps", Value::from(self.peak_cps as u64)),
+            (
+                "fastest_double_us",
+                Value::from(self.fastest_double.map(|d| d.as_micros() as u64)),
+            ),
+            ("clean_streak", Value::from(self.clean_streak as u64)),
+        ])
+    }
+
+    fn from_json(value: &Value) -> Self {
+        let count = |key| value.get(key).and_then(Value::as_u64).unwrap_or(0) as usize;
+        Self {
+            peak_cps: count("peak_cps"),
+            fastest_double: value
+                .get("fastest_double_us")
+                .and_then(Value::as_u64)
+                .map(Duration::from_micros),
+            clean_streak: count("clean_streak"),
+        }
+    }
+
+    /// the records of the device, none yet when the file is missing or unreadable
+    pub fn load(device: Option<&str>) -> Self {
+        load_all()
+            .get(key(device))
+            .map(Self::from_json)
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, device: Option<&str>) -> error::Result<()> {
+        let path = path();
+        let mut devices = load_all();
+        devices.insert(key(device).to_string(), self.to_json());
+        path.parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&path, Value::Object(devices).to_string_pretty()))
+            .map_err(|source| Error::Export { path, source })
+    }
+
+    /// take over what the other results beat, with a line for every new record
+    pub fn improve(&mut self, other: &Records) -> Vec<String> {
+        let mut beaten = Vec::new();
+        if other.peak_cps > self.peak_cps {
+            self.peak_cps = other.peak_cps;
+            beaten.push(i18n::fmt(Msg::RecordCps, &[&i18n::number(other.peak_cps)]));
+        }
+        if let Some(double) = other.fastest_double {
+            if self.fastest_double.is_none_or(|best| double < best) {
+                self.fastest_double = Some(double);
+                beaten.push(i18n::fmt(
+                    Msg::RecordDouble,
+                    &[&i18n::number(double.as_millis())],
+                ));
+            }
+        }
+        if other.clean_streak > self.clean_streak {
+            self.clean_streak = other.clean_streak;
+            beaten.push(i18n::fmt(
+                Msg::RecordStreak,
+                &[&i18n::number(other.clean_streak)],
+            ));
+        }
+        beaten
+    }
+
+    /// one line for the stats pane
+    pub fn describe(&self) -> String {
+        let double = self
+            .fastest_double
+            .map_or_else(|| "-".to_string(), |d| i18n::number(d.as_millis()));
+        i18n::fmt(
+            Msg::Records,
+            &[
+                &i18n::number(self.peak_cps),
+                &double,
+                &i18n::number(self.clean_streak),
+            ],
+        )
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
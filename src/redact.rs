@@ -0,0 +1,95 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use crate::session;
+
+/// words at least this long with at least `SERIAL_DIGITS` digits read as serial numbers
+const SERIAL_LEN: usize = 8;
+const SERIAL_DIGITS: usize = 4;
+
+/// exports and reports leave out what identifies the machine or the person, `--redact`
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+pub fn set(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+#[cfg(target_os = "linux")]
+pub fn hostname() -> Option<String> {
+    let mut name = [0u8; 256];
+    // SAFETY: gethostname writes at most `len` bytes into the buffer it is given
+    if unsafe { libc::gethostname(name.as_mut_ptr().cast(), name.len()) } != 0 {
+        return None;
+    }
+    let end = name.iter().position(|b| *b == 0).unwrap_or(name.len());
+    String::from_utf8(name[..end].to_vec()).ok()
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn hostname() -> Option<String> {
+    ["COMPUTERNAME", "HOSTNAME"]
+        .iter()
+        .find_map(|key| env::var(key).ok())
+}
+
+fn is_serial(word: &str) -> bool {
+    word.len() >= SERIAL_LEN && word.chars().filter(char::is_ascii_digit).count() >= SERIAL_DIGITS
+}
+
+/// the text with the home dir, the user, the host and words looking like serial numbers
+/// replaced, unchanged without `--redact`
+pub fn text(text: &str) -> String {
+    if !enabled() {
+        return text.to_string();
+    }
+    let mut text = text.to_string();
+    if let Some(home) = env::var("HOME").ok().filter(|h| h.len() > 1) {
+        text = text.replace(&home, "~");
+    }
+    let names = [(hostname(), "<host>"), (session::os_user(), "<user>")];
+    for (name, placeholder) in names {
+        // shorter names would match inside every other word
+        if let Some(name) = name.filter(|n| n.len() >= 3) {
+            text = text.replace(&name, placeholder);
+        }
+    }
+
+    let mut redacted = String::with_capacity(text.len());
+    let mut word = String::new();
+    for c in text.chars().chain(std::iter::once(' ')) {
+        if c.is_ascii_alphanumeric() {
+            word.push(c);
+            continue;
+        }
+        redacted.push_str(if is_serial(&word) { "<serial>" } else { &word });
+        word.clear();
+        redacted.push(c);
+    }
+    redacted.pop();
+    redacted
+}
+
+/// a name, redacted
+pub fn name(name: Option<&str>) -> Option<String> {
+    name.map(text)
+}
+
+/// who ran the test, left out with `--redact`
+pub fn operator(operator: Option<&str>) -> Option<String> {
+    operator.filter(|_| !enabled()).map(String::from)
+}
+
+/// a path as its file name with `--redact`, the dirs above it tell the user and the machine
+pub fn path(path: &Path) -> PathBuf {
+    match path.file_name() {
+        Some(name) if enabled() => PathBuf::from(name),
+        _ => path.to_path_buf(),
+    }
+}
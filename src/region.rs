@@ -0,0 +1,13 @@
+use ratatui::layout::Rect;
+
+/// rows one notch of the wheel scrolls a pane by
+pub const WHEEL_ROWS: isize = 3;
+
+/// whether the cell of a mouse event lies in an area drawn last frame, the panes
+/// and anything clickable are hit tested with it
+pub fn hit(area: Rect, column: u16, row: u16) -> bool {
+    column >= area.x
+        && column < area.x.saturating_add(area.width)
+        && row >= area.y
+        && row < area.y.saturating_add(area.height)
+}
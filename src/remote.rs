@@ -0,0 +1,378 @@
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::{IpAddr, Shutdown, SocketAddr, TcpStream, ToSocketAddrs},
+    sync::mpsc::{self, Sender, SyncSender, TrySendError},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use crossterm::event::MouseButton;
+
+use crate::{
+    control::Request,
+    error::{self, Error},
+    json::{self, Value},
+    session,
+};
+
+/// version of the bench protocol, a console speaks to agents of the same version only
+pub const VERSION: u64 = 2;
+/// port the console listens on unless told another
+pub const PORT: u16 = 7878;
+/// time the console gets to take the connection, to welcome an agent and to take a line
+const HANDSHAKE: Duration = Duration::from_secs(5);
+/// messages waiting for the console, more are dropped rather than holding up a click
+const OUTBOX: usize = 4096;
+
+/// the host and port of `host`, `host:port`, `[v6]:port` or a bare ipv6 address, the port
+/// defaults to `PORT`
+pub fn address(text: &str) -> (String, u16) {
+    if let Ok(address) = text.parse::<SocketAddr>() {
+        return (address.ip().to_string(), address.port());
+    }
+    let bare = text
+        .strip_prefix('[')
+        .and_then(|t| t.strip_suffix(']'))
+        .unwrap_or(text);
+    if bare.parse::<IpAddr>().is_ok() {
+        return (bare.to_string(), PORT);
+    }
+    match text
+        .rsplit_once(':')
+        .and_then(|(host, port)| Some((host, port.parse().ok()?)))
+    {
+        Some((host, port)) => (host.to_string(), port),
+        None => (text.to_string(), PORT),
+    }
+}
+
+/// one line of the bench protocol, a json object with its `type`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Message {
+    /// the first line an agent sends
+    Hello {
+        version: u64,
+        station: String,
+        device: Option<String>,
+        detect_window: Duration,
+    },
+    /// the console's answer to a hello it accepts
+    Welcome {
+        version: u64,
+    },
+    /// the console's answer to a hello it does not, the connection closes after it
+    Refused {
+        reason: String,
+    },
+    /// a press on the station, chatter when it has an interval
+    Click {
+        at: Duration,
+        button: MouseButton,
+        interval: Option<Duration>,
+    },
+    Release {
+        at: Duration,
+        button: MouseButton,
+    },
+    /// a command of `--plain` the console sends to the station
+    Control {
+        line: String,
+    },
+    /// what the station answered to the last control
+    Answer {
+        text: String,
+    },
+    /// the last line of an agent whose test ended
+    Bye,
+}
+
+fn micros(d: Duration) -> Value {
+    Value::from(d.as_micros() as u64)
+}
+
+impl Message {
+    pub fn to_json(&self) -> Value {
+        let (kind, mut fields) = match self {
+            Message::Hello {
+                version,
+                station,
+                device,
+                detect_window,
+            } => {
+                let mut fields = vec![
+                    ("version", Value::from(*version)),
+                    ("station", Value::from(station.as_str())),
+                    ("detect_window_us", micros(*detect_window)),
+                ];
+                if let Some(device) = device {
+                    fields.push(("device", Value::from(device.as_str())));
+                }
+                ("hello", fields)
+            }
+            Message::Welcome { version } => ("welcome", vec![("version", Value::from(*version))]),
+            Message::Refused { reason } => {
+                ("refused", vec![("reason", Value::from(reason.as_str()))])
+            }
+            Message::Click {
+                at,
+                button,
+                interval,
+            } => (
+                "click",
+                vec![
+                    ("at_us", micros(*at)),
+                    ("button", Value::from(session::button_name(*button))),
+                    (
+                        "interval_us",
+                        Value::from(interval.map(|i| i.as_micros() as u64)),
+                    ),
+                ],
+            ),
+            Message::Release { at, button } => (
+                "release",
+                vec![
+                    ("at_us", micros(*at)),
+                    ("button", Value::from(session::button_name(*button))),
+                ],
+            ),
+            Message::Control { line } => ("control", vec![("line", Value::from(line.as_str()))]),
+            Message::Answer { text } => ("answer", vec![("text", Value::from(text.as_str()))]),
+            Message::Bye => ("bye", Vec::new()),
+        };
+        fields.push(("type", Value::from(kind)));
+        json::object(fields)
+    }
+
+    pub fn from_json(value: &Value) -> Result<Self, String> {
+        let text = |key: &str| {
+            value
+                .get(key)
+                .and_then(Value::as_str)
+                .map(String::from)
+                .ok_or_else(|| format!("`{}` is missing", key))
+        };
+        let number = |key: &str| {
+            value
+                .get(key)
+                .and_then(Value::as_u64)
+                .ok_or_else(|| format!("`{}` is missing", key))
+        };
+        let time = |key: &str| number(key).map(Duration::from_micros);
+        let button = || {
+            text("button").and_then(|name| {
+                session::parse_button(&name).ok_or_else(|| format!("unknown button `{}`", name))
+            })
+        };
+        match text("type")?.as_str() {
+            "hello" => Ok(Message::Hello {
+                version: number("version")?,
+                station: text("station")?,
+                device: text("device").ok(),
+                detect_window: time("detect_window_us")?,
+            }),
+            "welcome" => Ok(Message::Welcome {
+                version: number("version")?,
+            }),
+            "refused" => Ok(Message::Refused {
+                reason: text("reason")?,
+            }),
+            "click" => Ok(Message::Click {
+                at: time("at_us")?,
+                button: button()?,
+                interval: value
+                    .get("interval_us")
+                    .and_then(Value::as_u64)
+                    .map(Duration::from_micros),
+            }),
+            "release" => Ok(Message::Release {
+                at: time("at_us")?,
+                button: button()?,
+            }),
+            "control" => Ok(Message::Control {
+                line: text("line")?,
+            }),
+            "answer" => Ok(Message::Answer {
+                text: text("text")?,
+            }),
+            "bye" => Ok(Message::Bye),
+            other => Err(format!("unknown message `{}`", other)),
+        }
+    }
+
+    pub fn parse(line: &str) -> Result<Self, String> {
+        Self::from_json(&json::parse(line)?)
+    }
+}
+
+/// write one message as a line, in a single write so the lines of two threads do not mix
+pub fn send(stream: &mut impl Write, message: &Message) -> io::Result<()> {
+    let mut line = message.to_json().to_string_compact();
+    line.push('\n');
+    stream.write_all(line.as_bytes())
+}
+
+/// the events of the click test forwarded to a console, `--agent`; a thread of its own
+/// writes them so a slow console never holds up the click test
+pub struct Agent {
+    /// none once the console hung up, the test goes on without it
+    outbox: Option<SyncSender<Message>>,
+    writer: Option<JoinHandle<()>>,
+}
+
+impl Agent {
+    /// say hello to the console at `address`, see `address`, and hand the controls it sends
+    /// to `commands`
+    pub fn connect(
+        address: &str,
+        hello: Message,
+        commands: Sender<Request>,
+    ) -> error::Result<Self> {
+        let failed = |message: String| Error::Remote {
+            address: address.to_string(),
+            message,
+        };
+        let (host, port) = self::address(address);
+        let mut last = io::Error::new(io::ErrorKind::NotFound, "no address for the host");
+        let mut connected = None;
+        for to in (host.as_str(), port)
+            .to_socket_addrs()
+            .map_err(|e| failed(e.to_string()))?
+        {
+            match TcpStream::connect_timeout(&to, HANDSHAKE) {
+                Ok(stream) => {
+                    connected = Some(stream);
+                    break;
+                }
+                Err(e) => last = e,
+            }
+        }
+        let mut stream = connected.ok_or_else(|| failed(last.to_string()))?;
+        let _ = stream.set_nodelay(true);
+        // a console that stops reading lets the writer give up instead of hanging
+        let _ = stream.set_write_timeout(Some(HANDSHAKE));
+        send(&mut stream, &hello).map_err(|e| failed(e.to_string()))?;
+
+        stream
+            .set_read_timeout(Some(HANDSHAKE))
+            .map_err(|e| failed(e.to_string()))?;
+        let mut reader = BufReader::new(stream.try_clone().map_err(|e| failed(e.to_string()))?);
+        let mut line = String::new();
+        reader
+            .read_line(&mut line)
+            .map_err(|e| failed(format!("no welcome: {}", e)))?;
+        match Message::parse(&line) {
+            Ok(Message::Welcome { version }) if version == VERSION => {}
+            Ok(Message::Refused { reason }) => return Err(failed(reason)),
+            Ok(other) => return Err(failed(format!("unexpected answer {:?}", other))),
+            Err(e) => return Err(failed(e)),
+        }
+        stream
+            .set_read_timeout(None)
+            .map_err(|e| failed(e.to_string()))?;
+
+        let (outbox, messages) = mpsc::sync_channel::<Message>(OUTBOX);
+        let mut out = stream.try_clone().map_err(|e| failed(e.to_string()))?;
+        let writer = thread::spawn(move || {
+            for message in messages {
+                if send(&mut out, &message).is_err() || message == Message::Bye {
+                    break;
+                }
+            }
+            // the reader stops too
+            let _ = out.shutdown(Shutdown::Both);
+        });
+        let answers = outbox.clone();
+        thread::spawn(move || {
+            for line in reader.lines().map_while(Result::ok) {
+                let Ok(Message::Control { line }) = Message::parse(&line) else {
+                    continue;
+                };
+                let (reply, answer) = mpsc::channel();
+                if commands.send(Request::new(line, Some(reply))).is_err() {
+                    break;
+                }
+                let Ok(text) = answer.recv() else { break };
+                if answers.send(Message::Answer { text }).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(Self {
+            outbox: Some(outbox),
+            writer: Some(writer),
+        })
+    }
+
+    /// queue a message for the console without waiting, a console that went away is let go
+    /// and one too far behind misses the message
+    pub fn send(&mut self, message: Message) {
+        let Some(outbox) = &self.outbox else {
+            return;
+        };
+        if let Err(TrySendError::Disconnected(_)) = outbox.try_send(message) {
+            self.outbox = None;
+        }
+    }
+}
+
+impl Drop for Agent {
+    /// the bye goes out before the test ends, the writer gives up after `HANDSHAKE`
+    fn drop(&mut self) {
+        if let Some(outbox) = self.outbox.take() {
+            let _ = outbox.send(Message::Bye);
+        }
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+
+    use super::*;
+
+    #[test]
+    fn addresses_default_to_the_port() {
+        assert_eq!(address("bench"), ("bench".to_string(), PORT));
+        assert_eq!(address("bench:9000"), ("bench".to_string(), 9000));
+        assert_eq!(address("10.0.0.2"), ("10.0.0.2".to_string(), PORT));
+        assert_eq!(address("::1"), ("::1".to_string(), PORT));
+        assert_eq!(address("[::1]"), ("::1".to_string(), PORT));
+        assert_eq!(address("[fe80::2]:9000"), ("fe80::2".to_string(), 9000));
+    }
+
+    #[test]
+    fn the_clicks_and_the_bye_reach_the_console() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let at = listener.local_addr().unwrap().to_string();
+        let console = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut lines = BufReader::new(stream.try_clone().unwrap()).lines();
+            let hello = Message::parse(&lines.next().unwrap().unwrap()).unwrap();
+            assert!(matches!(hello, Message::Hello { .. }));
+            send(&mut stream, &Message::Welcome { version: VERSION }).unwrap();
+            lines
+                .map(|l| Message::parse(&l.unwrap()).unwrap())
+                .collect::<Vec<_>>()
+        });
+
+        let hello = Message::Hello {
+            version: VERSION,
+            station: "station".to_string(),
+            device: None,
+            detect_window: Duration::from_millis(100),
+        };
+        let mut agent = Agent::connect(&at, hello, mpsc::channel().0).unwrap();
+        let click = |ms| Message::Click {
+            at: Duration::from_millis(ms),
+            button: MouseButton::Left,
+            interval: None,
+        };
+        agent.send(click(1));
+        agent.send(click(2));
+        drop(agent);
+        assert_eq!(console.join().unwrap(), [click(1), click(2), Message::Bye]);
+    }
+}
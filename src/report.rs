@@ -0,0 +1,331 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use clap::ValueEnum;
+
+use crate::{
+    acceptance::Criteria,
+    analyzer::{Context, Registry},
+    error::{self, Error},
+    i18n, json, redact,
+    session::{self, Session},
+    stats::Estimate,
+    summary::{Format, Summary},
+    template::Template,
+};
+
+/// problems of a saved session file, empty when it matches the schema
+fn problems(path: &Path) -> error::Result<Vec<String>> {
+    let failed = |message| Error::Config {
+        path: path.to_path_buf(),
+        message,
+    };
+    let text = fs::read_to_string(path).map_err(|e| failed(e.to_string()))?;
+    let value = json::parse(&text).map_err(failed)?;
+    Ok(session::validate(&value))
+}
+
+/// check saved session files against the schema, fails when any of them doesn't match
+pub fn validate(paths: &[PathBuf]) -> error::Result<()> {
+    let mut invalid = None;
+    for path in paths {
+        let problems = problems(path)?;
+        if problems.is_empty() {
+            println!("{}: ok", path.display());
+            continue;
+        }
+        println!("{}:", path.display());
+        for problem in &problems {
+            println!("  {}", problem);
+        }
+        invalid.get_or_insert((path, problems.len()));
+    }
+
+    match invalid {
+        Some((path, count)) => Err(Error::Config {
+            path: path.to_path_buf(),
+            message: format!("{} schema problems", count),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// pool the saved sessions of one device into a single estimate
+pub fn merge(paths: &[PathBuf], confidence: f64, target: f64) -> error::Result<()> {
+    for path in paths {
+        if let Some(problem) = problems(path)?.into_iter().next() {
+            return Err(Error::Config {
+                path: path.to_path_buf(),
+                message: format!("{}, see `mouse-checker validate`", problem),
+            });
+        }
+    }
+
+    let sessions = paths
+        .iter()
+        .map(|path| Session::load(path).map(|s| (path, s)))
+        .collect::<error::Result<Vec<_>>>()?;
+
+    if let Some((_, first)) = sessions.first() {
+        if let Some((path, other)) = sessions.iter().find(|(_, s)| s.device != first.device) {
+            let name = |device: &Option<String>| device.as_deref().unwrap_or("unnamed").to_string();
+            return Err(Error::Config {
+                path: path.to_path_buf(),
+                message: format!(
+                    "recorded with device `{}`, the first session with `{}`",
+                    name(&other.device),
+                    name(&first.device)
+                ),
+            });
+        }
+    }
+
+    let (mut clicks, mut chatter) = (0, 0);
+    for (path, session) in &sessions {
+        clicks += session.click_count();
+        chatter += session.chatter_count();
+        println!(
+            "{}: {}, {} clicks, {} chatter ({}%)",
+            redact::path(path).display(),
+            session::format_timestamp(session.started_at),
+            i18n::number(session.click_count()),
+            i18n::number(session.chatter_count()),
+            i18n::decimal(session.chatter_rate() * 100.0, 1)
+        );
+    }
+
+    println!(
+        "pooled: {} sessions, {} clicks, {} chatter",
+        i18n::number(sessions.len()),
+        i18n::number(clicks),
+        i18n::number(chatter)
+    );
+    println!(
+        "{}",
+        Estimate::new(chatter, clicks, confidence, target).describe()
+    );
+    Ok(())
+}
+
+/// settings a saved session is analyzed again with, `None` keeps the recorded ones
+pub struct Reanalysis {
+    pub detect_window: Option<Duration>,
+    pub human_floor: Option<Duration>,
+    pub confidence: f64,
+    pub target: f64,
+    pub criteria: Criteria,
+    pub format: Format,
+    pub template: Option<Template>,
+}
+
+/// run the analyzers over a saved session again and print a fresh summary
+pub fn reanalyze(path: &Path, settings: &Reanalysis) -> error::Result<()> {
+    let mut session = Session::load(path)?;
+    if let Some(window) = settings.detect_window {
+        session.reclassify(window);
+    }
+    if let Some(floor) = settings.human_floor {
+        session.human_floor = floor;
+    }
+
+    let estimate = Estimate::new(
+        session.chatter_count(),
+        session.click_count(),
+        settings.confidence,
+        settings.target,
+    );
+    let summary = Summary::new(&session, estimate)
+        .with_path(Some(path))
+        .with_acceptance(&settings.criteria, &session);
+    match &settings.template {
+        Some(template) => println!("{}", template.render(&summary.template_data())),
+        None => println!("{}", summary.render(settings.format)),
+    }
+
+    if settings.format == Format::Text && settings.template.is_none() {
+        let outliers = session.gap_outliers();
+        let cx = Context {
+            session: &session,
+            outliers: &outliers,
+            now: session.clicks.last().map(|c| c.at).unwrap_or_default(),
+        };
+        for line in Registry::replay(&session).lines(&cx) {
+            let text = line
+                .spans
+                .iter()
+                .map(|s| s.content.as_ref())
+                .collect::<String>();
+            println!("  {}", text);
+        }
+    }
+
+    match summary.rejected() {
+        0 => Ok(()),
+        failed => Err(Error::Rejected {
+            failed,
+            total: summary.acceptance.len(),
+        }),
+    }
+}
+
+/// sessions listed as the worst of a day
+const WORST_OFFENDERS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum Output {
+    #[default]
+    Text,
+    Markdown,
+    Html,
+}
+
+/// what the sessions of a day are judged by
+pub struct Daily {
+    pub day: session::Day,
+    pub criteria: Criteria,
+    pub confidence: f64,
+    pub target: f64,
+    pub output: Output,
+}
+
+/// one row of the worst offenders
+struct Offender {
+    time: String,
+    device: String,
+    operator: String,
+    chatter: String,
+}
+
+/// every session saved on one day in a single summary
+pub fn daily(settings: &Daily) -> error::Result<()> {
+    let day = settings.day.number();
+    let sessions = session::list()
+        .into_iter()
+        .map(|(_, s)| s)
+        .filter(|s| session::day_of(s.started_at) == day)
+        .collect::<Vec<_>>();
+
+    // without the criteria of the config a session passes below the target rate
+    let passed = |s: &Session| {
+        let checks = settings.criteria.check(s);
+        if checks.is_empty() {
+            s.chatter_rate() <= settings.target
+        } else {
+            checks.iter().all(|c| c.pass)
+        }
+    };
+    let pass = sessions.iter().filter(|s| passed(s)).count();
+    let mut units = sessions.iter().map(|s| &s.device).collect::<Vec<_>>();
+    units.sort();
+    units.dedup();
+    let (clicks, chatter) = sessions.iter().fold((0, 0), |(clicks, chatter), s| {
+        (clicks + s.click_count(), chatter + s.chatter_count())
+    });
+
+    let mut worst = sessions
+        .iter()
+        .filter(|s| s.chatter_count() > 0)
+        .collect::<Vec<_>>();
+    worst.sort_by(|a, b| b.chatter_rate().total_cmp(&a.chatter_rate()));
+    let worst = worst
+        .into_iter()
+        .take(WORST_OFFENDERS)
+        .map(|s| Offender {
+            time: session::format_zoned(s.started_at),
+            device: redact::name(s.device.as_deref()).unwrap_or_else(|| "-".to_string()),
+            operator: redact::operator(s.operator.as_deref()).unwrap_or_else(|| "-".to_string()),
+            chatter: format!(
+                "{} in {} clicks ({}%)",
+                i18n::number(s.chatter_count()),
+                i18n::number(s.click_count()),
+                i18n::decimal(s.chatter_rate() * 100.0, 1)
+            ),
+        })
+        .collect::<Vec<_>>();
+
+    let title = format!("daily report {}", session::format_day(day));
+    let facts = [
+        ("sessions", i18n::number(sessions.len())),
+        ("units tested", i18n::number(units.len())),
+        ("passed", i18n::number(pass)),
+        ("failed", i18n::number(sessions.len() - pass)),
+        (
+            "pooled chatter",
+            Estimate::new(chatter, clicks, settings.confidence, settings.target).describe(),
+        ),
+    ];
+    let columns = ["time", "device", "operator", "chatter"];
+    let rows = worst
+        .iter()
+        .map(|o| [&o.time, &o.device, &o.operator, &o.chatter])
+        .collect::<Vec<_>>();
+
+    let text = match settings.output {
+        Output::Text => {
+            let mut lines = vec![title];
+            lines.extend(facts.iter().map(|(k, v)| format!("{}: {}", k, v)));
+            if !rows.is_empty() {
+                lines.push("worst offenders:".to_string());
+                lines.extend(
+                    rows.iter()
+                        .map(|r| format!("  {:<24}{:<16}{:<12}{}", r[0], r[1], r[2], r[3])),
+                );
+            }
+            lines.join("\n")
+        }
+        Output::Markdown => {
+            let mut lines = vec![format!("# {}", title), String::new()];
+            lines.extend(facts.iter().map(|(k, v)| format!("- {}: {}", k, v)));
+            if !rows.is_empty() {
+                lines.push(String::new());
+                lines.push("## worst offenders".to_string());
+                lines.push(String::new());
+                lines.push(format!("| {} |", columns.join(" | ")));
+                lines.push(format!("|{}", " --- |".repeat(columns.len())));
+                lines.extend(rows.iter().map(|r| {
+                    let cells = r.iter().map(|c| c.replace('|', "\\|")).collect::<Vec<_>>();
+                    format!("| {} |", cells.join(" | "))
+                }));
+            }
+            lines.join("\n")
+        }
+        Output::Html => {
+            let mut html = format!(
+                "<!doctype html>\n<html>\n<head><meta charset=\"utf-8\"><title>{0}</title></head>\n<body>\n<h1>{0}</h1>\n<ul>\n",
+                escape(&title)
+            );
+            for (k, v) in &facts {
+                html.push_str(&format!("<li>{}: {}</li>\n", k, escape(v)));
+            }
+            html.push_str("</ul>\n");
+            if !rows.is_empty() {
+                html.push_str("<h2>worst offenders</h2>\n<table>\n<tr>");
+                for column in columns {
+                    html.push_str(&format!("<th>{}</th>", column));
+                }
+                html.push_str("</tr>\n");
+                for row in &rows {
+                    html.push_str("<tr>");
+                    for cell in row {
+                        html.push_str(&format!("<td>{}</td>", escape(cell)));
+                    }
+                    html.push_str("</tr>\n");
+                }
+                html.push_str("</table>\n");
+            }
+            html.push_str("</body>\n</html>");
+            html
+        }
+    };
+    println!("{}", text);
+    Ok(())
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
@@ -0,0 +1,122 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    error::{self, Error},
+    json::{self, Value},
+    session,
+};
+
+/// how much of the history is kept, declared in the config, nothing is removed without limits
+#[derive(Debug, Clone, Default)]
+pub struct Retention {
+    pub max_age_days: Option<u64>,
+    /// size of the sessions dir, the oldest sessions go first
+    pub max_size_mb: Option<u64>,
+}
+
+/// a saved session with the charts and reports written next to it
+struct Saved {
+    started_at: u64,
+    files: Vec<PathBuf>,
+    bytes: u64,
+}
+
+impl Retention {
+    pub fn to_json(&self) -> Value {
+        json::object([
+            ("max_age_days", Value::from(self.max_age_days)),
+            ("max_size_mb", Value::from(self.max_size_mb)),
+        ])
+    }
+
+    pub fn from_json(value: &Value) -> Self {
+        Self {
+            max_age_days: value.get("max_age_days").and_then(Value::as_u64),
+            max_size_mb: value.get("max_size_mb").and_then(Value::as_u64),
+        }
+    }
+
+    /// remove the sessions past the limits, the newest is always kept, returns how many went
+    pub fn prune(&self) -> error::Result<usize> {
+        if self.max_age_days.is_none() && self.max_size_mb.is_none() {
+            return Ok(0);
+        }
+        let dir = session::sessions_dir();
+        let export = |source| Error::Export {
+            path: dir.clone(),
+            source,
+        };
+
+        let mut saved = saved(&dir).map_err(export)?;
+        saved.sort_by_key(|s| s.started_at);
+        let Some(newest) = saved.pop() else {
+            return Ok(0);
+        };
+        let cutoff = self
+            .max_age_days
+            .map(|days| session::unix_now().saturating_sub(days * 86400));
+        let limit = self.max_size_mb.map(|mb| mb * 1024 * 1024);
+        let mut total = newest.bytes + saved.iter().map(|s| s.bytes).sum::<u64>();
+
+        let mut removed = 0;
+        for old in &saved {
+            let expired = cutoff.is_some_and(|c| old.started_at < c);
+            let too_big = limit.is_some_and(|l| total > l);
+            if !expired && !too_big {
+                // the rest is newer and fits
+                break;
+            }
+            for file in &old.files {
+                fs::remove_file(file).map_err(export)?;
+            }
+            total -= old.bytes;
+            removed += 1;
+        }
+        Ok(removed)
+    }
+}
+
+/// every `<started_at>.json` and `<started_at>_<n>.json` of the dir with the files sharing
+/// its stem
+fn saved(dir: &Path) -> io::Result<Vec<Saved>> {
+    let files = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(Result::ok)
+            .filter_map(|e| Some((e.path(), e.metadata().ok()?.len())))
+            .collect::<Vec<_>>(),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let name = |path: &PathBuf| {
+        path.file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    };
+
+    Ok(files
+        .iter()
+        .filter_map(|(path, _)| {
+            let stem = name(path).strip_suffix(".json")?.to_string();
+            // sessions started in the same second are saved as `<started_at>_2.json` and on
+            let (started_at, n) = stem.split_once('_').unwrap_or((&stem, "1"));
+            let started_at = started_at.parse::<u64>().ok()?;
+            n.parse::<u32>().ok()?;
+            let related = files
+                .iter()
+                .filter(|(p, _)| {
+                    name(p)
+                        .strip_prefix(&stem)
+                        .is_some_and(|rest| rest.starts_with(['.', '-']))
+                })
+                .collect::<Vec<_>>();
+            Some(Saved {
+                started_at,
+                files: related.iter().map(|(p, _)| p.clone()).collect(),
+                bytes: related.iter().map(|(_, len)| len).sum(),
+            })
+        })
+        .collect())
+}
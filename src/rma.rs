@@ -0,0 +1,207 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    battery, checksum,
+    diagnose::{self, Outcome},
+    doctor, dump,
+    error::{self, Error},
+    handedness, html,
+    session::{self, Session},
+    stats::Estimate,
+    summary::Summary,
+    usb,
+    version::Build,
+    zip::Zip,
+};
+
+/// what goes into a warranty claim besides the outcomes of the diagnosis
+pub struct Evidence<'a> {
+    pub device: Option<&'a str>,
+    /// the raw events of the run
+    pub dump: Option<&'a Path>,
+    pub confidence: f64,
+    pub target: f64,
+}
+
+/// the file the events of the run are dumped to when `--raw-dump` names none, it goes into
+/// the package and is removed after
+pub fn open_dump() -> error::Result<PathBuf> {
+    let dir = session::data_dir().join("rma");
+    let path = dir.join(format!("{}.jsonl", session::unix_now()));
+    fs::create_dir_all(&dir).map_err(|source| Error::Export {
+        path: dir.clone(),
+        source,
+    })?;
+    dump::open(&path, false)?;
+    Ok(path)
+}
+
+/// `rma-<device>-<date>.zip` in the working directory
+pub fn default_path(device: Option<&str>) -> PathBuf {
+    let device = device
+        .unwrap_or("mouse")
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect::<String>();
+    let day = session::format_day(session::day_of(session::unix_now()));
+    PathBuf::from(format!("rma-{}-{}.zip", device, day))
+}
+
+fn device_info(device: Option<&str>) -> Vec<String> {
+    let known = |value: Option<String>| value.unwrap_or_else(|| "unknown".to_string());
+    vec![
+        format!("device: {}", device.unwrap_or("unnamed")),
+        format!(
+            "usb polling rate: {}",
+            known(usb::polling_rate().map(|hz| format!("{} Hz", hz)))
+        ),
+        format!(
+            "battery: {}",
+            known(battery::level().map(|level| format!("{} %", level)))
+        ),
+        format!(
+            "buttons swapped by the os: {}",
+            known(handedness::detected().map(|swapped| swapped.to_string()))
+        ),
+    ]
+}
+
+fn environment() -> Vec<String> {
+    let mut lines = vec![
+        Build::current().describe(),
+        format!("os: {} {}", env::consts::OS, env::consts::ARCH),
+        format!(
+            "terminal: {}",
+            env::var("TERM_PROGRAM")
+                .or_else(|_| env::var("TERM"))
+                .unwrap_or_else(|_| "unknown".to_string())
+        ),
+        String::new(),
+    ];
+    lines.extend(doctor::checklist());
+    lines
+}
+
+/// the json and html report of a click step, named after the test
+fn click_step(
+    zip: &mut Zip,
+    files: &mut Vec<(String, String)>,
+    outcome: &Outcome,
+    evidence: &Evidence,
+) {
+    let Some(session) = &outcome.session else {
+        return;
+    };
+    let session = Session {
+        device: evidence.device.map(String::from),
+        ..session.clone()
+    };
+    let name = outcome.spec.test.key();
+    let estimate = Estimate::new(
+        session.chatter_count(),
+        session.click_count(),
+        evidence.confidence,
+        evidence.target,
+    );
+    let summary = Summary::new(&session, estimate);
+    let json = checksum::seal(session.to_json()).to_string_pretty();
+    let report = checksum::seal_markup(html::render(&session, &summary));
+    zip.add(&format!("{}.json", name), json.as_bytes());
+    zip.add(&format!("{}.html", name), report.as_bytes());
+    let component = outcome.spec.test.component();
+    files.push((
+        format!("{}.json", name),
+        format!("clicks of the {} step, sealed", component),
+    ));
+    files.push((
+        format!("{}.html", name),
+        format!("report of the {} step with its charts", component),
+    ));
+}
+
+/// bundle the diagnosis into one zip to attach to a warranty claim: a cover summary, the
+/// outcomes as json, the session and html report of every click step, the raw events and
+/// what is known about the device and the machine
+pub fn write(path: &Path, outcomes: &[Outcome], evidence: &Evidence) -> error::Result<PathBuf> {
+    let now = session::unix_now();
+    let mut zip = Zip::new(now);
+    let mut files = vec![
+        ("summary.txt".to_string(), "this summary".to_string()),
+        (
+            "diagnosis.json".to_string(),
+            "the outcome of every step and the health score".to_string(),
+        ),
+    ];
+
+    zip.add(
+        "diagnosis.json",
+        diagnose::to_json(outcomes).to_string_pretty().as_bytes(),
+    );
+    for outcome in outcomes {
+        click_step(&mut zip, &mut files, outcome, evidence);
+    }
+    if let Some(dump) = evidence.dump {
+        let bytes = fs::read(dump).map_err(|source| Error::Export {
+            path: dump.to_path_buf(),
+            source,
+        })?;
+        let name = if dump::is_compact(&bytes) {
+            "events.mcdump"
+        } else {
+            "events.jsonl"
+        };
+        zip.add(name, &bytes);
+        files.push((
+            name.to_string(),
+            "every event the terminal reported, `mouse-checker --script` replays it".to_string(),
+        ));
+    }
+    zip.add(
+        "device.txt",
+        (device_info(evidence.device).join("\n") + "\n").as_bytes(),
+    );
+    zip.add(
+        "environment.txt",
+        (environment().join("\n") + "\n").as_bytes(),
+    );
+    files.push((
+        "device.txt".to_string(),
+        "name, polling rate, battery and button swap".to_string(),
+    ));
+    files.push((
+        "environment.txt".to_string(),
+        "version, os and the doctor checklist".to_string(),
+    ));
+
+    let mut cover = vec![
+        "mouse-checker warranty evidence".to_string(),
+        format!("generated {}", session::format_zoned(now)),
+        format!("device: {}", evidence.device.unwrap_or("unnamed")),
+        format!("verdict: {}", diagnose::overall(outcomes).name()),
+        String::new(),
+    ];
+    cover.extend(diagnose::report(outcomes));
+    cover.push(String::new());
+    cover.push("files:".to_string());
+    let width = files
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or_default();
+    cover.extend(
+        files
+            .iter()
+            .map(|(name, what)| format!("  {:<width$}  {}", name, what, width = width)),
+    );
+    zip.add("summary.txt", (cover.join("\n") + "\n").as_bytes());
+
+    fs::write(path, zip.finish()).map_err(|source| Error::Export {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    checksum::sign(path)?;
+    Ok(path.to_path_buf())
+}
@@ -0,0 +1,312 @@
+use std::time::Duration;
+
+use crossterm::cursor;
+#[cfg(all(feature = "uinput", target_os = "linux"))]
+use crossterm::event::{Event, MouseButton, MouseEventKind};
+
+#[cfg(all(feature = "uinput", target_os = "linux"))]
+use crate::{
+    app::AppState,
+    error::Error,
+    i18n::{self, tr, Msg},
+    loopback::Device,
+    session::{self, Session},
+    timeline::{self, Verdict},
+};
+use crate::{clock::Clock, error, input, loopback, terminal::CaptureGuard};
+
+/// timeout of the idle polls, the overshoot is the wakeup delay
+const POLL: Duration = Duration::from_millis(1);
+
+/// delays the event pipeline adds on its own
+pub struct Report {
+    /// cursor position query written to the terminal until its answer is parsed
+    pub round_trip: Vec<Duration>,
+    /// how late an idle poll returns
+    pub wakeup: Vec<Duration>,
+}
+
+/// median, 95th percentile and maximum
+pub fn spread(samples: &[Duration]) -> Option<(Duration, Duration, Duration)> {
+    let mut sorted = samples.to_vec();
+    sorted.sort();
+    let last = sorted.last().copied()?;
+    let at = |q: f64| sorted[((sorted.len() - 1) as f64 * q).round() as usize];
+    Some((at(0.5), at(0.95), last))
+}
+
+impl Report {
+    /// intervals below this cannot be told apart from pipeline noise
+    pub fn floor(&self) -> Option<Duration> {
+        let (_, trip, _) = spread(&self.round_trip)?;
+        let (_, wakeup, _) = spread(&self.wakeup)?;
+        // only the input half of the round trip delays a click
+        Some(trip / 2 + wakeup)
+    }
+}
+
+/// time `samples` terminal round trips and idle polls, keep the mouse still
+pub fn run(clock: Clock, samples: usize) -> error::Result<Report> {
+    let _guard = CaptureGuard::new()?;
+    let mut report = Report {
+        round_trip: Vec::with_capacity(samples),
+        wakeup: Vec::with_capacity(samples),
+    };
+
+    for _ in 0..samples {
+        let start = clock.now();
+        cursor::position()?;
+        report.round_trip.push(clock.now().saturating_sub(start));
+
+        let start = clock.now();
+        while input::poll(Duration::ZERO)? {
+            // stray input would end the poll early
+            input::read()?;
+        }
+        if !input::poll(POLL)? {
+            let late = clock.now().saturating_sub(start).saturating_sub(POLL);
+            report.wakeup.push(late);
+        }
+    }
+    Ok(report)
+}
+
+pub fn print_report(report: &Report) {
+    let micros = |d: Duration| d.as_micros();
+    let rows = [
+        ("terminal round trip", &report.round_trip),
+        ("poll wakeup delay", &report.wakeup),
+    ];
+    for (name, samples) in rows {
+        if let Some((median, p95, max)) = spread(samples) {
+            println!(
+                "{}: median {} µs, p95 {} µs, max {} µs ({} samples)",
+                name,
+                micros(median),
+                micros(p95),
+                micros(max),
+                samples.len()
+            );
+        }
+    }
+    match report.floor() {
+        Some(floor) => println!(
+            "measurement floor: about ±{} µs, shorter intervals are pipeline noise",
+            micros(floor)
+        ),
+        None => println!("no samples, the terminal did not answer"),
+    }
+}
+
+/// a known click pattern and the detect window verdicts it has to give
+#[cfg(all(feature = "uinput", target_os = "linux"))]
+struct Pattern {
+    name: &'static str,
+    /// button, press or release, and milliseconds since the step before
+    steps: &'static [(MouseButton, bool, u64)],
+    verdicts: &'static [Verdict],
+}
+
+#[cfg(all(feature = "uinput", target_os = "linux"))]
+const PATTERNS: [Pattern; 4] = {
+    use MouseButton::{Left, Middle, Right};
+    [
+        Pattern {
+            name: "single clicks",
+            steps: &[
+                (Left, true, 0),
+                (Left, false, 80),
+                (Left, true, 420),
+                (Left, false, 80),
+                (Left, true, 420),
+                (Left, false, 80),
+            ],
+            verdicts: &[Verdict::Clean, Verdict::Clean, Verdict::Clean],
+        },
+        Pattern {
+            name: "double click",
+            steps: &[
+                (Left, true, 0),
+                (Left, false, 60),
+                (Left, true, 90),
+                (Left, false, 60),
+            ],
+            verdicts: &[Verdict::DoubleClick],
+        },
+        Pattern {
+            name: "bounce",
+            steps: &[
+                (Left, true, 0),
+                (Left, false, 30),
+                (Left, true, 10),
+                (Left, false, 70),
+            ],
+            verdicts: &[Verdict::Chatter],
+        },
+        Pattern {
+            name: "right and middle",
+            steps: &[
+                (Right, true, 0),
+                (Right, false, 80),
+                (Middle, true, 420),
+                (Middle, false, 80),
+            ],
+            verdicts: &[Verdict::Clean, Verdict::Clean],
+        },
+    ]
+};
+
+/// detect window of the injected patterns, their single clicks are further apart
+#[cfg(all(feature = "uinput", target_os = "linux"))]
+const INJECT_WINDOW: Duration = Duration::from_millis(300);
+/// how far a captured gap or hold may be off the injected one
+#[cfg(all(feature = "uinput", target_os = "linux"))]
+const TOLERANCE: Duration = Duration::from_millis(5);
+/// time the last events of a pattern get to arrive
+#[cfg(all(feature = "uinput", target_os = "linux"))]
+const TAIL: Duration = Duration::from_millis(300);
+
+/// what the capture made of an injected pattern, no problems when it came through as sent
+pub struct Injection {
+    pub name: &'static str,
+    pub problems: Vec<String>,
+}
+
+/// feed the events arriving for `wait` into the click test the way its loop does
+#[cfg(all(feature = "uinput", target_os = "linux"))]
+fn capture(app: &mut AppState, clock: Clock, wait: Duration) -> error::Result<()> {
+    let start = clock.now();
+    while let Some(left) = wait.checked_sub(clock.now().saturating_sub(start)) {
+        if !input::poll(left)? {
+            break;
+        }
+        if let Event::Mouse(event) = input::read()? {
+            match event.kind {
+                MouseEventKind::Down(_) => app.on_click(event),
+                MouseEventKind::Up(button) => app.on_release(button),
+                _ => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(all(feature = "uinput", target_os = "linux"))]
+fn verdict_names(verdicts: &[Verdict]) -> String {
+    verdicts
+        .iter()
+        .map(|v| {
+            tr(match v {
+                Verdict::Clean => Msg::WindowClean,
+                Verdict::DoubleClick => Msg::WindowDoubleClick,
+                Verdict::Chatter => Msg::WindowChatter,
+            })
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// every way the captured clicks differ from the injected presses and releases
+#[cfg(all(feature = "uinput", target_os = "linux"))]
+fn compare(
+    pattern: &Pattern,
+    session: &Session,
+    presses: &[(MouseButton, Duration, Option<Duration>)],
+) -> Vec<String> {
+    let off = |a: Duration, b: Duration| a.abs_diff(b) > TOLERANCE;
+    let clicks = &session.clicks;
+    if clicks.len() != presses.len() {
+        return vec![format!(
+            "{} clicks captured of {} injected",
+            clicks.len(),
+            presses.len()
+        )];
+    }
+    let mut problems = Vec::new();
+    for (i, (click, (button, at, hold))) in clicks.iter().zip(presses).enumerate() {
+        if click.button != *button {
+            problems.push(format!(
+                "click {}: {} captured, {} injected",
+                i + 1,
+                session::button_name(click.button),
+                session::button_name(*button)
+            ));
+        }
+        if let (Some(captured), Some(injected)) = (click.hold, hold) {
+            if off(captured, *injected) {
+                problems.push(format!(
+                    "click {}: held {}, injected {}",
+                    i + 1,
+                    i18n::duration(captured),
+                    i18n::duration(*injected)
+                ));
+            }
+        } else if click.hold.is_none() {
+            problems.push(format!("click {}: no release captured", i + 1));
+        }
+        if i > 0 {
+            let captured = click.at.saturating_sub(clicks[i - 1].at);
+            let injected = at.saturating_sub(presses[i - 1].1);
+            if off(captured, injected) {
+                problems.push(format!(
+                    "gap {}: {} captured, {} injected",
+                    i,
+                    i18n::duration(captured),
+                    i18n::duration(injected)
+                ));
+            }
+        }
+    }
+    let verdicts = timeline::groups(session)
+        .iter()
+        .map(|g| g.verdict(session.human_floor))
+        .collect::<Vec<_>>();
+    if verdicts != pattern.verdicts {
+        problems.push(format!(
+            "windows: {} instead of {}",
+            verdict_names(&verdicts),
+            verdict_names(pattern.verdicts)
+        ));
+    }
+    problems
+}
+
+/// send known click patterns through a uinput device and check the click test records
+/// and classifies them as sent, the pointer has to rest over the terminal window
+#[cfg(all(feature = "uinput", target_os = "linux"))]
+pub fn inject(clock: Clock) -> error::Result<Vec<Injection>> {
+    let device = Device::new().map_err(Error::Loopback)?;
+    let _guard = CaptureGuard::new()?;
+    std::thread::sleep(loopback::SETTLE);
+    while input::poll(Duration::ZERO)? {
+        input::read()?;
+    }
+
+    let mut results = Vec::new();
+    for pattern in &PATTERNS {
+        let mut app = AppState::new(INJECT_WINDOW, None);
+        app.set_clock(clock);
+        let mut presses: Vec<(MouseButton, Duration, Option<Duration>)> = Vec::new();
+        for &(button, down, after) in pattern.steps {
+            capture(&mut app, clock, Duration::from_millis(after))?;
+            let at = clock.now();
+            device.press(button, down).map_err(Error::Loopback)?;
+            if down {
+                presses.push((button, at, None));
+            } else if let Some(press) = presses.iter_mut().rev().find(|p| p.0 == button) {
+                press.2 = Some(at.saturating_sub(press.1));
+            }
+        }
+        capture(&mut app, clock, TAIL)?;
+        results.push(Injection {
+            name: pattern.name,
+            problems: compare(pattern, &app.session, &presses),
+        });
+    }
+    Ok(results)
+}
+
+#[cfg(not(all(feature = "uinput", target_os = "linux")))]
+pub fn inject(_: Clock) -> error::Result<Vec<Injection>> {
+    Err(loopback::unsupported())
+}
@@ -0,0 +1,1969 @@
+use std::{
+    cell::{Ref, RefCell},
+    env,
+    fs::{self, OpenOptions},
+    io::{self, BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use clap::ValueEnum;
+use crossterm::event::{KeyModifiers, MouseButton};
+
+use crate::{
+    checksum, clock,
+    error::{self, Error},
+    i18n::{self, Msg},
+    json::{self, Value},
+    stats,
+    tally::Tally,
+    usb::Identity,
+    version::Build,
+};
+
+/// version of the saved session format, files without one are version 0; before 2 the
+/// first click of a detect window had an interval of 0
+pub const SCHEMA_VERSION: u64 = 2;
+
+/// fastest double click a hand manages, faster chatter is the switch
+pub const HUMAN_FLOOR: Duration = Duration::from_millis(60);
+
+/// gaps further than this many deviations from the median are outliers
+pub const OUTLIER_MADS: f64 = 3.0;
+
+/// a button held down this long without a release is stuck
+pub const STUCK_AFTER: Duration = Duration::from_secs(2);
+/// clicks this long after a rig said it actuated the switch still belong to that actuation
+pub const ACTUATION_WINDOW: Duration = Duration::from_millis(100);
+
+/// clicks needed before the timestamps are checked for quantization
+const QUANTUM_CLICKS: usize = 10;
+/// timer steps from here on hide chatter of the same order
+const QUANTUM_MIN: Duration = Duration::from_millis(1);
+/// gaps needed before they are checked for coalescing, and the most recent ones checked
+const COALESCE_GAPS: usize = 20;
+const COALESCE_WINDOW: usize = 200;
+/// longer gaps drift off the frame grid and say nothing about it
+const COALESCE_MAX_GAP: Duration = Duration::from_millis(500);
+/// frame times tried, in steps of `COALESCE_STEP`
+const COALESCE_FRAMES: (Duration, Duration) = (Duration::from_millis(4), Duration::from_millis(50));
+const COALESCE_STEP: Duration = Duration::from_micros(100);
+/// share of the frame a gap may miss a multiple by, at least `COALESCE_MIN_JITTER`
+const COALESCE_JITTER: f64 = 0.08;
+const COALESCE_MIN_JITTER: Duration = Duration::from_micros(200);
+/// gaps on the grid needed, by chance about twice `COALESCE_JITTER` are
+const COALESCE_FIT: f64 = 0.9;
+/// gaps between clicks longer than this are the recording machine suspended, not a pause
+const SUSPEND_GAP: Duration = Duration::from_secs(3600);
+/// different multiples of the frame the gaps need to land on, evenly spaced clicks fit
+/// every fraction of their spacing
+const COALESCE_MULTIPLES: usize = 3;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Click {
+    /// time since session start
+    pub at: Duration,
+    /// time since the first click of the detect window, none for the first click itself
+    pub interval: Option<Duration>,
+    pub button: MouseButton,
+    /// time until the button was released
+    pub hold: Option<Duration>,
+    pub bookmarked: bool,
+    /// made with a key instead of the mouse
+    pub keyboard: bool,
+    /// terminal cell the click landed on, none for key clicks and imported ones
+    pub position: Option<(u16, u16)>,
+    /// keys held down with the button
+    pub modifiers: KeyModifiers,
+}
+
+impl Click {
+    /// the interval as the list and the interval charts show it, see [`Baseline`]
+    pub fn shown_interval(&self) -> Option<Duration> {
+        self.interval.or_else(|| {
+            WINDOW_START
+                .load(Ordering::Relaxed)
+                .then_some(Duration::ZERO)
+        })
+    }
+}
+
+/// how the first click of a detect window shows in the list and the interval charts, the
+/// chatter counts and statistics never take it for an interval
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum Baseline {
+    /// it has no interval, the list shows `-` and the charts leave it out
+    #[default]
+    FirstClick,
+    /// it is 0 ms into the window it opened, as before schema version 2
+    WindowStart,
+}
+
+static WINDOW_START: AtomicBool = AtomicBool::new(false);
+
+pub fn set_baseline(baseline: Baseline) {
+    WINDOW_START.store(baseline == Baseline::WindowStart, Ordering::Relaxed);
+}
+
+/// marks starting with this end a segment, the break reminder places them
+pub const BREAK_NOTE: &str = "break";
+
+/// the clicks between two rest breaks
+#[derive(Debug, Clone, Copy)]
+pub struct Segment {
+    /// time since session start, the break before it ended
+    pub start: Duration,
+    pub clicks: usize,
+    pub chatter: usize,
+    /// median time between the presses of the segment
+    pub median_gap: Option<Duration>,
+}
+
+/// a note placed on the timeline, by a harness driving a headless capture
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mark {
+    /// time since session start
+    pub at: Duration,
+    pub note: String,
+}
+
+/// running totals of the clicks moved from memory to the spill log
+#[derive(Debug, Clone, Default)]
+pub struct Spilled {
+    pub clicks: usize,
+    pub chatter: usize,
+    /// chatter faster than the human floor
+    pub fast_chatter: usize,
+    pub shortest_chatter: Option<Duration>,
+    /// summed interval of the spilled chatter
+    pub chatter_interval: Duration,
+    /// clean streak at the end of the spilled clicks
+    pub streak: usize,
+    pub longest: usize,
+    pub last_chatter: Option<Duration>,
+}
+
+/// what was left out while input arrived faster than the screen kept up, a click is never
+/// dropped
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Backlog {
+    /// events read while more were already waiting, their timestamps may be late
+    pub events: usize,
+    /// frames not drawn to get through the waiting events first
+    pub skipped_frames: usize,
+    /// frames drawn with the stats of an earlier frame, the analyzers were still busy
+    pub stale_frames: usize,
+}
+
+impl Backlog {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    fn to_json(self) -> Value {
+        json::object([
+            ("events", Value::from(self.events as u64)),
+            ("skipped_frames", Value::from(self.skipped_frames as u64)),
+            ("stale_frames", Value::from(self.stale_frames as u64)),
+        ])
+    }
+
+    fn from_json(value: &Value) -> Self {
+        let count = |key| value.get(key).and_then(Value::as_u64).unwrap_or(0) as usize;
+        Self {
+            events: count("events"),
+            skipped_frames: count("skipped_frames"),
+            stale_frames: count("stale_frames"),
+        }
+    }
+}
+
+/// a step of the recording clock between two clicks, the later clicks were moved to
+/// continue from the earlier one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Jump {
+    /// corrected time of the first click after the step
+    pub at: Duration,
+    /// how far the clock stepped
+    pub by: Duration,
+    /// the clock went backwards, otherwise it jumped ahead by more than `SUSPEND_GAP`
+    pub back: bool,
+}
+
+impl Jump {
+    fn to_json(self) -> Value {
+        json::object([
+            ("at_us", Value::from(self.at.as_micros() as u64)),
+            ("by_us", Value::from(self.by.as_micros() as u64)),
+            ("back", Value::from(self.back)),
+        ])
+    }
+
+    fn from_json(value: &Value) -> Option<Self> {
+        let micros = |key| {
+            value
+                .get(key)
+                .and_then(Value::as_u64)
+                .map(Duration::from_micros)
+        };
+        Some(Self {
+            at: micros("at_us")?,
+            by: micros("by_us")?,
+            back: value.get("back").and_then(Value::as_bool)?,
+        })
+    }
+}
+
+/// a press still down `stuck_after` later, a button that does not return or returns only
+/// part of the way
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stuck {
+    /// time of the press
+    pub at: Duration,
+    pub button: MouseButton,
+    /// how long it stayed down, up to the last look while it was never released
+    pub held: Duration,
+    pub released: bool,
+}
+
+impl Stuck {
+    fn to_json(self) -> Value {
+        json::object([
+            ("at_us", Value::from(self.at.as_micros() as u64)),
+            ("button", Value::from(button_name(self.button))),
+            ("held_us", Value::from(self.held.as_micros() as u64)),
+            ("released", Value::from(self.released)),
+        ])
+    }
+
+    fn from_json(value: &Value) -> Option<Self> {
+        let micros = |key| {
+            value
+                .get(key)
+                .and_then(Value::as_u64)
+                .map(Duration::from_micros)
+        };
+        Some(Self {
+            at: micros("at_us")?,
+            button: value
+                .get("button")
+                .and_then(Value::as_str)
+                .and_then(parse_button)?,
+            held: micros("held_us")?,
+            released: value.get("released").and_then(Value::as_bool)?,
+        })
+    }
+}
+
+/// the size of the terminal from `at` on, click cells only compare as shares of it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Screen {
+    pub at: Duration,
+    pub columns: u16,
+    pub rows: u16,
+}
+
+impl Screen {
+    fn to_json(self) -> Value {
+        json::object([
+            ("at_us", Value::from(self.at.as_micros() as u64)),
+            ("columns", Value::from(u64::from(self.columns))),
+            ("rows", Value::from(u64::from(self.rows))),
+        ])
+    }
+
+    fn from_json(value: &Value) -> Option<Self> {
+        let cells = |key| {
+            value
+                .get(key)
+                .and_then(Value::as_u64)
+                .and_then(|n| u16::try_from(n).ok())
+        };
+        Some(Self {
+            at: value
+                .get("at_us")
+                .and_then(Value::as_u64)
+                .map(Duration::from_micros)?,
+            columns: cells("columns")?,
+            rows: cells("rows")?,
+        })
+    }
+}
+
+/// a press an actuator rig said it made, the clicks that follow show whether the switch
+/// fired
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Actuation {
+    pub at: Duration,
+    pub button: MouseButton,
+}
+
+impl Actuation {
+    fn to_json(self) -> Value {
+        json::object([
+            ("at_us", Value::from(self.at.as_micros() as u64)),
+            ("button", Value::from(button_name(self.button))),
+        ])
+    }
+
+    fn from_json(value: &Value) -> Option<Self> {
+        Some(Self {
+            at: value
+                .get("at_us")
+                .and_then(Value::as_u64)
+                .map(Duration::from_micros)?,
+            button: value
+                .get("button")
+                .and_then(Value::as_str)
+                .and_then(parse_button)?,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Session {
+    /// unix timestamp in seconds
+    pub started_at: u64,
+    pub device: Option<String>,
+    /// who ran the test, on benches shared by several people
+    pub operator: Option<String>,
+    pub detect_window: Duration,
+    pub human_floor: Duration,
+    /// battery level in percent when the session started
+    pub battery: Option<u8>,
+    /// ids, names and connection the mouse gave over usb when the session started
+    pub identity: Option<Identity>,
+    /// source of the click timestamps
+    pub clock: clock::Source,
+    /// recent clicks, older ones may have been spilled to disk
+    pub clicks: Vec<Click>,
+    pub marks: Vec<Mark>,
+    pub spilled: Spilled,
+    pub backlog: Backlog,
+    /// steps of the recording clock the click times were corrected for
+    pub jumps: Vec<Jump>,
+    /// terminal quirks worked around while recording
+    pub quirks: Vec<String>,
+    /// times of the clicks opening the detect windows the operator marked as pressed
+    /// exactly once
+    pub one_press: Vec<Duration>,
+    /// the os swapped the primary and secondary button, `button` is the one pressed
+    pub swapped: bool,
+    /// presses held longer than this without a release are stuck
+    pub stuck_after: Duration,
+    /// the stuck presses, oldest first
+    pub stuck: Vec<Stuck>,
+    /// clicks up to this long after an actuation are its own
+    pub actuation_window: Duration,
+    /// the presses an actuator rig reported, oldest first
+    pub actuations: Vec<Actuation>,
+    /// round trip of a synthetic click through the input stack, taken off the latencies
+    pub loopback: Option<Duration>,
+    /// the terminal sizes the clicks were made on, oldest first
+    pub screens: Vec<Screen>,
+    /// the build that recorded the session, none for files older than the field
+    pub build: Option<Build>,
+    /// totals of the clicks in memory, read them through `tally()` which catches up first
+    pub tally: RefCell<Tally>,
+}
+
+impl Session {
+    pub fn new(device: Option<String>, detect_window: Duration) -> Self {
+        Self {
+            started_at: unix_now(),
+            device,
+            operator: os_user(),
+            detect_window,
+            human_floor: HUMAN_FLOOR,
+            battery: None,
+            identity: None,
+            clock: clock::Source::Instant,
+            clicks: Vec::new(),
+            marks: Vec::new(),
+            spilled: Spilled::default(),
+            backlog: Backlog::default(),
+            jumps: Vec::new(),
+            quirks: Vec::new(),
+            one_press: Vec::new(),
+            swapped: false,
+            stuck_after: STUCK_AFTER,
+            stuck: Vec::new(),
+            actuation_window: ACTUATION_WINDOW,
+            actuations: Vec::new(),
+            loopback: None,
+            screens: Vec::new(),
+            build: Some(Build::current()),
+            tally: RefCell::default(),
+        }
+    }
+
+    /// note the terminal size from `at` on, the size it already has is no change
+    pub fn resize(&mut self, at: Duration, columns: u16, rows: u16) {
+        let last = self.screens.last().map(|s| (s.columns, s.rows));
+        if last != Some((columns, rows)) {
+            self.screens.push(Screen { at, columns, rows });
+        }
+    }
+
+    /// the terminal size at a time, the first one known for a time before it
+    pub fn screen_at(&self, at: Duration) -> Option<Screen> {
+        let after = self.screens.partition_point(|s| s.at <= at);
+        self.screens.get(after.saturating_sub(1)).copied()
+    }
+
+    /// the middle of the cell of a click as a share of the width and height of the terminal
+    /// it was made on, none without a cell or a known size
+    pub fn relative(&self, click: &Click) -> Option<(f64, f64)> {
+        let (column, row) = click.position?;
+        let screen = self.screen_at(click.at)?;
+        Some((
+            (f64::from(column) + 0.5) / f64::from(screen.columns.max(1)),
+            (f64::from(row) + 0.5) / f64::from(screen.rows.max(1)),
+        ))
+    }
+
+    /// every click of the session including the spilled ones
+    pub fn click_count(&self) -> usize {
+        self.spilled.clicks + self.clicks.len()
+    }
+
+    /// the totals of the clicks in memory, taking in the clicks appended since the last read
+    pub fn tally(&self) -> Ref<'_, Tally> {
+        self.tally
+            .borrow_mut()
+            .update(&self.clicks, &self.spilled, self.human_floor);
+        self.tally.borrow()
+    }
+
+    /// clicks registered again inside a detect window
+    pub fn chatter_count(&self) -> usize {
+        self.spilled.chatter + self.tally().chatter
+    }
+
+    /// interval of the fastest chatter
+    pub fn shortest_chatter(&self) -> Option<Duration> {
+        self.tally()
+            .shortest_chatter
+            .into_iter()
+            .chain(self.spilled.shortest_chatter)
+            .min()
+    }
+
+    /// classify the clicks in memory again with another detect window, the spilled ones
+    /// keep theirs, returns when the last window opened
+    pub fn reclassify(&mut self, detect_window: Duration) -> Option<Duration> {
+        self.detect_window = detect_window;
+        self.tally.take();
+        let mut window: Option<Duration> = None;
+        for click in &mut self.clicks {
+            click.interval = match window {
+                Some(start) if click.at.saturating_sub(start) <= detect_window => {
+                    Some(click.at.saturating_sub(start))
+                }
+                _ => {
+                    window = Some(click.at);
+                    None
+                }
+            };
+        }
+        window
+    }
+
+    /// the session as a firmware debounce would have reported it: presses within
+    /// `debounce` of the last release of their button are swallowed, the spilled
+    /// clicks are kept as they were
+    pub fn debounced(&self, debounce: Duration) -> Session {
+        let mut released: Vec<(MouseButton, Duration)> = Vec::new();
+        let mut clicks = Vec::new();
+        for click in &self.clicks {
+            let last = released.iter_mut().find(|(b, _)| *b == click.button);
+            let bounce = last
+                .as_ref()
+                .is_some_and(|(_, at)| click.at.saturating_sub(*at) < debounce);
+            // without a release the press itself starts the debounce
+            let release = click.at + click.hold.unwrap_or_default();
+            match last {
+                Some(last) => last.1 = release,
+                None => released.push((click.button, release)),
+            }
+            if !bounce {
+                clicks.push(*click);
+            }
+        }
+        // the cached tally belongs to the clicks before debouncing
+        let mut session = Session {
+            clicks,
+            tally: RefCell::default(),
+            ..self.clone()
+        };
+        session.reclassify(self.detect_window);
+        session
+    }
+
+    /// the clicks in memory split at the break marks, empty without any
+    pub fn segments(&self) -> Vec<Segment> {
+        let mut starts = self
+            .marks
+            .iter()
+            .filter(|m| m.note.starts_with(BREAK_NOTE))
+            .map(|m| m.at)
+            .collect::<Vec<_>>();
+        if starts.is_empty() {
+            return Vec::new();
+        }
+        starts.sort();
+        starts.insert(0, Duration::ZERO);
+        starts
+            .iter()
+            .enumerate()
+            .map(|(i, start)| {
+                let end = starts.get(i + 1).copied().unwrap_or(Duration::MAX);
+                let clicks = self
+                    .clicks
+                    .iter()
+                    .filter(|c| c.at >= *start && c.at < end)
+                    .collect::<Vec<_>>();
+                let gaps = clicks
+                    .windows(2)
+                    .map(|w| w[1].at.saturating_sub(w[0].at).as_secs_f64())
+                    .collect::<Vec<_>>();
+                Segment {
+                    start: *start,
+                    clicks: clicks.len(),
+                    chatter: clicks.iter().filter(|c| c.interval.is_some()).count(),
+                    median_gap: stats::median(&gaps).map(Duration::from_secs_f64),
+                }
+            })
+            .collect()
+    }
+
+    /// time between the two closest clicks in memory, chatter or not
+    pub fn shortest_gap(&self) -> Option<Duration> {
+        self.clicks
+            .windows(2)
+            .map(|w| w[1].at.saturating_sub(w[0].at))
+            .min()
+    }
+
+    /// chatter too fast for a hand, the rest may be a deliberate double click
+    pub fn fast_chatter_count(&self) -> usize {
+        self.spilled.fast_chatter + self.tally().fast_chatter
+    }
+
+    /// share of clicks that were chatter
+    pub fn chatter_rate(&self) -> f64 {
+        if self.click_count() == 0 {
+            0.0
+        } else {
+            self.chatter_count() as f64 / self.click_count() as f64
+        }
+    }
+
+    /// time between each click and the one before it
+    pub fn gaps(&self) -> impl Iterator<Item = Duration> + '_ {
+        self.clicks
+            .windows(2)
+            .map(|w| w[1].at.saturating_sub(w[0].at))
+    }
+
+    /// whether the gap to the previous click of each click is an outlier, worked out again
+    /// only once clicks were added
+    pub fn gap_outliers(&self) -> Vec<bool> {
+        if let Some(flags) = &self.tally().outliers {
+            return flags.clone();
+        }
+        let gaps = self.gaps().map(|g| g.as_secs_f64()).collect::<Vec<_>>();
+        let mut flags = stats::mad_outliers(&gaps, OUTLIER_MADS);
+        flags.insert(0, false);
+        flags.truncate(self.clicks.len());
+        self.tally.borrow_mut().outliers = Some(flags.clone());
+        flags
+    }
+
+    /// move the clicks after a step of the recording clock, backwards or ahead by more than
+    /// `SUSPEND_GAP`, to follow the click before it just past the detect window so the gap
+    /// across reads as no chatter and no absurd interval, the steps are added to `jumps`
+    pub fn correct_jumps(&mut self) {
+        self.tally.take();
+        let across = self.detect_window + Duration::from_micros(1);
+        let known = self.jumps.len();
+        let mut last: Option<(Duration, Duration)> = None;
+        for click in &mut self.clicks {
+            let recorded = click.at;
+            if let Some((last_recorded, last_at)) = last {
+                let step = if recorded < last_recorded {
+                    Some((true, last_recorded - recorded))
+                } else if recorded - last_recorded > SUSPEND_GAP {
+                    Some((false, recorded - last_recorded))
+                } else {
+                    None
+                };
+                click.at = match step {
+                    Some((back, by)) => {
+                        let at = last_at + across;
+                        self.jumps.push(Jump { at, by, back });
+                        at
+                    }
+                    None => last_at + (recorded - last_recorded),
+                };
+            }
+            last = Some((recorded, click.at));
+        }
+        if self.jumps.len() > known {
+            self.reclassify(self.detect_window);
+        }
+    }
+
+    /// the newest click of the button, unless it was released already, is held until `now`;
+    /// returns the stuck press this released, if that click was one
+    pub fn release(&mut self, button: MouseButton, now: Duration) -> Option<Stuck> {
+        let index = self
+            .clicks
+            .iter()
+            .rposition(|c| c.button == button)
+            .filter(|i| self.clicks[*i].hold.is_none())?;
+        let click = &mut self.clicks[index];
+        let hold = now.saturating_sub(click.at);
+        click.hold = Some(hold);
+        let at = click.at;
+        self.tally.get_mut().released(index, click, hold);
+        let stuck = self
+            .stuck
+            .iter_mut()
+            .find(|s| s.at == at && s.button == button)?;
+        stuck.held = hold;
+        stuck.released = true;
+        Some(*stuck)
+    }
+
+    /// presses down for `stuck_after` by `now` are added to the stuck ones, the newly found
+    /// are returned; a press followed by another of its button was released unseen
+    pub fn watch_stuck(&mut self, now: Duration) -> Vec<Stuck> {
+        let newest = [MouseButton::Left, MouseButton::Right, MouseButton::Middle].map(|button| {
+            self.clicks
+                .iter()
+                .rev()
+                .find(|c| c.button == button && !c.keyboard)
+                .copied()
+        });
+        for stuck in self.stuck.iter_mut().filter(|s| !s.released) {
+            let pressed_again = newest
+                .iter()
+                .flatten()
+                .any(|c| c.button == stuck.button && c.at > stuck.at);
+            // it stays held as long as it was at the last look before the next press
+            if pressed_again {
+                stuck.released = true;
+            } else {
+                stuck.held = now.saturating_sub(stuck.at);
+            }
+        }
+        let mut found = Vec::new();
+        for click in newest.into_iter().flatten() {
+            let button = click.button;
+            let held = now.saturating_sub(click.at);
+            if click.hold.is_some()
+                || held < self.stuck_after
+                || self
+                    .stuck
+                    .iter()
+                    .any(|s| s.at == click.at && s.button == button)
+            {
+                continue;
+            }
+            let stuck = Stuck {
+                at: click.at,
+                button,
+                held,
+                released: false,
+            };
+            self.stuck.push(stuck);
+            found.push(stuck);
+        }
+        found
+    }
+
+    /// current and longest run of clicks without chatter
+    pub fn clean_streaks(&self) -> (usize, usize) {
+        let tally = self.tally();
+        (tally.streak, tally.longest)
+    }
+
+    /// session time of the last chatter
+    pub fn last_chatter(&self) -> Option<Duration> {
+        self.tally().last_chatter.or(self.spilled.last_chatter)
+    }
+
+    /// step every timestamp is a multiple of when the clock looks quantized
+    pub fn quantum(&self) -> Option<Duration> {
+        if self.clicks.len() < QUANTUM_CLICKS {
+            return None;
+        }
+        let step = self
+            .clicks
+            .iter()
+            .map(|c| c.at.as_micros() as u64)
+            .fold(0, gcd);
+        let step = Duration::from_micros(step);
+        (step >= QUANTUM_MIN).then_some(step)
+    }
+
+    /// frame time the gaps between clicks snap to when the terminal coalesces events,
+    /// nothing shorter can be measured
+    pub fn coalescing(&self) -> Option<Duration> {
+        let gaps = self
+            .clicks
+            .windows(2)
+            .rev()
+            .map(|w| w[1].at.saturating_sub(w[0].at))
+            .filter(|g| !g.is_zero() && *g <= COALESCE_MAX_GAP)
+            .take(COALESCE_WINDOW)
+            .map(|g| g.as_micros() as u64)
+            .collect::<Vec<_>>();
+        if gaps.len() < COALESCE_GAPS {
+            return None;
+        }
+
+        let (min, max) = COALESCE_FRAMES;
+        let (min, max) = (min.as_micros() as u64, max.as_micros() as u64);
+        let step = COALESCE_STEP.as_micros() as u64;
+        // fractions of the frame fit too, the longest frame that fits is the real one
+        (0..=(max - min) / step)
+            .map(|i| max - i * step)
+            .find(|frame| {
+                let jitter = ((*frame as f64 * COALESCE_JITTER) as u64)
+                    .max(COALESCE_MIN_JITTER.as_micros() as u64);
+                let mut multiples = gaps
+                    .iter()
+                    .filter(|g| **g >= *frame)
+                    .filter(|g| {
+                        let rest = *g % frame;
+                        rest.min(frame - rest) <= jitter
+                    })
+                    .map(|g| (g + frame / 2) / frame)
+                    .collect::<Vec<_>>();
+                let fits = multiples.len();
+                multiples.sort_unstable();
+                multiples.dedup();
+                fits as f64 >= gaps.len() as f64 * COALESCE_FIT
+                    && multiples.len() >= COALESCE_MULTIPLES
+            })
+            .map(Duration::from_micros)
+    }
+
+    /// over the chatter, with `--baseline window-start` over every click
+    pub fn mean_interval(&self) -> Option<Duration> {
+        // the first clicks of the windows add 0 ms to the sum
+        let count = if WINDOW_START.load(Ordering::Relaxed) {
+            self.click_count()
+        } else {
+            self.chatter_count()
+        };
+        if count == 0 {
+            None
+        } else {
+            let sum = self.spilled.chatter_interval + self.tally().chatter_interval;
+            Some(sum / count as u32)
+        }
+    }
+
+    fn spill_path(&self) -> PathBuf {
+        data_dir()
+            .join("spill")
+            .join(format!("{}.log", self.started_at))
+    }
+
+    /// append the oldest `count` clicks to the spill log and drop them from memory
+    pub fn spill(&mut self, count: usize) -> error::Result<()> {
+        let count = count.min(self.clicks.len());
+        let path = self.spill_path();
+        self.append_spill(&path, count)
+            .map_err(|source| Error::Export { path, source })?;
+        self.forget(count);
+        Ok(())
+    }
+
+    /// drop the oldest `count` clicks from memory, keeping them in the totals only; a
+    /// session that forgot clicks cannot be saved whole any more
+    pub fn forget(&mut self, count: usize) {
+        let count = count.min(self.clicks.len());
+        let (streak, longest) = streaks(
+            (self.spilled.streak, self.spilled.longest),
+            &self.clicks[..count],
+        );
+        for c in self.clicks.drain(..count) {
+            self.spilled.clicks += 1;
+            if let Some(interval) = c.interval {
+                self.spilled.chatter += 1;
+                self.spilled.fast_chatter += usize::from(interval < self.human_floor);
+                self.spilled.shortest_chatter = Some(
+                    self.spilled
+                        .shortest_chatter
+                        .map_or(interval, |s| s.min(interval)),
+                );
+                self.spilled.chatter_interval += interval;
+                self.spilled.last_chatter = Some(c.at);
+            }
+        }
+        self.spilled.streak = streak;
+        self.spilled.longest = longest;
+    }
+
+    fn append_spill(&self, path: &Path, count: usize) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut log = io::BufWriter::new(OpenOptions::new().create(true).append(true).open(path)?);
+        for c in &self.clicks[..count] {
+            writeln!(
+                log,
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+                c.at.as_micros(),
+                c.interval
+                    .map(|i| i.as_micros().to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+                button_name(c.button),
+                c.hold
+                    .map(|h| h.as_micros().to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+                if c.bookmarked { "b" } else { "-" },
+                if c.keyboard { "k" } else { "-" },
+                c.position
+                    .map(|(column, row)| format!("{},{}", column, row))
+                    .unwrap_or_else(|| "-".to_string()),
+                Some(modifier_names(c.modifiers))
+                    .filter(|m| !m.is_empty())
+                    .unwrap_or_else(|| "-".to_string())
+            )?;
+        }
+        log.flush()?;
+        Ok(())
+    }
+
+    /// read the spilled clicks back from the spill log
+    fn load_spilled(&self) -> io::Result<Vec<Click>> {
+        if self.spilled.clicks == 0 {
+            return Ok(Vec::new());
+        }
+
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "corrupt spill log");
+        let micros = |field: Option<&str>| {
+            field
+                .and_then(|f| f.parse().ok())
+                .map(Duration::from_micros)
+        };
+        BufReader::new(fs::File::open(self.spill_path())?)
+            .lines()
+            .map(|line| {
+                let line = line?;
+                let mut fields = line.split('\t');
+                Ok(Click {
+                    at: micros(fields.next()).ok_or_else(invalid)?,
+                    interval: match fields.next() {
+                        Some("-") => None,
+                        field => Some(micros(field).ok_or_else(invalid)?),
+                    },
+                    button: fields.next().and_then(parse_button).ok_or_else(invalid)?,
+                    hold: micros(fields.next()),
+                    bookmarked: fields.next() == Some("b"),
+                    keyboard: fields.next() == Some("k"),
+                    position: fields.next().and_then(|p| {
+                        let (column, row) = p.split_once(',')?;
+                        Some((column.parse().ok()?, row.parse().ok()?))
+                    }),
+                    modifiers: fields
+                        .next()
+                        .and_then(parse_modifiers)
+                        .unwrap_or(KeyModifiers::NONE),
+                })
+            })
+            .collect()
+    }
+
+    /// remove the spill log once the session is saved or thrown away
+    pub fn discard_spill(&self) {
+        if self.spilled.clicks > 0 {
+            // a leftover log only wastes disk space
+            let _ = fs::remove_file(self.spill_path());
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        let clicks = self
+            .clicks
+            .iter()
+            .map(|c| {
+                json::object([
+                    ("at_us", Value::from(c.at.as_micros() as u64)),
+                    (
+                        "interval_us",
+                        Value::from(c.interval.map(|i| i.as_micros() as u64)),
+                    ),
+                    ("button", Value::from(button_name(c.button))),
+                    ("hold_us", Value::from(c.hold.map(|h| h.as_micros() as u64))),
+                    ("bookmarked", Value::from(c.bookmarked)),
+                    ("keyboard", Value::from(c.keyboard)),
+                    ("column", Value::from(c.position.map(|p| p.0 as u64))),
+                    ("row", Value::from(c.position.map(|p| p.1 as u64))),
+                    ("x", Value::from(self.relative(c).map(|p| share(p.0)))),
+                    ("y", Value::from(self.relative(c).map(|p| share(p.1)))),
+                    (
+                        "modifiers",
+                        Value::from(Some(modifier_names(c.modifiers)).filter(|m| !m.is_empty())),
+                    ),
+                ])
+            })
+            .collect::<Vec<_>>();
+        let marks = self
+            .marks
+            .iter()
+            .map(|m| {
+                json::object([
+                    ("at_us", Value::from(m.at.as_micros() as u64)),
+                    ("note", Value::from(m.note.clone())),
+                ])
+            })
+            .collect::<Vec<_>>();
+
+        json::object([
+            ("schema_version", Value::from(SCHEMA_VERSION)),
+            ("started_at", Value::from(self.started_at)),
+            ("device", Value::from(self.device.clone())),
+            ("operator", Value::from(self.operator.clone())),
+            (
+                "detect_window_us",
+                Value::from(self.detect_window.as_micros() as u64),
+            ),
+            (
+                "human_floor_us",
+                Value::from(self.human_floor.as_micros() as u64),
+            ),
+            ("battery", Value::from(self.battery.map(u64::from))),
+            (
+                "identity",
+                self.identity
+                    .as_ref()
+                    .map_or(Value::Null, Identity::to_json),
+            ),
+            ("clock", Value::from(self.clock.name())),
+            ("buttons_swapped", Value::from(self.swapped)),
+            (
+                "stuck_after_us",
+                Value::from(self.stuck_after.as_micros() as u64),
+            ),
+            (
+                "actuation_window_us",
+                Value::from(self.actuation_window.as_micros() as u64),
+            ),
+            (
+                "loopback_us",
+                Value::from(self.loopback.map(|d| d.as_micros() as u64)),
+            ),
+            ("clicks", Value::Array(clicks)),
+            ("marks", Value::Array(marks)),
+            ("backlog", self.backlog.to_json()),
+            (
+                "clock_jumps",
+                Value::Array(self.jumps.iter().map(|j| j.to_json()).collect()),
+            ),
+            (
+                "terminal_quirks",
+                Value::Array(
+                    self.quirks
+                        .iter()
+                        .map(|q| Value::from(q.as_str()))
+                        .collect(),
+                ),
+            ),
+            (
+                "one_press_us",
+                Value::Array(
+                    self.one_press
+                        .iter()
+                        .map(|at| Value::from(at.as_micros() as u64))
+                        .collect(),
+                ),
+            ),
+            (
+                "stuck",
+                Value::Array(self.stuck.iter().map(|s| s.to_json()).collect()),
+            ),
+            (
+                "actuations",
+                Value::Array(self.actuations.iter().map(|a| a.to_json()).collect()),
+            ),
+            (
+                "screens",
+                Value::Array(self.screens.iter().map(|s| s.to_json()).collect()),
+            ),
+            (
+                "build",
+                self.build.as_ref().map_or(Value::Null, Build::to_json),
+            ),
+        ])
+    }
+
+    pub fn from_json(value: &Value) -> Result<Self, String> {
+        let version = value
+            .get("schema_version")
+            .and_then(Value::as_u64)
+            .unwrap_or(0);
+        if version > SCHEMA_VERSION {
+            return Err(format!(
+                "schema version {} is newer than this build supports ({})",
+                version, SCHEMA_VERSION
+            ));
+        }
+        let micros = |v: &Value, key: &str| {
+            v.get(key)
+                .and_then(Value::as_u64)
+                .map(Duration::from_micros)
+                .ok_or_else(|| format!("missing or invalid field `{}`", key))
+        };
+
+        let clicks = value
+            .get("clicks")
+            .and_then(Value::as_array)
+            .ok_or("missing or invalid field `clicks`")?
+            .iter()
+            .map(|c| {
+                Ok(Click {
+                    at: micros(c, "at_us")?,
+                    interval: match c.get("interval_us") {
+                        Some(Value::Null) if version >= 2 => None,
+                        _ => {
+                            Some(micros(c, "interval_us")?).filter(|i| version >= 2 || !i.is_zero())
+                        }
+                    },
+                    // sessions saved before buttons were tracked only had left clicks
+                    button: c
+                        .get("button")
+                        .and_then(Value::as_str)
+                        .and_then(parse_button)
+                        .unwrap_or(MouseButton::Left),
+                    hold: micros(c, "hold_us").ok(),
+                    bookmarked: c
+                        .get("bookmarked")
+                        .and_then(Value::as_bool)
+                        .unwrap_or(false),
+                    keyboard: c.get("keyboard").and_then(Value::as_bool).unwrap_or(false),
+                    position: c
+                        .get("column")
+                        .and_then(Value::as_u64)
+                        .zip(c.get("row").and_then(Value::as_u64))
+                        .map(|(column, row)| (column as u16, row as u16)),
+                    modifiers: c
+                        .get("modifiers")
+                        .and_then(Value::as_str)
+                        .and_then(parse_modifiers)
+                        .unwrap_or(KeyModifiers::NONE),
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        let marks = value
+            .get("marks")
+            .and_then(Value::as_array)
+            .unwrap_or_default()
+            .iter()
+            .map(|m| {
+                Ok(Mark {
+                    at: micros(m, "at_us")?,
+                    note: m
+                        .get("note")
+                        .and_then(Value::as_str)
+                        .ok_or("missing or invalid field `note`")?
+                        .to_string(),
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let mut session = Self {
+            started_at: value
+                .get("started_at")
+                .and_then(Value::as_u64)
+                .ok_or("missing or invalid field `started_at`")?,
+            device: value
+                .get("device")
+                .and_then(Value::as_str)
+                .map(String::from),
+            operator: value
+                .get("operator")
+                .and_then(Value::as_str)
+                .map(String::from),
+            detect_window: micros(value, "detect_window_us")?,
+            human_floor: micros(value, "human_floor_us").unwrap_or(HUMAN_FLOOR),
+            battery: value
+                .get("battery")
+                .and_then(Value::as_u64)
+                .map(|b| b.min(100) as u8),
+            identity: value.get("identity").and_then(Identity::from_json),
+            // sessions saved before the clock was recorded used the default
+            clock: value
+                .get("clock")
+                .and_then(Value::as_str)
+                .and_then(clock::Source::parse)
+                .unwrap_or_default(),
+            clicks,
+            marks,
+            spilled: Spilled::default(),
+            backlog: value
+                .get("backlog")
+                .map(Backlog::from_json)
+                .unwrap_or_default(),
+            jumps: value
+                .get("clock_jumps")
+                .and_then(Value::as_array)
+                .unwrap_or_default()
+                .iter()
+                .filter_map(Jump::from_json)
+                .collect(),
+            quirks: value
+                .get("terminal_quirks")
+                .and_then(Value::as_array)
+                .unwrap_or_default()
+                .iter()
+                .filter_map(Value::as_str)
+                .map(String::from)
+                .collect(),
+            one_press: value
+                .get("one_press_us")
+                .and_then(Value::as_array)
+                .unwrap_or_default()
+                .iter()
+                .filter_map(Value::as_u64)
+                .map(Duration::from_micros)
+                .collect(),
+            swapped: value
+                .get("buttons_swapped")
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+            stuck_after: micros(value, "stuck_after_us").unwrap_or(STUCK_AFTER),
+            stuck: value
+                .get("stuck")
+                .and_then(Value::as_array)
+                .unwrap_or_default()
+                .iter()
+                .filter_map(Stuck::from_json)
+                .collect(),
+            actuation_window: micros(value, "actuation_window_us").unwrap_or(ACTUATION_WINDOW),
+            actuations: value
+                .get("actuations")
+                .and_then(Value::as_array)
+                .unwrap_or_default()
+                .iter()
+                .filter_map(Actuation::from_json)
+                .collect(),
+            loopback: micros(value, "loopback_us").ok(),
+            screens: value
+                .get("screens")
+                .and_then(Value::as_array)
+                .unwrap_or_default()
+                .iter()
+                .filter_map(Screen::from_json)
+                .collect(),
+            build: value.get("build").and_then(Build::from_json),
+            tally: RefCell::default(),
+        };
+        // files written by other tools or a clock that stepped during the recording
+        session.correct_jumps();
+        Ok(session)
+    }
+
+    /// a saved session, `-` reads one piped to stdin
+    pub fn load(path: &Path) -> error::Result<Self> {
+        let text = if path == Path::new("-") {
+            io::read_to_string(io::stdin())
+        } else {
+            fs::read_to_string(path)
+        };
+        text.map_err(|e| e.to_string())
+            .and_then(|text| json::parse(&text))
+            .and_then(|v| Self::from_json(&v))
+            .map_err(|message| Error::Config {
+                path: path.to_path_buf(),
+                message,
+            })
+    }
+
+    /// the session with the clicks of the spill log read back in
+    fn full(&self) -> error::Result<Session> {
+        let mut clicks = self.load_spilled().map_err(|source| Error::Export {
+            path: self.spill_path(),
+            source,
+        })?;
+        clicks.extend_from_slice(&self.clicks);
+        Ok(Session {
+            clicks,
+            spilled: Spilled::default(),
+            ..self.clone()
+        })
+    }
+
+    /// write the session into the sessions dir with the hash of its content, signed with
+    /// `--sign`, and return its path; one started in the same second as a saved one goes
+    /// next to it as `<started_at>_2.json` and so on
+    pub fn save(&self) -> error::Result<PathBuf> {
+        let dir = sessions_dir();
+        fs::create_dir_all(&dir).map_err(|source| Error::Export {
+            path: dir.clone(),
+            source,
+        })?;
+        let mut n = 1;
+        loop {
+            let path = match n {
+                1 => dir.join(format!("{}.json", self.started_at)),
+                n => dir.join(format!("{}_{}.json", self.started_at, n)),
+            };
+            // the name is taken before the session is written, so a test saving in the same
+            // second elsewhere takes the next one
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(_) => {
+                    return match self.save_to(&path) {
+                        Ok(()) => Ok(path),
+                        Err(e) => {
+                            let _ = fs::remove_file(&path);
+                            Err(e)
+                        }
+                    }
+                }
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => n += 1,
+                Err(source) => return Err(Error::Export { path, source }),
+            }
+        }
+    }
+
+    /// write the session over `path`, where it was saved before
+    pub fn save_to(&self, path: &Path) -> error::Result<()> {
+        let sealed = checksum::seal(self.full()?.to_json());
+        write_atomic(path, &sealed.to_string_pretty())?;
+        checksum::sign(path)
+    }
+
+    /// write the session so far where a crash leaves it for the next start
+    pub fn checkpoint(&self) -> error::Result<()> {
+        let path = self.checkpoint_path();
+        hold_lock(&path)?;
+        write_atomic(&path, &self.full()?.to_json().to_string_compact())
+    }
+
+    /// remove the checkpoint once the session is finished
+    pub fn discard_checkpoint(&self) {
+        let path = self.checkpoint_path();
+        let _ = fs::remove_file(&path);
+        let lock = path.with_extension("lock");
+        if let Ok(mut held) = CHECKPOINT_LOCK.lock() {
+            if held.as_ref().is_some_and(|(p, _)| *p == lock) {
+                let _ = fs::remove_file(&lock);
+                *held = None;
+            }
+        }
+    }
+
+    fn checkpoint_path(&self) -> PathBuf {
+        checkpoint_dir().join(format!("{}-{}.json", self.started_at, std::process::id()))
+    }
+}
+
+/// a temporary file renamed over the target, a crash leaves the old file or the new one
+pub fn write_atomic(path: &Path, text: &str) -> error::Result<()> {
+    let tmp = path.with_extension("tmp");
+    path.parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write(&tmp, text))
+        .and_then(|_| fs::rename(&tmp, path))
+        .map_err(|source| Error::Export {
+            path: path.to_path_buf(),
+            source,
+        })
+}
+
+fn checkpoint_dir() -> PathBuf {
+    data_dir().join("autosave")
+}
+
+/// a checkpoint of a click test that never finished, with the spill log it leaves behind
+pub struct LeftOver {
+    pub path: PathBuf,
+    pub session: Session,
+}
+
+impl LeftOver {
+    /// remove the checkpoint and the spill log, after saving it or when it is not wanted
+    pub fn discard(&self) {
+        let _ = fs::remove_file(&self.path);
+        let _ = fs::remove_file(self.path.with_extension("lock"));
+        let _ = fs::remove_file(self.session.spill_path());
+    }
+}
+
+/// the lock the running click test holds next to its checkpoint, the system lets go of it
+/// when the process ends however it ends
+static CHECKPOINT_LOCK: Mutex<Option<(PathBuf, fs::File)>> = Mutex::new(None);
+
+/// lock the `.lock` of the checkpoint for as long as the test runs, the lock of an earlier
+/// session of the process is let go
+fn hold_lock(checkpoint: &Path) -> error::Result<()> {
+    let lock = checkpoint.with_extension("lock");
+    let Ok(mut held) = CHECKPOINT_LOCK.lock() else {
+        return Ok(());
+    };
+    if held.as_ref().is_some_and(|(p, _)| *p == lock) {
+        return Ok(());
+    }
+    let failed = |source| Error::Export {
+        path: lock.clone(),
+        source,
+    };
+    fs::create_dir_all(checkpoint_dir()).map_err(failed)?;
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock)
+        .map_err(failed)?;
+    file.lock().map_err(failed)?;
+    if let Some((earlier, _)) = held.replace((lock, file)) {
+        let _ = fs::remove_file(earlier);
+    }
+    Ok(())
+}
+
+/// whether a click test still holds the lock of the checkpoint, checkpoints without one
+/// are of builds before it and left over
+fn running(checkpoint: &Path) -> bool {
+    fs::File::open(checkpoint.with_extension("lock"))
+        .is_ok_and(|lock| matches!(lock.try_lock(), Err(fs::TryLockError::WouldBlock)))
+}
+
+/// checkpoints of click tests that crashed, not of the ones still running
+pub fn left_over() -> Vec<LeftOver> {
+    let Ok(entries) = fs::read_dir(checkpoint_dir()) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .filter(|p| !running(p))
+        .filter_map(|path| {
+            Session::load(&path)
+                .ok()
+                .map(|session| LeftOver { path, session })
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Kind {
+    Uint,
+    String,
+    Bool,
+    Array,
+}
+
+/// the field if it has the expected kind, optional fields may also be null or missing
+fn field<'a>(
+    problems: &mut Vec<String>,
+    scope: &str,
+    value: &'a Value,
+    key: &str,
+    kind: Kind,
+    required: bool,
+) -> Option<&'a Value> {
+    let name = if scope.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", scope, key)
+    };
+    let found = match value.get(key) {
+        None | Some(Value::Null) if !required => return None,
+        None => {
+            problems.push(format!("`{}` is missing", name));
+            return None;
+        }
+        Some(found) => found,
+    };
+    let matches = match kind {
+        Kind::Uint => matches!(found, Value::Number(n) if *n >= 0.0 && n.fract() == 0.0),
+        Kind::String => matches!(found, Value::String(_)),
+        Kind::Bool => matches!(found, Value::Bool(_)),
+        Kind::Array => matches!(found, Value::Array(_)),
+    };
+    if matches {
+        Some(found)
+    } else {
+        let expected = match kind {
+            Kind::Uint => "a whole number",
+            Kind::String => "a string",
+            Kind::Bool => "true or false",
+            Kind::Array => "an array",
+        };
+        problems.push(format!("`{}` should be {}", name, expected));
+        None
+    }
+}
+
+/// every way a saved session differs from the current schema, empty when it is valid
+pub fn validate(value: &Value) -> Vec<String> {
+    let mut problems = Vec::new();
+    if !matches!(value, Value::Object(_)) {
+        problems.push("not a json object".to_string());
+        return problems;
+    }
+    let p = &mut problems;
+
+    if let Some(version) = field(p, "", value, "schema_version", Kind::Uint, false)
+        .and_then(Value::as_u64)
+        .filter(|v| *v > SCHEMA_VERSION)
+    {
+        p.push(format!(
+            "schema version {} is newer than this build supports ({})",
+            version, SCHEMA_VERSION
+        ));
+    }
+    if field(p, "", value, checksum::FIELD, Kind::String, false).is_some()
+        && checksum::intact(value) == Some(false)
+    {
+        p.push(format!(
+            "`{}` does not match, the file was changed after it was saved",
+            checksum::FIELD
+        ));
+    }
+    field(p, "", value, "started_at", Kind::Uint, true);
+    field(p, "", value, "device", Kind::String, false);
+    field(p, "", value, "operator", Kind::String, false);
+    field(p, "", value, "detect_window_us", Kind::Uint, true);
+    field(p, "", value, "human_floor_us", Kind::Uint, false);
+    field(p, "", value, "buttons_swapped", Kind::Bool, false);
+    if field(p, "", value, "battery", Kind::Uint, false)
+        .and_then(Value::as_u64)
+        .is_some_and(|b| b > 100)
+    {
+        p.push("`battery` should be a percentage".to_string());
+    }
+    if let Some(name) = field(p, "", value, "clock", Kind::String, false).and_then(Value::as_str) {
+        if clock::Source::parse(name).is_none() {
+            p.push(format!("unknown clock `{}`", name));
+        }
+    }
+
+    let schema = value
+        .get("schema_version")
+        .and_then(Value::as_u64)
+        .unwrap_or(0);
+    let clicks = field(p, "", value, "clicks", Kind::Array, true).and_then(Value::as_array);
+    let mut last = None;
+    for (i, click) in clicks.unwrap_or_default().iter().enumerate() {
+        let scope = format!("clicks[{}]", i);
+        let at = field(p, &scope, click, "at_us", Kind::Uint, true).and_then(Value::as_u64);
+        if at.zip(last).is_some_and(|(at, last)| at < last) {
+            p.push(format!("`{}.at_us` is before the click before it", scope));
+        }
+        last = at.or(last);
+        // null for the first click of a detect window from version 2 on
+        if schema < 2 || !matches!(click.get("interval_us"), Some(Value::Null)) {
+            field(p, &scope, click, "interval_us", Kind::Uint, true);
+        }
+        field(p, &scope, click, "hold_us", Kind::Uint, false);
+        field(p, &scope, click, "bookmarked", Kind::Bool, false);
+        field(p, &scope, click, "keyboard", Kind::Bool, false);
+        field(p, &scope, click, "column", Kind::Uint, false);
+        field(p, &scope, click, "row", Kind::Uint, false);
+        if let Some(names) =
+            field(p, &scope, click, "modifiers", Kind::String, false).and_then(Value::as_str)
+        {
+            if parse_modifiers(names).is_none() {
+                p.push(format!(
+                    "`{}.modifiers`: unknown modifiers `{}`",
+                    scope, names
+                ));
+            }
+        }
+        if let Some(name) =
+            field(p, &scope, click, "button", Kind::String, false).and_then(Value::as_str)
+        {
+            if parse_button(name).is_none() {
+                p.push(format!("`{}.button`: unknown button `{}`", scope, name));
+            }
+        }
+    }
+    let jumps = field(p, "", value, "clock_jumps", Kind::Array, false).and_then(Value::as_array);
+    for (i, jump) in jumps.unwrap_or_default().iter().enumerate() {
+        let scope = format!("clock_jumps[{}]", i);
+        field(p, &scope, jump, "at_us", Kind::Uint, true);
+        field(p, &scope, jump, "by_us", Kind::Uint, true);
+        field(p, &scope, jump, "back", Kind::Bool, true);
+    }
+    field(p, "", value, "stuck_after_us", Kind::Uint, false);
+    let stuck = field(p, "", value, "stuck", Kind::Array, false).and_then(Value::as_array);
+    for (i, entry) in stuck.unwrap_or_default().iter().enumerate() {
+        let scope = format!("stuck[{}]", i);
+        field(p, &scope, entry, "at_us", Kind::Uint, true);
+        field(p, &scope, entry, "held_us", Kind::Uint, true);
+        field(p, &scope, entry, "released", Kind::Bool, true);
+        if let Some(name) =
+            field(p, &scope, entry, "button", Kind::String, true).and_then(Value::as_str)
+        {
+            if parse_button(name).is_none() {
+                p.push(format!("`{}.button`: unknown button `{}`", scope, name));
+            }
+        }
+    }
+    field(p, "", value, "actuation_window_us", Kind::Uint, false);
+    field(p, "", value, "loopback_us", Kind::Uint, false);
+    if value
+        .get("identity")
+        .filter(|i| !matches!(i, Value::Null))
+        .is_some_and(|i| Identity::from_json(i).is_none())
+    {
+        p.push("`identity` should have a hex `vendor_id` and `product_id`".to_string());
+    }
+    let screens = field(p, "", value, "screens", Kind::Array, false).and_then(Value::as_array);
+    for (i, entry) in screens.unwrap_or_default().iter().enumerate() {
+        let scope = format!("screens[{}]", i);
+        field(p, &scope, entry, "at_us", Kind::Uint, true);
+        for key in ["columns", "rows"] {
+            let cells = field(p, &scope, entry, key, Kind::Uint, true).and_then(Value::as_u64);
+            if cells.is_some_and(|n| u16::try_from(n).is_err()) {
+                p.push(format!("`{}.{}` is no terminal size", scope, key));
+            }
+        }
+    }
+    let actuations =
+        field(p, "", value, "actuations", Kind::Array, false).and_then(Value::as_array);
+    for (i, entry) in actuations.unwrap_or_default().iter().enumerate() {
+        let scope = format!("actuations[{}]", i);
+        field(p, &scope, entry, "at_us", Kind::Uint, true);
+        if let Some(name) =
+            field(p, &scope, entry, "button", Kind::String, true).and_then(Value::as_str)
+        {
+            if parse_button(name).is_none() {
+                p.push(format!("`{}.button`: unknown button `{}`", scope, name));
+            }
+        }
+    }
+    let one_press =
+        field(p, "", value, "one_press_us", Kind::Array, false).and_then(Value::as_array);
+    for (i, at) in one_press.unwrap_or_default().iter().enumerate() {
+        if at.as_u64().is_none() {
+            p.push(format!("`one_press_us[{}]` should be a whole number", i));
+        }
+    }
+    let quirks =
+        field(p, "", value, "terminal_quirks", Kind::Array, false).and_then(Value::as_array);
+    for (i, quirk) in quirks.unwrap_or_default().iter().enumerate() {
+        if quirk.as_str().is_none() {
+            p.push(format!("`terminal_quirks[{}]` should be a string", i));
+        }
+    }
+    if let Some(backlog) = value.get("backlog") {
+        for key in ["events", "skipped_frames", "stale_frames"] {
+            field(p, "backlog", backlog, key, Kind::Uint, false);
+        }
+    }
+    let marks = field(p, "", value, "marks", Kind::Array, false).and_then(Value::as_array);
+    for (i, mark) in marks.unwrap_or_default().iter().enumerate() {
+        let scope = format!("marks[{}]", i);
+        field(p, &scope, mark, "at_us", Kind::Uint, true);
+        field(p, &scope, mark, "note", Kind::String, true);
+    }
+    problems
+}
+
+/// a share rounded to four places, finer than any terminal cell
+fn share(value: f64) -> f64 {
+    (value * 10_000.0).round() / 10_000.0
+}
+
+pub fn button_name(button: MouseButton) -> &'static str {
+    match button {
+        MouseButton::Left => "left",
+        MouseButton::Right => "right",
+        MouseButton::Middle => "middle",
+    }
+}
+
+pub fn parse_button(name: &str) -> Option<MouseButton> {
+    match name {
+        "left" => Some(MouseButton::Left),
+        "right" => Some(MouseButton::Right),
+        "middle" => Some(MouseButton::Middle),
+        _ => None,
+    }
+}
+
+const MODIFIERS: [(KeyModifiers, &str); 3] = [
+    (KeyModifiers::SHIFT, "shift"),
+    (KeyModifiers::CONTROL, "ctrl"),
+    (KeyModifiers::ALT, "alt"),
+];
+
+/// `shift+ctrl` and the like, empty without modifiers
+pub fn modifier_names(modifiers: KeyModifiers) -> String {
+    MODIFIERS
+        .iter()
+        .filter(|(m, _)| modifiers.contains(*m))
+        .map(|(_, name)| *name)
+        .collect::<Vec<_>>()
+        .join("+")
+}
+
+pub fn parse_modifiers(names: &str) -> Option<KeyModifiers> {
+    names
+        .split('+')
+        .filter(|n| !n.is_empty())
+        .try_fold(KeyModifiers::NONE, |all, name| {
+            let (m, _) = MODIFIERS.iter().find(|(_, n)| *n == name)?;
+            Some(all | *m)
+        })
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// login name of the user running the test
+pub fn os_user() -> Option<String> {
+    ["USER", "USERNAME"]
+        .iter()
+        .find_map(|key| env::var(key).ok())
+        .filter(|name| !name.is_empty())
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// current and longest clean streak after the clicks, carrying on from `start`
+fn streaks(start: (usize, usize), clicks: &[Click]) -> (usize, usize) {
+    clicks.iter().fold(start, |(current, longest), c| {
+        let current = if c.interval.is_none() { current + 1 } else { 0 };
+        (current, longest.max(current))
+    })
+}
+
+pub fn data_dir() -> PathBuf {
+    if let Some(dir) = env::var_os("XDG_DATA_HOME").filter(|d| !d.is_empty()) {
+        return PathBuf::from(dir).join("mouse-checker");
+    }
+
+    if let Some(dir) = env::var_os("APPDATA") {
+        return PathBuf::from(dir).join("mouse-checker");
+    }
+
+    env::var_os("HOME")
+        .map(|home| PathBuf::from(home).join(".local/share/mouse-checker"))
+        .unwrap_or_else(|| PathBuf::from(".mouse-checker"))
+}
+
+pub fn sessions_dir() -> PathBuf {
+    data_dir().join("sessions")
+}
+
+/// load every readable session in the sessions dir, unreadable files are skipped
+pub fn list() -> Vec<(PathBuf, Session)> {
+    let Ok(entries) = fs::read_dir(sessions_dir()) else {
+        return Vec::new();
+    };
+
+    entries
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|p| Session::load(&p).ok().map(|s| (p, s)))
+        .collect()
+}
+
+/// timestamps are shown in utc instead of the local time zone, `--utc`
+static UTC: AtomicBool = AtomicBool::new(false);
+
+pub fn set_utc(utc: bool) {
+    UTC.store(utc, Ordering::Relaxed);
+}
+
+/// seconds the shown time zone is ahead of utc at the timestamp, daylight saving included
+fn utc_offset(secs: u64) -> i64 {
+    if UTC.load(Ordering::Relaxed) {
+        0
+    } else {
+        local_offset(secs)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn local_offset(secs: u64) -> i64 {
+    let time = secs as libc::time_t;
+    // SAFETY: tm is plain old data, all zeroes is a valid value
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    // SAFETY: localtime_r only reads the time and writes into the tm it is given
+    if unsafe { libc::localtime_r(&time, &mut tm) }.is_null() {
+        return 0;
+    }
+    tm.tm_gmtoff as i64
+}
+
+/// without a way to ask for the local zone the times are shown in utc
+#[cfg(not(target_os = "linux"))]
+fn local_offset(_secs: u64) -> i64 {
+    0
+}
+
+/// seconds since the epoch on the wall clock of the shown time zone
+fn local_secs(secs: u64) -> i64 {
+    secs as i64 + utc_offset(secs)
+}
+
+/// the day number since the epoch a timestamp falls on in the shown time zone
+pub fn day_of(secs: u64) -> i64 {
+    local_secs(secs).div_euclid(86400)
+}
+
+/// a calendar day given on the command line, today and yesterday are looked up when used
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Day {
+    Today,
+    Yesterday,
+    /// day number since the epoch
+    Date(i64),
+}
+
+impl Day {
+    pub fn number(self) -> i64 {
+        match self {
+            Day::Today => day_of(unix_now()),
+            Day::Yesterday => day_of(unix_now()) - 1,
+            Day::Date(day) => day,
+        }
+    }
+}
+
+/// a duration like `250ms`, `0.25s`, `1/4s` or `90 us`, a number without a unit counts in
+/// `bare`; the options and the settings menu all read durations with it
+pub fn parse_duration(text: &str, bare: Duration) -> Result<Duration, String> {
+    let invalid = || i18n::fmt(Msg::BadDuration, &[&text.trim()]);
+    let trimmed = text.trim();
+    let split = trimmed
+        .find(|c: char| c.is_alphabetic())
+        .unwrap_or(trimmed.len());
+    let (number, unit) = trimmed.split_at(split);
+    let unit = match unit.trim() {
+        "" => bare.as_secs_f64(),
+        "us" | "µs" => 1e-6,
+        "ms" => 1e-3,
+        "s" | "sec" => 1.0,
+        "m" | "min" => 60.0,
+        _ => return Err(invalid()),
+    };
+    let part = |p: &str| p.trim().parse::<f64>().ok();
+    let value = match number.split_once('/') {
+        Some((over, under)) => part(over)
+            .zip(part(under).filter(|u| *u != 0.0))
+            .map(|(o, u)| o / u),
+        None => part(number),
+    }
+    .ok_or_else(invalid)?;
+    Duration::try_from_secs_f64(value * unit).map_err(|_| invalid())
+}
+
+/// a duration in seconds unless it names its unit
+pub fn parse_secs(text: &str) -> Result<Duration, String> {
+    parse_duration(text, Duration::from_secs(1))
+}
+
+/// a duration in seconds longer than 0, for the lengths something is divided by
+pub fn parse_positive_secs(text: &str) -> Result<Duration, String> {
+    let duration = parse_secs(text)?;
+    if duration.is_zero() {
+        return Err(format!("`{}` {}", text.trim(), i18n::tr(Msg::DurationZero)));
+    }
+    Ok(duration)
+}
+
+/// a duration in milliseconds unless it names its unit
+pub fn parse_ms(text: &str) -> Result<Duration, String> {
+    parse_duration(text, Duration::from_millis(1))
+}
+
+/// `today`, `yesterday` or a `yyyy-mm-dd` date
+pub fn parse_day(text: &str) -> Result<Day, String> {
+    match text {
+        "today" => return Ok(Day::Today),
+        "yesterday" => return Ok(Day::Yesterday),
+        _ => {}
+    }
+    let invalid = || format!("`{}` is no date, use yyyy-mm-dd, today or yesterday", text);
+    let parts = text
+        .split('-')
+        .map(|p| p.parse::<i64>().ok())
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(invalid)?;
+    let [year, month, day] = parts[..] else {
+        return Err(invalid());
+    };
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return Err(invalid());
+    }
+
+    // days from civil, the inverse of `civil`
+    let year = year - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    Ok(Day::Date(era * 146097 + doe - 719468))
+}
+
+/// year, month and day of a day number since the epoch, see
+/// http://howardhinnant.github.io/date_algorithms.html
+fn civil(days: i64) -> (i64, i64, i64) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    (yoe + era * 400 + i64::from(month <= 2), month, day)
+}
+
+/// a day number since the epoch as `YYYY-MM-DD`
+pub fn format_day(day: i64) -> String {
+    let (year, month, day) = civil(day);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// year, month, day, hour, minute and second of a unix timestamp in the shown time zone
+pub fn date_time(secs: u64) -> [i64; 6] {
+    let local = local_secs(secs);
+    let (year, month, day) = civil(local.div_euclid(86400));
+    let rem = local.rem_euclid(86400);
+    [year, month, day, rem / 3600, rem % 3600 / 60, rem % 60]
+}
+
+/// a unix timestamp as `YYYY-MM-DD HH:MM` in the shown time zone
+pub fn format_timestamp(secs: u64) -> String {
+    let local = local_secs(secs);
+    let rem = local.rem_euclid(86400);
+    format!(
+        "{} {:02}:{:02}",
+        format_day(local.div_euclid(86400)),
+        rem / 3600,
+        rem % 3600 / 60
+    )
+}
+
+/// `format_timestamp` with the utc offset it is in, for files read elsewhere or later
+pub fn format_zoned(secs: u64) -> String {
+    let offset = utc_offset(secs);
+    let zone = if UTC.load(Ordering::Relaxed) {
+        "UTC".to_string()
+    } else {
+        let sign = if offset < 0 { '-' } else { '+' };
+        let minutes = offset.abs() / 60;
+        format!("{}{:02}:{:02}", sign, minutes / 60, minutes % 60)
+    };
+    format!("{} {}", format_timestamp(secs), zone)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// left clicks the gaps in microseconds apart
+    fn session(gaps: impl IntoIterator<Item = u64>) -> Session {
+        let mut session = Session::new(None, Duration::from_millis(100));
+        let mut at = Duration::from_secs(1);
+        for gap in gaps {
+            at += Duration::from_micros(gap);
+            session.clicks.push(Click {
+                at,
+                interval: None,
+                button: MouseButton::Left,
+                hold: None,
+                bookmarked: false,
+                keyboard: false,
+                position: None,
+                modifiers: KeyModifiers::NONE,
+            });
+        }
+        session
+    }
+
+    #[test]
+    fn gaps_on_a_60_hz_grid_find_the_frame() {
+        // one to four frames of 16.6 ms apart, a little off the grid
+        let gaps = (0..40).map(|i| (i % 4 + 1) * 16_600 + [0, 300, 150][i as usize % 3]);
+        let frame = session(gaps).coalescing().expect("a frame");
+        let off = frame.abs_diff(Duration::from_micros(16_600));
+        assert!(off <= Duration::from_micros(400), "{:?}", frame);
+    }
+
+    #[test]
+    fn a_metronome_is_no_frame() {
+        // every fraction of 125 ms fits these, but they land on one multiple only
+        let gaps = (0..40).map(|i| 125_000 + [0, 200, 100][i % 3]);
+        assert_eq!(session(gaps).coalescing(), None);
+    }
+
+    #[test]
+    fn forgotten_clicks_still_count() {
+        let mut session = session([0, 300_000, 20_000, 300_000, 300_000]);
+        session.reclassify(session.detect_window);
+        let counts = (session.click_count(), session.chatter_count());
+        session.forget(3);
+        assert_eq!(session.clicks.len(), 2);
+        assert_eq!((session.click_count(), session.chatter_count()), counts);
+        assert_eq!(counts, (5, 1));
+    }
+
+    #[test]
+    fn a_press_pressed_again_stops_being_held() {
+        let mut session = session([0]);
+        let after = session.stuck_after;
+        assert_eq!(session.watch_stuck(Duration::from_secs(1) + after).len(), 1);
+        // the release of the first press got lost
+        let pressed = Duration::from_secs(2) + after;
+        session.clicks.push(Click {
+            at: pressed,
+            ..session.clicks[0]
+        });
+        session.watch_stuck(pressed);
+        session.watch_stuck(pressed * 10);
+        assert!(session.stuck[0].released);
+        assert_eq!(session.stuck[0].held, after);
+    }
+
+    #[test]
+    fn a_checkpoint_runs_while_its_lock_is_held() {
+        let dir = env::temp_dir().join(format!("mouse-checker-lock-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let checkpoint = dir.join("1-2.json");
+        assert!(!running(&checkpoint));
+        let lock = fs::File::create(checkpoint.with_extension("lock")).unwrap();
+        lock.lock().unwrap();
+        assert!(running(&checkpoint));
+        // the process holding it ended
+        drop(lock);
+        assert!(!running(&checkpoint));
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn too_few_gaps_are_no_frame() {
+        let gaps = (0..COALESCE_GAPS as u64 - 1).map(|i| (i % 4 + 1) * 16_600);
+        assert_eq!(session(gaps).coalescing(), None);
+    }
+}
@@ -0,0 +1,293 @@
+use std::{fs, time::Duration};
+
+use crossterm::event::{Event, KeyCode};
+use ratatui::{
+    backend::Backend,
+    layout::Alignment,
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame, Terminal,
+};
+
+use crate::{app::AppState, config::Config, error, focus, input, theme};
+
+/// clicks of the sanity check
+const CHECK_CLICKS: usize = 10;
+const CONFIDENCES: [f64; 3] = [90.0, 95.0, 99.0];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    Backend,
+    Device,
+    Thresholds,
+    Check,
+}
+
+struct SetupState {
+    step: Step,
+    config: Config,
+    /// names of the detected mice
+    devices: Vec<String>,
+    device: String,
+    /// selected row of the thresholds
+    field: usize,
+    check: Option<AppState>,
+}
+
+/// input devices with a mouse handler, only known on linux
+fn detect_devices() -> Vec<String> {
+    let Ok(text) = fs::read_to_string("/proc/bus/input/devices") else {
+        return Vec::new();
+    };
+
+    text.split("\n\n")
+        .filter(|block| {
+            block
+                .lines()
+                .any(|l| l.starts_with("H: Handlers=") && l.contains("mouse"))
+        })
+        .filter_map(|block| {
+            block
+                .lines()
+                .find_map(|l| l.strip_prefix("N: Name="))
+                .map(|name| name.trim_matches('"').to_string())
+        })
+        .collect()
+}
+
+impl SetupState {
+    fn adjust(&mut self, delta: i64) {
+        let config = &mut self.config;
+        match self.field {
+            0 => {
+                let ms = config.detect_window.as_millis() as i64 + delta * 10;
+                config.detect_window = Duration::from_millis(ms.max(10) as u64);
+            }
+            1 => {
+                let current = CONFIDENCES
+                    .iter()
+                    .position(|c| *c == config.confidence)
+                    .unwrap_or(1) as i64;
+                let next = (current + delta).clamp(0, CONFIDENCES.len() as i64 - 1);
+                config.confidence = CONFIDENCES[next as usize];
+            }
+            _ => {
+                config.target_rate = (config.target_rate + delta as f64 * 0.5).max(0.5);
+            }
+        }
+    }
+
+    fn cycle_device(&mut self, delta: isize) {
+        if self.devices.is_empty() {
+            return;
+        }
+        let len = self.devices.len() as isize;
+        let index = self
+            .devices
+            .iter()
+            .position(|d| *d == self.device)
+            .map(|i| (i as isize + delta).rem_euclid(len))
+            .unwrap_or(0);
+        self.device = self.devices[index as usize].clone();
+    }
+}
+
+/// walk through the settings and a short click check, `None` when aborted
+pub fn run<B: Backend>(terminal: &mut Terminal<B>) -> error::Result<Option<Config>> {
+    let devices = detect_devices();
+    let mut state = SetupState {
+        step: Step::Backend,
+        config: Config::default(),
+        device: devices.first().cloned().unwrap_or_default(),
+        devices,
+        field: 0,
+        check: None,
+    };
+
+    loop {
+        terminal.draw(|f| ui(f, &state))?;
+
+        let event = input::read()?;
+        if let Event::Key(key) = event {
+            if key.code == KeyCode::Esc {
+                return Ok(None);
+            }
+        }
+
+        match (state.step, event) {
+            (Step::Backend, Event::Key(key)) if key.code == KeyCode::Enter => {
+                state.step = Step::Device;
+            }
+            (Step::Device, Event::Key(key)) => match key.code {
+                KeyCode::Enter => {
+                    let name = state.device.trim();
+                    state.config.device = (!name.is_empty()).then(|| name.to_string());
+                    state.step = Step::Thresholds;
+                }
+                KeyCode::Up => state.cycle_device(-1),
+                KeyCode::Down => state.cycle_device(1),
+                KeyCode::Backspace => {
+                    state.device.pop();
+                }
+                KeyCode::Char(c) => state.device.push(c),
+                _ => {}
+            },
+            (Step::Thresholds, Event::Key(key)) => match key.code {
+                KeyCode::Enter => {
+                    state.check = Some(AppState::new(
+                        state.config.detect_window,
+                        state.config.device.clone(),
+                    ));
+                    state.step = Step::Check;
+                }
+                KeyCode::Up => state.field = state.field.saturating_sub(1),
+                KeyCode::Down => state.field = (state.field + 1).min(2),
+                KeyCode::Tab => state.field = focus::step(state.field, 3, true),
+                KeyCode::BackTab => state.field = focus::step(state.field, 3, false),
+                KeyCode::Left => state.adjust(-1),
+                KeyCode::Right => state.adjust(1),
+                _ => {}
+            },
+            (Step::Check, event) => {
+                let Some(app) = state.check.as_mut() else {
+                    continue;
+                };
+                let done = app.session.click_count() >= CHECK_CLICKS;
+                match event {
+                    Event::Mouse(event) if !done => {
+                        app.on_click(event);
+                    }
+                    Event::Key(key) if done && key.code == KeyCode::Enter => {
+                        return Ok(Some(state.config));
+                    }
+                    // a mouse too broken for the check still gets its config
+                    Event::Key(key) if key.code == KeyCode::Char('s') => {
+                        return Ok(Some(state.config));
+                    }
+                    Event::Key(key) if key.code == KeyCode::Char('r') => {
+                        state.check = Some(AppState::new(
+                            state.config.detect_window,
+                            state.config.device.clone(),
+                        ));
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn ui<B: Backend>(f: &mut Frame<B>, state: &SetupState) {
+    let mut text = match state.step {
+        Step::Backend => vec![
+            Line::from("welcome to mouse-checker"),
+            Line::from(""),
+            Line::from(
+                "a few questions set up the click test, the answers are saved for the next runs",
+            ),
+            Line::from(""),
+            Line::from("backend: terminal mouse events, the only one of this build"),
+            Line::from(""),
+            Line::from("press enter to continue"),
+        ],
+        Step::Device => {
+            let mut text = vec![
+                Line::from("which mouse is tested?"),
+                Line::from(""),
+                Line::styled(
+                    format!("device: {}_", state.device),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Line::from(""),
+            ];
+            if state.devices.is_empty() {
+                text.push(Line::from(
+                    "no mouse detected, type a name or leave it empty",
+                ));
+            } else {
+                text.push(Line::from(format!(
+                    "{} detected, up/down to pick one or type a name",
+                    state.devices.len()
+                )));
+            }
+            text.push(Line::from("press enter to continue"));
+            text
+        }
+        Step::Thresholds => {
+            let fields = [
+                format!(
+                    "detect window: {} ms",
+                    state.config.detect_window.as_millis()
+                ),
+                format!("confidence: {}%", state.config.confidence),
+                format!("target chatter rate: {}%", state.config.target_rate),
+            ];
+            let mut text = vec![
+                Line::from("a second click inside the detect window counts as chatter"),
+                Line::from(""),
+            ];
+            text.extend(fields.into_iter().enumerate().map(|(i, field)| {
+                if i == state.field {
+                    Line::styled(
+                        format!("{}{}", focus::MARKER, field),
+                        theme::palette().highlight,
+                    )
+                } else {
+                    Line::from(field)
+                }
+            }));
+            text.push(Line::from(""));
+            text.push(Line::from(
+                "up/down or tab to pick, left/right to change, enter to continue",
+            ));
+            text
+        }
+        Step::Check => {
+            let (clicks, chatter) = state
+                .check
+                .as_ref()
+                .map(|app| (app.session.click_count(), app.session.chatter_count()))
+                .unwrap_or_default();
+            let mut text = vec![
+                Line::from(format!(
+                    "sanity check: click {} times, one at a time",
+                    CHECK_CLICKS
+                )),
+                Line::from(""),
+                Line::from(format!(
+                    "{}/{} clicks",
+                    clicks.min(CHECK_CLICKS),
+                    CHECK_CLICKS
+                )),
+            ];
+            if clicks < CHECK_CLICKS {
+                text.push(Line::from(""));
+                text.push(Line::from("s: save without the check"));
+            }
+            if clicks >= CHECK_CLICKS {
+                text.push(Line::from(""));
+                text.push(Line::from(if chatter == 0 {
+                    "no chatter, the mouse and the settings look fine".to_string()
+                } else {
+                    format!(
+                        "{} chatter already, the switch may be failing or the window is too long",
+                        chatter
+                    )
+                }));
+                text.push(Line::from("press enter to save, r to repeat the check"));
+            }
+            text
+        }
+    };
+    text.push(Line::from(""));
+    text.push(Line::from("esc: skip the setup"));
+
+    let p = Paragraph::new(text)
+        .block(Block::default().title("setup").borders(Borders::ALL))
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+
+    f.render_widget(p, f.size());
+}
@@ -0,0 +1,236 @@
+use std::time::Duration;
+
+use crossterm::event::{Event, KeyCode, MouseButton, MouseEventKind};
+use ratatui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout},
+    style::Color,
+    text::Line,
+    widgets::{
+        canvas::{Canvas, Points},
+        Block, Borders, Paragraph,
+    },
+    Frame, Terminal,
+};
+
+use crate::{error, input};
+
+/// terminal cells are about twice as tall as wide
+const CELL_ASPECT: f64 = 2.0;
+/// shorter strokes, in cell widths, are too coarse to tell a hand from a ruler
+const MIN_LENGTH: f64 = 20.0;
+/// strokes within this angle of an axis are not diagonals
+const AXIS_MARGIN: f64 = 20.0;
+/// the farthest cell of a stroke this close to its chord, in cell widths, is ruler straight
+const STRAIGHT: f64 = 1.5;
+/// diagonal strokes needed before a verdict
+const MIN_STROKES: usize = 5;
+/// share of straight strokes from which angle snapping is likely on, and below which off
+const LIKELY_ON: f64 = 0.8;
+const LIKELY_OFF: f64 = 0.2;
+
+/// the cells the pointer passed while the button was held
+pub struct Stroke {
+    points: Vec<(u16, u16)>,
+}
+
+impl Stroke {
+    /// chord from the first to the last cell, in cell widths
+    fn chord(&self) -> (f64, f64) {
+        let (Some(a), Some(b)) = (self.points.first(), self.points.last()) else {
+            return (0.0, 0.0);
+        };
+        (
+            f64::from(b.0) - f64::from(a.0),
+            (f64::from(b.1) - f64::from(a.1)) * CELL_ASPECT,
+        )
+    }
+
+    fn length(&self) -> f64 {
+        let (dx, dy) = self.chord();
+        dx.hypot(dy)
+    }
+
+    /// long enough and far enough from both axes to tell
+    fn is_diagonal(&self) -> bool {
+        let (dx, dy) = self.chord();
+        let angle = dy.abs().atan2(dx.abs()).to_degrees();
+        self.length() >= MIN_LENGTH && angle > AXIS_MARGIN && angle < 90.0 - AXIS_MARGIN
+    }
+
+    /// farthest any cell of the stroke strays from its chord
+    fn deviation(&self) -> f64 {
+        let (dx, dy) = self.chord();
+        let length = self.length();
+        let Some(a) = self.points.first() else {
+            return 0.0;
+        };
+        if length == 0.0 {
+            return 0.0;
+        }
+        self.points
+            .iter()
+            .map(|p| {
+                let x = f64::from(p.0) - f64::from(a.0);
+                let y = (f64::from(p.1) - f64::from(a.1)) * CELL_ASPECT;
+                (x * dy - y * dx).abs() / length
+            })
+            .fold(0.0, f64::max)
+    }
+}
+
+struct SnappingState {
+    current: Option<Stroke>,
+    strokes: Vec<Stroke>,
+}
+
+impl SnappingState {
+    fn diagonals(&self) -> impl Iterator<Item = &Stroke> {
+        self.strokes.iter().filter(|s| s.is_diagonal())
+    }
+}
+
+/// record freehand strokes until q, `None` when none were drawn
+pub fn run<B: Backend>(terminal: &mut Terminal<B>) -> error::Result<Option<Vec<Stroke>>> {
+    let mut state = SnappingState {
+        current: None,
+        strokes: Vec::new(),
+    };
+
+    loop {
+        terminal.draw(|f| ui(f, &state))?;
+
+        if !input::poll(Duration::from_millis(50))? {
+            continue;
+        }
+
+        match input::read()? {
+            Event::Mouse(event) => {
+                let cell = (event.column, event.row);
+                match event.kind {
+                    MouseEventKind::Down(MouseButton::Left) => {
+                        state.current = Some(Stroke { points: vec![cell] });
+                    }
+                    MouseEventKind::Drag(MouseButton::Left) => {
+                        if let Some(stroke) = state.current.as_mut() {
+                            if stroke.points.last() != Some(&cell) {
+                                stroke.points.push(cell);
+                            }
+                        }
+                    }
+                    MouseEventKind::Up(MouseButton::Left) => {
+                        state.strokes.extend(state.current.take());
+                    }
+                    _ => {}
+                }
+            }
+            Event::Key(key) => match key.code {
+                KeyCode::Char('q') => break,
+                KeyCode::Char('r') => {
+                    state.current = None;
+                    state.strokes.clear();
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    let strokes = state.strokes;
+    Ok((!strokes.is_empty()).then_some(strokes))
+}
+
+fn ui<B: Backend>(f: &mut Frame<B>, state: &SnappingState) {
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Length(4),
+                Constraint::Min(5),
+                Constraint::Length(1),
+            ]
+            .as_ref(),
+        )
+        .split(f.size());
+
+    let diagonals = state.diagonals().count();
+    let straight = state
+        .diagonals()
+        .filter(|s| s.deviation() <= STRAIGHT)
+        .count();
+    let summary = vec![
+        Line::from("hold the left button and draw long freehand diagonals, as you would by hand"),
+        Line::from(format!(
+            "{} diagonal strokes, {} of them ruler straight",
+            diagonals, straight
+        )),
+    ];
+    f.render_widget(
+        Paragraph::new(summary).block(
+            Block::default()
+                .title("angle snapping")
+                .borders(Borders::ALL),
+        ),
+        layout[0],
+    );
+
+    // the pointer draws right on the canvas, flip the rows for its y axis
+    let area = layout[1];
+    let (left, top) = (f64::from(area.x), f64::from(area.y));
+    let (right, bottom) = (left + f64::from(area.width), top + f64::from(area.height));
+    let coords = state
+        .current
+        .as_ref()
+        .or(state.strokes.last())
+        .map(|s| {
+            s.points
+                .iter()
+                .map(|(x, y)| (f64::from(*x), bottom + top - f64::from(*y)))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    let canvas = Canvas::default()
+        .block(Block::default().title("stroke").borders(Borders::ALL))
+        .x_bounds([left, right])
+        .y_bounds([top, bottom])
+        .paint(|ctx| {
+            ctx.draw(&Points {
+                coords: &coords,
+                color: Color::Cyan,
+            });
+        });
+    f.render_widget(canvas, area);
+
+    f.render_widget(Paragraph::new("r: reset  q: finish"), layout[2]);
+}
+
+pub fn print_report(strokes: &[Stroke]) {
+    let diagonals = strokes
+        .iter()
+        .filter(|s| s.is_diagonal())
+        .collect::<Vec<_>>();
+    let straight = diagonals
+        .iter()
+        .filter(|s| s.deviation() <= STRAIGHT)
+        .count();
+    println!(
+        "{} diagonal strokes of {}, {} ruler straight",
+        diagonals.len(),
+        strokes.len(),
+        straight
+    );
+    if diagonals.len() < MIN_STROKES {
+        println!("draw at least {} long diagonals for a verdict", MIN_STROKES);
+        return;
+    }
+
+    let share = straight as f64 / diagonals.len() as f64;
+    let verdict = if share >= LIKELY_ON {
+        "angle snapping is likely on"
+    } else if share <= LIKELY_OFF {
+        "angle snapping is likely off"
+    } else {
+        "unclear, draw more strokes"
+    };
+    println!("{} ({:.0}% straight)", verdict, share * 100.0);
+}
@@ -0,0 +1,102 @@
+use std::{fmt::Write, fs, path::PathBuf};
+
+use ratatui::{
+    buffer::Buffer,
+    style::{Color, Modifier},
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    error::{self, Error},
+    session,
+};
+
+const MODIFIERS: [(Modifier, u8); 6] = [
+    (Modifier::BOLD, 1),
+    (Modifier::DIM, 2),
+    (Modifier::ITALIC, 3),
+    (Modifier::UNDERLINED, 4),
+    (Modifier::REVERSED, 7),
+    (Modifier::CROSSED_OUT, 9),
+];
+
+/// sgr parameters of a color, `base` is 30 for the foreground and 40 for the background
+fn color(color: Color, base: u8) -> String {
+    let bright = base + 60;
+    match color {
+        Color::Reset => format!("{}", base + 9),
+        Color::Black => format!("{}", base),
+        Color::Red => format!("{}", base + 1),
+        Color::Green => format!("{}", base + 2),
+        Color::Yellow => format!("{}", base + 3),
+        Color::Blue => format!("{}", base + 4),
+        Color::Magenta => format!("{}", base + 5),
+        Color::Cyan => format!("{}", base + 6),
+        Color::Gray => format!("{}", base + 7),
+        Color::DarkGray => format!("{}", bright),
+        Color::LightRed => format!("{}", bright + 1),
+        Color::LightGreen => format!("{}", bright + 2),
+        Color::LightYellow => format!("{}", bright + 3),
+        Color::LightBlue => format!("{}", bright + 4),
+        Color::LightMagenta => format!("{}", bright + 5),
+        Color::LightCyan => format!("{}", bright + 6),
+        Color::White => format!("{}", bright + 7),
+        Color::Rgb(r, g, b) => format!("{};2;{};{};{}", base + 8, r, g, b),
+        Color::Indexed(i) => format!("{};5;{}", base + 8, i),
+    }
+}
+
+/// the frame as plain text and with ansi escapes for the colors
+pub fn render(buffer: &Buffer) -> (String, String) {
+    let (mut text, mut ansi) = (String::new(), String::new());
+    let width = buffer.area.width as usize;
+    for row in buffer.content.chunks(width) {
+        let mut style = None;
+        // cells covered by a wide symbol to their left
+        let mut skip = 0;
+        for cell in row {
+            if skip > 0 {
+                skip -= 1;
+                continue;
+            }
+            skip = cell.symbol.width().saturating_sub(1);
+
+            let current = (cell.fg, cell.bg, cell.modifier);
+            if style != Some(current) {
+                let mut sgr = vec!["0".to_string(), color(cell.fg, 30), color(cell.bg, 40)];
+                sgr.extend(
+                    MODIFIERS
+                        .iter()
+                        .filter(|(m, _)| cell.modifier.contains(*m))
+                        .map(|(_, code)| code.to_string()),
+                );
+                let _ = write!(ansi, "\x1b[{}m", sgr.join(";"));
+                style = Some(current);
+            }
+            text.push_str(&cell.symbol);
+            ansi.push_str(&cell.symbol);
+        }
+        let trimmed = text.trim_end_matches(' ').len();
+        text.truncate(trimmed);
+        text.push('\n');
+        ansi.push_str("\x1b[0m\n");
+    }
+    (text, ansi)
+}
+
+/// write the frame to a timestamped `.txt` and `.ansi` file and return the text one
+pub fn save(buffer: &Buffer) -> error::Result<PathBuf> {
+    let dir = session::data_dir().join("snapshots");
+    let name = session::unix_now().to_string();
+    let (text, ansi) = render(buffer);
+
+    let path = dir.join(format!("{}.txt", name));
+    let written = fs::create_dir_all(&dir)
+        .and_then(|_| fs::write(&path, text))
+        .and_then(|_| fs::write(dir.join(format!("{}.ansi", name)), ansi));
+    written.map_err(|source| Error::Export {
+        path: path.clone(),
+        source,
+    })?;
+    Ok(path)
+}
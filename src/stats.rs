@@ -0,0 +1,507 @@
+use crate::i18n::{self, Msg};
+
+/// chatter probability with its confidence interval
+pub struct Estimate {
+    pub rate: f64,
+    pub low: f64,
+    pub high: f64,
+    pub confidence: f64,
+    pub target: f64,
+    /// more clicks needed before the target can be confirmed
+    pub needed: Option<usize>,
+}
+
+impl Estimate {
+    pub fn new(chatter: usize, clicks: usize, confidence: f64, target: f64) -> Self {
+        let z = z_score(confidence);
+        let (low, high) = wilson(chatter, clicks, z);
+        Self {
+            rate: if clicks == 0 {
+                0.0
+            } else {
+                chatter as f64 / clicks as f64
+            },
+            low,
+            high,
+            confidence,
+            target,
+            needed: trials_needed(chatter, clicks, target, z),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        let percent = |p: f64, precision: usize| i18n::decimal(p * 100.0, precision);
+        let target = i18n::number(self.target * 100.0);
+        let mut text = i18n::fmt(
+            Msg::Estimate,
+            &[
+                &percent(self.rate, 1),
+                &percent(self.confidence, 0),
+                &percent(self.low, 1),
+                &percent(self.high, 1),
+            ],
+        );
+        text.push_str(&match self.needed {
+            Some(0) => i18n::fmt(Msg::EstimateConfirmed, &[&target]),
+            Some(n) => i18n::fmt(Msg::EstimateNeeded, &[&i18n::number(n), &target]),
+            None => i18n::fmt(Msg::EstimateAbove, &[&target]),
+        });
+        text
+    }
+}
+
+/// samples needed before outliers are flagged
+const MIN_OUTLIER_SAMPLES: usize = 8;
+
+pub fn median(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let mid = sorted.len() / 2;
+    Some(if sorted.len().is_multiple_of(2) {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    } else {
+        sorted[mid]
+    })
+}
+
+/// value below which the share `q` of the values lies, by nearest rank
+pub fn quantile(values: &[f64], q: f64) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let rank = (q * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+/// the median and the scaled median absolute deviation, none with too few values to flag
+/// any or no spread at all
+pub fn median_deviation(values: &[f64]) -> Option<(f64, f64)> {
+    if values.len() < MIN_OUTLIER_SAMPLES {
+        return None;
+    }
+    let med = median(values)?;
+    let deviations = values.iter().map(|v| (v - med).abs()).collect::<Vec<_>>();
+    // 1.4826 makes the mad consistent with the standard deviation
+    let mad = median(&deviations)? * 1.4826;
+    (mad > 0.0).then_some((med, mad))
+}
+
+/// the points thinned to the lowest and highest of each of `bins` runs, in order, so a
+/// single spike still shows; fewer than twice as many points are kept as they are
+pub fn min_max_bins(points: &[(f64, f64)], bins: usize) -> Vec<(f64, f64)> {
+    if bins == 0 || points.len() <= 2 * bins {
+        return points.to_vec();
+    }
+    let mut thinned = Vec::with_capacity(2 * bins);
+    for run in points.chunks(points.len().div_ceil(bins)) {
+        let low = run.iter().min_by(|a, b| a.1.total_cmp(&b.1));
+        let high = run.iter().max_by(|a, b| a.1.total_cmp(&b.1));
+        let (Some(low), Some(high)) = (low, high) else {
+            continue;
+        };
+        if low.0 <= high.0 {
+            thinned.extend([*low, *high]);
+        } else {
+            thinned.extend([*high, *low]);
+        }
+    }
+    thinned.dedup_by(|a, b| a.0 == b.0);
+    thinned
+}
+
+/// each point the mean of the `width` points centred on it, fewer at the ends
+pub fn moving_mean(points: &[(f64, f64)], width: usize) -> Vec<(f64, f64)> {
+    if width <= 1 {
+        return points.to_vec();
+    }
+    let mut sums = Vec::with_capacity(points.len() + 1);
+    sums.push(0.0);
+    for (_, y) in points {
+        sums.push(sums[sums.len() - 1] + y);
+    }
+    let (before, after) = ((width - 1) / 2, width / 2);
+    points
+        .iter()
+        .enumerate()
+        .map(|(i, (x, _))| {
+            let (from, to) = (i.saturating_sub(before), (i + after + 1).min(points.len()));
+            (*x, (sums[to] - sums[from]) / (to - from) as f64)
+        })
+        .collect()
+}
+
+/// flag values further than `k` scaled median absolute deviations from the median
+pub fn mad_outliers(values: &[f64], k: f64) -> Vec<bool> {
+    let Some((med, mad)) = median_deviation(values) else {
+        return vec![false; values.len()];
+    };
+    values.iter().map(|v| (v - med).abs() > k * mad).collect()
+}
+
+/// samples in each of `bins` equal bins from 0 to `max`, larger ones are left out; no bins
+/// hold nothing
+pub fn histogram(samples: &[f64], max: f64, bins: usize) -> Vec<usize> {
+    if bins == 0 {
+        return Vec::new();
+    }
+    let width = max / bins as f64;
+    let mut counts = vec![0; bins];
+    for sample in samples.iter().filter(|s| **s >= 0.0 && **s <= max) {
+        counts[((sample / width) as usize).min(bins - 1)] += 1;
+    }
+    counts
+}
+
+/// largest gap between the empirical distribution functions of two samples
+pub fn ks_distance(a: &[f64], b: &[f64]) -> f64 {
+    let sorted = |values: &[f64]| {
+        let mut sorted = values.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        sorted
+    };
+    let (a, b) = (sorted(a), sorted(b));
+    let (mut i, mut j, mut distance) = (0, 0, 0.0f64);
+    while i < a.len() && j < b.len() {
+        let x = a[i].min(b[j]);
+        while i < a.len() && a[i] <= x {
+            i += 1;
+        }
+        while j < b.len() && b[j] <= x {
+            j += 1;
+        }
+        distance = distance.max((i as f64 / a.len() as f64 - j as f64 / b.len() as f64).abs());
+    }
+    distance
+}
+
+/// ks distance samples of these sizes from one distribution exceed with probability `alpha`
+pub fn ks_critical(n: usize, m: usize, alpha: f64) -> f64 {
+    let (n, m) = (n as f64, m as f64);
+    (-(alpha / 2.0).ln() / 2.0).sqrt() * ((n + m) / (n * m)).sqrt()
+}
+
+/// least squares line through the points
+pub struct Fit {
+    pub slope: f64,
+    pub intercept: f64,
+    /// standard error of the slope
+    pub slope_error: f64,
+}
+
+impl Fit {
+    /// needs three points with different x to tell the error
+    pub fn new(points: &[(f64, f64)]) -> Option<Self> {
+        let n = points.len() as f64;
+        if points.len() < 3 {
+            return None;
+        }
+        let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+        let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+        let sxx = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum::<f64>();
+        if sxx == 0.0 {
+            return None;
+        }
+        let sxy = points
+            .iter()
+            .map(|p| (p.0 - mean_x) * (p.1 - mean_y))
+            .sum::<f64>();
+        let slope = sxy / sxx;
+        let intercept = mean_y - slope * mean_x;
+        let residuals = points
+            .iter()
+            .map(|p| (p.1 - intercept - slope * p.0).powi(2))
+            .sum::<f64>();
+        Some(Self {
+            slope,
+            intercept,
+            slope_error: (residuals / (n - 2.0) / sxx).sqrt(),
+        })
+    }
+
+    pub fn at(&self, x: f64) -> f64 {
+        self.intercept + self.slope * x
+    }
+}
+
+/// two sided z score for a confidence level in (0, 1)
+pub fn z_score(confidence: f64) -> f64 {
+    inverse_normal_cdf(1.0 - (1.0 - confidence) / 2.0)
+}
+
+/// wilson score interval of `k` successes in `n` trials
+pub fn wilson(k: usize, n: usize, z: f64) -> (f64, f64) {
+    if n == 0 {
+        return (0.0, 1.0);
+    }
+
+    let n = n as f64;
+    let p = k as f64 / n;
+    let z2 = z * z;
+    let center = (p + z2 / (2.0 * n)) / (1.0 + z2 / n);
+    let half = z / (1.0 + z2 / n) * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt();
+
+    ((center - half).max(0.0), (center + half).min(1.0))
+}
+
+/// additional trials until the upper bound drops below `target`, assuming
+/// the observed rate holds, `None` when the observed rate is already too high
+pub fn trials_needed(k: usize, n: usize, target: f64, z: f64) -> Option<usize> {
+    if wilson(k, n, z).1 < target {
+        return Some(0);
+    }
+
+    let p = if n == 0 { 0.0 } else { k as f64 / n as f64 };
+    if p >= target {
+        return None;
+    }
+
+    // the upper bound shrinks monotonically with n, so search for the size
+    let upper = |total: usize| wilson((p * total as f64).round() as usize, total, z).1;
+    let mut hi = n.max(1);
+    while upper(hi) >= target {
+        hi = hi.checked_mul(2)?;
+    }
+    let mut lo = hi / 2;
+    while lo + 1 < hi {
+        let mid = (lo + hi) / 2;
+        if upper(mid) < target {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+
+    Some(hi.saturating_sub(n))
+}
+
+/// acklam's rational approximation of the standard normal quantile
+fn inverse_normal_cdf(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969683028665376e1,
+        2.209460984245205e2,
+        -2.759285104469687e2,
+        1.38357751867269e2,
+        -3.066479806614716e1,
+        2.506628277459239e0,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e1,
+        1.615858368580409e2,
+        -1.556989798598866e2,
+        6.680131188771972e1,
+        -1.328068155288572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-3,
+        -3.223964580411365e-1,
+        -2.400758277161838e0,
+        -2.549732539343734e0,
+        4.374664141464968e0,
+        2.938163982698783e0,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-3,
+        3.224671290700398e-1,
+        2.445134137142996e0,
+        3.754408661907416e0,
+    ];
+    const LOW: f64 = 0.02425;
+
+    let p = p.clamp(1e-12, 1.0 - 1e-12);
+    if p < LOW {
+        let q = (-2.0 * p.ln()).sqrt();
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    } else if p <= 1.0 - LOW {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    } else {
+        -inverse_normal_cdf(1.0 - p)
+    }
+}
+
+/// one quantile estimated by the p² algorithm, five markers moved with every sample instead
+/// of keeping and sorting the samples; exact up to five samples
+#[derive(Debug, Clone)]
+pub struct P2 {
+    q: f64,
+    count: usize,
+    /// the first five samples, sorted once they are all in, then the marker heights
+    heights: Vec<f64>,
+    positions: [f64; 5],
+    desired: [f64; 5],
+    increments: [f64; 5],
+}
+
+impl P2 {
+    pub fn new(q: f64) -> Self {
+        Self {
+            q,
+            count: 0,
+            heights: Vec::with_capacity(5),
+            positions: [1.0, 2.0, 3.0, 4.0, 5.0],
+            desired: [1.0, 1.0 + 2.0 * q, 1.0 + 4.0 * q, 3.0 + 2.0 * q, 5.0],
+            increments: [0.0, q / 2.0, q, (1.0 + q) / 2.0, 1.0],
+        }
+    }
+
+    pub fn push(&mut self, x: f64) {
+        self.count += 1;
+        if self.count <= 5 {
+            self.heights.push(x);
+            if self.count == 5 {
+                self.heights.sort_by(f64::total_cmp);
+            }
+            return;
+        }
+        let h = &mut self.heights;
+        // the cell the sample falls into, the outer markers follow the extremes
+        let cell = if x < h[0] {
+            h[0] = x;
+            0
+        } else if x >= h[4] {
+            h[4] = x;
+            3
+        } else {
+            (1..5).find(|i| x < h[*i]).unwrap_or(4) - 1
+        };
+        for position in &mut self.positions[cell + 1..] {
+            *position += 1.0;
+        }
+        for (desired, increment) in self.desired.iter_mut().zip(self.increments) {
+            *desired += increment;
+        }
+        for i in 1..4 {
+            let d = self.desired[i] - self.positions[i];
+            let n = &self.positions;
+            if (d >= 1.0 && n[i + 1] - n[i] > 1.0) || (d <= -1.0 && n[i - 1] - n[i] < -1.0) {
+                let d = d.signum();
+                let parabolic = self.parabolic(i, d);
+                let h = &self.heights;
+                self.heights[i] = if h[i - 1] < parabolic && parabolic < h[i + 1] {
+                    parabolic
+                } else {
+                    self.linear(i, d)
+                };
+                self.positions[i] += d;
+            }
+        }
+    }
+
+    fn parabolic(&self, i: usize, d: f64) -> f64 {
+        let (n, h) = (&self.positions, &self.heights);
+        h[i] + d / (n[i + 1] - n[i - 1])
+            * ((n[i] - n[i - 1] + d) * (h[i + 1] - h[i]) / (n[i + 1] - n[i])
+                + (n[i + 1] - n[i] - d) * (h[i] - h[i - 1]) / (n[i] - n[i - 1]))
+    }
+
+    fn linear(&self, i: usize, d: f64) -> f64 {
+        let (n, h) = (&self.positions, &self.heights);
+        let j = if d > 0.0 { i + 1 } else { i - 1 };
+        h[i] + d * (h[j] - h[i]) / (n[j] - n[i])
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// none before the first sample
+    pub fn value(&self) -> Option<f64> {
+        if self.count <= 5 {
+            quantile(&self.heights, self.q)
+        } else {
+            Some(self.heights[2])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn histogram_bins() {
+        let samples = [0.0, 0.5, 1.0, 2.5, 3.0, 4.0, -1.0];
+        assert_eq!(histogram(&samples, 3.0, 3), [2, 1, 2]);
+        assert_eq!(histogram(&samples, 3.0, 0), Vec::<usize>::new());
+        assert_eq!(histogram(&samples, 0.0, 2), [1, 0]);
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-4
+    }
+
+    #[test]
+    fn wilson_intervals() {
+        let z = z_score(0.95);
+        assert!(close(z, 1.959964), "{}", z);
+        let (low, high) = wilson(5, 10, z);
+        assert!(
+            close(low, 0.236593) && close(high, 0.763407),
+            "{} {}",
+            low,
+            high
+        );
+        // no chatter seen yet still leaves room above zero
+        let (low, high) = wilson(0, 10, z);
+        assert!(low == 0.0 && close(high, 0.277535), "{}", high);
+        assert_eq!(wilson(0, 0, z), (0.0, 1.0));
+    }
+
+    #[test]
+    fn trials_until_the_target_is_confirmed() {
+        let z = z_score(0.95);
+        // z² / (n + z²) drops below 1% past 380 clean clicks
+        assert_eq!(trials_needed(0, 0, 0.01, z), Some(381));
+        assert_eq!(trials_needed(0, 381, 0.01, z), Some(0));
+        assert_eq!(trials_needed(5, 100, 0.01, z), None);
+    }
+
+    #[test]
+    fn ks_distances() {
+        let a = [1.0, 2.0, 3.0, 4.0];
+        assert_eq!(ks_distance(&a, &a), 0.0);
+        assert_eq!(ks_distance(&a, &[3.0, 4.0, 5.0, 6.0]), 0.5);
+        assert_eq!(ks_distance(&a, &[10.0, 20.0]), 1.0);
+        // ties across the samples step together
+        assert_eq!(ks_distance(&[1.0, 1.0, 2.0], &[1.0, 2.0, 2.0]), 1.0 / 3.0);
+        assert!(close(ks_critical(100, 100, 0.05), 0.192064));
+    }
+
+    #[test]
+    fn p2_tracks_the_quantile() {
+        let mut median = P2::new(0.5);
+        assert_eq!(median.value(), None);
+        for x in [5.0, 1.0, 4.0] {
+            median.push(x);
+        }
+        // exact while the samples are kept
+        assert_eq!(median.value(), Some(4.0));
+
+        // the numbers up to 10006 in a scrambled order
+        let samples = (0..10_007)
+            .map(|i| (i * 7919 % 10_007) as f64)
+            .collect::<Vec<_>>();
+        for q in [0.5, 0.9, 0.99] {
+            let mut p2 = P2::new(q);
+            samples.iter().for_each(|x| p2.push(*x));
+            let exact = quantile(&samples, q).unwrap();
+            let estimate = p2.value().unwrap();
+            assert_eq!(p2.count(), samples.len());
+            assert!(
+                (estimate - exact).abs() < 100.0,
+                "{}: {} {}",
+                q,
+                estimate,
+                exact
+            );
+        }
+    }
+}
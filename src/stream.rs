@@ -0,0 +1,189 @@
+use std::{panic, time::Duration};
+
+use crossterm::event::{KeyModifiers, MouseButton};
+
+use crate::{
+    acceptance::Criteria,
+    analyzer::{Context, Registry},
+    config::Config,
+    error::{self, Error},
+    records::Records,
+    session::{Click, Session},
+    stats::Estimate,
+    summary::{Format, Summary},
+};
+
+/// longest stream a fuzz run sends
+const MAX_EVENTS: u64 = 300;
+
+/// a press or release of a button as a capture or a replay file reports it
+#[derive(Debug, Clone, Copy)]
+pub struct RawEvent {
+    /// time since the stream started
+    pub at: Duration,
+    pub button: MouseButton,
+    pub pressed: bool,
+}
+
+/// the session a live capture records from the events in the order they arrived: the clicks
+/// are corrected for time going backwards or jumping far ahead, a release without a held
+/// press is left out and a press without a release stays held
+pub fn session_of(events: &[RawEvent], detect_window: Duration) -> Session {
+    let mut session = Session::new(None, detect_window);
+    for event in events {
+        if event.pressed {
+            session.clicks.push(Click {
+                at: event.at,
+                interval: None,
+                button: event.button,
+                hold: None,
+                bookmarked: false,
+                keyboard: false,
+                position: None,
+                modifiers: KeyModifiers::NONE,
+            });
+        } else {
+            session.release(event.button, event.at);
+        }
+    }
+    session.reclassify(detect_window);
+    session.correct_jumps();
+    session
+}
+
+fn summary_of(session: &Session, config: &Config) -> Summary {
+    let estimate = Estimate::new(
+        session.chatter_count(),
+        session.click_count(),
+        config.confidence / 100.0,
+        config.target_rate / 100.0,
+    );
+    Summary::new(session, estimate)
+}
+
+/// the summary of the events with the settings of a fresh config
+pub fn from_event_stream(events: &[RawEvent]) -> Summary {
+    let config = Config::default();
+    summary_of(&session_of(events, config.detect_window), &config)
+}
+
+/// xorshift, the streams only need to be reproducible from their seed
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        // zero is the one state xorshift never leaves
+        Self(seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+}
+
+/// events in an order no mouse sends: releases before presses, repeated presses, time
+/// standing still, going backwards and jumping far ahead
+fn pathological(seed: u64) -> Vec<RawEvent> {
+    let mut rng = Rng::new(seed);
+    let mut at = Duration::ZERO;
+    (0..rng.below(MAX_EVENTS))
+        .map(|_| {
+            at = match rng.below(10) {
+                0 => Duration::ZERO,
+                1 => at.saturating_sub(Duration::from_micros(rng.below(1_000_000))),
+                2 => at.saturating_add(Duration::from_secs(rng.below(u32::MAX as u64))),
+                3 => at,
+                _ => at + Duration::from_micros(rng.below(200_000)),
+            };
+            RawEvent {
+                at,
+                button: [MouseButton::Left, MouseButton::Right, MouseButton::Middle]
+                    [rng.below(3) as usize],
+                pressed: rng.below(2) == 0,
+            }
+        })
+        .collect()
+}
+
+/// the clicks at the time each event reports and uncorrected, out of order like a file
+/// another tool wrote
+fn recorded(events: &[RawEvent], detect_window: Duration) -> Session {
+    let mut session = session_of(events, detect_window);
+    let presses = events.iter().filter(|e| e.pressed);
+    for (click, event) in session.clicks.iter_mut().zip(presses) {
+        click.at = event.at;
+    }
+    session.jumps.clear();
+    session.reclassify(detect_window);
+    session
+}
+
+/// everything that reads a session: the summary in every format, the acceptance criteria,
+/// the records, a debounce and the analyzers of the stats pane
+fn exercise(summary: &Summary, session: &Session) {
+    for format in [Format::Text, Format::Json, Format::Yaml, Format::Toml] {
+        summary.render(format);
+    }
+    let criteria = Criteria {
+        max_chatter_per_1000: Some(1.0),
+        min_peak_cps: Some(10.0),
+        max_bounce_p99: Some(Duration::from_millis(10)),
+    };
+    criteria.check(session);
+    Records::of(session);
+    session.debounced(Duration::from_millis(10));
+    let registry = Registry::replay(session);
+    let outliers = session.gap_outliers();
+    let last = session.clicks.last().map(|c| c.at).unwrap_or_default();
+    for now in [
+        Duration::ZERO,
+        last,
+        last.saturating_add(Duration::from_secs(60)),
+    ] {
+        let cx = Context {
+            session,
+            outliers: &outliers,
+            now,
+        };
+        registry.lines(&cx);
+        registry.banner(&cx);
+    }
+}
+
+/// feed `runs` pathological streams through the analyzers, the seeds of the ones that
+/// panicked are returned
+pub fn fuzz(runs: u64, seed: u64) -> Vec<u64> {
+    (seed..seed.saturating_add(runs))
+        .filter(|&seed| {
+            let events = pathological(seed);
+            let config = Config::default();
+            panic::catch_unwind(|| {
+                let live = session_of(&events, config.detect_window);
+                exercise(&from_event_stream(&events), &live);
+                let recorded = recorded(&events, config.detect_window);
+                exercise(&summary_of(&recorded, &config), &recorded);
+                if let Ok(loaded) = Session::from_json(&recorded.to_json()) {
+                    exercise(&summary_of(&loaded, &config), &loaded);
+                }
+            })
+            .is_err()
+        })
+        .collect()
+}
+
+pub fn run(runs: u64, seed: u64) -> error::Result<()> {
+    let panicked = fuzz(runs, seed);
+    if panicked.is_empty() {
+        println!("{} streams from seed {}, none panicked", runs, seed);
+        Ok(())
+    } else {
+        Err(Error::Panicked(panicked))
+    }
+}
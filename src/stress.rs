@@ -0,0 +1,160 @@
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{backend::Backend, Terminal};
+
+use crate::{
+    app::{self, AppState},
+    clock::Clock,
+    error,
+    selftest::spread,
+};
+
+/// waits shorter than this are spun, a sleep oversleeps them
+const SPIN: Duration = Duration::from_millis(1);
+/// frames are drawn at most this often, like in the click test
+const MIN_FRAME: Duration = Duration::from_millis(16);
+/// where the simulated clicks land
+const CELL: (u16, u16) = (10, 10);
+
+/// how the click test kept up with a flood of simulated events
+pub struct Report {
+    pub rate: u32,
+    /// presses sent, the duplicates included
+    pub presses: usize,
+    pub duplicates: usize,
+    /// presses the session counted
+    pub registered: usize,
+    /// from when an event was due until it was handled
+    pub latency: Vec<Duration>,
+    /// events handled after the next one was already due
+    pub behind: usize,
+    pub frames: Vec<Duration>,
+    /// gap the session recorded between a duplicate and its original
+    pub duplicate_gaps: Vec<Duration>,
+}
+
+fn mouse(kind: MouseEventKind) -> MouseEvent {
+    MouseEvent {
+        kind,
+        column: CELL.0,
+        row: CELL.1,
+        modifiers: KeyModifiers::NONE,
+    }
+}
+
+/// send `events` presses and releases at `rate` a second through the click test, every
+/// `duplicate_every`th press twice the way some terminals report it, drawing frames as
+/// the event loop does
+pub fn run<B: Backend>(
+    terminal: &mut Terminal<B>,
+    mut app: AppState,
+    clock: Clock,
+    events: usize,
+    rate: u32,
+    duplicate_every: usize,
+) -> error::Result<Report> {
+    let period = Duration::from_secs(1) / rate.max(1);
+    let mut report = Report {
+        rate,
+        presses: 0,
+        duplicates: 0,
+        registered: 0,
+        latency: Vec::with_capacity(events),
+        behind: 0,
+        frames: Vec::new(),
+        duplicate_gaps: Vec::new(),
+    };
+    let mut last_draw: Option<Instant> = None;
+    let start = clock.now();
+
+    for i in 0..events {
+        if last_draw.is_none_or(|t| t.elapsed() >= MIN_FRAME) {
+            let drawing = Instant::now();
+            terminal.draw(|f| app::ui(f, &mut app))?;
+            report.frames.push(drawing.elapsed());
+            last_draw = Some(Instant::now());
+        }
+
+        let due = start + period * i as u32;
+        while let Some(left) = due.checked_sub(clock.now()).filter(|d| !d.is_zero()) {
+            if left > SPIN {
+                thread::sleep(left - SPIN);
+            }
+        }
+
+        if i % 2 == 0 {
+            let press = mouse(MouseEventKind::Down(MouseButton::Left));
+            app.on_click(press);
+            report.presses += 1;
+            if duplicate_every > 0 && (i / 2 + 1) % duplicate_every == 0 {
+                app.on_click(press);
+                report.presses += 1;
+                report.duplicates += 1;
+                if let [.., original, copy] = app.session.clicks.as_slice() {
+                    report
+                        .duplicate_gaps
+                        .push(copy.at.saturating_sub(original.at));
+                }
+            }
+        } else {
+            app.on_release(MouseButton::Left);
+        }
+
+        let late = clock.now().saturating_sub(due);
+        if late >= period {
+            report.behind += 1;
+        }
+        report.latency.push(late);
+    }
+
+    terminal.draw(|f| app::ui(f, &mut app))?;
+    report.registered = app.session.click_count();
+    app.session.discard_spill();
+    Ok(report)
+}
+
+pub fn print_report(report: &Report) {
+    let micros = |d: Duration| d.as_micros();
+    println!(
+        "{} events at {} a second, {} presses of which {} duplicates",
+        report.latency.len(),
+        report.rate,
+        report.presses,
+        report.duplicates
+    );
+    let rows = [
+        ("handling latency", &report.latency),
+        ("frame draw time", &report.frames),
+        ("duplicate gap", &report.duplicate_gaps),
+    ];
+    for (name, samples) in rows {
+        if let Some((median, p95, max)) = spread(samples) {
+            println!(
+                "{}: median {} µs, p95 {} µs, max {} µs ({} samples)",
+                name,
+                micros(median),
+                micros(p95),
+                micros(max),
+                samples.len()
+            );
+        }
+    }
+
+    let dropped = report.presses.saturating_sub(report.registered);
+    println!(
+        "registered {} of {} presses, {} dropped",
+        report.registered, report.presses, dropped
+    );
+    if report.behind == 0 && dropped == 0 {
+        println!("kept up, every event was handled before the next was due");
+    } else {
+        println!(
+            "fell behind on {} events, timings at this rate are late by up to the max latency",
+            report.behind
+        );
+    }
+}
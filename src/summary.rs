@@ -0,0 +1,630 @@
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use clap::ValueEnum;
+
+use crate::{
+    acceptance::{Check, Criteria},
+    analyzer::{self, Actuations, Agreement, Chords, Fatigue, HoldProfile},
+    clock,
+    health::Health,
+    i18n::{self, Msg},
+    json::{self, Value},
+    redact,
+    session::{self, Backlog, Jump, Segment, Session},
+    stats::Estimate,
+    usb::Identity,
+    version::Build,
+};
+
+/// how the end of run summary is printed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum Format {
+    /// sentences in the language of the ui
+    #[default]
+    Text,
+    Json,
+    Yaml,
+    Toml,
+}
+
+/// the outcome of one session, printed at the end and handed to `--on-finish`
+pub struct Summary {
+    /// unix timestamp in seconds
+    pub started_at: u64,
+    pub device: Option<String>,
+    pub operator: Option<String>,
+    pub detect_window: Duration,
+    pub clock: clock::Source,
+    /// the os swapped the buttons, left and right are the buttons pressed
+    pub buttons_swapped: bool,
+    pub clicks: usize,
+    pub chatter: usize,
+    /// chatter faster than `human_floor`
+    pub fast_chatter: usize,
+    pub human_floor: Duration,
+    pub longest_clean_streak: usize,
+    pub mean_interval: Option<Duration>,
+    /// step the timestamps move in, when they look quantized
+    pub quantum: Option<Duration>,
+    /// frame time of a terminal coalescing events, the shortest gap that is real
+    pub measurement_floor: Option<Duration>,
+    pub holds: Option<HoldProfile>,
+    /// the detector against the windows marked as one press
+    pub agreement: Option<Agreement>,
+    /// first quarter against last of every button clicked enough
+    pub fatigue: Vec<Fatigue>,
+    /// the clicks between the rest breaks, empty without any
+    pub segments: Vec<Segment>,
+    /// presses held past the stuck timeout, with how long
+    pub stuck: Vec<Duration>,
+    pub stuck_text: Option<String>,
+    /// the presses of an actuator rig matched with their clicks, none without a rig
+    pub actuations: Option<Actuations>,
+    /// round trip of a synthetic click through the input stack, taken off their latencies
+    pub loopback: Option<Duration>,
+    /// presses of a button while another was held
+    pub chords: Chords,
+    /// firmware debounce that would have swallowed the bounces
+    pub debounce: Option<Duration>,
+    /// detect window fitting the clicking of a clean run, with the fastest gap it is based on
+    pub suggestion: Option<(Duration, Duration)>,
+    pub estimate: Estimate,
+    /// one score of the whole session for whoever does not read the rest
+    pub health: Option<Health>,
+    /// the acceptance criteria of the config, empty without any
+    pub acceptance: Vec<Check>,
+    /// where the session was saved
+    pub path: Option<PathBuf>,
+    pub backlog: Backlog,
+    /// steps of the recording clock the click times were corrected for
+    pub jumps: Vec<Jump>,
+    /// terminal quirks worked around while recording
+    pub quirks: Vec<String>,
+    /// the build that analyzed the session
+    pub build: Build,
+    /// the build that recorded it, none for files older than the field
+    pub recorded_with: Option<Build>,
+    /// what the mouse said it is over usb
+    pub identity: Option<Identity>,
+    /// human or hardware, already in the language of the ui
+    verdict: Option<String>,
+}
+
+impl Summary {
+    pub fn new(session: &Session, estimate: Estimate) -> Self {
+        Self {
+            started_at: session.started_at,
+            device: redact::name(session.device.as_deref()),
+            operator: redact::operator(session.operator.as_deref()),
+            detect_window: session.detect_window,
+            clock: session.clock,
+            buttons_swapped: session.swapped,
+            clicks: session.click_count(),
+            chatter: session.chatter_count(),
+            fast_chatter: session.fast_chatter_count(),
+            human_floor: session.human_floor,
+            longest_clean_streak: session.clean_streaks().1,
+            mean_interval: session.mean_interval(),
+            quantum: session.quantum(),
+            measurement_floor: session.coalescing(),
+            holds: HoldProfile::new(session),
+            agreement: Agreement::new(session),
+            fatigue: Fatigue::new(session),
+            segments: session.segments(),
+            stuck: session.stuck.iter().map(|s| s.held).collect(),
+            stuck_text: analyzer::describe_stuck(session),
+            chords: Chords::new(session),
+            actuations: Actuations::new(session),
+            loopback: session.loopback,
+            debounce: analyzer::debounce_recommendation(session),
+            suggestion: analyzer::suggested_window(session).zip(session.shortest_gap()),
+            estimate,
+            health: Health::of_session(session),
+            acceptance: Vec::new(),
+            path: None,
+            backlog: session.backlog,
+            jumps: session.jumps.clone(),
+            quirks: session.quirks.clone(),
+            build: Build::current(),
+            recorded_with: session.build.clone(),
+            identity: session.identity.clone(),
+            verdict: analyzer::human_verdict(session),
+        }
+    }
+
+    pub fn with_path(mut self, path: Option<&Path>) -> Self {
+        self.path = path.map(redact::path);
+        self
+    }
+
+    pub fn with_acceptance(mut self, criteria: &Criteria, session: &Session) -> Self {
+        self.acceptance = criteria.check(session);
+        self
+    }
+
+    /// criteria that failed
+    pub fn rejected(&self) -> usize {
+        self.acceptance.iter().filter(|c| !c.pass).count()
+    }
+
+    /// the machine readable fields in the order they are printed
+    fn fields(&self) -> Vec<(&'static str, Value)> {
+        let micros = |d: Duration| Value::from(d.as_micros() as u64);
+        vec![
+            ("started_at", Value::from(self.started_at)),
+            ("device", Value::from(self.device.clone())),
+            (
+                "identity",
+                self.identity
+                    .as_ref()
+                    .map_or(Value::Null, Identity::to_json),
+            ),
+            ("operator", Value::from(self.operator.clone())),
+            ("detect_window_us", micros(self.detect_window)),
+            ("clock", Value::from(self.clock.name())),
+            ("buttons_swapped", Value::from(self.buttons_swapped)),
+            ("clicks", Value::from(self.clicks as u64)),
+            ("chatter", Value::from(self.chatter as u64)),
+            ("fast_chatter", Value::from(self.fast_chatter as u64)),
+            ("human_floor_us", micros(self.human_floor)),
+            (
+                "holds",
+                Value::from(self.holds.as_ref().map(|h| h.holds as u64)),
+            ),
+            (
+                "hold_median_us",
+                Value::from(self.holds.as_ref().map(|h| micros(h.median))),
+            ),
+            (
+                "hold_p10_us",
+                Value::from(self.holds.as_ref().map(|h| micros(h.p10))),
+            ),
+            (
+                "short_holds",
+                Value::from(self.holds.as_ref().map(|h| h.short as u64)),
+            ),
+            (
+                "chatter_hold_median_us",
+                Value::from(
+                    self.holds
+                        .as_ref()
+                        .and_then(|h| h.chatter_median)
+                        .map(micros),
+                ),
+            ),
+            ("debounce_us", Value::from(self.debounce.map(micros))),
+            (
+                "one_press_marks",
+                Value::from(self.agreement.as_ref().map(|a| a.marked as u64)),
+            ),
+            (
+                "one_press_doubled",
+                Value::from(self.agreement.as_ref().map(|a| a.doubled as u64)),
+            ),
+            (
+                "one_press_agreed",
+                Value::from(self.agreement.as_ref().map(|a| a.agreed as u64)),
+            ),
+            (
+                "fatigue",
+                Value::Array(
+                    self.fatigue
+                        .iter()
+                        .map(|f| {
+                            json::object(vec![
+                                ("button", Value::from(session::button_name(f.button))),
+                                ("clicks", Value::from(f.clicks as u64)),
+                                ("first_gap_us", micros(f.first.gap)),
+                                ("last_gap_us", micros(f.last.gap)),
+                                (
+                                    "first_hold_us",
+                                    Value::from(f.first.hold.map(|h| h.as_micros() as u64)),
+                                ),
+                                (
+                                    "last_hold_us",
+                                    Value::from(f.last.hold.map(|h| h.as_micros() as u64)),
+                                ),
+                                ("first_chatter", Value::from(f.first.chatter)),
+                                ("last_chatter", Value::from(f.last.chatter)),
+                                ("rest_break", Value::from(f.around_break.is_some())),
+                                ("wear", Value::from(f.wear().map(|w| w.name()))),
+                            ])
+                        })
+                        .collect(),
+                ),
+            ),
+            ("stuck", Value::from(self.stuck.len() as u64)),
+            ("chords", Value::from(self.chords.chords.len() as u64)),
+            (
+                "actuations",
+                Value::from(self.actuations.as_ref().map(|a| {
+                    Value::Array(
+                        a.outcomes
+                            .iter()
+                            .map(|o| {
+                                json::object([
+                                    ("at_us", Value::from(o.actuation.at.as_micros() as u64)),
+                                    (
+                                        "button",
+                                        Value::from(session::button_name(o.actuation.button)),
+                                    ),
+                                    ("clicks", Value::from(o.clicks as u64)),
+                                    (
+                                        "latency_us",
+                                        Value::from(o.latency.map(|l| l.as_micros() as u64)),
+                                    ),
+                                ])
+                            })
+                            .collect(),
+                    )
+                })),
+            ),
+            (
+                "loopback_us",
+                Value::from(self.loopback.map(|d| d.as_micros() as u64)),
+            ),
+            (
+                "missed_actuations",
+                Value::from(self.actuations.as_ref().map(|a| a.missed() as u64)),
+            ),
+            (
+                "extra_clicks",
+                Value::from(self.actuations.as_ref().map(|a| a.extra() as u64)),
+            ),
+            (
+                "median_actuation_latency_us",
+                Value::from(
+                    self.actuations
+                        .as_ref()
+                        .and_then(Actuations::latency)
+                        .map(|(median, _)| median.as_micros() as u64),
+                ),
+            ),
+            (
+                "crosstalk_chords",
+                Value::from(self.chords.crosstalk() as u64),
+            ),
+            (
+                "longest_stuck_us",
+                Value::from(self.stuck.iter().max().map(|d| d.as_micros() as u64)),
+            ),
+            (
+                "segments",
+                Value::Array(
+                    self.segments
+                        .iter()
+                        .map(|s| {
+                            json::object(vec![
+                                ("start_us", micros(s.start)),
+                                ("clicks", Value::from(s.clicks as u64)),
+                                ("chatter", Value::from(s.chatter as u64)),
+                                (
+                                    "median_gap_us",
+                                    Value::from(s.median_gap.map(|g| g.as_micros() as u64)),
+                                ),
+                            ])
+                        })
+                        .collect(),
+                ),
+            ),
+            (
+                "suggested_detect_window_us",
+                Value::from(self.suggestion.map(|(window, _)| micros(window))),
+            ),
+            ("chatter_rate", Value::from(self.estimate.rate)),
+            ("chatter_low", Value::from(self.estimate.low)),
+            ("chatter_high", Value::from(self.estimate.high)),
+            ("confidence", Value::from(self.estimate.confidence)),
+            ("target_rate", Value::from(self.estimate.target)),
+            (
+                "clicks_needed",
+                Value::from(self.estimate.needed.map(|n| n as u64)),
+            ),
+            (
+                "longest_clean_streak",
+                Value::from(self.longest_clean_streak as u64),
+            ),
+            (
+                "mean_interval_us",
+                Value::from(self.mean_interval.map(micros)),
+            ),
+            ("quantum_us", Value::from(self.quantum.map(micros))),
+            (
+                "measurement_floor_us",
+                Value::from(self.measurement_floor.map(micros)),
+            ),
+            ("backlog_events", Value::from(self.backlog.events as u64)),
+            (
+                "skipped_frames",
+                Value::from(self.backlog.skipped_frames as u64),
+            ),
+            (
+                "stale_frames",
+                Value::from(self.backlog.stale_frames as u64),
+            ),
+            ("clock_jumps", Value::from(self.jumps.len() as u64)),
+            (
+                "terminal_quirks",
+                Value::Array(
+                    self.quirks
+                        .iter()
+                        .map(|q| Value::from(q.as_str()))
+                        .collect(),
+                ),
+            ),
+            (
+                "health",
+                self.health.as_ref().map_or(Value::Null, Health::to_json),
+            ),
+            (
+                "acceptance",
+                Value::Array(self.acceptance.iter().map(Check::to_json).collect()),
+            ),
+            (
+                "path",
+                Value::from(self.path.as_ref().map(|p| p.display().to_string())),
+            ),
+            ("build", self.build.to_json()),
+            (
+                "recorded_with",
+                self.recorded_with
+                    .as_ref()
+                    .map_or(Value::Null, Build::to_json),
+            ),
+        ]
+    }
+
+    pub fn to_json(&self) -> Value {
+        json::object(self.fields())
+    }
+
+    /// what a `--template` sees: the json fields and the sentences as `text`
+    pub fn template_data(&self) -> Value {
+        let mut fields = self.fields();
+        fields.push(("text", Value::from(self.text())));
+        json::object(fields)
+    }
+
+    pub fn render(&self, format: Format) -> String {
+        match format {
+            Format::Text => self.text(),
+            Format::Json => self.to_json().to_string_pretty(),
+            // json scalars are valid yaml flow scalars
+            Format::Yaml => self
+                .fields()
+                .into_iter()
+                .map(|(key, value)| format!("{}: {}", key, value.to_string_compact()))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            // toml has no null, missing keys stand for it
+            Format::Toml => self
+                .fields()
+                .into_iter()
+                .filter(|(_, value)| *value != Value::Null)
+                .map(|(key, value)| format!("{} = {}", key, toml(&value)))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
+
+    fn text(&self) -> String {
+        let mut lines = vec![
+            i18n::fmt(
+                Msg::Summary,
+                &[
+                    &i18n::number(self.clicks),
+                    &i18n::number(self.chatter),
+                    &i18n::number(self.longest_clean_streak),
+                ],
+            ),
+            self.estimate.describe(),
+        ];
+        if let Some(health) = &self.health {
+            lines.extend(health.describe());
+        }
+        lines.extend(self.verdict.clone());
+        if let Some(identity) = &self.identity {
+            lines.push(i18n::fmt(Msg::Identity, &[&identity.describe()]));
+        }
+        if self.buttons_swapped {
+            lines.push(i18n::tr(Msg::ButtonsSwapped).to_string());
+        }
+        lines.extend(self.debounce.map(analyzer::debounce_advice));
+        if let Some(holds) = &self.holds {
+            lines.push(holds.describe());
+            lines.extend(holds.reading());
+        }
+        if let Some(agreement) = &self.agreement {
+            lines.push(agreement.describe());
+            lines.extend(agreement.missed(self.human_floor));
+        }
+        lines.extend(self.stuck_text.clone());
+        lines.extend(self.chords.describe());
+        if let Some(actuations) = &self.actuations {
+            lines.extend(actuations.describe());
+        }
+        if let Some(loopback) = self.loopback {
+            lines.push(i18n::fmt(Msg::Loopback, &[&i18n::duration(loopback)]));
+        }
+        for (i, segment) in self.segments.iter().enumerate() {
+            let share = segment.chatter as f64 / segment.clicks.max(1) as f64;
+            lines.push(i18n::fmt(
+                Msg::Segment,
+                &[
+                    &i18n::number(i + 1),
+                    &i18n::number(segment.clicks),
+                    &i18n::number(segment.chatter),
+                    &i18n::decimal(share * 100.0, 1),
+                    &segment
+                        .median_gap
+                        .map_or_else(|| "-".to_string(), i18n::duration),
+                ],
+            ));
+        }
+        for fatigue in &self.fatigue {
+            lines.push(fatigue.describe());
+            lines.extend(fatigue.reading());
+        }
+        if let Some(step) = self.quantum {
+            lines.push(i18n::fmt(
+                Msg::Quantized,
+                &[&i18n::number(step.as_micros()), &self.clock.name()],
+            ));
+        }
+        if let Some((window, fastest)) = self.suggestion {
+            lines.push(i18n::fmt(
+                Msg::SuggestWindow,
+                &[
+                    &i18n::number(fastest.as_millis()),
+                    &i18n::number(window.as_millis()),
+                ],
+            ));
+        }
+        lines.extend(self.measurement_floor.map(analyzer::coalescing_warning));
+        if !self.backlog.is_empty() {
+            lines.push(i18n::fmt(
+                Msg::BacklogSummary,
+                &[
+                    &i18n::number(self.backlog.events),
+                    &i18n::number(self.backlog.skipped_frames),
+                    &i18n::number(self.backlog.stale_frames),
+                ],
+            ));
+        }
+        if !self.jumps.is_empty() {
+            let back = self.jumps.iter().filter(|j| j.back).count();
+            lines.push(i18n::fmt(
+                Msg::ClockJumps,
+                &[&i18n::number(back), &i18n::number(self.jumps.len() - back)],
+            ));
+        }
+        if !self.quirks.is_empty() {
+            lines.push(i18n::fmt(Msg::Quirks, &[&self.quirks.join(", ")]));
+        }
+        for check in &self.acceptance {
+            lines.push(check.describe());
+            lines.extend(check.explain().map(|why| format!("  {}", why)));
+        }
+        if let Some(recorded) = self
+            .recorded_with
+            .as_ref()
+            .filter(|b| !b.same_code(&self.build))
+        {
+            lines.push(i18n::fmt(
+                Msg::RecordedWith,
+                &[&recorded.describe(), &self.build.describe()],
+            ));
+        }
+        lines.join("\n")
+    }
+}
+
+/// a value in toml syntax, tables inline and nulls left out
+fn toml(value: &Value) -> String {
+    match value {
+        Value::Array(items) => format!(
+            "[{}]",
+            items.iter().map(toml).collect::<Vec<_>>().join(", ")
+        ),
+        Value::Object(map) => format!(
+            "{{ {} }}",
+            map.iter()
+                .filter(|(_, v)| **v != Value::Null)
+                .map(|(k, v)| format!("{} = {}", k, toml(v)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        scalar => scalar.to_string_compact(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::{KeyModifiers, MouseButton};
+
+    use super::*;
+    use crate::session::Click;
+
+    /// three clicks, the second chattering off the first
+    fn summary() -> Summary {
+        let mut session = Session::new(
+            Some("desk \"mouse\"".to_string()),
+            Duration::from_millis(50),
+        );
+        for ms in [0, 10, 500] {
+            session.clicks.push(Click {
+                at: Duration::from_millis(ms),
+                interval: None,
+                button: MouseButton::Left,
+                hold: None,
+                bookmarked: false,
+                keyboard: false,
+                position: None,
+                modifiers: KeyModifiers::NONE,
+            });
+        }
+        session.reclassify(session.detect_window);
+        Summary::new(&session, Estimate::new(1, 3, 0.95, 0.01))
+    }
+
+    #[test]
+    fn text_starts_with_the_counts() {
+        let summary = summary();
+        let text = summary.render(Format::Text);
+        let counts = i18n::fmt(
+            Msg::Summary,
+            &[&i18n::number(3), &i18n::number(1), &i18n::number(1)],
+        );
+        assert_eq!(text.lines().next(), Some(counts.as_str()));
+    }
+
+    #[test]
+    fn json_holds_every_field() {
+        let summary = summary();
+        let value = json::parse(&summary.render(Format::Json)).unwrap();
+        assert_eq!(value, summary.to_json());
+        assert_eq!(value.get("clicks").and_then(Value::as_u64), Some(3));
+        assert_eq!(value.get("chatter").and_then(Value::as_u64), Some(1));
+        assert_eq!(
+            value.get("device").and_then(Value::as_str),
+            Some("desk \"mouse\"")
+        );
+    }
+
+    #[test]
+    fn yaml_has_a_line_per_field() {
+        let summary = summary();
+        let yaml = summary.render(Format::Yaml);
+        let lines = yaml.lines().collect::<Vec<_>>();
+        let fields = summary.fields();
+        assert_eq!(lines.len(), fields.len());
+        for (line, (key, value)) in lines.into_iter().zip(fields) {
+            let (name, scalar) = line.split_once(": ").unwrap();
+            assert_eq!(name, key);
+            assert_eq!(json::parse(scalar), Ok(value), "{}", line);
+        }
+    }
+
+    #[test]
+    fn toml_leaves_out_the_nulls() {
+        let summary = summary();
+        let toml = summary.render(Format::Toml);
+        let keys = toml
+            .lines()
+            .map(|line| line.split_once(" = ").unwrap().0)
+            .collect::<Vec<_>>();
+        let set = summary
+            .fields()
+            .into_iter()
+            .filter(|(_, value)| *value != Value::Null)
+            .map(|(key, _)| key)
+            .collect::<Vec<_>>();
+        assert_eq!(keys, set);
+        assert!(!toml.contains("null"), "{}", toml);
+        assert!(toml.contains("clicks = 3\n"), "{}", toml);
+        assert!(toml.contains(r#"device = "desk \"mouse\"""#), "{}", toml);
+    }
+}
@@ -0,0 +1,204 @@
+use std::{
+    fmt::Write as _,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    checksum,
+    error::{self, Error},
+    redact,
+    session::{self, Session},
+    stats,
+    version::Build,
+};
+
+const WIDTH: f64 = 640.0;
+const HEIGHT: f64 = 320.0;
+/// room for the axis labels on the left and bottom
+const MARGIN: f64 = 48.0;
+const BINS: usize = 30;
+/// the histogram stops above this quantile of the gaps, long pauses would flatten it
+const RANGE_QUANTILE: f64 = 0.95;
+
+/// the plot area a chart draws into, in svg units
+struct Plot {
+    max_x: f64,
+    max_y: f64,
+}
+
+impl Plot {
+    fn x(&self, x: f64) -> f64 {
+        MARGIN + x / self.max_x.max(f64::EPSILON) * (WIDTH - 2.0 * MARGIN)
+    }
+
+    fn y(&self, y: f64) -> f64 {
+        HEIGHT - MARGIN - y.min(self.max_y) / self.max_y.max(f64::EPSILON) * (HEIGHT - 2.0 * MARGIN)
+    }
+
+    /// frame, axes with their end labels and the title
+    fn frame(&self, title: &str, x_label: &str, y_label: &str) -> String {
+        let (left, right) = (MARGIN, WIDTH - MARGIN);
+        let (top, bottom) = (MARGIN, HEIGHT - MARGIN);
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" \
+             viewBox=\"0 0 {w} {h}\" font-family=\"sans-serif\" font-size=\"12\">\n\
+             <desc>{build}</desc>\n\
+             <rect width=\"{w}\" height=\"{h}\" fill=\"white\"/>\n\
+             <text x=\"{left}\" y=\"{t}\" font-size=\"14\">{title}</text>\n\
+             <path d=\"M{left} {top}V{bottom}H{right}\" fill=\"none\" stroke=\"black\"/>\n",
+            w = WIDTH,
+            h = HEIGHT,
+            build = Build::current().describe(),
+            t = MARGIN / 2.0,
+            title = escape(title),
+        );
+        let _ = write!(
+            svg,
+            "<text x=\"{left}\" y=\"{}\" text-anchor=\"middle\">0</text>\n\
+             <text x=\"{right}\" y=\"{}\" text-anchor=\"end\">{:.0}</text>\n\
+             <text x=\"{}\" y=\"{}\" text-anchor=\"middle\">{}</text>\n\
+             <text x=\"{}\" y=\"{bottom}\" text-anchor=\"end\">0</text>\n\
+             <text x=\"{}\" y=\"{}\" text-anchor=\"end\">{:.0}</text>\n\
+             <text x=\"{}\" y=\"{}\" text-anchor=\"end\">{}</text>\n",
+            bottom + 16.0,
+            bottom + 16.0,
+            self.max_x,
+            (left + right) / 2.0,
+            bottom + 32.0,
+            escape(x_label),
+            left - 6.0,
+            left - 6.0,
+            top + 4.0,
+            self.max_y,
+            left - 6.0,
+            top - 8.0,
+            escape(y_label),
+        );
+        svg
+    }
+}
+
+fn title(session: &Session, chart: &str) -> String {
+    format!(
+        "{} {} - {}",
+        redact::name(session.device.as_deref()).unwrap_or_else(|| "unnamed device".to_string()),
+        session::format_zoned(session.started_at),
+        chart
+    )
+}
+
+fn ms(d: std::time::Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+/// the gap before every click with the detect window as a dashed line
+pub fn interval_chart(session: &Session) -> String {
+    let threshold = ms(session.detect_window);
+    let points = session
+        .gaps()
+        .enumerate()
+        .map(|(i, gap)| ((i + 2) as f64, ms(gap)))
+        .collect::<Vec<_>>();
+    // long pauses between tests would flatten the interesting range, like the tui chart
+    let max_y = points
+        .iter()
+        .map(|p| p.1)
+        .fold(threshold * 1.5, f64::max)
+        .min(threshold * 4.0);
+    let plot = Plot {
+        max_x: session.clicks.len().max(2) as f64,
+        max_y,
+    };
+
+    let mut svg = plot.frame(&title(session, "interval"), "click", "ms");
+    let _ = writeln!(
+        svg,
+        "<line x1=\"{}\" y1=\"{y:.1}\" x2=\"{}\" y2=\"{y:.1}\" stroke=\"red\" stroke-dasharray=\"6 4\"/>",
+        plot.x(0.0),
+        plot.x(plot.max_x),
+        y = plot.y(threshold),
+    );
+    let path = points
+        .iter()
+        .enumerate()
+        .map(|(i, (x, y))| {
+            format!(
+                "{}{:.1} {:.1}",
+                if i == 0 { "M" } else { "L" },
+                plot.x(*x),
+                plot.y(*y)
+            )
+        })
+        .collect::<String>();
+    if !path.is_empty() {
+        let _ = writeln!(
+            svg,
+            "<path d=\"{}\" fill=\"none\" stroke=\"steelblue\"/>",
+            path
+        );
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// share of the gaps in each bin, up to the 95th percentile
+pub fn histogram(session: &Session) -> String {
+    let gaps = session.gaps().map(ms).collect::<Vec<_>>();
+    let max = stats::quantile(&gaps, RANGE_QUANTILE)
+        .unwrap_or(1.0)
+        .max(1.0);
+    let counts = stats::histogram(&gaps, max, BINS);
+    let shares = counts
+        .iter()
+        .map(|c| *c as f64 * 100.0 / gaps.len().max(1) as f64)
+        .collect::<Vec<_>>();
+    let plot = Plot {
+        max_x: max,
+        max_y: shares.iter().copied().fold(1.0, f64::max),
+    };
+
+    let mut svg = plot.frame(&title(session, "gaps between clicks"), "ms", "%");
+    let width = max / BINS as f64;
+    for (i, share) in shares.iter().enumerate().filter(|(_, s)| **s > 0.0) {
+        let (x0, x1) = (plot.x(i as f64 * width), plot.x((i + 1) as f64 * width));
+        let y = plot.y(*share);
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"steelblue\"/>",
+            x0,
+            y,
+            (x1 - x0 - 1.0).max(0.5),
+            plot.y(0.0) - y
+        );
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// both charts next to `path` as `<stem>-intervals.svg` and `<stem>-histogram.svg`, each
+/// ending in the hash of its content and signed with `--sign`
+pub fn write(session: &Session, path: &Path) -> error::Result<Vec<PathBuf>> {
+    let stem = path.with_extension("");
+    let charts = [
+        ("intervals", interval_chart(session)),
+        ("histogram", histogram(session)),
+    ];
+    let mut written = Vec::new();
+    for (name, svg) in charts {
+        let out = PathBuf::from(format!("{}-{}.svg", stem.display(), name));
+        fs::write(&out, checksum::seal_markup(svg)).map_err(|source| Error::Export {
+            path: out.clone(),
+            source,
+        })?;
+        checksum::sign(&out)?;
+        written.push(out);
+    }
+    Ok(written)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
@@ -0,0 +1,130 @@
+use std::time::Duration;
+
+use crate::{
+    analyzer::SHORT_HOLD,
+    session::{self, Click, Spilled},
+    stats::P2,
+};
+
+/// totals of the clicks in memory kept up as clicks are appended, so what the stats pane
+/// draws every frame costs the new clicks instead of all of them; anything that changes
+/// the clicks already taken in starts it over
+#[derive(Debug, Clone)]
+pub struct Tally {
+    /// clicks taken in, the first ones in memory
+    seen: usize,
+    /// spilled clicks and the times of the first and last click taken in, a tally that no
+    /// longer matches the clicks is started over
+    spilled: usize,
+    first: Option<Duration>,
+    last: Option<Duration>,
+    /// the floor `fast_chatter` was counted with
+    human_floor: Duration,
+    pub chatter: usize,
+    pub fast_chatter: usize,
+    pub shortest_chatter: Option<Duration>,
+    pub last_chatter: Option<Duration>,
+    /// summed interval of the chatter
+    pub chatter_interval: Duration,
+    /// clean streak at the last click and the longest one, counted on from the spilled ones
+    pub streak: usize,
+    pub longest: usize,
+    pub holds: usize,
+    pub short_holds: usize,
+    pub hold_median: P2,
+    pub hold_p10: P2,
+    pub chatter_hold_median: P2,
+    /// gap outlier flags of the clicks taken in, worked out again once new clicks come
+    pub outliers: Option<Vec<bool>>,
+}
+
+impl Default for Tally {
+    fn default() -> Self {
+        Self {
+            seen: 0,
+            spilled: 0,
+            first: None,
+            last: None,
+            human_floor: session::HUMAN_FLOOR,
+            chatter: 0,
+            fast_chatter: 0,
+            shortest_chatter: None,
+            last_chatter: None,
+            chatter_interval: Duration::ZERO,
+            streak: 0,
+            longest: 0,
+            holds: 0,
+            short_holds: 0,
+            hold_median: P2::new(0.5),
+            hold_p10: P2::new(0.1),
+            chatter_hold_median: P2::new(0.5),
+            outliers: None,
+        }
+    }
+}
+
+impl Tally {
+    fn matches(&self, clicks: &[Click], spilled: &Spilled, human_floor: Duration) -> bool {
+        self.seen <= clicks.len()
+            && self.spilled == spilled.clicks
+            && self.human_floor == human_floor
+            && self.first == clicks.first().filter(|_| self.seen > 0).map(|c| c.at)
+            && self.last == self.seen.checked_sub(1).map(|i| clicks[i].at)
+    }
+
+    /// take in the clicks appended since the last look
+    pub fn update(&mut self, clicks: &[Click], spilled: &Spilled, human_floor: Duration) {
+        if !self.matches(clicks, spilled, human_floor) {
+            *self = Self {
+                spilled: spilled.clicks,
+                human_floor,
+                streak: spilled.streak,
+                longest: spilled.longest,
+                ..Self::default()
+            };
+        }
+        let new = &clicks[self.seen..];
+        if new.is_empty() {
+            return;
+        }
+        for click in new {
+            if let Some(interval) = click.interval {
+                self.chatter += 1;
+                self.fast_chatter += usize::from(interval < human_floor);
+                self.shortest_chatter =
+                    Some(self.shortest_chatter.map_or(interval, |s| s.min(interval)));
+                self.last_chatter = Some(click.at);
+                self.chatter_interval += interval;
+                self.streak = 0;
+            } else {
+                self.streak += 1;
+                self.longest = self.longest.max(self.streak);
+            }
+            if let Some(hold) = click.hold {
+                self.hold(click, hold);
+            }
+        }
+        self.seen = clicks.len();
+        self.first = clicks.first().map(|c| c.at);
+        self.last = clicks.last().map(|c| c.at);
+        self.outliers = None;
+    }
+
+    /// a click already taken in was released
+    pub fn released(&mut self, index: usize, click: &Click, hold: Duration) {
+        if index < self.seen {
+            self.hold(click, hold);
+        }
+    }
+
+    fn hold(&mut self, click: &Click, hold: Duration) {
+        let secs = hold.as_secs_f64();
+        self.holds += 1;
+        self.short_holds += usize::from(hold < SHORT_HOLD);
+        self.hold_median.push(secs);
+        self.hold_p10.push(secs);
+        if click.interval.is_some() {
+            self.chatter_hold_median.push(secs);
+        }
+    }
+}
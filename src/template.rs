@@ -0,0 +1,211 @@
+use std::{fs, path::Path};
+
+use crate::{
+    error::{self, Error},
+    json::Value,
+};
+
+/// a jinja style template: `{{ path.to.value }}`, `{% for x in list %}…{% endfor %}` and
+/// `{% if value %}…{% else %}…{% endif %}`, enough to lay out a report
+pub struct Template {
+    nodes: Vec<Node>,
+}
+
+enum Node {
+    Text(String),
+    Value(Vec<String>),
+    For {
+        name: String,
+        list: Vec<String>,
+        body: Vec<Node>,
+    },
+    If {
+        condition: Vec<String>,
+        then: Vec<Node>,
+        otherwise: Vec<Node>,
+    },
+}
+
+/// what a `{% %}` tag closed a block with
+enum Closing {
+    Eof,
+    EndFor,
+    Else,
+    EndIf,
+}
+
+fn path(expression: &str) -> Result<Vec<String>, String> {
+    let parts = expression
+        .trim()
+        .split('.')
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    if parts
+        .iter()
+        .any(|p| p.is_empty() || !p.chars().all(|c| c.is_alphanumeric() || c == '_'))
+    {
+        return Err(format!("`{}` is no value name", expression.trim()));
+    }
+    Ok(parts)
+}
+
+impl Template {
+    pub fn load(path: &Path) -> error::Result<Self> {
+        fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|source| Self::parse(&source))
+            .map_err(|message| Error::Config {
+                path: path.to_path_buf(),
+                message,
+            })
+    }
+
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut rest = source;
+        let (nodes, end) = Self::block(&mut rest)?;
+        match end {
+            Closing::Eof => Ok(Self { nodes }),
+            Closing::EndFor => Err("`endfor` without a `for`".to_string()),
+            Closing::Else => Err("`else` without an `if`".to_string()),
+            Closing::EndIf => Err("`endif` without an `if`".to_string()),
+        }
+    }
+
+    /// nodes up to the tag ending the block, `rest` is left after that tag
+    fn block(rest: &mut &str) -> Result<(Vec<Node>, Closing), String> {
+        let mut nodes = Vec::new();
+        loop {
+            let Some(start) = rest.find("{{").into_iter().chain(rest.find("{%")).min() else {
+                if !rest.is_empty() {
+                    nodes.push(Node::Text(rest.to_string()));
+                }
+                *rest = "";
+                return Ok((nodes, Closing::Eof));
+            };
+            if start > 0 {
+                nodes.push(Node::Text(rest[..start].to_string()));
+            }
+            let value = rest[start..].starts_with("{{");
+            let close = if value { "}}" } else { "%}" };
+            let end = rest[start..]
+                .find(close)
+                .ok_or_else(|| format!("unclosed `{}`", &rest[start..start + 2]))?;
+            let inner = rest[start + 2..start + end].trim().to_string();
+            *rest = &rest[start + end + 2..];
+
+            if value {
+                nodes.push(Node::Value(path(&inner)?));
+                continue;
+            }
+            let words = inner.split_whitespace().collect::<Vec<_>>();
+            match words.as_slice() {
+                ["for", name, "in", list] => {
+                    let (body, end) = Self::block(rest)?;
+                    if !matches!(end, Closing::EndFor) {
+                        return Err(format!("`for {} in {}` without `endfor`", name, list));
+                    }
+                    nodes.push(Node::For {
+                        name: name.to_string(),
+                        list: path(list)?,
+                        body,
+                    });
+                }
+                ["if", condition] => {
+                    let (then, end) = Self::block(rest)?;
+                    let otherwise = match end {
+                        Closing::EndIf => Vec::new(),
+                        Closing::Else => match Self::block(rest)? {
+                            (otherwise, Closing::EndIf) => otherwise,
+                            _ => return Err(format!("`if {}` without `endif`", condition)),
+                        },
+                        _ => return Err(format!("`if {}` without `endif`", condition)),
+                    };
+                    nodes.push(Node::If {
+                        condition: path(condition)?,
+                        then,
+                        otherwise,
+                    });
+                }
+                ["endfor"] => return Ok((nodes, Closing::EndFor)),
+                ["else"] => return Ok((nodes, Closing::Else)),
+                ["endif"] => return Ok((nodes, Closing::EndIf)),
+                _ => return Err(format!("unknown tag `{{% {} %}}`", inner)),
+            }
+        }
+    }
+
+    pub fn render(&self, data: &Value) -> String {
+        let mut out = String::new();
+        render(&self.nodes, data, &mut Vec::new(), &mut out);
+        out
+    }
+}
+
+static NULL: Value = Value::Null;
+
+/// the value a path names, loop variables first and the data last
+fn lookup<'a>(data: &'a Value, vars: &'a [(String, Value)], path: &[String]) -> &'a Value {
+    let Some((first, rest)) = path.split_first() else {
+        return &NULL;
+    };
+    let mut value = vars
+        .iter()
+        .rev()
+        .find(|(name, _)| name == first)
+        .map(|(_, value)| value)
+        .or_else(|| data.get(first));
+    for key in rest {
+        value = value.and_then(|v| match v {
+            Value::Array(items) => key.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => v.get(key),
+        });
+    }
+    value.unwrap_or(&NULL)
+}
+
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => *n != 0.0,
+        Value::String(s) => !s.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(map) => !map.is_empty(),
+    }
+}
+
+fn render(nodes: &[Node], data: &Value, vars: &mut Vec<(String, Value)>, out: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Value(path) => match lookup(data, vars, path) {
+                Value::Null => {}
+                Value::String(s) => out.push_str(s),
+                value => out.push_str(&value.to_string_compact()),
+            },
+            Node::For { name, list, body } => {
+                let items = lookup(data, vars, list)
+                    .as_array()
+                    .unwrap_or_default()
+                    .to_vec();
+                for item in items {
+                    vars.push((name.clone(), item));
+                    render(body, data, vars, out);
+                    vars.pop();
+                }
+            }
+            Node::If {
+                condition,
+                then,
+                otherwise,
+            } => {
+                let branch = if truthy(lookup(data, vars, condition)) {
+                    then
+                } else {
+                    otherwise
+                };
+                render(branch, data, vars, out);
+            }
+        }
+    }
+}
@@ -0,0 +1,249 @@
+use std::{
+    io::{self, Stdout, Write},
+    ops::{Deref, DerefMut},
+};
+
+use crossterm::{
+    event::{DisableMouseCapture, EnableMouseCapture},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+#[cfg(feature = "test-util")]
+use ratatui::backend::TestBackend;
+use ratatui::{
+    backend::{Backend, ClearType, CrosstermBackend},
+    buffer::Cell,
+    layout::Rect,
+    Terminal, TerminalOptions, Viewport,
+};
+
+use crate::error::{self, Error};
+
+/// what the modes draw on, the terminal or the test backend of a `--script`
+pub enum Screen {
+    Terminal(CrosstermBackend<Stdout>),
+    #[cfg(feature = "test-util")]
+    Test(TestBackend),
+}
+
+macro_rules! each_screen {
+    ($screen:expr, $backend:ident => $call:expr) => {
+        match $screen {
+            Screen::Terminal($backend) => $call,
+            #[cfg(feature = "test-util")]
+            Screen::Test($backend) => $call,
+        }
+    };
+}
+
+impl Backend for Screen {
+    fn draw<'a, I>(&mut self, content: I) -> io::Result<()>
+    where
+        I: Iterator<Item = (u16, u16, &'a Cell)>,
+    {
+        each_screen!(self, b => b.draw(content))
+    }
+
+    fn append_lines(&mut self, n: u16) -> io::Result<()> {
+        each_screen!(self, b => b.append_lines(n))
+    }
+
+    fn hide_cursor(&mut self) -> io::Result<()> {
+        each_screen!(self, b => b.hide_cursor())
+    }
+
+    fn show_cursor(&mut self) -> io::Result<()> {
+        each_screen!(self, b => b.show_cursor())
+    }
+
+    fn get_cursor(&mut self) -> io::Result<(u16, u16)> {
+        each_screen!(self, b => b.get_cursor())
+    }
+
+    fn set_cursor(&mut self, x: u16, y: u16) -> io::Result<()> {
+        each_screen!(self, b => b.set_cursor(x, y))
+    }
+
+    fn clear(&mut self) -> io::Result<()> {
+        each_screen!(self, b => b.clear())
+    }
+
+    fn clear_region(&mut self, clear_type: ClearType) -> io::Result<()> {
+        each_screen!(self, b => b.clear_region(clear_type))
+    }
+
+    fn size(&self) -> io::Result<Rect> {
+        each_screen!(self, b => b.size())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        each_screen!(self, b => Backend::flush(b))
+    }
+}
+
+/// raw mode, alternate screen and mouse capture for as long as the guard lives
+pub struct TerminalGuard {
+    terminal: Terminal<Screen>,
+    /// false with `--no-altscreen`, the modes draw in a viewport of the screen as it is and
+    /// the last frame stays in the scrollback
+    alternate: bool,
+}
+
+impl TerminalGuard {
+    /// `inline` draws in that many rows of the screen as it is, at most all of them,
+    /// instead of the alternate screen
+    pub fn new(inline: Option<u16>) -> error::Result<Self> {
+        let alternate = inline.is_none();
+        // a script needs none of the terminal
+        #[cfg(feature = "test-util")]
+        if let Some((width, height)) = crate::harness::size() {
+            let terminal = Terminal::new(Screen::Test(TestBackend::new(width, height)))
+                .map_err(Error::Terminal)?;
+            return Ok(Self {
+                terminal,
+                alternate,
+            });
+        }
+
+        enable_raw_mode().map_err(Error::Terminal)?;
+
+        // undo the steps already taken when a later one fails
+        let setup = || {
+            let mut stdout = io::stdout();
+            if alternate {
+                execute!(stdout, EnterAlternateScreen).map_err(Error::Terminal)?;
+            }
+            execute!(stdout, EnableMouseCapture).map_err(Error::MouseCapture)?;
+            let backend = Screen::Terminal(CrosstermBackend::new(stdout));
+            let Some(inline) = inline else {
+                return Terminal::new(backend).map_err(Error::Terminal);
+            };
+            // the rows above scroll up into the scrollback
+            let (_, rows) = crossterm::terminal::size().map_err(Error::Terminal)?;
+            let options = TerminalOptions {
+                viewport: Viewport::Inline(inline.min(rows)),
+            };
+            Terminal::with_options(backend, options).map_err(Error::Terminal)
+        };
+        match setup() {
+            Ok(terminal) => Ok(Self {
+                terminal,
+                alternate,
+            }),
+            Err(e) => {
+                restore(&mut io::stdout(), alternate);
+                Err(e)
+            }
+        }
+    }
+}
+
+/// raw mode and mouse capture without the alternate screen, for the plain output
+pub struct CaptureGuard;
+
+impl CaptureGuard {
+    pub fn new() -> error::Result<Self> {
+        enable_raw_mode().map_err(Error::Terminal)?;
+        if let Err(e) = execute!(io::stdout(), EnableMouseCapture) {
+            let _ = disable_raw_mode();
+            return Err(Error::MouseCapture(e));
+        }
+        Ok(Self)
+    }
+}
+
+impl Drop for CaptureGuard {
+    fn drop(&mut self) {
+        let _ = disable_raw_mode();
+        let _ = execute!(io::stdout(), DisableMouseCapture);
+    }
+}
+
+/// hand the mouse back to the terminal for selecting text or take it again, a script has
+/// no terminal to hand it to
+pub fn set_capture(on: bool) -> io::Result<()> {
+    #[cfg(feature = "test-util")]
+    if crate::harness::size().is_some() {
+        return Ok(());
+    }
+    if on {
+        execute!(io::stdout(), EnableMouseCapture)
+    } else {
+        execute!(io::stdout(), DisableMouseCapture)
+    }
+}
+
+/// put text on the clipboard with osc 52, terminals that do not allow it ignore the request
+pub fn copy(text: &str) -> io::Result<()> {
+    #[cfg(feature = "test-util")]
+    if crate::harness::size().is_some() {
+        return Ok(());
+    }
+    let mut out = io::stdout();
+    write!(out, "\x1b]52;c;{}\x07", base64(text.as_bytes()))?;
+    out.flush()
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut text = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, b)| n | u32::from(*b) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                text.push(char::from(ALPHABET[(n >> (18 - 6 * i) & 63) as usize]));
+            } else {
+                text.push('=');
+            }
+        }
+    }
+    text
+}
+
+fn restore(out: &mut impl Write, alternate: bool) {
+    // nothing sensible is left to do when restoring fails
+    let _ = disable_raw_mode();
+    let _ = execute!(out, DisableMouseCapture);
+    if alternate {
+        let _ = execute!(out, LeaveAlternateScreen);
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        if !self.alternate {
+            // what is printed next goes below the last frame instead of over it
+            let bottom = self.terminal.get_frame().size().bottom();
+            let _ = self.terminal.set_cursor(0, bottom.saturating_sub(1));
+        }
+        let alternate = self.alternate;
+        match self.terminal.backend_mut() {
+            Screen::Terminal(backend) => {
+                restore(backend, alternate);
+                if !alternate {
+                    let _ = writeln!(backend);
+                }
+            }
+            #[cfg(feature = "test-util")]
+            Screen::Test(backend) => crate::harness::keep(backend.buffer()),
+        }
+        let _ = self.terminal.show_cursor();
+    }
+}
+
+impl Deref for TerminalGuard {
+    type Target = Terminal<Screen>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.terminal
+    }
+}
+
+impl DerefMut for TerminalGuard {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.terminal
+    }
+}
@@ -0,0 +1,137 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    RwLock,
+};
+
+use clap::ValueEnum;
+use ratatui::{
+    style::{Color, Modifier, Style},
+    symbols::Marker,
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Theme {
+    #[default]
+    Default,
+    HighContrast,
+    /// no colors at all, flags are shown with symbols and text attributes
+    Mono,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 3] = [Theme::Default, Theme::HighContrast, Theme::Mono];
+
+    /// the name of the flag value, also used in the config
+    pub fn name(self) -> &'static str {
+        match self {
+            Theme::Default => "default",
+            Theme::HighContrast => "high-contrast",
+            Theme::Mono => "mono",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.name() == name)
+    }
+}
+
+pub struct Palette {
+    pub chatter: Style,
+    pub outlier: Style,
+    pub highlight: Style,
+    pub threshold: Style,
+    pub interval: Style,
+    /// highlighted cue of the paced test
+    pub flash: Style,
+    /// border of the pane tab has moved the focus to
+    pub focus: Style,
+    pub threshold_marker: Marker,
+    /// prefix of flagged rows so the flag doesn't rely on color alone
+    pub chatter_mark: &'static str,
+    pub outlier_mark: &'static str,
+}
+
+static THEME: RwLock<Theme> = RwLock::new(Theme::Default);
+static REDUCED_MOTION: AtomicBool = AtomicBool::new(false);
+
+pub fn init(theme: Theme, reduced_motion: bool) {
+    set(theme);
+    REDUCED_MOTION.store(reduced_motion, Ordering::Relaxed);
+}
+
+pub fn current() -> Theme {
+    *THEME.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// switch the colors, the next frame is drawn with them
+pub fn set(theme: Theme) {
+    *THEME.write().unwrap_or_else(|e| e.into_inner()) = theme;
+}
+
+/// no flashing and at most one chart or gauge update per second
+pub fn reduced_motion() -> bool {
+    REDUCED_MOTION.load(Ordering::Relaxed)
+}
+
+pub fn toggle_reduced_motion() {
+    REDUCED_MOTION.fetch_xor(true, Ordering::Relaxed);
+}
+
+pub fn palette() -> Palette {
+    match current() {
+        Theme::Default => Palette {
+            chatter: Style::default().fg(Color::Red),
+            outlier: Style::default().fg(Color::Yellow),
+            highlight: Style::default().add_modifier(Modifier::REVERSED),
+            threshold: Style::default().fg(Color::Red),
+            interval: Style::default().fg(Color::Cyan),
+            flash: Style::default()
+                .fg(Color::Black)
+                .bg(Color::White)
+                .add_modifier(Modifier::BOLD),
+            focus: Style::default()
+                .fg(Color::LightBlue)
+                .add_modifier(Modifier::BOLD),
+            threshold_marker: Marker::Braille,
+            chatter_mark: "",
+            outlier_mark: "",
+        },
+        Theme::HighContrast => Palette {
+            chatter: Style::default()
+                .fg(Color::Black)
+                .bg(Color::LightRed)
+                .add_modifier(Modifier::BOLD),
+            outlier: Style::default()
+                .fg(Color::Black)
+                .bg(Color::LightYellow)
+                .add_modifier(Modifier::BOLD),
+            highlight: Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD),
+            threshold: Style::default()
+                .fg(Color::LightRed)
+                .add_modifier(Modifier::BOLD),
+            interval: Style::default().fg(Color::White),
+            flash: Style::default()
+                .fg(Color::Black)
+                .bg(Color::White)
+                .add_modifier(Modifier::BOLD),
+            focus: Style::default()
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD),
+            threshold_marker: Marker::Dot,
+            chatter_mark: "!! ",
+            outlier_mark: "?? ",
+        },
+        Theme::Mono => Palette {
+            chatter: Style::default().add_modifier(Modifier::BOLD),
+            outlier: Style::default().add_modifier(Modifier::UNDERLINED),
+            highlight: Style::default().add_modifier(Modifier::REVERSED),
+            threshold: Style::default(),
+            interval: Style::default(),
+            flash: Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD),
+            focus: Style::default().add_modifier(Modifier::BOLD),
+            threshold_marker: Marker::Dot,
+            chatter_mark: "!! ",
+            outlier_mark: "?? ",
+        },
+    }
+}
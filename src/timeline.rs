@@ -0,0 +1,142 @@
+use std::time::Duration;
+
+use ratatui::{
+    backend::Backend,
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    focus,
+    i18n::{self, tr, Msg},
+    session::Session,
+    theme,
+};
+
+/// the clicks that fell into one detect window
+pub struct Group {
+    /// index into the clicks in memory of the one that opened the window
+    pub first: usize,
+    /// time of each later click since the first
+    pub offsets: Vec<Duration>,
+}
+
+/// what the rule made of a window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    /// a single click
+    Clean,
+    /// clicked again inside the window, but no faster than a hand can
+    DoubleClick,
+    /// clicked again faster than the human floor
+    Chatter,
+}
+
+impl Group {
+    pub fn span(&self) -> Duration {
+        self.offsets.last().copied().unwrap_or_default()
+    }
+
+    pub fn verdict(&self, human_floor: Duration) -> Verdict {
+        if self.offsets.is_empty() {
+            Verdict::Clean
+        } else if self.offsets.iter().any(|o| *o < human_floor) {
+            Verdict::Chatter
+        } else {
+            Verdict::DoubleClick
+        }
+    }
+}
+
+/// the detect windows of the clicks in memory, oldest first; clicks whose window opened
+/// among the spilled ones are left out
+pub fn groups(session: &Session) -> Vec<Group> {
+    let mut groups: Vec<Group> = Vec::new();
+    for (i, click) in session.clicks.iter().enumerate() {
+        match (click.interval, groups.last_mut()) {
+            (None, _) => groups.push(Group {
+                first: i,
+                offsets: Vec::new(),
+            }),
+            (Some(offset), Some(group)) => group.offsets.push(offset),
+            (Some(_), None) => {}
+        }
+    }
+    groups
+}
+
+/// the window as a bar `width` cells wide, a tick for every click and a line over the span
+fn bar(group: &Group, window: Duration, width: usize) -> String {
+    let last = width.saturating_sub(1);
+    let cell = |d: Duration| {
+        let share = d.as_secs_f64() / window.as_secs_f64().max(f64::EPSILON);
+        ((share * last as f64).round() as usize).min(last)
+    };
+    let span = cell(group.span());
+    let mut cells = (0..width)
+        .map(|i| if i <= span { '─' } else { '·' })
+        .collect::<Vec<_>>();
+    for at in [Duration::ZERO].iter().chain(&group.offsets) {
+        if let Some(c) = cells.get_mut(cell(*at)) {
+            *c = '|';
+        }
+    }
+    cells.into_iter().collect()
+}
+
+/// the newest windows that fit, one row each with its clicks on a bar as long as the
+/// detect window
+pub fn draw<B: Backend>(f: &mut Frame<B>, session: &Session, focused: bool, area: Rect) {
+    let block = focus::block(tr(Msg::Timeline), focused);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let groups = groups(session);
+    let shown = &groups[groups.len().saturating_sub(usize::from(inner.height))..];
+    let palette = theme::palette();
+    let rows = shown
+        .iter()
+        .map(|group| {
+            let number = i18n::number(session.spilled.clicks + group.first + 1);
+            let label = if group.offsets.is_empty() {
+                i18n::fmt(Msg::TimelineSingle, &[&number])
+            } else {
+                i18n::fmt(
+                    Msg::TimelineRow,
+                    &[
+                        &number,
+                        &i18n::number(group.offsets.len() + 1),
+                        &i18n::duration(group.span()),
+                    ],
+                )
+            };
+            let (verdict, style) = match group.verdict(session.human_floor) {
+                Verdict::Clean => (Msg::WindowClean, Style::default()),
+                Verdict::DoubleClick => (Msg::WindowDoubleClick, palette.interval),
+                Verdict::Chatter => (Msg::WindowChatter, palette.chatter),
+            };
+            (label, tr(verdict), style)
+        })
+        .collect::<Vec<_>>();
+    let label_width = rows.iter().map(|r| r.0.width()).max().unwrap_or(0);
+    let verdict_width = rows.iter().map(|r| r.1.width()).max().unwrap_or(0);
+    let bar_width = usize::from(inner.width).saturating_sub(label_width + verdict_width + 4);
+
+    let lines = shown
+        .iter()
+        .zip(rows)
+        .map(|(group, (label, verdict, style))| {
+            let pad = " ".repeat(label_width - label.width());
+            Line::from(vec![
+                Span::raw(format!("{}{}  ", label, pad)),
+                Span::styled(bar(group, session.detect_window, bar_width), style),
+                Span::styled(format!("  {}", verdict), style),
+            ])
+        })
+        .collect::<Vec<_>>();
+    f.render_widget(Paragraph::new(lines), inner);
+}
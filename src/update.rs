@@ -0,0 +1,150 @@
+use std::{
+    fs,
+    path::PathBuf,
+    process::{Command, Stdio},
+    sync::Mutex,
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    i18n::{self, Msg},
+    json::{self, Value},
+    session,
+};
+
+/// the latest release as github reports it
+const RELEASES: &str = "https://api.github.com/repos/ckaznable/mouse-checker/releases/latest";
+/// how long a cached answer is trusted before it is asked again
+const STALE: Duration = Duration::from_secs(24 * 60 * 60);
+/// how long the exit waits for a check still running, a slow network is asked again next time
+const FINISH: Duration = Duration::from_secs(2);
+
+static CHECKING: Mutex<Option<JoinHandle<()>>> = Mutex::new(None);
+
+/// what the last check found, kept in the data dir so that a start offline still knows
+#[derive(Debug, Clone, Default)]
+struct Cache {
+    /// unix timestamp in seconds
+    checked_at: u64,
+    latest: Option<String>,
+    /// the release notes mention the detectors
+    detector: bool,
+}
+
+impl Cache {
+    fn path() -> PathBuf {
+        session::data_dir().join("update.json")
+    }
+
+    fn load() -> Option<Self> {
+        let value = json::parse(&fs::read_to_string(Self::path()).ok()?).ok()?;
+        Some(Self {
+            checked_at: value.get("checked_at").and_then(Value::as_u64)?,
+            latest: value
+                .get("latest")
+                .and_then(Value::as_str)
+                .map(String::from),
+            detector: value
+                .get("detector")
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+        })
+    }
+
+    fn save(&self) {
+        let value = json::object([
+            ("checked_at", Value::from(self.checked_at)),
+            ("latest", Value::from(self.latest.clone())),
+            ("detector", Value::from(self.detector)),
+        ]);
+        let _ = fs::create_dir_all(session::data_dir());
+        let _ = session::write_atomic(&Self::path(), &value.to_string_pretty());
+    }
+}
+
+/// the tag and whether the notes mention the detectors, none offline or on any failure
+fn fetch() -> Option<(String, bool)> {
+    let out = Command::new("curl")
+        .args(["--silent", "--fail", "--max-time", "5", RELEASES])
+        .args(["--header", "Accept: application/vnd.github+json"])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    let value = json::parse(&String::from_utf8_lossy(&out.stdout))
+        .ok()
+        .filter(|_| out.status.success())?;
+    let tag = value.get("tag_name").and_then(Value::as_str)?.to_string();
+    let notes = value.get("body").and_then(Value::as_str).unwrap_or("");
+    Some((tag, notes.to_lowercase().contains("detector")))
+}
+
+/// `v1.2.3` as `[1, 2, 3]`, anything after a `-` and trailing zeros left out
+fn numbers(version: &str) -> Vec<u64> {
+    let mut numbers = version
+        .trim_start_matches('v')
+        .split('-')
+        .next()
+        .unwrap_or("")
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect::<Vec<_>>();
+    while numbers.last() == Some(&0) {
+        numbers.pop();
+    }
+    numbers
+}
+
+/// the note of a newer release from the cached answer, refreshing a stale or missing one in
+/// the background for the next start so the check never holds this one up
+pub fn check() -> Option<String> {
+    let cache = Cache::load();
+    let now = session::unix_now();
+    let stale = cache
+        .as_ref()
+        .is_none_or(|c| now.saturating_sub(c.checked_at) >= STALE.as_secs());
+    if stale {
+        let previous = cache.clone().unwrap_or_default();
+        let handle = thread::spawn(move || {
+            // an offline check keeps the last answer and waits out the day before asking again
+            let (latest, detector) = fetch()
+                .map(|(tag, detector)| (Some(tag), detector))
+                .unwrap_or((previous.latest, previous.detector));
+            Cache {
+                checked_at: now,
+                latest,
+                detector,
+            }
+            .save();
+        });
+        *CHECKING.lock().unwrap_or_else(|e| e.into_inner()) = Some(handle);
+    }
+    let cache = cache?;
+    let latest = cache.latest?;
+    let current = env!("CARGO_PKG_VERSION");
+    (numbers(&latest) > numbers(current)).then(|| {
+        i18n::fmt(
+            if cache.detector {
+                Msg::UpdateDetectors
+            } else {
+                Msg::UpdateAvailable
+            },
+            &[&latest, &current],
+        )
+    })
+}
+
+/// give a check still running a moment to store its answer before the process ends
+pub fn finish() {
+    let Some(handle) = CHECKING.lock().unwrap_or_else(|e| e.into_inner()).take() else {
+        return;
+    };
+    let started = Instant::now();
+    while !handle.is_finished() && started.elapsed() < FINISH {
+        thread::sleep(Duration::from_millis(20));
+    }
+    if handle.is_finished() {
+        let _ = handle.join();
+    }
+}
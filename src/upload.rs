@@ -0,0 +1,71 @@
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+use crate::{
+    error::{self, Error},
+    json::{self, Value},
+};
+
+/// fields identifying the machine or the person, left out of what is posted
+const PRIVATE: [&str; 2] = ["path", "operator"];
+
+/// the link a paste service answers with, as plain text or a json object
+fn link(response: &str) -> Option<String> {
+    let response = response.trim();
+    let url = match json::parse(response) {
+        Ok(value) => ["url", "link"]
+            .iter()
+            .find_map(|key| value.get(key).and_then(Value::as_str))?
+            .to_string(),
+        Err(_) => response.lines().next()?.trim().to_string(),
+    };
+    (!url.is_empty()).then_some(url)
+}
+
+/// post the json summary to the paste endpoint with curl and return the link to it
+pub fn run(endpoint: &str, summary: &Value) -> error::Result<String> {
+    let failed = |message: String| Error::Upload {
+        endpoint: Some(endpoint.to_string()),
+        message,
+    };
+    let public = match summary {
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .filter(|(key, _)| !PRIVATE.contains(&key.as_str()))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+        ),
+        other => other.clone(),
+    };
+
+    let mut child = Command::new("curl")
+        .args(["--silent", "--show-error", "--fail", "--max-time", "30"])
+        .args(["--header", "Content-Type: application/json"])
+        .args(["--data-binary", "@-", endpoint])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| failed(format!("could not run curl: {}", e)))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(public.to_string_pretty().as_bytes())
+            .map_err(|e| failed(e.to_string()))?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| failed(e.to_string()))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(failed(if stderr.is_empty() {
+            format!("curl exited with {}", output.status)
+        } else {
+            stderr
+        }));
+    }
+    link(&String::from_utf8_lossy(&output.stdout))
+        .ok_or_else(|| failed("the answer holds no link".to_string()))
+}
@@ -0,0 +1,192 @@
+#[cfg(feature = "usb")]
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::json::{self, Value};
+
+#[cfg(feature = "usb")]
+const USB_DEVICES: &str = "/sys/bus/usb/devices";
+
+/// nominal polling rate in Hz of the first usb hid mouse found
+///
+/// reads the interrupt endpoint `bInterval` from sysfs, which holds the
+/// same descriptor values libusb would report
+#[cfg(feature = "usb")]
+pub fn polling_rate() -> Option<u32> {
+    mice().find_map(|p| interface_rate(&p))
+}
+
+#[cfg(not(feature = "usb"))]
+pub fn polling_rate() -> Option<u32> {
+    None
+}
+
+/// what a mouse says it is over usb
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Identity {
+    pub vendor_id: u16,
+    pub product_id: u16,
+    pub manufacturer: Option<String>,
+    pub product: Option<String>,
+    /// a cable or a wireless receiver, with the usb speed
+    pub connection: String,
+}
+
+impl Identity {
+    /// the name and `vid:pid` as lsusb shows them, with the connection
+    pub fn describe(&self) -> String {
+        let name = [self.manufacturer.as_deref(), self.product.as_deref()]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" ");
+        let ids = format!(
+            "{:04x}:{:04x}, {}",
+            self.vendor_id, self.product_id, self.connection
+        );
+        if name.is_empty() {
+            ids
+        } else {
+            format!("{} ({})", name, ids)
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        json::object([
+            ("vendor_id", Value::from(format!("{:04x}", self.vendor_id))),
+            (
+                "product_id",
+                Value::from(format!("{:04x}", self.product_id)),
+            ),
+            ("manufacturer", Value::from(self.manufacturer.clone())),
+            ("product", Value::from(self.product.clone())),
+            ("connection", Value::from(self.connection.as_str())),
+        ])
+    }
+
+    /// none without both ids
+    pub fn from_json(value: &Value) -> Option<Self> {
+        let text = |key: &str| value.get(key).and_then(Value::as_str).map(String::from);
+        let id = |key: &str| u16::from_str_radix(&text(key)?, 16).ok();
+        Some(Self {
+            vendor_id: id("vendor_id")?,
+            product_id: id("product_id")?,
+            manufacturer: text("manufacturer"),
+            product: text("product"),
+            connection: text("connection").unwrap_or_else(|| "usb".to_string()),
+        })
+    }
+}
+
+/// the first usb hid mouse found, from the descriptors in sysfs
+#[cfg(feature = "usb")]
+pub fn identity() -> Option<Identity> {
+    let interface = mice().next()?;
+    let device = interface
+        .file_name()?
+        .to_str()?
+        .split(':')
+        .next()?
+        .to_string();
+    let device = Path::new(USB_DEVICES).join(device);
+    let id = |name| u16::from_str_radix(&read(&device, name)?, 16).ok();
+    let product = read(&device, "product");
+    Some(Identity {
+        vendor_id: id("idVendor")?,
+        product_id: id("idProduct")?,
+        manufacturer: read(&device, "manufacturer"),
+        connection: connection(product.as_deref(), read(&device, "speed").as_deref()),
+        product,
+    })
+}
+
+#[cfg(not(feature = "usb"))]
+pub fn identity() -> Option<Identity> {
+    None
+}
+
+/// the kernel cannot tell a receiver from a cable, the receivers name themselves so
+#[cfg(feature = "usb")]
+fn connection(product: Option<&str>, speed: Option<&str>) -> String {
+    let product = product.unwrap_or_default().to_lowercase();
+    let kind = if ["receiver", "dongle", "wireless", "unifying", "lightspeed"]
+        .iter()
+        .any(|w| product.contains(w))
+    {
+        "usb receiver"
+    } else {
+        "usb cable"
+    };
+    match speed {
+        Some(speed) => format!("{}, {} Mbit/s", kind, speed),
+        None => kind.to_string(),
+    }
+}
+
+/// the interface dirs of the usb hid mice
+#[cfg(feature = "usb")]
+fn mice() -> impl Iterator<Item = PathBuf> {
+    fs::read_dir(USB_DEVICES)
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| read(p, "bInterfaceClass").as_deref() == Some("03"))
+        .filter(|p| read(p, "bInterfaceProtocol").as_deref() == Some("02"))
+}
+
+#[cfg(feature = "usb")]
+fn interface_rate(interface: &Path) -> Option<u32> {
+    // interface dirs are named `<device>:<config>.<interface>`
+    let device = interface.file_name()?.to_str()?.split(':').next()?;
+    let speed = read(&Path::new(USB_DEVICES).join(device), "speed")?;
+
+    let interval = fs::read_dir(interface)
+        .ok()?
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| read(p, "type").as_deref() == Some("Interrupt"))
+        .find_map(|p| u32::from_str_radix(&read(&p, "bInterval")?, 16).ok())
+        .filter(|i| *i > 0)?;
+
+    match speed.as_str() {
+        // full and low speed count bInterval in frames of 1 ms
+        "1.5" | "12" => Some(1000 / interval),
+        // high speed and up use 2^(bInterval - 1) microframes of 125 us
+        _ => Some(8000 >> (interval - 1).min(13)),
+    }
+}
+
+#[cfg(feature = "usb")]
+fn read(dir: &Path, name: &str) -> Option<String> {
+    fs::read_to_string(dir.join(name))
+        .ok()
+        .map(|s| s.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn an_identity_comes_back_from_json() {
+        let identity = Identity {
+            vendor_id: 0x046d,
+            product_id: 0xc52b,
+            manufacturer: Some("Logitech".to_string()),
+            product: Some("USB Receiver".to_string()),
+            connection: "usb receiver, 12 Mbit/s".to_string(),
+        };
+        assert_eq!(
+            Identity::from_json(&identity.to_json()),
+            Some(identity.clone())
+        );
+        assert_eq!(
+            identity.describe(),
+            "Logitech USB Receiver (046d:c52b, usb receiver, 12 Mbit/s)"
+        );
+        assert_eq!(Identity::from_json(&Value::Null), None);
+    }
+}
@@ -0,0 +1,85 @@
+use crate::json::{self, Value};
+
+/// the build that recorded a session or produced a verdict, detectors change between
+/// versions so verdicts of different builds are compared with care
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Build {
+    pub version: String,
+    /// commit the binary was built from, `unknown` outside a checkout
+    pub git: String,
+    /// where the events are read from
+    pub backend: String,
+    /// cargo features compiled in
+    pub features: Vec<String>,
+}
+
+impl Build {
+    pub fn current() -> Self {
+        let features = [
+            ("battery", cfg!(feature = "battery")),
+            ("usb", cfg!(feature = "usb")),
+            ("cast", cfg!(feature = "cast")),
+            ("uinput", cfg!(feature = "uinput")),
+            ("test-util", cfg!(feature = "test-util")),
+        ];
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            git: env!("MOUSE_CHECKER_GIT_HASH").to_string(),
+            // terminal mouse events are the only backend of this build
+            backend: "terminal".to_string(),
+            features: features
+                .iter()
+                .filter(|(_, on)| *on)
+                .map(|(name, _)| name.to_string())
+                .collect(),
+        }
+    }
+
+    /// same version and commit, the detectors gave the same verdicts whatever the features
+    pub fn same_code(&self, other: &Build) -> bool {
+        self.version == other.version && self.git == other.git
+    }
+
+    pub fn to_json(&self) -> Value {
+        json::object([
+            ("version", Value::from(self.version.as_str())),
+            ("git", Value::from(self.git.as_str())),
+            ("backend", Value::from(self.backend.as_str())),
+            (
+                "features",
+                Value::Array(
+                    self.features
+                        .iter()
+                        .map(|f| Value::from(f.as_str()))
+                        .collect(),
+                ),
+            ),
+        ])
+    }
+
+    /// none without a version
+    pub fn from_json(value: &Value) -> Option<Self> {
+        let text = |key: &str| value.get(key).and_then(Value::as_str).map(String::from);
+        Some(Self {
+            version: text("version")?,
+            git: text("git").unwrap_or_else(|| "unknown".to_string()),
+            backend: text("backend").unwrap_or_else(|| "terminal".to_string()),
+            features: value
+                .get("features")
+                .and_then(Value::as_array)
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|f| f.as_str().map(String::from))
+                .collect(),
+        })
+    }
+
+    /// `mouse-checker 0.1.0 (1a2b3c4d5e6f, terminal, usb battery)`
+    pub fn describe(&self) -> String {
+        let mut parts = vec![self.git.clone(), self.backend.clone()];
+        if !self.features.is_empty() {
+            parts.push(self.features.join(" "));
+        }
+        format!("mouse-checker {} ({})", self.version, parts.join(", "))
+    }
+}
@@ -0,0 +1,167 @@
+use std::time::Duration;
+
+use crossterm::event::{Event, KeyCode, MouseEventKind};
+use ratatui::{
+    backend::Backend,
+    layout::Alignment,
+    text::Line,
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame, Terminal,
+};
+
+use crate::{app::AppState, error, i18n, input, session::Session};
+
+pub struct PhaseResult {
+    pub session: Session,
+    pub peak_rate: usize,
+}
+
+const PHASES: [(&str, &str); 2] = [
+    ("wired", "connect the mouse with the usb cable"),
+    ("wireless", "connect the mouse with the wireless dongle"),
+];
+
+/// run the same click protocol over cable and dongle, `None` when aborted
+pub fn run<B: Backend>(
+    terminal: &mut Terminal<B>,
+    detect_duration: Duration,
+    device: Option<&str>,
+    clicks: usize,
+) -> error::Result<Option<[PhaseResult; 2]>> {
+    let mut results = Vec::with_capacity(2);
+
+    for (label, instruction) in PHASES {
+        let name = match device {
+            Some(device) => format!("{} ({})", device, label),
+            None => label.to_string(),
+        };
+        let mut app = AppState::new(detect_duration, Some(name));
+        let mut ready = false;
+        let mut peak_rate = 0;
+
+        while app.session.click_count() < clicks {
+            terminal.draw(|f| ui(f, &app, label, instruction, ready, clicks))?;
+
+            match input::read()? {
+                Event::Key(key) if key.code == KeyCode::Char('q') => return Ok(None),
+                Event::Key(key) if key.code == KeyCode::Enter => ready = true,
+                Event::Mouse(event) if ready => match event.kind {
+                    MouseEventKind::Down(_) => app.on_click(event),
+                    MouseEventKind::Up(button) => app.on_release(button),
+                    MouseEventKind::Moved | MouseEventKind::Drag(_) => {
+                        app.on_move();
+                        peak_rate = peak_rate.max(app.moves.len());
+                    }
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+
+        results.push(PhaseResult {
+            session: app.session,
+            peak_rate,
+        });
+    }
+
+    let wireless = results.pop().unwrap();
+    let wired = results.pop().unwrap();
+    Ok(Some([wired, wireless]))
+}
+
+fn ui<B: Backend>(
+    f: &mut Frame<B>,
+    app: &AppState,
+    label: &str,
+    instruction: &str,
+    ready: bool,
+    clicks: usize,
+) {
+    let text = if ready {
+        vec![
+            Line::from(format!("{} phase", label)),
+            Line::from(""),
+            Line::from(format!(
+                "click once at a time and move the mouse around: {}/{}",
+                app.session.click_count(),
+                clicks
+            )),
+        ]
+    } else {
+        vec![
+            Line::from(format!("{} phase", label)),
+            Line::from(""),
+            Line::from(instruction.to_string()),
+            Line::from("press enter when ready, q to abort"),
+        ]
+    };
+
+    let p = Paragraph::new(text)
+        .block(
+            Block::default()
+                .title("wired vs wireless")
+                .borders(Borders::ALL),
+        )
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+
+    f.render_widget(p, f.size());
+}
+
+/// standard deviation of the gaps between consecutive clicks
+fn click_jitter(session: &Session) -> Option<Duration> {
+    let gaps = session
+        .clicks
+        .windows(2)
+        .map(|w| (w[1].at - w[0].at).as_secs_f64())
+        .collect::<Vec<_>>();
+
+    if gaps.len() < 2 {
+        return None;
+    }
+
+    let mean = gaps.iter().sum::<f64>() / gaps.len() as f64;
+    let variance = gaps.iter().map(|g| (g - mean).powi(2)).sum::<f64>() / gaps.len() as f64;
+    Some(Duration::from_secs_f64(variance.sqrt()))
+}
+
+pub fn print_comparison(results: &[PhaseResult; 2]) {
+    let ms = |d: Option<Duration>| {
+        d.map(|d| format!("{} ms", i18n::number(d.as_millis())))
+            .unwrap_or_else(|| "-".to_string())
+    };
+
+    let rows = [
+        (
+            "clicks",
+            results
+                .each_ref()
+                .map(|r| i18n::number(r.session.click_count())),
+        ),
+        (
+            "chatter",
+            results
+                .each_ref()
+                .map(|r| i18n::number(r.session.chatter_count())),
+        ),
+        (
+            "mean chatter interval",
+            results.each_ref().map(|r| ms(r.session.mean_interval())),
+        ),
+        (
+            "click jitter",
+            results.each_ref().map(|r| ms(click_jitter(&r.session))),
+        ),
+        (
+            "peak event rate",
+            results
+                .each_ref()
+                .map(|r| format!("{} Hz", i18n::number(r.peak_rate))),
+        ),
+    ];
+
+    println!("{:<24}{:>12}{:>12}", "", PHASES[0].0, PHASES[1].0);
+    for (name, [wired, wireless]) in rows {
+        println!("{:<24}{:>12}{:>12}", name, wired, wireless);
+    }
+}
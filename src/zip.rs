@@ -0,0 +1,187 @@
+use crate::session;
+
+/// crc-32 of the zip format, reflected polynomial 0xedb88320
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in bytes {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// time and date of a unix timestamp the way ms-dos stored them, 1980 at the earliest
+fn dos_time(secs: u64) -> (u16, u16) {
+    let [year, month, day, hour, minute, second] = session::date_time(secs);
+    if year < 1980 {
+        return (0, 0x21);
+    }
+    let time = (hour << 11) | (minute << 5) | (second / 2);
+    let date = ((year - 1980).min(127) << 9) | (month << 5) | day;
+    (time as u16, date as u16)
+}
+
+struct Entry {
+    name: String,
+    crc: u32,
+    size: u32,
+    offset: u32,
+}
+
+/// a zip archive built in memory with the files stored as they are, every unzip and file
+/// manager reads it; each file has to stay under 4 GiB
+pub struct Zip {
+    out: Vec<u8>,
+    entries: Vec<Entry>,
+    time: (u16, u16),
+}
+
+impl Zip {
+    /// the files get the time `secs`
+    pub fn new(secs: u64) -> Self {
+        Self {
+            out: Vec::new(),
+            entries: Vec::new(),
+            time: dos_time(secs),
+        }
+    }
+
+    fn header(&mut self, signature: u32, entry: &Entry) {
+        let (time, date) = self.time;
+        self.out.extend(signature.to_le_bytes());
+        if signature == 0x0201_4b50 {
+            // made by: zip 2.0 on unix
+            self.out.extend(0x0314u16.to_le_bytes());
+        }
+        // version needed, flags: utf-8 names, method: stored
+        self.out.extend(20u16.to_le_bytes());
+        self.out.extend(0x0800u16.to_le_bytes());
+        self.out.extend(0u16.to_le_bytes());
+        self.out.extend(time.to_le_bytes());
+        self.out.extend(date.to_le_bytes());
+        self.out.extend(entry.crc.to_le_bytes());
+        self.out.extend(entry.size.to_le_bytes());
+        self.out.extend(entry.size.to_le_bytes());
+        self.out.extend((entry.name.len() as u16).to_le_bytes());
+        // no extra field
+        self.out.extend(0u16.to_le_bytes());
+        if signature == 0x0201_4b50 {
+            // comment, disk, internal attributes, external attributes: -rw-r--r--
+            self.out.extend([0; 6]);
+            self.out.extend((0o100644u32 << 16).to_le_bytes());
+            self.out.extend(entry.offset.to_le_bytes());
+        }
+        self.out.extend(entry.name.as_bytes());
+    }
+
+    pub fn add(&mut self, name: &str, bytes: &[u8]) {
+        let entry = Entry {
+            name: name.to_string(),
+            crc: crc32(bytes),
+            size: bytes.len() as u32,
+            offset: self.out.len() as u32,
+        };
+        self.header(0x0403_4b50, &entry);
+        self.out.extend(bytes);
+        self.entries.push(entry);
+    }
+
+    /// the archive with its central directory
+    pub fn finish(mut self) -> Vec<u8> {
+        let start = self.out.len() as u32;
+        let entries = std::mem::take(&mut self.entries);
+        for entry in &entries {
+            self.header(0x0201_4b50, entry);
+        }
+        let size = self.out.len() as u32 - start;
+        let count = entries.len() as u16;
+        self.out.extend(0x0605_4b50u32.to_le_bytes());
+        // this disk and the disk the directory starts on
+        self.out.extend([0; 4]);
+        self.out.extend(count.to_le_bytes());
+        self.out.extend(count.to_le_bytes());
+        self.out.extend(size.to_le_bytes());
+        self.out.extend(start.to_le_bytes());
+        // no comment
+        self.out.extend(0u16.to_le_bytes());
+        self.out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u16_at(bytes: &[u8], at: usize) -> usize {
+        usize::from(u16::from_le_bytes([bytes[at], bytes[at + 1]]))
+    }
+
+    fn u32_at(bytes: &[u8], at: usize) -> usize {
+        u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]]) as usize
+    }
+
+    /// the names and contents of the files, read through the central directory the way
+    /// unzip does
+    fn files(archive: &[u8]) -> Vec<(String, Vec<u8>)> {
+        let end = archive.len() - 22;
+        assert_eq!(u32_at(archive, end), 0x0605_4b50);
+        let count = u16_at(archive, end + 10);
+        let mut at = u32_at(archive, end + 16);
+        assert_eq!(at + u32_at(archive, end + 12), end);
+        let mut files = Vec::new();
+        for _ in 0..count {
+            assert_eq!(u32_at(archive, at), 0x0201_4b50);
+            let crc = u32_at(archive, at + 16);
+            let size = u32_at(archive, at + 24);
+            let name_len = u16_at(archive, at + 28);
+            let offset = u32_at(archive, at + 42);
+            let name = String::from_utf8(archive[at + 46..at + 46 + name_len].to_vec()).unwrap();
+            at += 46 + name_len;
+
+            assert_eq!(u32_at(archive, offset), 0x0403_4b50);
+            assert_eq!(u16_at(archive, offset + 26), name_len);
+            let data = offset + 30 + name_len;
+            let bytes = archive[data..data + size].to_vec();
+            assert_eq!(crc32(&bytes) as usize, crc, "{}", name);
+            files.push((name, bytes));
+        }
+        files
+    }
+
+    #[test]
+    fn crc32_check_value() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn dos_times_start_in_1980() {
+        assert_eq!(dos_time(0), (0, 0x21));
+        // the middle of june 2024 in any time zone
+        let (_, date) = dos_time(1_718_000_000);
+        assert_eq!((date >> 9, (date >> 5) & 0xf), (44, 6));
+    }
+
+    #[test]
+    fn the_files_come_back_out() {
+        let mut zip = Zip::new(1_718_000_000);
+        zip.add("session.json", b"{}\n");
+        zip.add("empty", b"");
+        zip.add("說明.txt", "滑鼠".as_bytes());
+        assert_eq!(
+            files(&zip.finish()),
+            [
+                ("session.json".to_string(), b"{}\n".to_vec()),
+                ("empty".to_string(), Vec::new()),
+                ("說明.txt".to_string(), "滑鼠".as_bytes().to_vec()),
+            ]
+        );
+        assert!(files(&Zip::new(0).finish()).is_empty());
+    }
+}